@@ -484,15 +484,15 @@ impl BiomeType {
 
     /// (surface_block, shallow_under_block, deep_under_block, shallow_depth)
     pub(crate) fn block_distribution(self) -> (BlockType, BlockType, BlockType, i32) {
-        use world_types::BlockType::*;
+        use BlockType as B;
         match self {
             BiomeType::Ocean | BiomeType::IcyOcean | BiomeType::CoastOcean => {
-                (Dirt, Sand, Stone, 1)
+                (B::Dirt, B::Sand, B::Stone, 1)
             }
-            BiomeType::Beach => (Sand, Dirt, Stone, 4),
-            BiomeType::Plains => (LightGrass, Dirt, Stone, 3),
-            BiomeType::Forest | BiomeType::Tundra => (Grass, Dirt, Stone, 3),
-            BiomeType::Desert => (Sand, Sand, Stone, 6),
+            BiomeType::Beach => (B::Sand, B::Dirt, B::Stone, 4),
+            BiomeType::Plains => (B::LightGrass, B::Dirt, B::Stone, 3),
+            BiomeType::Forest | BiomeType::Tundra => (B::Grass, B::Dirt, B::Stone, 3),
+            BiomeType::Desert => (B::Sand, B::Sand, B::Stone, 6),
         }
     }
 
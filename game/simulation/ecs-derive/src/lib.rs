@@ -13,13 +13,14 @@ enum CloneBehaviour {
 }
 
 /// Expects `simulation::ecs::*` to be imported
-#[proc_macro_derive(EcsComponent, attributes(name, interactive, clone))]
+#[proc_macro_derive(EcsComponent, attributes(name, interactive, clone, save))]
 #[proc_macro_error]
 pub fn ecs_component_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = extract_name(&input);
     let interactive = extract_interactive(&input);
     let clone = extract_clone_behaviour(&input);
+    let save = extract_save(&input);
     let comp = input.ident;
 
     let as_interactive = if interactive {
@@ -43,11 +44,17 @@ pub fn ecs_component_derive(input: TokenStream) -> TokenStream {
                     let _ = storage.insert(dest.into(), comp);
                 }
             },
-            quote! { Some(#comp ::clone_to), },
+            quote! { Some(#comp ::clone_to) },
         ),
         CloneBehaviour::Disallow => (quote! {}, quote! { None }),
     };
 
+    let save_fns = if save {
+        quote! { Some((save_component_erased::<#comp>, load_component_erased::<#comp>)) }
+    } else {
+        quote! { None }
+    };
+
     let result = quote! {
         impl #comp {
             pub const COMPONENT_NAME: &'static str = #name;
@@ -78,7 +85,8 @@ pub fn ecs_component_derive(input: TokenStream) -> TokenStream {
             has_comp_fn: #comp ::has_component,
             register_comp_fn: #comp ::register_component,
             get_comp_fn: #comp ::get_component,
-            clone_to_fn: #clone_fn
+            clone_to_fn: #clone_fn,
+            save_fns: #save_fns,
         });
     };
 
@@ -103,6 +111,11 @@ fn extract_interactive(item: &DeriveInput) -> bool {
     item.attrs.iter().any(|a| a.path.is_ident("interactive"))
 }
 
+/// Component must implement SaveComponent
+fn extract_save(item: &DeriveInput) -> bool {
+    item.attrs.iter().any(|a| a.path.is_ident("save"))
+}
+
 fn extract_clone_behaviour(item: &DeriveInput) -> CloneBehaviour {
     let span = item.span();
     let attribute = item.attrs.iter().find(|a| a.path.is_ident("clone"));
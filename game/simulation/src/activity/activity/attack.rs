@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use futures::future::Either;
+use futures::pin_mut;
+
+use common::*;
+use world::SearchGoal;
+
+use crate::activity::activity::Activity;
+use crate::activity::context::{ActivityContext, ActivityResult, DistanceCheckResult};
+use crate::activity::status::Status;
+use crate::activity::subactivity::GoingToStatus;
+use crate::combat::choose_weapon;
+use crate::ecs::*;
+use crate::{HealthComponent, TransformComponent};
+
+/// Ticks to chase a moving target before checking its position again
+const CHASE_TICKS: u32 = 10;
+
+/// Attacking {0}
+#[derive(Debug, Clone, Display)]
+pub struct AttackActivity(Entity);
+
+#[derive(Debug, Error)]
+pub enum AttackActivityError {
+    #[error("No weapon to attack with")]
+    NoWeapon,
+
+    #[error("Target is missing transform")]
+    MissingTransform,
+}
+
+enum State {
+    Chasing,
+    Striking,
+}
+
+#[async_trait]
+impl Activity for AttackActivity {
+    fn description(&self) -> Box<dyn Display> {
+        Box::new(self.clone())
+    }
+
+    async fn dew_it(&self, ctx: &ActivityContext) -> ActivityResult {
+        while !self.is_target_dead(ctx) {
+            // check weapon each time in case it changes
+            let (_, weapon) =
+                choose_weapon(ctx.world(), ctx.entity()).ok_or(AttackActivityError::NoWeapon)?;
+
+            match ctx.check_entity_distance(self.0, weapon.reach.powi(2)) {
+                DistanceCheckResult::NotAvailable => {
+                    return Err(AttackActivityError::MissingTransform.into())
+                }
+                DistanceCheckResult::InRange => {
+                    ctx.update_status(State::Striking);
+                    ctx.attack(self.0).await?;
+                }
+                DistanceCheckResult::TooFar => {
+                    let target_pos = ctx
+                        .world()
+                        .component::<TransformComponent>(self.0)
+                        .map_err(|_| AttackActivityError::MissingTransform)?
+                        .position;
+
+                    // chase for a bit before checking again, as the target is probably moving
+                    let goto_fut = ctx.go_to(
+                        target_pos,
+                        NormalizedFloat::new(1.0),
+                        SearchGoal::Adjacent,
+                        GoingToStatus::Custom(State::Chasing),
+                    );
+
+                    let timeout_fut = ctx.wait(CHASE_TICKS);
+                    pin_mut!(goto_fut);
+                    pin_mut!(timeout_fut);
+
+                    if let Either::Left((Err(err), _)) =
+                        futures::future::select(goto_fut, timeout_fut).await
+                    {
+                        return Err(err.into());
+                    }
+                }
+            }
+        }
+
+        debug!("attack target is dead"; "target" => self.0);
+        Ok(())
+    }
+}
+
+impl AttackActivity {
+    pub fn new(target: Entity) -> Self {
+        Self(target)
+    }
+
+    fn is_target_dead(&self, ctx: &ActivityContext) -> bool {
+        let world = ctx.world();
+        !world.is_entity_alive(self.0)
+            || world
+                .component::<HealthComponent>(self.0)
+                .map(|health| health.is_dead())
+                .unwrap_or(false)
+    }
+}
+
+//noinspection DuplicatedCode
+impl Display for State {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            State::Chasing => "Chasing target",
+            State::Striking => "Fighting",
+        })
+    }
+}
+
+impl Status for State {
+    fn exertion(&self) -> f32 {
+        match self {
+            State::Chasing => 1.2,
+            State::Striking => 1.5,
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use async_trait::async_trait;
+
+use common::*;
+use unit::world::WorldPoint;
+use world::SearchGoal;
+
+use crate::activity::activity::Activity;
+use crate::activity::context::{ActivityContext, ActivityResult, InterruptResult};
+use crate::activity::status::Status;
+use crate::activity::subactivity::GoingToStatus;
+use crate::ecs::ComponentGetError;
+use crate::event::{EntityEvent, EntityEventSubscription, EventSubscription};
+use crate::{ComponentWorld, Entity, TransformComponent};
+
+/// Butchering {0}
+#[derive(Debug, Clone, Display)]
+pub struct GoButcherActivity(Entity);
+
+#[derive(Debug, Error)]
+pub enum GoButcherError {
+    #[error("Can't get corpse transform")]
+    MissingTransform(#[source] ComponentGetError),
+}
+
+/// Butchering
+#[derive(Display)]
+struct ButcheringState;
+
+#[async_trait]
+impl Activity for GoButcherActivity {
+    fn description(&self) -> Box<dyn Display> {
+        Box::new(self.clone())
+    }
+
+    async fn dew_it(&self, ctx: &ActivityContext) -> ActivityResult {
+        // cancel if any destructive event happens to the corpse
+        ctx.subscribe_to(EntityEventSubscription {
+            subject: self.0,
+            subscription: EventSubscription::All,
+        });
+
+        let pos = self.find_corpse(ctx)?;
+        ctx.go_to(
+            pos,
+            NormalizedFloat::new(0.8),
+            SearchGoal::Adjacent,
+            GoingToStatus::target("corpse"),
+        )
+        .await?;
+
+        ctx.update_status(ButcheringState);
+        ctx.butcher(self.0).await?;
+
+        Ok(())
+    }
+
+    fn on_unhandled_event(&self, event: EntityEvent, me: Entity) -> InterruptResult {
+        if event.subject == self.0 && event.payload.is_destructive_for(Some(me)) {
+            debug!("corpse has been destroyed, cancelling butchering");
+            InterruptResult::Cancel
+        } else {
+            InterruptResult::Continue
+        }
+    }
+}
+
+impl GoButcherActivity {
+    pub fn new(corpse: Entity) -> Self {
+        Self(corpse)
+    }
+
+    fn find_corpse(&self, ctx: &ActivityContext) -> Result<WorldPoint, GoButcherError> {
+        let transform = ctx
+            .world()
+            .component::<TransformComponent>(self.0)
+            .map_err(GoButcherError::MissingTransform)?;
+
+        Ok(transform.position)
+    }
+}
+
+impl Status for ButcheringState {
+    fn exertion(&self) -> f32 {
+        1.0
+    }
+}
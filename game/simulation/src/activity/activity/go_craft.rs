@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+
+use common::*;
+
+use crate::activity::activity::Activity;
+use crate::activity::context::{ActivityContext, ActivityResult};
+use crate::activity::status::Status;
+use crate::activity::subactivity::GoingToStatus;
+use crate::job::{CraftDetails, SocietyJobHandle};
+
+use world::SearchGoal;
+
+#[derive(Debug, Clone)]
+pub struct GoCraftActivity {
+    job: SocietyJobHandle,
+    details: CraftDetails,
+}
+
+/// Crafting
+#[derive(Display)]
+struct CraftStatus;
+
+#[async_trait]
+impl Activity for GoCraftActivity {
+    fn description(&self) -> Box<dyn Display> {
+        Box::new(self.clone())
+    }
+
+    async fn dew_it(&self, ctx: &ActivityContext) -> ActivityResult {
+        // walk to the workshop
+        ctx.go_to(
+            self.details.pos.centred(),
+            NormalizedFloat::new(0.8),
+            SearchGoal::Adjacent,
+            GoingToStatus::target("workshop"),
+        )
+        .await?;
+
+        ctx.update_status(CraftStatus);
+        ctx.craft(self.job, &self.details).await?;
+
+        Ok(())
+    }
+}
+
+impl GoCraftActivity {
+    pub fn new(job: SocietyJobHandle, details: CraftDetails) -> Self {
+        Self { job, details }
+    }
+}
+
+impl Status for CraftStatus {
+    // TODO depends on recipe
+    fn exertion(&self) -> f32 {
+        1.1
+    }
+}
+
+impl Display for GoCraftActivity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Crafting {}", self.details.output)
+    }
+}
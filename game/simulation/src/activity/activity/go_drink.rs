@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+
+use common::derive_more::Display;
+use common::*;
+use unit::world::WorldPoint;
+use world::SearchGoal;
+
+use crate::activity::activity::Activity;
+use crate::activity::context::{ActivityContext, ActivityResult, InterruptResult};
+use crate::activity::status::Status;
+use crate::activity::subactivity::GoingToStatus;
+use crate::ecs::ComponentGetError;
+use crate::event::{EntityEvent, EntityEventSubscription, EventSubscription};
+use crate::needs::thirst::DrinkSource;
+
+use crate::{ComponentWorld, Entity, TransformComponent};
+
+/// Go drink from a water block or drinkable item without picking it up first
+#[derive(Debug, Clone, Display)]
+#[display(fmt = "Going to drink from {_0}")]
+pub struct GoDrinkActivity(DrinkSource);
+
+#[derive(Debug, Error)]
+pub enum DrinkActivityError {
+    #[error("Can't get item transform")]
+    MissingTransform(#[source] ComponentGetError),
+}
+
+#[derive(Display)]
+#[display(fmt = "Drinking")]
+struct DrinkingState;
+
+#[async_trait]
+impl Activity for GoDrinkActivity {
+    fn description(&self) -> Box<dyn Display> {
+        Box::new(self.clone())
+    }
+
+    async fn dew_it(&self, ctx: &ActivityContext) -> ActivityResult {
+        // cancel if any destructive event happens to the drink
+        if let DrinkSource::Item(item) = self.0 {
+            ctx.subscribe_to(EntityEventSubscription {
+                subject: item,
+                subscription: EventSubscription::All,
+            });
+        }
+
+        let pos = self.find_source(ctx)?;
+        ctx.go_to(
+            pos,
+            NormalizedFloat::new(0.8),
+            SearchGoal::Adjacent,
+            GoingToStatus::target("water"),
+        )
+        .await?;
+
+        // glug glug
+        ctx.update_status(DrinkingState);
+        ctx.drink(self.0).await?;
+
+        Ok(())
+    }
+
+    fn on_unhandled_event(&self, event: EntityEvent, me: Entity) -> InterruptResult {
+        match self.0 {
+            DrinkSource::Item(item)
+                if event.subject == item && event.payload.is_destructive_for(Some(me)) =>
+            {
+                debug!("drink has been destroyed, cancelling drink");
+                InterruptResult::Cancel
+            }
+            _ => InterruptResult::Continue,
+        }
+    }
+}
+
+impl GoDrinkActivity {
+    pub fn new(source: DrinkSource) -> Self {
+        Self(source)
+    }
+
+    fn find_source(&self, ctx: &ActivityContext) -> Result<WorldPoint, DrinkActivityError> {
+        match self.0 {
+            DrinkSource::Block(pos) => Ok(pos.centred()),
+            DrinkSource::Item(item) => {
+                let transform = ctx
+                    .world()
+                    .component::<TransformComponent>(item)
+                    .map_err(DrinkActivityError::MissingTransform)?;
+
+                Ok(transform.position)
+            }
+        }
+    }
+}
+
+impl Status for DrinkingState {
+    fn exertion(&self) -> f32 {
+        0.1
+    }
+}
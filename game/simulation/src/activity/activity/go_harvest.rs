@@ -0,0 +1,86 @@
+use async_trait::async_trait;
+
+use common::*;
+use unit::world::WorldPoint;
+use world::SearchGoal;
+
+use crate::activity::activity::Activity;
+use crate::activity::context::{ActivityContext, ActivityResult, InterruptResult};
+use crate::activity::status::Status;
+use crate::activity::subactivity::GoingToStatus;
+use crate::ecs::ComponentGetError;
+use crate::event::{EntityEvent, EntityEventSubscription, EventSubscription};
+use crate::{ComponentWorld, Entity, TransformComponent};
+
+/// Harvesting {0}
+#[derive(Debug, Clone, Display)]
+pub struct GoHarvestActivity(Entity);
+
+#[derive(Debug, Error)]
+pub enum GoHarvestError {
+    #[error("Can't get plant transform")]
+    MissingTransform(#[source] ComponentGetError),
+}
+
+/// Harvesting
+#[derive(Display)]
+struct HarvestingState;
+
+#[async_trait]
+impl Activity for GoHarvestActivity {
+    fn description(&self) -> Box<dyn Display> {
+        Box::new(self.clone())
+    }
+
+    async fn dew_it(&self, ctx: &ActivityContext) -> ActivityResult {
+        // cancel if any destructive event happens to the plant
+        ctx.subscribe_to(EntityEventSubscription {
+            subject: self.0,
+            subscription: EventSubscription::All,
+        });
+
+        let pos = self.find_plant(ctx)?;
+        ctx.go_to(
+            pos,
+            NormalizedFloat::new(0.8),
+            SearchGoal::Adjacent,
+            GoingToStatus::target("plant"),
+        )
+        .await?;
+
+        ctx.update_status(HarvestingState);
+        ctx.harvest(self.0).await?;
+
+        Ok(())
+    }
+
+    fn on_unhandled_event(&self, event: EntityEvent, me: Entity) -> InterruptResult {
+        if event.subject == self.0 && event.payload.is_destructive_for(Some(me)) {
+            debug!("plant has been destroyed, cancelling harvest");
+            InterruptResult::Cancel
+        } else {
+            InterruptResult::Continue
+        }
+    }
+}
+
+impl GoHarvestActivity {
+    pub fn new(plant: Entity) -> Self {
+        Self(plant)
+    }
+
+    fn find_plant(&self, ctx: &ActivityContext) -> Result<WorldPoint, GoHarvestError> {
+        let transform = ctx
+            .world()
+            .component::<TransformComponent>(self.0)
+            .map_err(GoHarvestError::MissingTransform)?;
+
+        Ok(transform.position)
+    }
+}
+
+impl Status for HarvestingState {
+    fn exertion(&self) -> f32 {
+        1.0
+    }
+}
@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+
+use common::*;
+use world::SearchGoal;
+
+use crate::activity::activity::Activity;
+use crate::activity::context::{ActivityContext, ActivityResult};
+use crate::activity::status::Status;
+use crate::activity::subactivity::GoingToStatus;
+use crate::string::CachedStr;
+use crate::WorldPosition;
+
+/// Sowing {crop} at {soil}
+#[derive(Debug, Clone, Display)]
+pub struct GoSowActivity {
+    soil: WorldPosition,
+    crop: CachedStr,
+}
+
+/// Sowing
+#[derive(Display)]
+struct SowStatus;
+
+#[async_trait]
+impl Activity for GoSowActivity {
+    fn description(&self) -> Box<dyn Display> {
+        Box::new(self.clone())
+    }
+
+    async fn dew_it(&self, ctx: &ActivityContext) -> ActivityResult {
+        // walk to the soil
+        ctx.go_to(
+            self.soil.above().centred(),
+            NormalizedFloat::new(0.8),
+            SearchGoal::Adjacent,
+            GoingToStatus::target("soil"),
+        )
+        .await?;
+
+        ctx.update_status(SowStatus);
+        ctx.sow(self.soil, self.crop).await?;
+
+        Ok(())
+    }
+}
+
+impl GoSowActivity {
+    pub fn new(soil: WorldPosition, crop: CachedStr) -> Self {
+        Self { soil, crop }
+    }
+}
+
+impl Status for SowStatus {
+    fn exertion(&self) -> f32 {
+        1.0
+    }
+}
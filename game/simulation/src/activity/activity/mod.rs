@@ -1,26 +1,40 @@
 pub use activity_trait::Activity;
+pub use attack::AttackActivity;
 pub use eat_held_item::EatHeldItemActivity;
 pub use follow::FollowActivity;
 pub use go_break_block::GoBreakBlockActivity;
 pub use go_build::GoBuildActivity;
+pub use go_butcher::GoButcherActivity;
+pub use go_craft::GoCraftActivity;
+pub use go_drink::GoDrinkActivity;
 pub use go_equip::GoEquipActivity;
+pub use go_harvest::GoHarvestActivity;
 pub use go_haul::GoHaulActivity;
+pub use go_sow::GoSowActivity;
 pub use go_to::GoToActivity;
 pub use nop::NopActivity;
 pub use return_to_herd::ReturnToHerdActivity;
+pub use sleep::SleepActivity;
 pub use wander::WanderActivity;
 
 mod go_eat;
 pub use go_eat::GoEatActivity;
+mod attack;
 mod eat_held_item;
 mod follow;
 mod go_break_block;
 mod go_build;
+mod go_butcher;
+mod go_craft;
+mod go_drink;
 mod go_equip;
+mod go_harvest;
 mod go_haul;
+mod go_sow;
 mod go_to;
 mod nop;
 mod return_to_herd;
+mod sleep;
 mod wander;
 
 mod activity_trait {
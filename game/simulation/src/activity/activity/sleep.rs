@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+
+use common::derive_more::Display;
+use common::*;
+use unit::world::WorldPoint;
+use world::SearchGoal;
+
+use crate::activity::activity::Activity;
+use crate::activity::context::{ActivityContext, ActivityResult, InterruptResult};
+use crate::activity::status::Status;
+use crate::activity::subactivity::GoingToStatus;
+use crate::ecs::ComponentGetError;
+use crate::event::{EntityEvent, EntityEventSubscription, EventSubscription};
+use crate::needs::sleep::{BedComponent, EnergyComponent};
+
+use crate::{ComponentWorld, Entity, TransformComponent};
+
+/// Lie down on the spot or in the given bed and sleep until fully rested
+#[derive(Debug, Clone)]
+pub struct SleepActivity {
+    bed: Option<Entity>,
+}
+
+#[derive(Debug, Error)]
+pub enum SleepError {
+    #[error("Can't get bed transform")]
+    MissingTransform(#[source] ComponentGetError),
+
+    #[error("Sleeper has no energy component")]
+    MissingEnergy(#[source] ComponentGetError),
+}
+
+#[derive(Display)]
+#[display(fmt = "Sleeping")]
+struct SleepingState;
+
+/// Ticks between each energy recovery
+const SLEEP_INTERVAL: u32 = 20;
+
+/// Comfort of sleeping without a bed
+const GROUND_COMFORT: f32 = 0.6;
+
+#[async_trait]
+impl Activity for SleepActivity {
+    fn description(&self) -> Box<dyn Display> {
+        Box::new(self.clone())
+    }
+
+    async fn dew_it(&self, ctx: &ActivityContext) -> ActivityResult {
+        if let Some(bed) = self.bed {
+            // cancel if any destructive event happens to the bed
+            ctx.subscribe_to(EntityEventSubscription {
+                subject: bed,
+                subscription: EventSubscription::All,
+            });
+
+            let pos = self.find_bed(ctx, bed)?;
+            ctx.go_to(
+                pos,
+                NormalizedFloat::new(0.6),
+                SearchGoal::Arrive,
+                GoingToStatus::target("bed"),
+            )
+            .await?;
+        }
+
+        ctx.update_status(SleepingState);
+        loop {
+            ctx.wait(SLEEP_INTERVAL).await;
+
+            let comfort = self
+                .bed
+                .and_then(|bed| ctx.world().component::<BedComponent>(bed).ok())
+                .map(|bed| bed.comfort())
+                .unwrap_or_else(|| NormalizedFloat::new(GROUND_COMFORT));
+
+            let mut energy = ctx
+                .world()
+                .component_mut::<EnergyComponent>(ctx.entity())
+                .map_err(SleepError::MissingEnergy)?;
+
+            if energy.recover(SLEEP_INTERVAL, comfort) {
+                debug!("woke up fully rested");
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_unhandled_event(&self, event: EntityEvent, me: Entity) -> InterruptResult {
+        match self.bed {
+            Some(bed) if event.subject == bed && event.payload.is_destructive_for(Some(me)) => {
+                debug!("bed has been destroyed, cancelling sleep");
+                InterruptResult::Cancel
+            }
+            _ => InterruptResult::Continue,
+        }
+    }
+}
+
+impl SleepActivity {
+    pub fn new(bed: Option<Entity>) -> Self {
+        Self { bed }
+    }
+
+    fn find_bed(&self, ctx: &ActivityContext, bed: Entity) -> Result<WorldPoint, SleepError> {
+        let transform = ctx
+            .world()
+            .component::<TransformComponent>(bed)
+            .map_err(SleepError::MissingTransform)?;
+
+        Ok(transform.position)
+    }
+}
+
+impl Display for SleepActivity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.bed {
+            Some(bed) => write!(f, "Sleeping in bed {}", bed),
+            None => write!(f, "Sleeping"),
+        }
+    }
+}
+
+impl Status for SleepingState {
+    fn exertion(&self) -> f32 {
+        0.0
+    }
+}
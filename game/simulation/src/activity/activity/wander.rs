@@ -42,10 +42,10 @@ impl Activity for WanderActivity {
 
         loop {
             let (wander_distance, loiter_ticks) = {
-                let mut random = thread_rng();
+                let mut rng = random::get();
                 (
-                    distr_wander_distance.sample(&mut random),
-                    distr_loiter_ticks.sample(&mut random),
+                    distr_wander_distance.sample(&mut *rng),
+                    distr_loiter_ticks.sample(&mut *rng),
                 )
             };
 
@@ -10,16 +10,20 @@ use world::SearchGoal;
 use crate::activity::context::EventResult::{Consumed, Unconsumed};
 use crate::activity::status::Status;
 use crate::activity::subactivity::{
-    BreakBlockError, BreakBlockSubactivity, BuildBlockError, BuildBlockSubactivity, EatItemError,
-    EatItemSubactivity, EquipSubActivity, GoToSubactivity, GoingToStatus, GotoError, HaulSource,
-    HaulSubactivity, PickupSubactivity,
+    AttackError, AttackSubactivity, BreakBlockError, BreakBlockSubactivity, BuildBlockError,
+    BuildBlockSubactivity, ButcherError, ButcherSubactivity, CraftItemError, CraftSubactivity,
+    DrinkError, DrinkSubactivity, EatItemError, EatItemSubactivity, EquipSubActivity, FarmError,
+    GoToSubactivity, GoingToStatus, GotoError, HarvestSubactivity, HaulSource, HaulSubactivity,
+    PickupSubactivity, SowSubactivity,
 };
 use crate::activity::{Activity, EquipItemError, HaulError, StatusUpdater};
 use crate::ecs::*;
 use crate::event::prelude::*;
 use crate::event::{EntityEventQueue, RuntimeTimers};
-use crate::job::{BuildDetails, SocietyJobHandle};
+use crate::job::{BuildDetails, CraftDetails, SocietyJobHandle};
+use crate::needs::thirst::DrinkSource;
 use crate::runtime::{TaskRef, TimerFuture};
+use crate::string::CachedStr;
 use crate::{
     ComponentWorld, EcsWorld, Entity, FollowPathComponent, TransformComponent, WorldPosition,
 };
@@ -135,6 +139,30 @@ impl ActivityContext {
         BuildBlockSubactivity.build_block(self, job, details).await
     }
 
+    /// Must be close enough
+    pub async fn craft(
+        &self,
+        job: SocietyJobHandle,
+        details: &CraftDetails,
+    ) -> Result<(), CraftItemError> {
+        CraftSubactivity.craft(self, job, details).await
+    }
+
+    /// Checks if close enough first
+    pub async fn sow(&self, soil: WorldPosition, crop: CachedStr) -> Result<(), FarmError> {
+        SowSubactivity.sow(self, soil, crop).await
+    }
+
+    /// Checks if close enough first
+    pub async fn harvest(&self, plant: Entity) -> Result<(), FarmError> {
+        HarvestSubactivity.harvest(self, plant).await
+    }
+
+    /// Checks if close enough first
+    pub async fn butcher(&self, corpse: Entity) -> Result<(), ButcherError> {
+        ButcherSubactivity.butcher(self, corpse).await
+    }
+
     /// Pick up item off the ground, checks if close enough first
     pub async fn pick_up(&self, item: Entity) -> Result<(), EquipItemError> {
         PickupSubactivity.pick_up(self, item).await
@@ -155,6 +183,11 @@ impl ActivityContext {
         EatItemSubactivity::new(self, item).eat_nearby().await
     }
 
+    /// Checks if close enough first, then drinks until no longer thirsty
+    pub async fn drink(&self, source: DrinkSource) -> Result<(), DrinkError> {
+        DrinkSubactivity.drink(self, source).await
+    }
+
     /// Picks up thing for hauling, checks if close enough first
     pub async fn haul(
         &self,
@@ -164,6 +197,11 @@ impl ActivityContext {
         HaulSubactivity::start_hauling(self, thing, source).await
     }
 
+    /// Strikes the target once, checks if close enough first
+    pub async fn attack(&self, target: Entity) -> Result<(), AttackError> {
+        AttackSubactivity.attack(self, target).await
+    }
+
     pub fn check_entity_distance(&self, entity: Entity, max_dist_2: f32) -> DistanceCheckResult {
         let transforms = self.world().read_storage::<TransformComponent>();
         let my_pos = transforms.get(self.entity().into());
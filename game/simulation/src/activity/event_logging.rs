@@ -8,8 +8,10 @@ use unit::world::WorldPoint;
 
 use crate::ecs::*;
 use crate::event::DeathReason;
-use crate::job::BuildDetails;
+use crate::job::{BuildDetails, CraftDetails};
+use crate::needs::thirst::DrinkSource;
 use crate::simulation::Tick;
+use crate::string::CachedStr;
 use crate::WorldPosition;
 
 struct RingBuffer<T>(VecDeque<T>, usize);
@@ -50,6 +52,7 @@ pub enum LoggedEntityDecision {
     GoPickup(Cow<'static, str>),
     GoEquip(Entity),
     GoEat(Entity),
+    GoDrink(DrinkSource),
     EatHeldItem(Entity),
     Wander,
     Goto(WorldPoint),
@@ -58,6 +61,12 @@ pub enum LoggedEntityDecision {
     Follow(Entity),
     Haul { item: Entity, dest: HaulTarget },
     GoBuild(BuildDetails),
+    GoCraft(CraftDetails),
+    Sleep(Option<Entity>),
+    Attack(Entity),
+    GoButcher(Entity),
+    GoSow(WorldPosition, CachedStr),
+    GoHarvest(Entity),
 }
 
 impl<T> RingBuffer<T> {
@@ -138,6 +147,7 @@ impl Display for LoggedEntityEvent {
                     GoPickup(what) => write!(f, "pickup nearby {}", what),
                     GoEquip(e) => write!(f, "go pickup {}", *e),
                     GoEat(e) => write!(f, "go eat {}", *e),
+                    GoDrink(source) => write!(f, "go drink from {}", source),
                     EatHeldItem(e) => write!(f, "eat held {}", e),
                     Wander => write!(f, "wander around"),
                     Goto(target) => write!(f, "go to {}", target),
@@ -146,6 +156,15 @@ impl Display for LoggedEntityEvent {
                     Follow(e) => write!(f, "follow {}", e),
                     Haul { item, dest } => write!(f, "haul {} to {}", item, dest),
                     GoBuild(details) => write!(f, "build {} at {}", details.target, details.pos),
+                    GoCraft(details) => {
+                        write!(f, "craft {} at {}", details.output, details.workshop)
+                    }
+                    Sleep(Some(bed)) => write!(f, "sleep in bed {}", bed),
+                    Sleep(None) => write!(f, "sleep on the ground"),
+                    Attack(e) => write!(f, "attack {}", e),
+                    GoButcher(e) => write!(f, "butcher {}", e),
+                    GoSow(soil, crop) => write!(f, "sow {} at {}", crop, soil),
+                    GoHarvest(e) => write!(f, "harvest {}", e),
                 }
             }
         }
@@ -37,8 +37,10 @@ mod action_to_activity {
                 Nop => activity!(NopActivity::default()),
                 GoBreakBlock(pos) => activity!(GoBreakBlockActivity::new(pos)),
                 GoBuild { job, details } => activity!(GoBuildActivity::new(job, details)),
+                GoCraft { job, details } => activity!(GoCraftActivity::new(job, details)),
                 GoEquip(e) => activity!(GoEquipActivity::new(e)),
                 GoEat(e) => activity!(GoEatActivity::new(e)),
+                GoDrink(source) => activity!(GoDrinkActivity::new(source)),
                 EatHeldItem(item) => activity!(EatHeldItemActivity::new(item)),
                 Goto(target) => activity!(GoToActivity::new(
                     target,
@@ -51,6 +53,11 @@ mod action_to_activity {
                 Follow { target, radius } => {
                     activity!(FollowActivity::new(target, radius))
                 }
+                Sleep(bed) => activity!(SleepActivity::new(bed)),
+                Attack(target) => activity!(AttackActivity::new(target)),
+                GoButcher(corpse) => activity!(GoButcherActivity::new(corpse)),
+                GoSow { soil, crop } => activity!(GoSowActivity::new(soil, crop)),
+                GoHarvest(plant) => activity!(GoHarvestActivity::new(plant)),
                 Haul(thing, source, target, purpose) => {
                     activity!(GoHaulActivity::new_with_purpose(
                         thing, source, target, purpose
@@ -0,0 +1,45 @@
+use common::*;
+
+use crate::activity::context::{ActivityContext, DistanceCheckResult};
+use crate::combat::{choose_weapon, strike, StrikeError};
+use crate::ecs::*;
+
+#[derive(Debug, Error, Clone)]
+pub enum AttackError {
+    #[error("No weapon to attack with")]
+    NoWeapon,
+
+    #[error("Target is missing transform")]
+    BadTarget,
+
+    #[error("Too far from target to attack")]
+    TooFar,
+
+    #[error("{0}")]
+    Strike(#[from] StrikeError),
+}
+
+#[derive(Default)]
+pub struct AttackSubactivity;
+
+impl AttackSubactivity {
+    /// Strikes once with the best available weapon, then waits for the weapon's cooldown
+    pub async fn attack(&self, ctx: &ActivityContext, target: Entity) -> Result<(), AttackError> {
+        let (weapon_entity, weapon) =
+            choose_weapon(ctx.world(), ctx.entity()).ok_or(AttackError::NoWeapon)?;
+
+        // ensure close enough
+        match ctx.check_entity_distance(target, weapon.reach.powi(2)) {
+            DistanceCheckResult::NotAvailable => return Err(AttackError::BadTarget),
+            DistanceCheckResult::TooFar => return Err(AttackError::TooFar),
+            DistanceCheckResult::InRange => {} // good
+        };
+
+        trace!("striking"; "target" => target, "weapon" => weapon_entity);
+        strike(ctx.world(), ctx.entity(), target, &weapon)?;
+
+        // recover before next strike
+        ctx.wait(weapon.cooldown).await;
+        Ok(())
+    }
+}
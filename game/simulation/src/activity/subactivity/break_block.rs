@@ -2,6 +2,7 @@ use crate::ecs::ComponentGetError;
 
 use crate::activity::context::ActivityContext;
 use crate::queued_update::QueuedUpdates;
+use crate::senses::{NoiseKind, Noises};
 use crate::ComponentWorld;
 use crate::{TransformComponent, WorldPosition};
 use common::*;
@@ -64,6 +65,12 @@ impl BreakBlockSubactivity {
                 ctx.world()
                     .resource::<QueuedUpdates>()
                     .queue_block_damage(block, break_rate);
+
+                ctx.world().resource_mut::<Noises>().emit(
+                    ctx.entity(),
+                    block.centred(),
+                    NoiseKind::BreakBlock,
+                );
             }
 
             // check again next tick
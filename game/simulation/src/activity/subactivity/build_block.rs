@@ -2,6 +2,7 @@ use crate::ecs::ComponentGetError;
 
 use crate::activity::context::ActivityContext;
 use crate::job::{BuildDetails, BuildThingJob, SocietyJobHandle};
+use crate::senses::{NoiseKind, Noises};
 
 use crate::ComponentWorld;
 use crate::{TransformComponent, WorldPosition};
@@ -78,6 +79,12 @@ impl BuildBlockSubactivity {
                 })
                 .ok_or(BuildBlockError::InvalidJob(job))?;
 
+            ctx.world().resource_mut::<Noises>().emit(
+                ctx.entity(),
+                details.pos.centred(),
+                NoiseKind::Build,
+            );
+
             if new_progress >= progress_details.total_steps_needed {
                 break;
             }
@@ -89,6 +96,15 @@ impl BuildBlockSubactivity {
         helper
             .complete_build(ctx.world())
             .map_err(|_| BuildBlockError::CompletionFailed)?;
+
+        // doors built for a society can only be opened by its members
+        if details.target.is_door() {
+            ctx.world()
+                .voxel_world()
+                .borrow_mut()
+                .set_door_owner(details.pos, Some(job.society().into()));
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,56 @@
+use common::*;
+
+use crate::activity::context::{ActivityContext, DistanceCheckResult};
+use crate::ecs::*;
+use crate::item::{butcher_corpse, ButcherableComponent};
+use crate::queued_update::QueuedUpdates;
+
+const MAX_BUTCHER_DISTANCE: f32 = 2.0;
+
+#[derive(Debug, Error, Clone)]
+pub enum ButcherError {
+    #[error("Corpse is not butcherable")]
+    NotButcherable,
+
+    #[error("Corpse is missing transform")]
+    BadCorpse,
+
+    #[error("Too far from corpse to butcher it")]
+    TooFar,
+}
+
+#[derive(Default)]
+pub struct ButcherSubactivity;
+
+impl ButcherSubactivity {
+    /// Works on the corpse for its butchering time, then replaces it with meat
+    pub async fn butcher(&self, ctx: &ActivityContext, corpse: Entity) -> Result<(), ButcherError> {
+        let ticks = ctx
+            .world()
+            .component::<ButcherableComponent>(corpse)
+            .map_err(|_| ButcherError::NotButcherable)?
+            .ticks;
+
+        // ensure close enough
+        match ctx.check_entity_distance(corpse, MAX_BUTCHER_DISTANCE.powi(2)) {
+            DistanceCheckResult::NotAvailable => return Err(ButcherError::BadCorpse),
+            DistanceCheckResult::TooFar => return Err(ButcherError::TooFar),
+            DistanceCheckResult::InRange => {} // good
+        };
+
+        ctx.wait(ticks).await;
+
+        // may have rotted away in the meantime
+        if !ctx.world().has_component::<ButcherableComponent>(corpse) {
+            return Err(ButcherError::NotButcherable);
+        }
+
+        ctx.world()
+            .resource::<QueuedUpdates>()
+            .queue("butcher corpse", move |world| {
+                butcher_corpse(&world, corpse).map(|_| ())
+            });
+
+        Ok(())
+    }
+}
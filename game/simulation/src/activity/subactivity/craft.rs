@@ -0,0 +1,107 @@
+use crate::ecs::ComponentGetError;
+
+use crate::activity::context::ActivityContext;
+use crate::event::DeathReason;
+use crate::job::{CraftDetails, CraftJob, SocietyJobHandle};
+use crate::queued_update::QueuedUpdates;
+
+use crate::{ComponentWorld, Entity};
+use crate::{TransformComponent, WorldPosition};
+use common::*;
+use unit::world::WorldPoint;
+
+/// Max distance from the workshop to work on a craft
+const MAX_CRAFT_DISTANCE: f32 = 2.25;
+
+#[derive(Debug, Error)]
+pub enum CraftItemError {
+    #[error("Bad entity with no transform")]
+    MissingTransform(#[from] ComponentGetError),
+
+    #[error("Too far from workshop at {target} to craft from {current}")]
+    TooFar {
+        current: WorldPoint,
+        target: WorldPosition,
+    },
+
+    #[error("Job not found or is not a craft job")]
+    InvalidJob(SocietyJobHandle),
+}
+
+#[derive(Default)]
+pub struct CraftSubactivity;
+
+impl CraftSubactivity {
+    pub async fn craft(
+        &self,
+        ctx: &ActivityContext,
+        job: SocietyJobHandle,
+        details: &CraftDetails,
+    ) -> Result<(), CraftItemError> {
+        // check we are close enough
+        let my_pos = ctx
+            .world()
+            .component::<TransformComponent>(ctx.entity())
+            .map_err(CraftItemError::MissingTransform)?
+            .position;
+
+        if my_pos.distance2(details.pos) > MAX_CRAFT_DISTANCE.powi(2) {
+            return Err(CraftItemError::TooFar {
+                current: my_pos,
+                target: details.pos,
+            });
+        }
+
+        let ((total_steps_needed, progress_rate), output) = job
+            .resolve_and_cast(ctx.world().resource(), |craft_job: &CraftJob| {
+                let recipe = craft_job.recipe();
+                (recipe.progression(), recipe.output())
+            })
+            .ok_or(CraftItemError::InvalidJob(job))?;
+
+        loop {
+            // need to reresolve the job each time
+            let new_progress = job
+                .resolve_and_cast_mut(ctx.world().resource(), |craft_job: &mut CraftJob| {
+                    craft_job.make_progress()
+                })
+                .ok_or(CraftItemError::InvalidJob(job))?;
+
+            if new_progress >= total_steps_needed {
+                break;
+            }
+
+            ctx.wait(progress_rate).await;
+        }
+
+        // collect reserved materials only now, in case more were reserved during
+        let materials = job
+            .resolve_and_cast(ctx.world().resource(), |craft_job: &CraftJob| {
+                craft_job.reserved_materials().collect::<Vec<Entity>>()
+            })
+            .ok_or(CraftItemError::InvalidJob(job))?;
+
+        let pos = details.pos;
+        debug!("craft job was completed, queueing material destruction and output"; "materials" => ?materials);
+        ctx.world().resource::<QueuedUpdates>().queue(
+            "consuming inputs and spawning outputs for completed craft",
+            move |world| {
+                world.kill_entities(&materials, DeathReason::CompletedCraft);
+
+                let (definition, count) = output;
+                for _ in 0..count.get() {
+                    let item = world
+                        .build_entity(definition.as_ref())?
+                        .with_position(pos.centred())
+                        .doesnt_need_to_be_accessible()
+                        .spawn()?;
+                    debug!("spawned craft output"; item, "definition" => %definition);
+                }
+
+                Ok(())
+            },
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+use common::*;
+use unit::drink::Hydration;
+use unit::world::{WorldPoint, WorldPosition};
+
+use crate::activity::context::{ActivityContext, DistanceCheckResult};
+use crate::ecs::ComponentGetError;
+use crate::event::DeathReason;
+use crate::needs::thirst::{DrinkSource, ThirstComponent};
+use crate::{
+    ComponentWorld, ConditionComponent, ContainedInComponent, DrinkableItemComponent, Entity,
+    InventoryComponent, TransformComponent,
+};
+
+/// Drinks from a water block or drinkable item until no longer thirsty
+#[derive(Default)]
+pub struct DrinkSubactivity;
+
+const MAX_DRINK_DISTANCE: f32 = 2.0;
+
+/// Ticks between each sip
+const SIP_INTERVAL: u32 = 4;
+
+/// Hydration gained per sip
+const SIP_HYDRATION: Hydration = Hydration::new(20);
+
+#[derive(Debug, Error)]
+pub enum DrinkError {
+    #[error("Bad entity with no transform")]
+    MissingTransform(#[source] ComponentGetError),
+
+    #[error("Drinker has no thirst")]
+    NotThirsty(#[source] ComponentGetError),
+
+    #[error("Too far from water at {target} to drink from {current}")]
+    TooFarFromBlock {
+        current: WorldPoint,
+        target: WorldPosition,
+    },
+
+    #[error("Too far from drink item to drink")]
+    TooFarFromItem,
+
+    #[error("Drink item is missing transform")]
+    BadItemEntity,
+
+    #[error("Block at {0} is not water")]
+    NotWater(WorldPosition),
+
+    #[error("Item {0} is not drinkable")]
+    NotDrinkable(Entity),
+}
+
+enum Sip {
+    KeepDrinking,
+    Finished,
+}
+
+impl DrinkSubactivity {
+    pub async fn drink(
+        &self,
+        ctx: &ActivityContext,
+        source: DrinkSource,
+    ) -> Result<(), DrinkError> {
+        // ensure close enough
+        match source {
+            DrinkSource::Block(pos) => {
+                let my_pos = ctx
+                    .world()
+                    .component::<TransformComponent>(ctx.entity())
+                    .map_err(DrinkError::MissingTransform)?
+                    .position;
+
+                // TODO stop hardcoding distance check for block actions
+                if my_pos.distance2(pos) > 5.0 {
+                    return Err(DrinkError::TooFarFromBlock {
+                        current: my_pos,
+                        target: pos,
+                    });
+                }
+            }
+            DrinkSource::Item(item) => {
+                match ctx.check_entity_distance(item, MAX_DRINK_DISTANCE.powi(2)) {
+                    DistanceCheckResult::NotAvailable => return Err(DrinkError::BadItemEntity),
+                    DistanceCheckResult::TooFar => return Err(DrinkError::TooFarFromItem),
+                    DistanceCheckResult::InRange => {} // good
+                }
+            }
+        }
+
+        loop {
+            let sip = match source {
+                DrinkSource::Block(pos) => self.sip_from_block(ctx, pos)?,
+                DrinkSource::Item(item) => self.sip_from_item(ctx, item)?,
+            };
+
+            if let Sip::Finished = sip {
+                break;
+            }
+
+            ctx.wait(SIP_INTERVAL).await;
+        }
+
+        Ok(())
+    }
+
+    fn sip_from_block(&self, ctx: &ActivityContext, pos: WorldPosition) -> Result<Sip, DrinkError> {
+        let is_water = {
+            let world = ctx.world().voxel_world();
+            let world = world.borrow();
+            matches!(world.block(pos), Some(b) if b.is_water())
+        };
+
+        if !is_water {
+            return Err(DrinkError::NotWater(pos));
+        }
+
+        let mut thirst = ctx
+            .world()
+            .component_mut::<ThirstComponent>(ctx.entity())
+            .map_err(DrinkError::NotThirsty)?;
+
+        // unlimited water in a block
+        let thirst = thirst.thirst_mut();
+        thirst.drink(SIP_HYDRATION);
+        trace!("drinking from water block"; "block" => %pos, "hydration" => ?thirst.hydration());
+
+        Ok(if thirst.remaining().is_zero() {
+            Sip::Finished
+        } else {
+            Sip::KeepDrinking
+        })
+    }
+
+    fn sip_from_item(&self, ctx: &ActivityContext, item: Entity) -> Result<Sip, DrinkError> {
+        let world = ctx.world();
+        let (emptied, full) = {
+            let drinkable = world
+                .component::<DrinkableItemComponent>(item)
+                .map_err(|_| DrinkError::NotDrinkable(item))?;
+            let mut condition = world
+                .component_mut::<ConditionComponent>(item)
+                .map_err(|_| DrinkError::NotDrinkable(item))?;
+            let mut thirst = world
+                .component_mut::<ThirstComponent>(ctx.entity())
+                .map_err(DrinkError::NotThirsty)?;
+            let thirst = thirst.thirst_mut();
+
+            let available = drinkable.total_hydration * condition.0.value();
+            let drunk = thirst.drink(SIP_HYDRATION.min(available));
+            condition.0 -= drunk.proportion_of(drinkable.total_hydration);
+            trace!("drinking from item"; "item" => item, "hydration" => ?thirst.hydration(),
+                "item_condition" => ?condition.0);
+
+            (condition.0.is_broken(), thirst.remaining().is_zero())
+        };
+
+        if emptied {
+            debug!("drink item has been emptied"; "item" => item);
+
+            // remove from drinker's inventory if applicable
+            let me = ctx.entity();
+            if let Ok(ContainedInComponent::InventoryOf(holder)) =
+                world.component::<ContainedInComponent>(item).as_deref()
+            {
+                if *holder == me {
+                    if let Ok(mut inventory) = world.component_mut::<InventoryComponent>(me) {
+                        inventory.remove_item(item);
+                    }
+                }
+            }
+
+            world.kill_entity(item, DeathReason::Consumed);
+            return Ok(Sip::Finished);
+        }
+
+        Ok(if full {
+            Sip::Finished
+        } else {
+            Sip::KeepDrinking
+        })
+    }
+}
@@ -0,0 +1,104 @@
+use common::*;
+use unit::world::WorldPosition;
+
+use crate::activity::context::{ActivityContext, DistanceCheckResult};
+use crate::ecs::*;
+use crate::farming::{harvest_plant, sow_crop, GrowthComponent, HarvestableComponent};
+use crate::queued_update::QueuedUpdates;
+use crate::string::CachedStr;
+use crate::TransformComponent;
+
+const MAX_FARM_DISTANCE: f32 = 2.0;
+
+/// Ticks taken to sow a crop
+const SOW_TICKS: u32 = 60;
+
+#[derive(Debug, Error, Clone)]
+pub enum FarmError {
+    #[error("Plant is not harvestable")]
+    NotHarvestable,
+
+    #[error("Plant is not ripe")]
+    NotRipe,
+
+    #[error("Plant or farmer is missing transform")]
+    BadPlant,
+
+    #[error("Too far from the plant or soil")]
+    TooFar,
+}
+
+#[derive(Default)]
+pub struct SowSubactivity;
+
+#[derive(Default)]
+pub struct HarvestSubactivity;
+
+impl SowSubactivity {
+    /// Works the soil for a bit, then plants the crop on top of it
+    pub async fn sow(
+        &self,
+        ctx: &ActivityContext,
+        soil: WorldPosition,
+        crop: CachedStr,
+    ) -> Result<(), FarmError> {
+        // ensure close enough
+        let pos = ctx
+            .world()
+            .component::<TransformComponent>(ctx.entity())
+            .map_err(|_| FarmError::BadPlant)?
+            .position;
+
+        if pos.distance2(soil.above()) > MAX_FARM_DISTANCE.powi(2) {
+            return Err(FarmError::TooFar);
+        }
+
+        ctx.wait(SOW_TICKS).await;
+
+        ctx.world()
+            .resource::<QueuedUpdates>()
+            .queue("sow crop", move |world| {
+                sow_crop(&world, crop, soil).map(|_| ())
+            });
+
+        Ok(())
+    }
+}
+
+impl HarvestSubactivity {
+    /// Works on the plant for its harvesting time, then spawns its produce
+    pub async fn harvest(&self, ctx: &ActivityContext, plant: Entity) -> Result<(), FarmError> {
+        let ticks = ctx
+            .world()
+            .component::<HarvestableComponent>(plant)
+            .map_err(|_| FarmError::NotHarvestable)?
+            .ticks;
+
+        // ensure close enough
+        match ctx.check_entity_distance(plant, MAX_FARM_DISTANCE.powi(2)) {
+            DistanceCheckResult::NotAvailable => return Err(FarmError::BadPlant),
+            DistanceCheckResult::TooFar => return Err(FarmError::TooFar),
+            DistanceCheckResult::InRange => {} // good
+        };
+
+        ctx.wait(ticks).await;
+
+        // may have been eaten or otherwise disturbed in the meantime
+        let is_ripe = ctx
+            .world()
+            .component::<GrowthComponent>(plant)
+            .map(|growth| growth.is_ripe())
+            .unwrap_or(true);
+        if !is_ripe {
+            return Err(FarmError::NotRipe);
+        }
+
+        ctx.world()
+            .resource::<QueuedUpdates>()
+            .queue("harvest plant", move |world| {
+                harvest_plant(&world, plant).map(|_| ())
+            });
+
+        Ok(())
+    }
+}
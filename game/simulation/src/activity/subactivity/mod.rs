@@ -1,13 +1,23 @@
+mod attack;
 mod break_block;
 mod build_block;
+mod butcher;
+mod craft;
+mod drink;
 mod eat;
 mod equip;
+mod farm;
 mod go_to;
 mod haul;
 
+pub use attack::{AttackError, AttackSubactivity};
 pub use break_block::{BreakBlockError, BreakBlockSubactivity};
 pub use build_block::{BuildBlockError, BuildBlockSubactivity};
+pub use butcher::{ButcherError, ButcherSubactivity};
+pub use craft::{CraftItemError, CraftSubactivity};
+pub use drink::{DrinkError, DrinkSubactivity};
 pub use eat::{EatItemError, EatItemSubactivity};
 pub use equip::{EquipItemError, EquipSubActivity, PickupSubactivity};
+pub use farm::{FarmError, HarvestSubactivity, SowSubactivity};
 pub use go_to::{GoToSubactivity, GoingToStatus, GotoError};
 pub use haul::{HaulError, HaulPurpose, HaulSource, HaulSubactivity, HaulTarget};
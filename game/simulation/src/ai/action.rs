@@ -7,7 +7,9 @@ use crate::activity::{
     HaulPurpose, HaulSource, HaulTarget, LoggedEntityDecision, LoggedEntityEvent,
 };
 use crate::ecs::Entity;
-use crate::job::{BuildDetails, SocietyJobHandle};
+use crate::job::{BuildDetails, CraftDetails, SocietyJobHandle};
+use crate::needs::thirst::DrinkSource;
+use crate::string::CachedStr;
 use crate::{ComponentWorld, EcsWorld, ItemStackComponent, Tick};
 
 // TODO speed should be specified as an enum for all go??? actions
@@ -32,6 +34,9 @@ pub enum AiAction {
     /// Go and eat the given entity without picking it up
     GoEat(Entity),
 
+    /// Go and drink from the given water block or drinkable item
+    GoDrink(DrinkSource),
+
     /// Equip and eat the given entity, assuming it's already in the inventory
     EatHeldItem(Entity),
 
@@ -44,11 +49,35 @@ pub enum AiAction {
         details: BuildDetails,
     },
 
+    /// Go work on the given craft job at its workshop, assuming its inputs are already present
+    GoCraft {
+        job: SocietyJobHandle,
+        details: CraftDetails,
+    },
+
     /// Follow the entity, keeping to the given distance
     Follow { target: Entity, radius: u8 },
 
     /// Haul the entity from the source to the destination target
     Haul(Entity, HaulSource, HaulTarget, HaulPurpose),
+
+    /// Sleep on the spot, or go and sleep in the given bed
+    Sleep(Option<Entity>),
+
+    /// Chase and strike the given entity until it's dead
+    Attack(Entity),
+
+    /// Go and butcher the given corpse into meat
+    GoButcher(Entity),
+
+    /// Go and sow the crop on top of the given soil block
+    GoSow {
+        soil: WorldPosition,
+        crop: CachedStr,
+    },
+
+    /// Go and harvest the given ripe plant
+    GoHarvest(Entity),
 }
 
 impl ai::Action for AiAction {
@@ -106,6 +135,7 @@ impl TryInto<LoggedEntityEvent> for &AiAction {
             A::ReturnToHerd => B::ReturnToHerd,
             A::GoEquip(item) => B::GoEquip(*item),
             A::GoEat(item) => B::GoEat(*item),
+            A::GoDrink(source) => B::GoDrink(*source),
             A::EatHeldItem(item) => B::EatHeldItem(*item),
             A::GoBreakBlock(pos) => B::GoBreakBlock(*pos),
             A::Follow { target, .. } => B::Follow(*target),
@@ -114,6 +144,12 @@ impl TryInto<LoggedEntityEvent> for &AiAction {
                 dest: *tgt,
             },
             A::GoBuild { details, .. } => B::GoBuild(details.clone()),
+            A::GoCraft { details, .. } => B::GoCraft(details.clone()),
+            A::Sleep(bed) => B::Sleep(*bed),
+            A::Attack(target) => B::Attack(*target),
+            A::GoButcher(corpse) => B::GoButcher(*corpse),
+            A::GoSow { soil, crop } => B::GoSow(*soil, *crop),
+            A::GoHarvest(plant) => B::GoHarvest(*plant),
         }))
     }
 }
@@ -1,11 +1,11 @@
 pub use can_use_held_item::CanUseHeldItemConsideration;
-pub use find_local_graded_item::FindLocalGradedItemConsideration;
 pub use has_extra_hands_for_hauling::HasExtraHandsForHaulingConsideration;
 pub use has_free_hands::HasFreeHandsToHoldTargetConsideration;
 pub use holding_item::HoldingItemConsideration;
+pub use target_condition::TargetConditionConsideration;
 
 mod can_use_held_item;
-mod find_local_graded_item;
 mod has_extra_hands_for_hauling;
 mod has_free_hands;
 mod holding_item;
+mod target_condition;
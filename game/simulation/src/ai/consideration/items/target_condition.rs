@@ -0,0 +1,21 @@
+use ai::{Consideration, ConsiderationParameter, Context, Curve};
+
+use crate::ai::{AiContext, AiInput};
+
+/// Prefers target items in better condition, e.g. fresh food over rotting food
+pub struct TargetConditionConsideration;
+
+impl Consideration<AiContext> for TargetConditionConsideration {
+    fn curve(&self) -> Curve {
+        // still worth considering items in terrible condition
+        Curve::Linear(0.8, 0.2)
+    }
+
+    fn input(&self) -> <AiContext as Context>::Input {
+        AiInput::TargetCondition
+    }
+
+    fn parameter(&self) -> ConsiderationParameter {
+        ConsiderationParameter::Nop // already normalized
+    }
+}
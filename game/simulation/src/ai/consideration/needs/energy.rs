@@ -0,0 +1,28 @@
+use ai::{Consideration, ConsiderationParameter, Context, Curve};
+use common::*;
+
+use crate::ai::{AiContext, AiInput};
+
+declare_entity_metric!(AI_ENERGY, "ai_energy", "Energy level");
+
+pub struct EnergyConsideration;
+
+impl Consideration<AiContext> for EnergyConsideration {
+    fn curve(&self) -> Curve {
+        // barely considered while reasonably rested, then quickly overwhelming once exhausted
+        Curve::Exponential(1000.0, -1.0, 0.2, 1.0, -0.01)
+    }
+
+    fn input(&self) -> <AiContext as Context>::Input {
+        AiInput::Energy
+    }
+
+    fn parameter(&self) -> ConsiderationParameter {
+        ConsiderationParameter::Nop // already normalized
+    }
+
+    #[cfg(feature = "metrics")]
+    fn log_metric(&self, entity: &str, value: f32) {
+        entity_metric!(AI_ENERGY, entity, value);
+    }
+}
@@ -1,5 +1,9 @@
+pub use energy::EnergyConsideration;
 pub use hunger::HungerConsideration;
 pub use likes_to_eat_target::LikesToEatTargetConsideration;
+pub use thirst::ThirstConsideration;
 
+mod energy;
 mod hunger;
 mod likes_to_eat_target;
+mod thirst;
@@ -0,0 +1,28 @@
+use ai::{Consideration, ConsiderationParameter, Context, Curve};
+use common::*;
+
+use crate::ai::{AiContext, AiInput};
+
+declare_entity_metric!(AI_THIRST, "ai_thirst", "Thirst level");
+
+pub struct ThirstConsideration;
+
+impl Consideration<AiContext> for ThirstConsideration {
+    fn curve(&self) -> Curve {
+        // rises sooner and more steeply than hunger, going without water is more urgent
+        Curve::Exponential(50.0, -1.0, 0.3, 1.0, -0.05)
+    }
+
+    fn input(&self) -> <AiContext as Context>::Input {
+        AiInput::Thirst
+    }
+
+    fn parameter(&self) -> ConsiderationParameter {
+        ConsiderationParameter::Nop // already normalized
+    }
+
+    #[cfg(feature = "metrics")]
+    fn log_metric(&self, entity: &str, value: f32) {
+        entity_metric!(AI_THIRST, entity, value);
+    }
+}
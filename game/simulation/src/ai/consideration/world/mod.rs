@@ -1,7 +1,9 @@
 pub use my_proximity_to::MyProximityToConsideration;
 pub use my_proximity_to_target::MyProximityToTargetConsideration;
+pub use night::NightConsideration;
 pub use target_block_type_matches::TargetBlockTypeMatchesConsideration;
 
 mod my_proximity_to;
 mod my_proximity_to_target;
+mod night;
 mod target_block_type_matches;
@@ -0,0 +1,23 @@
+use ai::{Consideration, ConsiderationParameter, Context, Curve};
+
+use crate::ai::{AiContext, AiInput};
+
+/// Prefers night time, but doesn't rule out the day
+pub struct NightConsideration;
+
+impl Consideration<AiContext> for NightConsideration {
+    fn curve(&self) -> Curve {
+        Curve::Linear(0.5, 0.5)
+    }
+
+    fn input(&self) -> <AiContext as Context>::Input {
+        AiInput::Darkness
+    }
+
+    fn parameter(&self) -> ConsiderationParameter {
+        ConsiderationParameter::Nop // already normalized
+    }
+
+    #[cfg(feature = "metrics")]
+    fn log_metric(&self, _: &str, _: f32) {}
+}
@@ -4,7 +4,7 @@ use std::rc::Rc;
 
 use common::*;
 use unit::world::{WorldPoint, WorldPosition};
-use world::WorldArea;
+use world::{NavigationProfile, WorldArea};
 
 use crate::ai::dse::AdditionalDse;
 use crate::ai::input::LocalAreaSearch;
@@ -13,10 +13,12 @@ use crate::ai::AiInput;
 use crate::build::ReservedMaterialComponent;
 use crate::ecs::*;
 use crate::item::{FoundSlot, ItemFilter, ItemFilterable};
+use crate::movement::MovementConfigComponent;
+use crate::path::navigation_profile;
 use crate::spatial::Spatial;
 use crate::{
-    AiAction, ContainedInComponent, EcsWorld, Entity, InventoryComponent, SocietyComponent,
-    SocietyHandle, TransformComponent, WorldRef,
+    AiAction, ContainedInComponent, EcsWorld, Entity, InventoryComponent, PhysicalComponent,
+    SocietyComponent, SocietyHandle, TransformComponent, WorldRef,
 };
 
 pub struct AiContext;
@@ -57,7 +59,8 @@ pub struct AiBlackboard<'a> {
 
 #[derive(Default)]
 pub struct SharedBlackboard {
-    pub area_link_cache: HashMap<(WorldArea, WorldArea), bool>,
+    /// Shared between agents, so keyed by the profile of the agent checking too
+    pub area_link_cache: HashMap<(WorldArea, WorldArea, NavigationProfile), bool>,
 }
 
 impl ai::Blackboard for AiBlackboard<'_> {
@@ -135,6 +138,14 @@ impl<'a> AiBlackboard<'a> {
             }
         };
 
+        // items are only accessible through doors and gaps this agent can get through
+        let profile = navigation_profile(
+            self.entity.get(&world.read_storage::<PhysicalComponent>()),
+            self.entity
+                .get(&world.read_storage::<MovementConfigComponent>()),
+            self.entity.get(&world.read_storage::<SocietyComponent>()),
+        );
+
         let reservations = world.read_storage::<ReservedMaterialComponent>();
         let containeds = world.read_storage::<ContainedInComponent>();
         let transforms = world.read_storage::<TransformComponent>();
@@ -180,16 +191,18 @@ impl<'a> AiBlackboard<'a> {
                     // different areas, do a cached cheap path find to see if its accessible
                     // consistent key ordering
                     let cache_key = if self_area < item_area {
-                        (self_area, item_area)
+                        (self_area, item_area, profile)
                     } else {
-                        (item_area, self_area)
+                        (item_area, self_area, profile)
                     };
                     if !*self
                         .shared
                         .borrow_mut()
                         .area_link_cache
                         .entry(cache_key)
-                        .or_insert_with(|| voxel_world.area_path_exists(self_area, item_area))
+                        .or_insert_with(|| {
+                            voxel_world.area_path_exists(self_area, item_area, &profile)
+                        })
                     {
                         return None;
                     }
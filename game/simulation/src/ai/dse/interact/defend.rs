@@ -0,0 +1,48 @@
+use ai::{Considerations, DecisionWeight, Dse, TargetOutput, Targets};
+
+use crate::ai::consideration::MyProximityToTargetConsideration;
+use crate::ai::{AiAction, AiBlackboard, AiContext, AiTarget};
+use crate::combat::AttackedComponent;
+use crate::ecs::*;
+use crate::simulation::Tick;
+
+/// Ticks after being attacked that the attacker is still fought back against
+const DEFEND_TICKS: u32 = 200;
+
+/// Fights back against a recent attacker
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct DefendSelfDse;
+
+impl Dse<AiContext> for DefendSelfDse {
+    fn considerations(&self, out: &mut Considerations<AiContext>) {
+        out.add(MyProximityToTargetConsideration);
+    }
+
+    fn weight(&self) -> DecisionWeight {
+        DecisionWeight::Emergency
+    }
+
+    fn target(
+        &self,
+        targets: &mut Targets<AiContext>,
+        blackboard: &mut AiBlackboard,
+    ) -> TargetOutput {
+        if let Ok(attacked) = blackboard
+            .world
+            .component::<AttackedComponent>(blackboard.entity)
+        {
+            if Tick::fetch().elapsed_since(attacked.when) < DEFEND_TICKS
+                && blackboard.world.is_entity_alive(attacked.attacker)
+            {
+                targets.add(AiTarget::Entity(attacked.attacker));
+            }
+        }
+
+        TargetOutput::TargetsCollected
+    }
+
+    fn action(&self, _: &mut AiBlackboard, target: Option<AiTarget>) -> AiAction {
+        let target = target.and_then(|t| t.entity()).expect("bad target");
+        AiAction::Attack(target)
+    }
+}
@@ -0,0 +1,63 @@
+use ai::{Considerations, DecisionWeight, Dse, TargetOutput, Targets};
+
+use crate::ai::consideration::{HungerConsideration, MyProximityToTargetConsideration};
+use crate::ai::{AiAction, AiBlackboard, AiContext, AiTarget};
+use crate::combat::HunterComponent;
+use crate::ecs::*;
+use crate::senses::SensesComponent;
+use crate::{HealthComponent, SpeciesComponent};
+
+/// Hunts sensed prey when hungry
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct HuntPreyDse;
+
+impl Dse<AiContext> for HuntPreyDse {
+    fn considerations(&self, out: &mut Considerations<AiContext>) {
+        out.add(HungerConsideration);
+        out.add(MyProximityToTargetConsideration);
+    }
+
+    fn weight(&self) -> DecisionWeight {
+        DecisionWeight::Normal
+    }
+
+    fn target(
+        &self,
+        targets: &mut Targets<AiContext>,
+        blackboard: &mut AiBlackboard,
+    ) -> TargetOutput {
+        let world = blackboard.world;
+        if let Some((hunter, senses)) = world.components(
+            blackboard.entity,
+            (
+                &world.read_storage::<HunterComponent>(),
+                &world.read_storage::<SensesComponent>(),
+            ),
+        ) {
+            let species = world.read_storage::<SpeciesComponent>();
+            let health = world.read_storage::<HealthComponent>();
+            for prey in senses.sensed_entities() {
+                let is_prey = prey
+                    .get(&species)
+                    .map(|comp| hunter.hunts(comp.species()))
+                    .unwrap_or(false);
+
+                let is_alive = prey
+                    .get(&health)
+                    .map(|comp| !comp.is_dead())
+                    .unwrap_or(false);
+
+                if is_prey && is_alive {
+                    targets.add(AiTarget::Entity(prey));
+                }
+            }
+        }
+
+        TargetOutput::TargetsCollected
+    }
+
+    fn action(&self, _: &mut AiBlackboard, target: Option<AiTarget>) -> AiAction {
+        let target = target.and_then(|t| t.entity()).expect("bad target");
+        AiAction::Attack(target)
+    }
+}
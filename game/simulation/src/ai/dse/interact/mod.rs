@@ -1,3 +1,7 @@
+pub use defend::DefendSelfDse;
+pub use hunt::HuntPreyDse;
 pub use stay_close_to_herd::StayCloseToHerdDse;
 
+mod defend;
+mod hunt;
 mod stay_close_to_herd;
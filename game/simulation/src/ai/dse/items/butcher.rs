@@ -0,0 +1,41 @@
+use ai::{Considerations, DecisionWeight, Dse, TargetOutput, Targets};
+
+use crate::ai::consideration::{HungerConsideration, MyProximityToTargetConsideration};
+use crate::ai::{AiAction, AiBlackboard, AiContext, AiTarget};
+use crate::item::ItemFilter;
+
+/// Finds a nearby corpse to butcher into meat when hungry
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ButcherCorpseDse;
+
+const CORPSE_FILTER: ItemFilter = ItemFilter::HasComponent("butcherable");
+const CORPSE_MAX_RADIUS: f32 = 20.0;
+
+impl Dse<AiContext> for ButcherCorpseDse {
+    fn considerations(&self, out: &mut Considerations<AiContext>) {
+        out.add(HungerConsideration);
+        out.add(MyProximityToTargetConsideration);
+    }
+
+    fn weight(&self) -> DecisionWeight {
+        DecisionWeight::Normal
+    }
+
+    fn target(
+        &self,
+        targets: &mut Targets<AiContext>,
+        blackboard: &mut AiBlackboard,
+    ) -> TargetOutput {
+        blackboard.search_local_entities(CORPSE_FILTER, CORPSE_MAX_RADIUS, 5, |item| {
+            targets.add(AiTarget::Entity(item.entity));
+            true
+        });
+
+        TargetOutput::TargetsCollected
+    }
+
+    fn action(&self, _: &mut AiBlackboard, target: Option<AiTarget>) -> AiAction {
+        let corpse = target.and_then(|t| t.entity()).expect("bad target");
+        AiAction::GoButcher(corpse)
+    }
+}
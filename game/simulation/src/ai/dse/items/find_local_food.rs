@@ -5,7 +5,7 @@ use common::*;
 
 use crate::ai::consideration::{
     HasFreeHandsToHoldTargetConsideration, HungerConsideration, LikesToEatTargetConsideration,
-    MyProximityToTargetConsideration,
+    MyProximityToTargetConsideration, TargetConditionConsideration,
 };
 use crate::ai::{AiAction, AiBlackboard, AiContext, AiTarget};
 use crate::item::ItemFilter;
@@ -26,7 +26,7 @@ fn common_considerations(out: &mut Considerations<AiContext>) {
     out.add(HungerConsideration);
     out.add(LikesToEatTargetConsideration);
     out.add(MyProximityToTargetConsideration);
-    // TODO target food condition consideration
+    out.add(TargetConditionConsideration);
     // TODO "I can/want to move" consideration
 }
 
@@ -0,0 +1,72 @@
+use ai::{Considerations, DecisionWeight, Dse, TargetOutput, Targets};
+use unit::world::{WorldPosition, WorldPositionRange};
+
+use crate::ai::consideration::{MyProximityToTargetConsideration, ThirstConsideration};
+use crate::ai::{AiAction, AiBlackboard, AiContext, AiTarget};
+use crate::item::ItemFilter;
+use crate::needs::thirst::DrinkSource;
+use crate::ComponentWorld;
+
+/// Finds water nearby to drink, either a water block or a drinkable item
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct FindLocalWaterDse;
+
+const DRINK_FILTER: ItemFilter = ItemFilter::HasComponent("drinkable");
+const WATER_MAX_RADIUS: i32 = 15;
+const WATER_MAX_DEPTH: i32 = 3;
+
+impl Dse<AiContext> for FindLocalWaterDse {
+    fn considerations(&self, out: &mut Considerations<AiContext>) {
+        out.add(ThirstConsideration);
+        out.add(MyProximityToTargetConsideration);
+    }
+
+    fn weight(&self) -> DecisionWeight {
+        DecisionWeight::Normal
+    }
+
+    fn target(
+        &self,
+        targets: &mut Targets<AiContext>,
+        blackboard: &mut AiBlackboard,
+    ) -> TargetOutput {
+        // drinkable items
+        blackboard.search_local_entities(DRINK_FILTER, WATER_MAX_RADIUS as f32, 5, |item| {
+            targets.add(AiTarget::Entity(item.entity));
+            true
+        });
+
+        // water blocks that can be reached
+        let (x, y, z) = {
+            let pos = blackboard.transform.position.floor();
+            (pos.0, pos.1, pos.2.slice())
+        };
+        let range = WorldPositionRange::with_inclusive_range(
+            WorldPosition::from((
+                x - WATER_MAX_RADIUS,
+                y - WATER_MAX_RADIUS,
+                z - WATER_MAX_DEPTH,
+            )),
+            WorldPosition::from((x + WATER_MAX_RADIUS, y + WATER_MAX_RADIUS, z + 1)),
+        );
+
+        let voxel_world = blackboard.world.voxel_world();
+        let voxel_world = voxel_world.borrow();
+        voxel_world
+            .filter_reachable_blocks_in_range(&range, |b| b.is_water())
+            .take(5)
+            .for_each(|pos| targets.add(AiTarget::Block(pos)));
+
+        TargetOutput::TargetsCollected
+    }
+
+    fn action(&self, _: &mut AiBlackboard, target: Option<AiTarget>) -> AiAction {
+        let source = match target {
+            Some(AiTarget::Entity(item)) => DrinkSource::Item(item),
+            Some(AiTarget::Block(pos)) => DrinkSource::Block(pos),
+            _ => unreachable!("bad drink target {:?}", target),
+        };
+
+        AiAction::GoDrink(source)
+    }
+}
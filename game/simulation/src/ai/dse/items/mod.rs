@@ -1,7 +1,11 @@
+pub use butcher::ButcherCorpseDse;
 pub use eat_held_food::EatHeldFoodDse;
 pub use find_local_food::{FindLocalEquippableFoodDse, FindLocalGrazingFoodDse};
+pub use find_local_water::FindLocalWaterDse;
 pub use haul::HaulDse;
 
+mod butcher;
 mod eat_held_food;
 mod find_local_food;
+mod find_local_water;
 mod haul;
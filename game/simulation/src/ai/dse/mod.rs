@@ -1,5 +1,6 @@
 pub use items::*;
 pub use obey_divine_command::*;
+pub use sleep::*;
 pub use species::*;
 pub use wander::*;
 
@@ -8,6 +9,7 @@ pub use self::world::*;
 mod interact;
 mod items;
 mod obey_divine_command;
+mod sleep;
 mod wander;
 mod world;
 
@@ -20,7 +22,7 @@ pub enum AdditionalDse {
 pub mod species {
     use ai::{AiBox, Dse};
 
-    use crate::ai::dse::interact::StayCloseToHerdDse;
+    use crate::ai::dse::interact::{DefendSelfDse, HuntPreyDse, StayCloseToHerdDse};
     use crate::ai::AiContext;
     use crate::dse;
 
@@ -31,12 +33,24 @@ pub mod species {
             dse!(WanderDse),
             dse!(EatHeldFoodDse),
             dse!(FindLocalEquippableFoodDse),
+            dse!(FindLocalWaterDse),
+            dse!(SleepInBedDse),
+            dse!(SleepInPlaceDse),
+            dse!(DefendSelfDse),
+            dse!(HuntPreyDse),
+            dse!(ButcherCorpseDse),
         ]
         .into_iter()
     }
 
     pub fn dog_dses() -> impl Iterator<Item = AiBox<dyn Dse<AiContext>>> {
-        [dse!(WanderDse)].into_iter()
+        [
+            dse!(WanderDse),
+            dse!(HuntPreyDse),
+            dse!(DefendSelfDse),
+            dse!(FindLocalWaterDse),
+        ]
+        .into_iter()
     }
 
     pub fn sheep_dses() -> impl Iterator<Item = AiBox<dyn Dse<AiContext>>> {
@@ -44,6 +58,7 @@ pub mod species {
             dse!(WanderDse),
             dse!(StayCloseToHerdDse),
             dse![FindLocalGrazingFoodDse],
+            dse!(FindLocalWaterDse),
         ]
         .into_iter()
     }
@@ -0,0 +1,86 @@
+use ai::{Considerations, DecisionWeight, Dse, TargetOutput, Targets};
+use common::*;
+use specs::WorldExt;
+
+use crate::ai::consideration::{
+    ConstantConsideration, EnergyConsideration, MyProximityToTargetConsideration,
+    NightConsideration,
+};
+use crate::ai::{AiAction, AiBlackboard, AiContext, AiTarget};
+use crate::ecs::*;
+use crate::needs::sleep::BedComponent;
+use crate::{ContainerComponent, TransformComponent};
+
+/// Goes to sleep in an owned or communal bed
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SleepInBedDse;
+
+/// Lies down and sleeps on the spot
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SleepInPlaceDse;
+
+const BED_MAX_RADIUS: f32 = 50.0;
+
+impl Dse<AiContext> for SleepInBedDse {
+    fn considerations(&self, out: &mut Considerations<AiContext>) {
+        out.add(EnergyConsideration);
+        out.add(NightConsideration);
+        out.add(MyProximityToTargetConsideration);
+    }
+
+    fn weight(&self) -> DecisionWeight {
+        DecisionWeight::BasicNeeds
+    }
+
+    fn target(
+        &self,
+        targets: &mut Targets<AiContext>,
+        blackboard: &mut AiBlackboard,
+    ) -> TargetOutput {
+        let entities = blackboard.world.read_resource::<EntitiesRes>();
+        let beds = blackboard.world.read_component::<BedComponent>();
+        let containers = blackboard.world.read_component::<ContainerComponent>();
+        let transforms = blackboard.world.read_component::<TransformComponent>();
+
+        // beds share ownership with their container
+        let my_pos = blackboard.transform.position;
+        for (bed_entity, _, container, transform) in
+            (&entities, &beds, &containers, &transforms).join()
+        {
+            if container.can_be_used_by(blackboard.entity, blackboard.society)
+                && transform.position.distance2(my_pos) < BED_MAX_RADIUS * BED_MAX_RADIUS
+            {
+                targets.add(AiTarget::Entity(bed_entity.into()));
+            }
+        }
+
+        TargetOutput::TargetsCollected
+    }
+
+    fn action(&self, _: &mut AiBlackboard, target: Option<AiTarget>) -> AiAction {
+        match target.and_then(|t| t.entity()) {
+            Some(bed) => AiAction::Sleep(Some(bed)),
+            None => {
+                error!("sleep target is not a bed entity"; "target" => ?target);
+                AiAction::Nop
+            }
+        }
+    }
+}
+
+impl Dse<AiContext> for SleepInPlaceDse {
+    fn considerations(&self, out: &mut Considerations<AiContext>) {
+        out.add(EnergyConsideration);
+        out.add(NightConsideration);
+        // the ground is less appealing than a bed
+        out.add(ConstantConsideration(0.8));
+    }
+
+    fn weight(&self) -> DecisionWeight {
+        DecisionWeight::Normal
+    }
+
+    fn action(&self, _: &mut AiBlackboard, _: Option<AiTarget>) -> AiAction {
+        AiAction::Sleep(None)
+    }
+}
@@ -0,0 +1,33 @@
+use crate::ai::consideration::MyProximityToConsideration;
+
+use crate::ai::{AiAction, AiBlackboard, AiContext, AiTarget};
+
+use crate::job::{CraftDetails, SocietyJobHandle};
+
+use ai::{Considerations, DecisionWeight, Dse};
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct CraftDse {
+    pub job: SocietyJobHandle,
+    pub details: CraftDetails,
+}
+
+impl Dse<AiContext> for CraftDse {
+    fn considerations(&self, out: &mut Considerations<AiContext>) {
+        // TODO wants to work, can work
+        out.add(MyProximityToConsideration(AiTarget::Block(
+            self.details.pos,
+        )));
+    }
+
+    fn weight(&self) -> DecisionWeight {
+        DecisionWeight::Normal
+    }
+
+    fn action(&self, _: &mut AiBlackboard, _: Option<AiTarget>) -> AiAction {
+        AiAction::GoCraft {
+            job: self.job,
+            details: self.details.clone(),
+        }
+    }
+}
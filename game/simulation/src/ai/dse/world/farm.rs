@@ -0,0 +1,48 @@
+use ai::{Considerations, DecisionWeight, Dse};
+use unit::world::WorldPosition;
+
+use crate::ai::consideration::MyProximityToConsideration;
+use crate::ai::{AiAction, AiBlackboard, AiContext, AiTarget};
+use crate::ecs::Entity;
+use crate::string::CachedStr;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SowDse {
+    pub soil: WorldPosition,
+    pub crop: CachedStr,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct HarvestDse(pub Entity);
+
+impl Dse<AiContext> for SowDse {
+    fn considerations(&self, out: &mut Considerations<AiContext>) {
+        // TODO has seeds
+        out.add(MyProximityToConsideration(AiTarget::Block(self.soil)));
+    }
+
+    fn weight(&self) -> DecisionWeight {
+        DecisionWeight::Normal
+    }
+
+    fn action(&self, _: &mut AiBlackboard, _: Option<AiTarget>) -> AiAction {
+        AiAction::GoSow {
+            soil: self.soil,
+            crop: self.crop,
+        }
+    }
+}
+
+impl Dse<AiContext> for HarvestDse {
+    fn considerations(&self, out: &mut Considerations<AiContext>) {
+        out.add(MyProximityToConsideration(AiTarget::Entity(self.0)));
+    }
+
+    fn weight(&self) -> DecisionWeight {
+        DecisionWeight::Normal
+    }
+
+    fn action(&self, _: &mut AiBlackboard, _: Option<AiTarget>) -> AiAction {
+        AiAction::GoHarvest(self.0)
+    }
+}
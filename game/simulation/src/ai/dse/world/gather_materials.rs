@@ -17,7 +17,7 @@ use unit::world::WorldPosition;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct GatherMaterialsDse {
-    pub build_pos: WorldPosition,
+    pub target: WorldPosition,
     pub material: BuildMaterial,
     pub job: SocietyJobHandle,
     pub extra_hands_for_haul: u16,
@@ -36,8 +36,9 @@ impl Dse<AiContext> for GatherMaterialsDse {
             target: None, // target entity instead
         });
         out.add(MyProximityToTargetConsideration); // distance to material
-        out.add(MyProximityToConsideration(AiTarget::Block(self.build_pos))); // distance to build
-                                                                              // TODO consider item stack size and condition
+        out.add(MyProximityToConsideration(AiTarget::Block(self.target))); // distance to job
+
+        // TODO consider item stack size and condition
     }
 
     fn weight(&self) -> DecisionWeight {
@@ -82,7 +83,7 @@ impl Dse<AiContext> for GatherMaterialsDse {
         AiAction::Haul(
             item,
             src,
-            HaulTarget::Drop(self.build_pos.centred()),
+            HaulTarget::Drop(self.target.centred()),
             HaulPurpose::MaterialGathering(self.job),
         )
     }
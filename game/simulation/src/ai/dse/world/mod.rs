@@ -1,7 +1,11 @@
 pub use break_block::BreakBlockDse;
 pub use build::BuildDse;
+pub use craft::CraftDse;
+pub use farm::{HarvestDse, SowDse};
 pub use gather_materials::GatherMaterialsDse;
 
 mod break_block;
 mod build;
+mod craft;
+mod farm;
 mod gather_materials;
@@ -11,16 +11,31 @@ use crate::interact::herd::HerdInfo;
 use crate::item::{
     FoundSlot, HaulableItemComponent, HauledItemComponent, InventoryComponent, ItemFilter,
 };
-use crate::{ContainedInComponent, EdibleItemComponent, HungerComponent, TransformComponent};
+use crate::{
+    Calendar, ConditionComponent, ContainedInComponent, EdibleItemComponent, EnergyComponent,
+    HungerComponent, ThirstComponent, TransformComponent,
+};
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub enum AiInput {
     /// Hunger level, 0=starving 1=completely full
     Hunger,
 
+    /// Thirst level, 0=dehydrated 1=completely hydrated
+    Thirst,
+
+    /// Energy level, 0=exhausted 1=fully rested
+    Energy,
+
+    /// Time of day, 0=broad daylight 1=dead of night
+    Darkness,
+
     /// Interest in target food flavours, 0=hates or doesn't eat, 1=absolutely loves
     FoodInterestInTarget,
 
+    /// Condition of the target item, 0=broken 1=perfect. 1 if not applicable
+    TargetCondition,
+
     /// Switch, 1=has at least 1 matching filter, 0=none
     HasInInventory(ItemFilter),
 
@@ -36,13 +51,6 @@ pub enum AiInput {
 
     // TODO HasInInventoryGraded - returns number,quality of matches
     // TODO should include check for n free slots anywhere in inventory (not just hands)
-    /// Does not look in inventory
-    CanFindGradedItemsLocally {
-        filter: ItemFilter,
-        max_radius: u32,
-        max_count: u32,
-    },
-
     Constant(OrderedFloat<f32>),
 
     /// Distance squared to given target, -INF on error
@@ -72,6 +80,9 @@ impl ai::Input<AiContext> for AiInput {
         use AiInput::*;
         match self {
             Hunger => hunger(blackboard),
+            Thirst => thirst(blackboard),
+            Energy => energy(blackboard),
+            Darkness => 1.0 - blackboard.world.resource::<Calendar>().daylight().value(),
             FoodInterestInTarget => food_interest_in_target(blackboard, target).unwrap_or(0.0),
             HasInInventory(filter) => has_in_inventory(blackboard, filter).unwrap_or(0.0),
             HasExtraHandsForHauling(hands, item) => {
@@ -81,7 +92,7 @@ impl ai::Input<AiContext> for AiInput {
                 has_free_hands_to_hold_target(blackboard, target).unwrap_or(0.0)
             }
             CanUseHeldItem(filter) => can_use_held_item(blackboard, filter).unwrap_or(0.0),
-            CanFindGradedItemsLocally { .. } => todo!(), // TODO remove this
+            TargetCondition => target_condition(blackboard, target).unwrap_or(1.0),
             Constant(f) => f.0,
             MyDistance2To(tgt) => distance_to_target(blackboard, tgt).unwrap_or(f32::NEG_INFINITY),
             MyDistance2ToHerd => find_herd_target(blackboard)
@@ -107,6 +118,26 @@ fn hunger(blackboard: &mut AiBlackboard) -> f32 {
     }
 }
 
+fn thirst(blackboard: &mut AiBlackboard) -> f32 {
+    match blackboard
+        .world
+        .component::<ThirstComponent>(blackboard.entity)
+    {
+        Ok(comp) => comp.thirst().hydration().value(),
+        Err(_) => 1.0, // not thirsty if not applicable
+    }
+}
+
+fn energy(blackboard: &mut AiBlackboard) -> f32 {
+    match blackboard
+        .world
+        .component::<EnergyComponent>(blackboard.entity)
+    {
+        Ok(comp) => comp.energy().value(),
+        Err(_) => 1.0, // not tired if not applicable
+    }
+}
+
 fn food_interest_in_target(
     blackboard: &mut AiBlackboard,
     target: Option<&AiTarget>,
@@ -129,6 +160,15 @@ fn food_interest_in_target(
         .map(|f| f.value())
 }
 
+fn target_condition(blackboard: &mut AiBlackboard, target: Option<&AiTarget>) -> Option<f32> {
+    let target = target.and_then(|t| t.entity())?;
+    blackboard
+        .world
+        .component::<ConditionComponent>(target)
+        .ok()
+        .map(|condition| condition.0.value().value())
+}
+
 fn has_in_inventory(blackboard: &mut AiBlackboard, filter: &ItemFilter) -> Option<f32> {
     let inventory = blackboard.inventory?;
     let _found = search_inventory_with_cache(blackboard, inventory, filter)?;
@@ -291,17 +331,12 @@ impl Display for AiInput {
         use AiInput::*;
         match self {
             Hunger => f.write_str("Hunger"),
+            Thirst => f.write_str("Thirst"),
+            Energy => f.write_str("Energy"),
+            Darkness => f.write_str("Darkness"),
             FoodInterestInTarget => write!(f, "Interest in target food flavours"),
+            TargetCondition => f.write_str("Target item condition"),
             HasInInventory(filter) => write!(f, "Has an item matching {}", filter),
-            CanFindGradedItemsLocally {
-                filter,
-                max_radius,
-                max_count,
-            } => write!(
-                f,
-                "Can find max {} items in {} radius if {}",
-                max_count, max_radius, filter
-            ),
             Constant(c) => write!(f, "Constant {:?}", c.0),
 
             MyDistance2To(pos) => write!(f, "Distance to {}", pos),
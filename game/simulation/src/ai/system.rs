@@ -19,7 +19,7 @@ use crate::item::InventoryComponent;
 use crate::job::JobIndex;
 use crate::simulation::{EcsWorldRef, Tick};
 use crate::society::job::SocietyTask;
-use crate::society::{Society, SocietyComponent};
+use crate::society::{Society, SocietyComponent, WorkPreferencesComponent};
 use crate::string::StringCache;
 use crate::{dse, Societies};
 use crate::{EntityLoggingComponent, TransformComponent};
@@ -177,6 +177,9 @@ impl<'a> System<'a> for UpdateSocietyJobs {
             log_scope!(o!("society" => society.handle()));
             let mut jobs = society.jobs_mut();
             jobs.refresh_jobs(&ecs_world);
+
+            // generate hauls after finished jobs have been removed
+            society.generate_haul_jobs(&ecs_world, &mut jobs);
         }
     }
 }
@@ -533,9 +536,20 @@ fn collect_society_tasks(
 
         // TODO collect jobs from society directly, which can filter them from the applicable work items too
         let jobs = society.jobs();
+        let preferences = ecs_world.component::<WorkPreferencesComponent>(entity).ok();
         let mut n = 0usize;
-        jobs.filter_applicable_tasks(entity, |task, job_idx, reservation| {
-            match task.as_dse(ecs_world, reservation) {
+        jobs.filter_applicable_tasks(entity, |task, job_idx, reservation, priority| {
+            let preference = preferences
+                .as_ref()
+                .map(|prefs| prefs.weight(task.category()))
+                .unwrap_or(1.0);
+
+            if preference <= 0.0 {
+                // category disabled for this entity
+                return;
+            }
+
+            match task.as_dse(ecs_world, reservation, priority.weight() * preference) {
                 Some(dse) => {
                     add_dse(task, job_idx, dse);
                     n += 1;
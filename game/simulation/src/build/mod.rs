@@ -3,4 +3,5 @@ mod template;
 mod world_helper;
 
 pub use material::{BuildMaterial, ConsumedMaterialForJobComponent, ReservedMaterialComponent};
+pub(crate) use template::parse_materials;
 pub use template::BuildTemplate;
@@ -17,6 +17,8 @@ pub struct BuildTemplate {
     output: BlockType,
     /// For walls
     supports_outline: bool,
+    /// Replaces the selected floor block rather than building in the air above it, e.g. roads
+    replaces_floor: bool,
 }
 
 impl BuildTemplate {
@@ -37,6 +39,10 @@ impl BuildTemplate {
         self.supports_outline
     }
 
+    pub const fn replaces_floor(&self) -> bool {
+        self.replaces_floor
+    }
+
     #[cfg(any(test, feature = "testing"))]
     pub fn new(materials: Vec<BuildMaterial>, steps: u32, rate: u32, output: BlockType) -> Self {
         Self {
@@ -45,10 +51,35 @@ impl BuildTemplate {
             rate,
             output,
             supports_outline: false,
+            replaces_floor: false,
         }
     }
 }
 
+/// Parses a list of (definition, count) materials, e.g. `[("core_brick_stone", 6)]`
+pub(crate) fn parse_materials<V: Value>(
+    values: &mut Map<V>,
+    key: &str,
+    string_cache: &StringCache,
+) -> Result<Vec<BuildMaterial>, ComponentBuildError> {
+    #[derive(Deserialize, Debug)]
+    struct Material(String, u16);
+
+    let raw: Vec<Material> = values.get(key).and_then(|val| val.into_type())?;
+    let mut materials = Vec::with_capacity(raw.len());
+    for mat in raw {
+        let n = NonZeroU16::new(mat.1).ok_or_else(|| {
+            ComponentBuildError::TemplateSpecific(format!(
+                "material count for {:?} cannot be zero",
+                mat.0
+            ))
+        })?;
+        materials.push(BuildMaterial::new(string_cache.get(&mat.0), n))
+    }
+
+    Ok(materials)
+}
+
 impl<V: Value> ComponentTemplate<V> for BuildTemplate {
     fn construct(
         values: &mut Map<V>,
@@ -57,24 +88,7 @@ impl<V: Value> ComponentTemplate<V> for BuildTemplate {
     where
         Self: Sized,
     {
-        #[derive(Deserialize, Debug)]
-        struct Material(String, u16);
-
-        let materials = {
-            let raw: Vec<Material> = values.get("materials").and_then(|val| val.into_type())?;
-            let mut materials = Vec::with_capacity(raw.len());
-            for mat in raw {
-                let n = NonZeroU16::new(mat.1).ok_or_else(|| {
-                    ComponentBuildError::TemplateSpecific(format!(
-                        "material count for {:?} cannot be zero",
-                        mat.0
-                    ))
-                })?;
-                materials.push(BuildMaterial::new(string_cache.get(&mat.0), n))
-            }
-            materials
-        };
-
+        let materials = parse_materials(values, "materials", string_cache)?;
         let steps = values.get_int("steps")?;
         let rate = values.get_int("rate")?;
         let output = {
@@ -88,6 +102,11 @@ impl<V: Value> ComponentTemplate<V> for BuildTemplate {
             Err(ComponentBuildError::KeyNotFound(_)) => false, // default if not present
             Err(err) => return Err(err),
         };
+        let replaces_floor = match values.get_bool("floor") {
+            Ok(b) => b,
+            Err(ComponentBuildError::KeyNotFound(_)) => false, // default if not present
+            Err(err) => return Err(err),
+        };
 
         Ok(Rc::new(BuildTemplate {
             materials,
@@ -95,6 +114,7 @@ impl<V: Value> ComponentTemplate<V> for BuildTemplate {
             rate,
             output,
             supports_outline,
+            replaces_floor,
         }))
     }
 
@@ -99,7 +99,14 @@ impl BuildHelper {
             world.block(self.details.pos).map(|b| b.block_type())
         };
 
-        if !matches!(prev_block, Some(BlockType::Air)) {
+        // floors are replaced, everything else is built in the air
+        let expected = if self.details.replaces_floor {
+            prev_block.map_or(false, |bt| bt.can_be_walked_on())
+        } else {
+            matches!(prev_block, Some(BlockType::Air))
+        };
+
+        if !expected {
             warn!("unexpected block type when finishing build"; "block" => %self.details.pos, "current" => ?prev_block);
             return Err(());
         }
@@ -127,3 +134,51 @@ impl BuildHelper {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use world::ChunkBuilder;
+
+    use super::*;
+    use crate::WorldContext;
+
+    fn setup() -> EcsWorld {
+        let voxel_world = world::helpers::context_world_from_chunks_blocking::<WorldContext>(vec![
+            ChunkBuilder::new()
+                .fill_slice(0, BlockType::Stone)
+                .build((0, 0)),
+        ]);
+
+        let mut world = EcsWorld::new();
+        world.insert(voxel_world);
+        world.insert(TerrainUpdatesRes::default());
+        world.insert(QueuedUpdates::default());
+        world
+    }
+
+    fn complete(world: &EcsWorld, pos: (i32, i32, i32), replaces_floor: bool) -> Result<(), ()> {
+        let helper = BuildHelper {
+            details: BuildDetails {
+                pos: pos.into(),
+                target: BlockType::Stone,
+                replaces_floor,
+            },
+            reserved_materials: Arc::new(Vec::new()),
+        };
+        helper.complete_build(world)
+    }
+
+    #[test]
+    fn floors_replace_the_floor_block() {
+        let world = setup();
+
+        // can't build a wall into the floor, or a floor in the air
+        assert!(complete(&world, (2, 2, 0), false).is_err());
+        assert!(complete(&world, (2, 2, 1), true).is_err());
+        assert!(world.resource::<TerrainUpdatesRes>().is_empty());
+
+        assert!(complete(&world, (2, 2, 0), true).is_ok());
+        assert!(complete(&world, (3, 2, 1), false).is_ok());
+        assert_eq!(world.resource::<TerrainUpdatesRes>().len(), 2);
+    }
+}
@@ -0,0 +1,180 @@
+use std::f32::consts::PI;
+
+use common::derive_more::Display;
+use common::*;
+
+use crate::Tick;
+
+/// In-game calendar, derived from the current tick so it survives save/load without being saved
+/// itself. Updated at the start of every tick
+pub struct Calendar {
+    /// Ticks in a full day and night
+    day_length: u32,
+    days_per_season: u32,
+
+    /// Ticks since midnight of the first day that the game starts at
+    start_offset: u32,
+
+    /// Ticks since midnight of the first day
+    elapsed: u32,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Display)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+/// Ambient light level at midnight
+const MIN_LIGHT_LEVEL: f32 = 0.25;
+
+/// Width of dawn and dusk in terms of the sun's height, where 0 is the horizon and 1 is noon
+const TWILIGHT: f32 = 0.2;
+
+impl Calendar {
+    /// Panics if day length or days per season are 0
+    pub fn new(day_length: u32, days_per_season: u32, start_time_of_day: NormalizedFloat) -> Self {
+        assert!(day_length > 0, "day length must be > 0");
+        assert!(days_per_season > 0, "days per season must be > 0");
+
+        let start_offset = (start_time_of_day.value() * day_length as f32) as u32;
+        Self {
+            day_length,
+            days_per_season,
+            start_offset,
+            elapsed: start_offset,
+        }
+    }
+
+    pub fn update(&mut self, tick: Tick) {
+        self.elapsed = self.start_offset + tick.value();
+    }
+
+    /// 0=midnight, 0.5=noon
+    pub fn time_of_day(&self) -> NormalizedFloat {
+        NormalizedFloat::clamped((self.elapsed % self.day_length) as f32 / self.day_length as f32)
+    }
+
+    /// 24 hour clock time
+    pub fn hours_minutes(&self) -> (u8, u8) {
+        let minutes = (self.time_of_day().value() * 24.0 * 60.0) as u32;
+        ((minutes / 60) as u8, (minutes % 60) as u8)
+    }
+
+    /// Days since the game started, starting from 0
+    pub fn day(&self) -> u32 {
+        self.elapsed / self.day_length
+    }
+
+    /// Day within the current season, starting from 0
+    pub fn day_of_season(&self) -> u32 {
+        self.day() % self.days_per_season
+    }
+
+    pub fn season(&self) -> Season {
+        match (self.day() / self.days_per_season) % 4 {
+            0 => Season::Spring,
+            1 => Season::Summer,
+            2 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+
+    /// Years since the game started, starting from 0
+    pub fn year(&self) -> u32 {
+        self.day() / (self.days_per_season * 4)
+    }
+
+    /// 0=fully dark, 1=broad daylight, with a short dawn and dusk around 06:00 and 18:00
+    pub fn daylight(&self) -> NormalizedFloat {
+        // -1 at midnight, 1 at noon
+        let sun_height = -(self.time_of_day().value() * 2.0 * PI).cos();
+        NormalizedFloat::clamped((sun_height + TWILIGHT) / (2.0 * TWILIGHT))
+    }
+
+    /// Ambient light level for rendering, never fully dark
+    pub fn light_level(&self) -> NormalizedFloat {
+        NormalizedFloat::new(MIN_LIGHT_LEVEL + (1.0 - MIN_LIGHT_LEVEL) * self.daylight().value())
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.daylight().value() < 0.5
+    }
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        let config = &config::get().simulation;
+        Self::new(
+            config.day_length_ticks,
+            config.days_per_season,
+            NormalizedFloat::clamped(config.start_time_of_day),
+        )
+    }
+}
+
+impl Display for Calendar {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (hours, minutes) = self.hours_minutes();
+        write!(
+            f,
+            "{:02}:{:02}, day {} of {}, year {}",
+            hours,
+            minutes,
+            self.day_of_season() + 1,
+            self.season(),
+            self.year() + 1
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calendar_at(tick: u32) -> Calendar {
+        let mut calendar = Calendar::new(1000, 2, NormalizedFloat::zero());
+        calendar.update(Tick::with(tick));
+        calendar
+    }
+
+    #[test]
+    fn time_of_day() {
+        let cal = calendar_at(0);
+        assert_eq!(cal.hours_minutes(), (0, 0));
+        assert!(cal.is_night());
+        assert!(cal.light_level().value() <= MIN_LIGHT_LEVEL + 0.001);
+
+        let cal = calendar_at(500);
+        assert_eq!(cal.hours_minutes(), (12, 0));
+        assert!(!cal.is_night());
+        assert!(cal.light_level().value() >= 0.999);
+
+        let cal = calendar_at(1250);
+        assert_eq!(cal.day(), 1);
+        assert_eq!(cal.hours_minutes(), (6, 0));
+    }
+
+    #[test]
+    fn seasons_wrap() {
+        assert_eq!(calendar_at(0).season(), Season::Spring);
+        assert_eq!(calendar_at(1999).season(), Season::Spring);
+        assert_eq!(calendar_at(2000).season(), Season::Summer);
+        assert_eq!(calendar_at(7999).season(), Season::Winter);
+
+        let cal = calendar_at(8000);
+        assert_eq!(cal.season(), Season::Spring);
+        assert_eq!(cal.year(), 1);
+        assert_eq!(cal.day_of_season(), 0);
+    }
+
+    #[test]
+    fn start_offset() {
+        let mut cal = Calendar::new(1000, 2, NormalizedFloat::new(0.5));
+        cal.update(Tick::with(0));
+        assert_eq!(cal.hours_minutes(), (12, 0));
+        assert_eq!(cal.day(), 0);
+    }
+}
@@ -0,0 +1,141 @@
+use std::rc::Rc;
+
+use common::*;
+
+use crate::ecs::*;
+use crate::simulation::Tick;
+use crate::species::Species;
+use crate::StringCache;
+
+/// Distance a weapon can strike from if not specified
+const DEFAULT_REACH: f32 = 1.5;
+
+/// Damage dealt by striking with this, either as an equipped item or as a natural weapon of a
+/// living entity e.g. teeth or fists
+#[derive(Component, EcsComponent, Clone, Debug)]
+#[storage(DenseVecStorage)]
+#[name("weapon")]
+#[interactive]
+#[clone(disallow)]
+pub struct WeaponComponent {
+    /// Health lost immediately on a strike
+    pub damage: f32,
+
+    /// Bleeding per tick inflicted on a strike
+    pub bleeding: f32,
+
+    /// Ticks to recover between strikes
+    pub cooldown: u32,
+
+    /// Max distance to the target to be able to strike
+    pub reach: f32,
+}
+
+/// Hunts entities of other species
+#[derive(Component, EcsComponent, Clone, Debug)]
+#[storage(DenseVecStorage)]
+#[name("hunter")]
+#[clone(disallow)]
+pub struct HunterComponent {
+    prey: Vec<Species>,
+}
+
+/// The entity was recently attacked by another
+#[derive(Component, EcsComponent, Clone, Debug)]
+#[storage(HashMapStorage)]
+#[name("attacked")]
+#[clone(disallow)]
+pub struct AttackedComponent {
+    pub attacker: Entity,
+    pub when: Tick,
+}
+
+impl HunterComponent {
+    pub fn hunts(&self, species: Species) -> bool {
+        self.prey.contains(&species)
+    }
+}
+
+impl<V: Value> ComponentTemplate<V> for WeaponComponent {
+    fn construct(
+        values: &mut Map<V>,
+        _: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let damage: f32 = values.get_float("damage")?;
+        let cooldown = values.get_int("cooldown")?;
+        let bleeding: f32 = match values.get_float("bleeding") {
+            Ok(f) => f,
+            Err(ComponentBuildError::KeyNotFound(_)) => 0.0,
+            Err(e) => return Err(e),
+        };
+        let reach = match values.get_float("reach") {
+            Ok(f) => f,
+            Err(ComponentBuildError::KeyNotFound(_)) => DEFAULT_REACH,
+            Err(e) => return Err(e),
+        };
+
+        if damage < 0.0 || bleeding < 0.0 {
+            return Err(ComponentBuildError::TemplateSpecific(format!(
+                "weapon damage and bleeding should not be negative but are {} and {}",
+                damage, bleeding
+            )));
+        }
+
+        Ok(Rc::new(Self {
+            damage,
+            bleeding,
+            cooldown,
+            reach,
+        }))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
+impl<V: Value> ComponentTemplate<V> for HunterComponent {
+    fn construct(
+        values: &mut Map<V>,
+        string_cache: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let prey = values
+            .get_string("prey")?
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| Species::new(s, string_cache))
+            .collect_vec();
+
+        if prey.is_empty() {
+            return Err(ComponentBuildError::TemplateSpecific(
+                "hunter has no prey".to_owned(),
+            ));
+        }
+
+        Ok(Rc::new(Self { prey }))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
+impl InteractiveComponent for WeaponComponent {
+    fn as_debug(&self) -> Option<&dyn Debug> {
+        Some(self)
+    }
+}
+
+register_component_template!("weapon", WeaponComponent);
+register_component_template!("hunter", HunterComponent);
@@ -0,0 +1,5 @@
+mod component;
+mod strike;
+
+pub use component::{AttackedComponent, HunterComponent, WeaponComponent};
+pub use strike::{choose_weapon, strike, StrikeError};
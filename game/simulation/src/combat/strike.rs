@@ -0,0 +1,173 @@
+use common::*;
+
+use crate::combat::{AttackedComponent, WeaponComponent};
+use crate::ecs::*;
+use crate::event::DeathReason;
+use crate::item::InventoryComponent;
+use crate::senses::{NoiseKind, Noises};
+use crate::simulation::Tick;
+use crate::{HealthComponent, TransformComponent};
+
+#[derive(Debug, Error, Clone)]
+pub enum StrikeError {
+    #[error("Target has no health and cannot be harmed")]
+    Invulnerable,
+
+    #[error("Target is already dead")]
+    AlreadyDead,
+}
+
+/// The first equipped item that is a weapon, otherwise the attacker's natural weapon if any.
+/// Returns (weapon entity, weapon)
+pub fn choose_weapon(world: &EcsWorld, attacker: Entity) -> Option<(Entity, WeaponComponent)> {
+    let weapons = world.read_storage::<WeaponComponent>();
+    let equipped = world
+        .component::<InventoryComponent>(attacker)
+        .ok()
+        .and_then(|inv| inv.all_equipped_items().find(|item| item.has(&weapons)));
+
+    let weapon = equipped.unwrap_or(attacker);
+    weapon.get(&weapons).map(|comp| (weapon, comp.clone()))
+}
+
+/// Injures the target with the given weapon. The target is killed by the health system if this
+/// takes all of its health
+pub fn strike(
+    world: &EcsWorld,
+    attacker: Entity,
+    target: Entity,
+    weapon: &WeaponComponent,
+) -> Result<(), StrikeError> {
+    {
+        let mut health = world
+            .component_mut::<HealthComponent>(target)
+            .map_err(|_| StrikeError::Invulnerable)?;
+
+        if health.is_dead() {
+            return Err(StrikeError::AlreadyDead);
+        }
+
+        health.injure(
+            weapon.damage,
+            weapon.bleeding,
+            DeathReason::Killed(attacker),
+        );
+        debug!("struck target"; attacker, "target" => target, "damage" => weapon.damage,
+            "health" => ?health.health());
+    }
+
+    let _ = world.add_now(
+        target,
+        AttackedComponent {
+            attacker,
+            when: Tick::fetch(),
+        },
+    );
+
+    if let Ok(transform) = world.component::<TransformComponent>(target) {
+        world
+            .resource_mut::<Noises>()
+            .emit(attacker, transform.position, NoiseKind::Fight);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use unit::space::length::Length3;
+    use unit::space::volume::Volume;
+    use unit::world::WorldPoint;
+
+    use super::*;
+
+    fn weapon(damage: f32) -> WeaponComponent {
+        WeaponComponent {
+            damage,
+            bleeding: 0.5,
+            cooldown: 10,
+            reach: 1.0,
+        }
+    }
+
+    #[test]
+    fn choose_equipped_weapon_over_natural() {
+        let world = EcsWorld::new();
+        let attacker: Entity = world
+            .create_entity()
+            .with(weapon(1.0))
+            .with(InventoryComponent::new(2))
+            .build()
+            .into();
+        let sword: Entity = world.create_entity().with(weapon(5.0)).build().into();
+        let rock: Entity = world.create_entity().build().into();
+
+        // natural weapon with nothing equipped
+        let (chosen, _) = choose_weapon(&world, attacker).expect("should have natural weapon");
+        assert_eq!(chosen, attacker);
+
+        // non-weapons are ignored
+        let equip = |item| {
+            let mut inv = world.component_mut::<InventoryComponent>(attacker).unwrap();
+            let size = Length3::new(1, 1, 1);
+            assert!(inv.insert_item(&world, item, 0, Volume::new(1), size, |_, _| {}));
+        };
+        equip(rock);
+        let (chosen, _) = choose_weapon(&world, attacker).expect("should have natural weapon");
+        assert_eq!(chosen, attacker);
+
+        equip(sword);
+        let (chosen, comp) = choose_weapon(&world, attacker).expect("should have sword");
+        assert_eq!(chosen, sword);
+        assert!((comp.damage - 5.0).abs() < 0.0001);
+
+        // no weapon at all
+        let unarmed: Entity = world.create_entity().build().into();
+        assert!(choose_weapon(&world, unarmed).is_none());
+    }
+
+    #[test]
+    fn strike_injures_and_alerts() {
+        let mut world = EcsWorld::new();
+        world.insert(Noises::default());
+
+        let attacker: Entity = world.create_entity().build().into();
+        let target: Entity = world
+            .create_entity()
+            .with(HealthComponent::new(10.0, 100))
+            .with(TransformComponent::new(
+                WorldPoint::new(5.0, 5.0, 1.0).unwrap(),
+            ))
+            .build()
+            .into();
+        let invulnerable: Entity = world.create_entity().build().into();
+
+        strike(&world, attacker, target, &weapon(4.0)).expect("strike should succeed");
+        {
+            let health = world.component::<HealthComponent>(target).unwrap();
+            assert!((health.health().value() - 0.6).abs() < 0.0001);
+            assert!((health.bleeding() - 0.5).abs() < 0.0001);
+            assert!(matches!(health.cause_of_death(), DeathReason::Killed(e) if e == attacker));
+        }
+
+        let attacked = world.component::<AttackedComponent>(target).unwrap();
+        assert_eq!(attacked.attacker, attacker);
+
+        let noises = world.resource::<Noises>();
+        assert!(noises
+            .iter()
+            .any(|n| n.source == attacker && n.kind == NoiseKind::Fight));
+
+        assert!(matches!(
+            strike(&world, attacker, invulnerable, &weapon(4.0)),
+            Err(StrikeError::Invulnerable)
+        ));
+
+        // finish it off, then it can't be struck again
+        strike(&world, attacker, target, &weapon(100.0)).expect("strike should succeed");
+        assert!(matches!(
+            strike(&world, attacker, target, &weapon(4.0)),
+            Err(StrikeError::AlreadyDead)
+        ));
+    }
+}
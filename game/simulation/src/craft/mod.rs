@@ -0,0 +1,5 @@
+mod recipe;
+mod workshop;
+
+pub use recipe::CraftRecipe;
+pub use workshop::WorkshopComponent;
@@ -0,0 +1,146 @@
+use std::num::NonZeroU16;
+use std::rc::Rc;
+
+use crate::build::parse_materials;
+use crate::ecs::*;
+use crate::string::{CachedStr, StringCache};
+use crate::BuildMaterial;
+
+#[derive(Debug)]
+pub struct CraftRecipe {
+    inputs: Vec<BuildMaterial>,
+    /// Definition to spawn on completion
+    output: CachedStr,
+    output_count: NonZeroU16,
+    steps: u32,
+    rate: u32,
+    /// Definition of the workshop entity this must be crafted at
+    workshop: CachedStr,
+}
+
+impl CraftRecipe {
+    pub fn inputs(&self) -> &[BuildMaterial] {
+        &self.inputs
+    }
+
+    /// (output definition, count)
+    pub fn output(&self) -> (CachedStr, NonZeroU16) {
+        (self.output, self.output_count)
+    }
+
+    /// (number of steps required, ticks to sleep between each step)
+    pub const fn progression(&self) -> (u32, u32) {
+        (self.steps, self.rate)
+    }
+
+    pub fn workshop(&self) -> CachedStr {
+        self.workshop
+    }
+}
+
+impl<V: Value> ComponentTemplate<V> for CraftRecipe {
+    fn construct(
+        values: &mut Map<V>,
+        string_cache: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let inputs = parse_materials(values, "inputs", string_cache)?;
+        let output = string_cache.get(&values.get_string("output")?);
+        let output_count = match values.get_int::<u16>("count") {
+            Ok(n) => NonZeroU16::new(n).ok_or_else(|| {
+                ComponentBuildError::TemplateSpecific("output count cannot be zero".to_owned())
+            })?,
+            Err(ComponentBuildError::KeyNotFound(_)) => NonZeroU16::new(1).unwrap(),
+            Err(err) => return Err(err),
+        };
+        let steps = values.get_int("steps")?;
+        let rate = values.get_int("rate")?;
+        let workshop = string_cache.get(&values.get_string("workshop")?);
+
+        Ok(Rc::new(CraftRecipe {
+            inputs,
+            output,
+            output_count,
+            steps,
+            rate,
+            workshop,
+        }))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder
+    }
+
+    crate::as_any!();
+}
+
+register_component_template!("recipe", CraftRecipe);
+
+#[cfg(test)]
+mod tests {
+    use common::*;
+
+    use super::*;
+
+    fn load_recipe(recipe: &str) -> Result<Rc<CraftRecipe>, String> {
+        let input = format!(
+            r#"[ (uid: "test_recipe", category: "recipes", components: [ {{"recipe": {}}} ]) ]"#,
+            recipe
+        );
+
+        let definitions = crate::definitions::load_from_str(&input).map_err(|e| e.to_string())?;
+        let recipe = definitions
+            .lookup_definition(CachedStr::from("test_recipe"))
+            .and_then(|def| def.find_component_ref::<CraftRecipe>("recipe"))
+            .expect("recipe not found");
+        Ok(recipe)
+    }
+
+    #[test]
+    fn parse_recipe() {
+        let recipe = load_recipe(
+            r#"(
+                inputs: [("test_log", 2), ("test_nail", 4)],
+                output: "test_plank",
+                steps: 6,
+                rate: 4,
+                workshop: "test_workshop",
+            )"#,
+        )
+        .expect("recipe should be valid");
+
+        let inputs = recipe
+            .inputs()
+            .iter()
+            .map(|mat| (mat.definition().as_ref().to_owned(), mat.quantity().get()))
+            .collect_vec();
+        assert_eq!(
+            inputs,
+            vec![("test_log".to_owned(), 2), ("test_nail".to_owned(), 4)]
+        );
+
+        // count defaults to 1
+        let (output, count) = recipe.output();
+        assert_eq!((output.as_ref(), count.get()), ("test_plank", 1));
+        assert_eq!(recipe.progression(), (6, 4));
+        assert_eq!(recipe.workshop().as_ref(), "test_workshop");
+    }
+
+    #[test]
+    fn zero_output_is_invalid() {
+        let result = load_recipe(
+            r#"(
+                inputs: [("test_log", 2)],
+                output: "test_plank",
+                count: 0,
+                steps: 6,
+                rate: 4,
+                workshop: "test_workshop",
+            )"#,
+        );
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,28 @@
+use std::rc::Rc;
+
+use crate::ecs::*;
+use crate::string::StringCache;
+
+/// An entity that crafting recipes can be worked on at. Recipes specify which workshop definition
+/// they require
+#[derive(Component, EcsComponent, Default, Debug, Clone)]
+#[storage(NullStorage)]
+#[name("workshop")]
+pub struct WorkshopComponent;
+
+impl<V: Value> ComponentTemplate<V> for WorkshopComponent {
+    fn construct(
+        _: &mut Map<V>,
+        _: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError> {
+        Ok(Rc::new(Self))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(WorkshopComponent)
+    }
+
+    crate::as_any!();
+}
+
+register_component_template!("workshop", WorkshopComponent);
@@ -0,0 +1,83 @@
+use std::rc::Rc;
+
+use common::*;
+use world_types::{BlockTypeDefinition, BlockTypes};
+
+use crate::definitions::loader::{ComponentFields, DeserializedDefinition};
+use crate::definitions::{DefinitionError, DefinitionErrorKind, DefinitionErrors, ValueImpl};
+use crate::ecs::*;
+use crate::string::StringCache;
+
+/// Category of definitions that declare block types with a "block" component
+pub const BLOCKS_CATEGORY: &str = "blocks";
+
+/// Block type declared in data. Block types are registered globally before the world is created,
+/// so this template only exists for validation.
+#[derive(Debug)]
+pub struct BlockTypeTemplate(BlockTypeDefinition);
+
+impl ComponentTemplate<ValueImpl> for BlockTypeTemplate {
+    fn construct(
+        values: &mut Map<ValueImpl>,
+        _: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<ValueImpl>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let def = BlockTypeDefinition::from_fields(values.take())?;
+        Ok(Rc::new(Self(def)))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder
+    }
+
+    crate::as_any!();
+}
+
+register_component_template!("block", BlockTypeTemplate);
+
+/// Registers all block types declared in the blocks category. Must happen before any other
+/// definitions are instantiated, as they can refer to block types by name
+pub fn register_block_types(defs: &[DeserializedDefinition]) -> Result<(), DefinitionErrors> {
+    let mut errors = Vec::new();
+    let mut block_types = Vec::new();
+
+    for def in defs {
+        if def.category() != Some(BLOCKS_CATEGORY) {
+            continue;
+        }
+
+        let fields = def
+            .processed_components()
+            .iter()
+            .find_map(|(name, fields)| match fields {
+                ComponentFields::Fields(fields) if name == "block" => Some(fields.clone()),
+                _ => None,
+            });
+
+        let result = fields
+            .ok_or_else(|| DefinitionErrorKind::NoSuchComponent("block".to_owned()))
+            .and_then(|fields| {
+                BlockTypeDefinition::from_fields(fields)
+                    .map_err(|err| DefinitionErrorKind::from(ComponentBuildError::from(err)))
+            });
+
+        match result {
+            Ok(block_type) => block_types.push(block_type),
+            Err(err) => errors.push(def.make_error(Some(def.uid().to_owned()), err)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(DefinitionErrors(errors));
+    }
+
+    BlockTypes::init(block_types).map_err(|err| {
+        DefinitionErrors(vec![DefinitionError {
+            uid: None,
+            src: Default::default(),
+            kind: DefinitionErrorKind::BlockType(err.to_string()),
+        }])
+    })
+}
@@ -1,3 +1,6 @@
+use world_types::BlockTypes;
+
+use crate::definitions::block::register_block_types;
 use crate::definitions::loader::step1_deserialization::{
     collect_raw_definitions, DeserializedDefinition,
 };
@@ -13,10 +16,27 @@ pub fn load(
     string_cache: &StringCache,
 ) -> Result<DefinitionRegistry, DefinitionErrors> {
     let defs = load_and_preprocess_with(|| collect_raw_definitions(resources))?;
+
+    // block types may have already been registered before world creation
+    if !BlockTypes::is_initialized() {
+        register_block_types(&defs)?;
+    }
+
     let instantiated = instantiate(defs, &TemplateLookup::init(), string_cache)?;
     build_registry(instantiated)
 }
 
+/// Registers block types only, so the world can be created before the rest of the definitions are
+/// loaded. Does nothing if block types have already been registered, e.g. on restart
+pub fn load_block_types(resources: resources::Definitions) -> Result<(), DefinitionErrors> {
+    if BlockTypes::is_initialized() {
+        return Ok(());
+    }
+
+    let defs = load_and_preprocess_with(|| collect_raw_definitions(resources))?;
+    register_block_types(&defs)
+}
+
 #[cfg(test)]
 pub fn load_from_str(definitions: &str) -> Result<DefinitionRegistry, DefinitionErrors> {
     let defs = preprocess_from_str(definitions)?;
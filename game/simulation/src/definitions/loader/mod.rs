@@ -1,7 +1,8 @@
-pub use load::load;
 #[cfg(test)]
 pub use load::load_from_str;
-pub use step1_deserialization::DefinitionSource;
+pub use load::{load, load_block_types};
+pub use step1_deserialization::{DefinitionSource, DeserializedDefinition};
+pub use step2_preprocessing::ComponentFields;
 pub use step3_construction::Definition;
 
 pub type ValueImpl = ron::Value;
@@ -75,6 +75,14 @@ impl DeserializedDefinition {
         self.source.clone()
     }
 
+    pub fn category(&self) -> Option<&str> {
+        if self.category.is_empty() {
+            None
+        } else {
+            Some(&self.category)
+        }
+    }
+
     pub fn is_abstract(&self) -> bool {
         self.r#abstract
     }
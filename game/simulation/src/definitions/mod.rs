@@ -1,3 +1,4 @@
+mod block;
 mod builder;
 mod component;
 mod loader;
@@ -5,7 +6,7 @@ mod registry;
 
 pub use builder::{BuilderError, DefinitionBuilder, EntityPosition};
 pub use component::DefinitionNameComponent;
-pub use loader::{load, Definition, ValueImpl};
+pub use loader::{load, load_block_types, Definition, ValueImpl};
 pub use registry::DefinitionRegistry;
 
 #[cfg(test)]
@@ -57,6 +58,9 @@ pub enum DefinitionErrorKind {
 
     #[error("Duplicate component with type {0:?}")]
     DuplicateComponent(String),
+
+    #[error("Failed to register block types: {0}")]
+    BlockType(String),
 }
 
 #[derive(Debug, Error)]
@@ -4,10 +4,10 @@
 use common::*;
 use unit::world::{WorldPoint, WorldPosition};
 
-use crate::activity::{HaulPurpose, HaulSource, HaulTarget};
+use crate::activity::{EquipItemError, HaulPurpose, HaulSource, HaulTarget};
 use crate::ai::{AiAction, AiComponent};
 use crate::ecs::{EcsWorld, Entity};
-use crate::item::{ContainedInComponent, ContainerComponent};
+use crate::item::{ContainedInComponent, ContainerComponent, HaulableItemComponent};
 
 use crate::queued_update::QueuedUpdates;
 use crate::simulation::AssociatedBlockData;
@@ -49,6 +49,49 @@ impl EcsExtDev<'_> {
         bag
     }
 
+    /// Puts the item straight into the holder's hands, shifting anything already held into
+    /// containers if needed. The item must be free on the ground
+    pub fn give_item(&self, lucky_holder: Entity, item: Entity) -> Result<(), EquipItemError> {
+        let mut shifted_items = SmallVec::<[(Entity, Entity); 3]>::new();
+        {
+            let mut inventories = self.write_storage::<InventoryComponent>();
+            let transforms = self.read_storage::<TransformComponent>();
+            let haulables = self.read_storage::<HaulableItemComponent>();
+            let physicals = self.read_storage::<PhysicalComponent>();
+
+            let (_, haulable, physical) = self
+                .components(item, (&transforms, &haulables, &physicals))
+                .ok_or(EquipItemError::NotAvailable)?;
+
+            let inventory = lucky_holder
+                .get_mut(&mut inventories)
+                .ok_or(EquipItemError::NoInventory)?;
+
+            inventory
+                .insert_item(
+                    self.0,
+                    item,
+                    haulable.extra_hands,
+                    physical.volume,
+                    physical.size,
+                    |item, container| shifted_items.push((item, container)),
+                )
+                .ok_or(EquipItemError::NoFreeHands)?;
+        }
+
+        info!("giving item {} to {}", item, lucky_holder);
+
+        for (item, container) in shifted_items {
+            self.helpers_comps()
+                .add_to_container(item, ContainedInComponent::Container(container));
+        }
+
+        self.helpers_comps()
+            .add_to_container(item, ContainedInComponent::InventoryOf(lucky_holder));
+
+        Ok(())
+    }
+
     pub fn put_food_in_container(&mut self, food: Entity, lucky_holder: Entity) {
         let mut inv = self
             .component_mut::<InventoryComponent>(lucky_holder)
@@ -3,6 +3,7 @@ use std::convert::TryFrom;
 
 use common::*;
 
+use crate::ecs::save::{ComponentSaveError, LoadCompFn, LoadContext, SaveCompFn, SaveContext};
 use crate::ecs::world::{ComponentRefErased, SpecsWorld};
 use crate::{ComponentWorld, EcsWorld, Entity};
 
@@ -79,6 +80,7 @@ pub struct ComponentEntry {
     pub register_comp_fn: RegisterCompFn,
     pub get_comp_fn: GetComponentFn,
     pub clone_to_fn: Option<CloneToFn>,
+    pub save_fns: Option<(SaveCompFn, LoadCompFn)>,
 }
 
 inventory::collect!(ComponentEntry);
@@ -87,12 +89,14 @@ pub struct ComponentFunctions {
     has_comp: HasCompFn,
     get_comp: GetComponentFn,
     clone_to_fn: Option<CloneToFn>,
+    load_fn: Option<LoadCompFn>,
 }
 
 pub struct ComponentRegistry {
     // TODO perfect hashing
     map: HashMap<&'static str, ComponentFunctions>,
     cloneables: Vec<(&'static str, CloneToFn)>,
+    saveables: Vec<(&'static str, SaveCompFn)>,
 }
 
 impl<V: Value> Map<V> {
@@ -184,6 +188,7 @@ impl ComponentRegistry {
     pub fn new(world: &mut SpecsWorld) -> Self {
         let mut map = HashMap::with_capacity(128);
         let mut cloneables = Vec::with_capacity(64);
+        let mut saveables = Vec::with_capacity(32);
         for comp in inventory::iter::<ComponentEntry> {
             let cloneable = if comp.clone_to_fn.is_some() {
                 " (cloneable)"
@@ -197,6 +202,7 @@ impl ComponentRegistry {
                     has_comp: comp.has_comp_fn,
                     get_comp: comp.get_comp_fn,
                     clone_to_fn: comp.clone_to_fn,
+                    load_fn: comp.save_fns.map(|(_, load)| load),
                 },
             );
 
@@ -209,13 +215,22 @@ impl ComponentRegistry {
             if let Some(clone_fn) = comp.clone_to_fn {
                 cloneables.push((comp.name, clone_fn));
             }
+
+            if let Some((save_fn, _)) = comp.save_fns {
+                saveables.push((comp.name, save_fn));
+            }
         }
 
         info!("registered {} components", map.len());
         map.shrink_to_fit();
         cloneables.shrink_to_fit();
+        saveables.shrink_to_fit();
 
-        ComponentRegistry { map, cloneables }
+        ComponentRegistry {
+            map,
+            cloneables,
+            saveables,
+        }
     }
 
     pub fn has_component(&self, comp: &str, world: &EcsWorld, entity: Entity) -> bool {
@@ -263,6 +278,38 @@ impl ComponentRegistry {
         }
     }
 
+    /// Serializes all components marked as `#[save]` that this entity has
+    pub fn save_components_for<'a>(
+        &'a self,
+        world: &'a EcsWorld,
+        entity: Entity,
+        ctx: &'a SaveContext,
+    ) -> impl Iterator<Item = (&'static str, Result<String, ron::Error>)> + 'a {
+        self.saveables
+            .iter()
+            .filter_map(move |(name, save)| (save)(world, entity, ctx).map(|res| (*name, res)))
+    }
+
+    /// Deserializes and applies the given saved component to the entity
+    pub fn load_component_for(
+        &self,
+        comp: &str,
+        world: &EcsWorld,
+        entity: Entity,
+        serialized: &str,
+        ctx: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let load = self
+            .map
+            .get(comp)
+            .and_then(|funcs| funcs.load_fn)
+            .ok_or_else(|| {
+                ComponentSaveError::Specific(format!("component {:?} is not loadable", comp))
+            })?;
+
+        (load)(world, entity, serialized, ctx)
+    }
+
     /// Returns the name of the first non-copyable component that this entity has
     pub fn find_non_copyable(&self, world: &EcsWorld, entity: Entity) -> Option<&'static str> {
         self.map.iter().find_map(move |(name, comp)| {
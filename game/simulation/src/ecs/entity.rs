@@ -125,6 +125,13 @@ impl From<EntityWrapper> for Entity {
     }
 }
 
+impl From<Entity> for EntityWrapper {
+    fn from(e: Entity) -> Self {
+        // safety: see doc comment on EntityWrapper (and unit test below)
+        unsafe { std::mem::transmute::<_, EntityWrapper>(e.0) }
+    }
+}
+
 impl<'a> EntityBomb<'a> {
     pub fn new(entity: Entity, world: &'a EcsWorld, death_reason: DeathReason) -> Self {
         Self {
@@ -168,6 +175,11 @@ mod tests {
             let my_e = Entity::from(my_e);
             assert_eq!(e, my_e.0, "specs entity layout has changed");
             assert_eq!(Entity::from(e), my_e, "specs entity layout has changed");
+            assert_eq!(
+                EntityWrapper::from(my_e),
+                EntityWrapper(index, NonZeroI32::new(gen.id()).unwrap()),
+                "specs entity layout has changed"
+            );
 
             if i % 2 == 0 {
                 // try out some other generations too
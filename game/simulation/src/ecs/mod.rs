@@ -13,6 +13,10 @@ pub use name::{
     DisplayComponent, DisplayTextSystem, KindComponent, NameComponent,
     NoDisplayTextOnHoverComponent,
 };
+pub use save::{
+    load_component_erased, save_component_erased, ComponentSaveError, LoadContext, SaveComponent,
+    SaveContext, SavedEntity, SavedSociety,
+};
 pub use template::{ComponentTemplate, ComponentTemplateEntry, ValueImpl};
 
 pub use crate::register_component_template;
@@ -26,6 +30,7 @@ mod component;
 mod debug;
 mod entity;
 mod name;
+mod save;
 mod template;
 mod world;
 mod world_ext;
@@ -36,6 +36,7 @@ enum KindModifier {
 #[derive(Component, EcsComponent, Clone, Debug)]
 #[storage(HashMapStorage)]
 #[name("name")]
+#[save]
 pub struct NameComponent(String);
 
 /// Caches the display string rendered on each entity
@@ -142,9 +143,8 @@ impl<'a> System<'a> for DisplayTextSystem {
 
         // show more info for entities close to the mouse
         let nearby = spatial
-            .query_in_radius(&world, mouse.0, HOVER_RADIUS)
+            .query_nearest(&world, mouse.0, 8, HOVER_RADIUS)
             .map(|(e, _, _)| specs::Entity::from(e))
-            .take(8)
             .collect::<ArrayVec<_, 8>>();
 
         for (e, ui, stack, selected, _, no_hover) in (
@@ -335,6 +335,24 @@ impl NameComponent {
     }
 }
 
+impl SaveComponent for NameComponent {
+    type Saved = String;
+
+    fn save(&self, _: &SaveContext) -> Option<Self::Saved> {
+        Some(self.0.clone())
+    }
+
+    fn load(
+        name: Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        _: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let _ = world.add_now(entity, Self(name));
+        Ok(())
+    }
+}
+
 impl KindComponent {
     pub fn make_stack(&mut self) {
         self.1 = Some(KindModifier::Stack);
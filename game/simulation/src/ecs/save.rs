@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use common::*;
+
+use crate::ecs::*;
+use crate::SocietyHandle;
+
+/// Index of an entity within a save file, stable only within that file
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct SavedEntity(pub u32);
+
+/// Index of a society within a save file
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct SavedSociety(pub u32);
+
+#[derive(Debug, Error)]
+pub enum ComponentSaveError {
+    #[error("Failed to (de)serialize component: {0}")]
+    Ron(#[from] ron::Error),
+
+    #[error("Unknown saved entity {0:?}")]
+    UnknownEntity(SavedEntity),
+
+    #[error("Unknown saved society {0:?}")]
+    UnknownSociety(SavedSociety),
+
+    #[error("Component error: {0}")]
+    Component(#[from] ComponentGetError),
+
+    #[error("Component specific error: {0}")]
+    Specific(String),
+}
+
+/// A component that can be persisted in a save file. Implementors must also be marked with
+/// `#[save]` to be registered with the component registry.
+///
+/// Entities are first spawned from their original definition, then saved components are applied
+/// on top, so loading should modify the existing component where possible rather than replacing
+/// state that is already provided by the definition.
+pub trait SaveComponent: Component + Sized {
+    type Saved: Serialize + DeserializeOwned;
+
+    /// None if this component should be skipped, e.g. it references an entity that isn't saved
+    fn save(&self, ctx: &SaveContext) -> Option<Self::Saved>;
+
+    fn load(
+        saved: Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        ctx: &LoadContext,
+    ) -> Result<(), ComponentSaveError>;
+}
+
+pub type SaveCompFn = fn(&EcsWorld, Entity, &SaveContext) -> Option<Result<String, ron::Error>>;
+pub type LoadCompFn = fn(&EcsWorld, Entity, &str, &LoadContext) -> Result<(), ComponentSaveError>;
+
+/// Maps live entities and societies to their index in the save file
+#[derive(Default)]
+pub struct SaveContext {
+    entities: HashMap<Entity, SavedEntity>,
+    societies: HashMap<SocietyHandle, SavedSociety>,
+}
+
+/// Maps saved entities and societies to their newly created counterparts
+#[derive(Default)]
+pub struct LoadContext {
+    entities: Vec<Entity>,
+    societies: Vec<SocietyHandle>,
+}
+
+impl SaveContext {
+    pub fn register_entity(&mut self, entity: Entity) -> SavedEntity {
+        let next = SavedEntity(self.entities.len() as u32);
+        *self.entities.entry(entity).or_insert(next)
+    }
+
+    pub fn register_society(&mut self, society: SocietyHandle) -> SavedSociety {
+        let next = SavedSociety(self.societies.len() as u32);
+        *self.societies.entry(society).or_insert(next)
+    }
+
+    pub fn entity(&self, entity: Entity) -> Option<SavedEntity> {
+        self.entities.get(&entity).copied()
+    }
+
+    pub fn society(&self, society: SocietyHandle) -> Option<SavedSociety> {
+        self.societies.get(&society).copied()
+    }
+}
+
+impl LoadContext {
+    /// Must be called in the same order as [SaveContext::register_entity]
+    pub fn register_entity(&mut self, entity: Entity) {
+        self.entities.push(entity);
+    }
+
+    /// Must be called in the same order as [SaveContext::register_society]
+    pub fn register_society(&mut self, society: SocietyHandle) {
+        self.societies.push(society);
+    }
+
+    pub fn entity(&self, saved: SavedEntity) -> Result<Entity, ComponentSaveError> {
+        self.entities
+            .get(saved.0 as usize)
+            .copied()
+            .ok_or(ComponentSaveError::UnknownEntity(saved))
+    }
+
+    pub fn society(&self, saved: SavedSociety) -> Result<SocietyHandle, ComponentSaveError> {
+        self.societies
+            .get(saved.0 as usize)
+            .copied()
+            .ok_or(ComponentSaveError::UnknownSociety(saved))
+    }
+}
+
+/// Referenced by `#[save]` in the EcsComponent derive
+pub fn save_component_erased<C: SaveComponent>(
+    world: &EcsWorld,
+    entity: Entity,
+    ctx: &SaveContext,
+) -> Option<Result<String, ron::Error>> {
+    let comp = world.component::<C>(entity).ok()?;
+    comp.save(ctx).map(|saved| ron::to_string(&saved))
+}
+
+/// Referenced by `#[save]` in the EcsComponent derive
+pub fn load_component_erased<C: SaveComponent>(
+    world: &EcsWorld,
+    entity: Entity,
+    serialized: &str,
+    ctx: &LoadContext,
+) -> Result<(), ComponentSaveError> {
+    let saved = ron::from_str::<C::Saved>(serialized)?;
+    C::load(saved, world, entity, ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ComponentWorld;
+
+    #[test]
+    fn context_indices_match() {
+        let world = EcsWorld::new();
+        let a: Entity = world.create_entity().build().into();
+        let b: Entity = world.create_entity().build().into();
+
+        let mut save = SaveContext::default();
+        assert_eq!(save.register_entity(a), SavedEntity(0));
+        assert_eq!(save.register_entity(b), SavedEntity(1));
+        assert_eq!(save.register_entity(a), SavedEntity(0));
+
+        let mut load = LoadContext::default();
+        load.register_entity(b);
+        load.register_entity(a);
+        assert_eq!(load.entity(SavedEntity(0)).unwrap(), b);
+        assert_eq!(load.entity(SavedEntity(1)).unwrap(), a);
+        assert!(load.entity(SavedEntity(2)).is_err());
+    }
+
+    #[test]
+    fn component_round_trip() {
+        let world = EcsWorld::new();
+        let src: Entity = world.create_entity().build().into();
+        let dst: Entity = world.create_entity().build().into();
+        let _ = world.add_now(src, NameComponent::new("Steve".to_owned()));
+
+        let ctx = SaveContext::default();
+        let saved = world
+            .save_components_for(src, &ctx)
+            .map(|(name, res)| (name, res.expect("failed to save")))
+            .collect_vec();
+        assert_eq!(saved.len(), 1);
+
+        let (name, serialized) = &saved[0];
+        assert_eq!(*name, "name");
+        world
+            .load_component_for(name, dst, serialized, &LoadContext::default())
+            .expect("failed to load");
+
+        let resaved = world.save_components_for(dst, &ctx).collect_vec();
+        assert_eq!(resaved.len(), 1);
+        assert_eq!(resaved[0].1.as_ref().unwrap(), serialized);
+    }
+}
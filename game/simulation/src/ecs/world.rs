@@ -12,14 +12,18 @@ use specs::LazyUpdate;
 use common::*;
 
 use crate::build::BuildTemplate;
+use crate::craft::CraftRecipe;
 use crate::definitions::{DefinitionBuilder, DefinitionErrorKind, DefinitionRegistry};
 use crate::ecs::component::{AsInteractiveFn, ComponentRegistry};
 use crate::ecs::*;
 use crate::event::{DeathReason, EntityEvent, EntityEventQueue};
 use crate::item::{ContainerComponent, ContainerResolver};
+use crate::senses::{NoiseKind, Noises};
 use crate::spatial::Spatial;
 use crate::string::CachedStr;
-use crate::{Entity, InnerWorldRef, ItemStackComponent, TransformComponent, WorldRef};
+use crate::{
+    Entity, InnerWorldRef, ItemStackComponent, SpeciesComponent, TransformComponent, WorldRef,
+};
 
 pub type SpecsWorld = specs::World;
 pub struct EcsWorld {
@@ -27,6 +31,8 @@ pub struct EcsWorld {
     component_registry: ComponentRegistry,
     /// (definition name, build template, rendered KindComponent)
     build_templates: Vec<(CachedStr, Rc<BuildTemplate>, Option<String>)>,
+    /// (definition name, recipe, rendered KindComponent)
+    recipes: Vec<(CachedStr, Rc<CraftRecipe>, Option<String>)>,
 }
 
 pub struct CachedWorldRef<'a> {
@@ -44,7 +50,7 @@ pub enum ComponentGetError {
 }
 
 #[derive(Debug, Error)]
-#[error("There are build templates with invalid materials: {0:?}")]
+#[error("There are build templates or recipes with invalid materials: {0:?}")]
 pub struct InvalidBuildTemplatesError(Vec<String>);
 
 /// Resource to hold entities to kill
@@ -268,10 +274,49 @@ impl EcsWorld {
             }
         };
 
+        // collect and validate crafting recipes
+        let recipes = {
+            let recipes = definitions
+                .iter_category("recipes")
+                .filter_map(|(uid, definition)| {
+                    let recipe = definition.find_component_ref::<CraftRecipe>("recipe")?;
+
+                    let name = definition
+                        .find_component("kind")
+                        .and_then(|any| any.downcast_ref::<KindComponent>())
+                        .map(|kind| format!("{}", kind));
+
+                    Some((uid, recipe, name))
+                })
+                .collect_vec();
+
+            let mut invalids = Vec::new();
+            for (def, recipe, _) in &recipes {
+                let (output, _) = recipe.output();
+                let referenced = recipe
+                    .inputs()
+                    .iter()
+                    .map(|mat| mat.definition())
+                    .chain([output, recipe.workshop()]);
+                for referenced in referenced {
+                    if definitions.lookup_definition(referenced).is_none() {
+                        invalids.push(format!("{}:{}", def, referenced));
+                    }
+                }
+            }
+
+            if invalids.is_empty() {
+                recipes
+            } else {
+                return Err(InvalidBuildTemplatesError(invalids));
+            }
+        };
+
         let mut world = EcsWorld {
             world,
             component_registry: reg,
             build_templates,
+            recipes,
         };
 
         world.world.insert(definitions);
@@ -304,6 +349,28 @@ impl EcsWorld {
         self.component_registry.find_non_copyable(self, entity)
     }
 
+    /// Serializes all components marked as `#[save]` that this entity has
+    pub fn save_components_for<'a>(
+        &'a self,
+        entity: Entity,
+        ctx: &'a SaveContext,
+    ) -> impl Iterator<Item = (&'static str, Result<String, ron::Error>)> + 'a {
+        self.component_registry
+            .save_components_for(self, entity, ctx)
+    }
+
+    /// Applies a component previously serialized with [save_components_for]
+    pub fn load_component_for(
+        &self,
+        comp: &str,
+        entity: Entity,
+        serialized: &str,
+        ctx: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        self.component_registry
+            .load_component_for(comp, self, entity, serialized, ctx)
+    }
+
     /// (definition name, template, build name)
     pub fn build_templates(&self) -> &[(CachedStr, Rc<BuildTemplate>, Option<String>)] {
         &self.build_templates
@@ -318,6 +385,21 @@ impl EcsWorld {
             }
         })
     }
+
+    /// (definition name, recipe, recipe name)
+    pub fn recipes(&self) -> &[(CachedStr, Rc<CraftRecipe>, Option<String>)] {
+        &self.recipes
+    }
+
+    pub fn find_recipe(&self, name: &str) -> Option<Rc<CraftRecipe>> {
+        self.recipes.iter().find_map(|(def, recipe, _)| {
+            if def.as_ref() == name {
+                Some(recipe.clone())
+            } else {
+                None
+            }
+        })
+    }
 }
 
 impl ComponentWorld for EcsWorld {
@@ -439,7 +521,7 @@ impl ComponentWorld for EcsWorld {
                 .map(|t| t.position);
             match container_pos {
                 Ok(scatter_around) => {
-                    let mut rng = thread_rng();
+                    let mut rng = random::get();
                     for item in container.container.contents().map(|e| e.entity) {
                         self.helpers_comps().remove_from_container(item);
 
@@ -460,6 +542,16 @@ impl ComponentWorld for EcsWorld {
             }
         }
 
+        // living things make a racket when they die
+        if self.has_component::<SpeciesComponent>(entity) {
+            let transform = self.component::<TransformComponent>(entity);
+            if let Some((mut noises, transform)) =
+                self.try_fetch_mut::<Noises>().zip(transform.ok())
+            {
+                noises.emit(entity, transform.position, NoiseKind::Death);
+            }
+        }
+
         // kill before next maintain
         let deathlist = self.resource_mut::<EntitiesToKill>();
         deathlist.add_many(to_kill.into_iter());
@@ -6,7 +6,7 @@ use crate::{ComponentWorld, ContainersError, TransformComponent};
 use common::*;
 
 use crate::item::{ContainedInComponent, EndHaulBehaviour, HaulType, HauledItemComponent};
-use crate::job::{BuildThingError, BuildThingJob, MaterialReservation, SocietyJobHandle};
+use crate::job::{BuildThingError, MaterialReservation, SocietyJobHandle};
 
 #[derive(common::derive_more::Deref, common::derive_more::DerefMut)]
 pub struct EcsExtComponents<'w>(&'w EcsWorld);
@@ -109,19 +109,24 @@ impl EcsExtComponents<'_> {
         job: SocietyJobHandle,
     ) -> Result<(), ReservationError> {
         // find job in society and try to reserve
-        let (surplus, remaining) = job
-            .resolve_and_cast_mut(self.0.resource(), |build_job: &mut BuildThingJob| {
-                build_job.add_reservation(material, self.0)
-            })
-            .ok_or(ReservationError::InvalidJob(job))??;
+        let (surplus, remaining) = {
+            let job_ref = job
+                .resolve(self.0.resource())
+                .ok_or(ReservationError::JobNotFound(job))?;
+            let mut job_ref = job_ref.borrow_mut();
+            let materials = job_ref
+                .materials_mut()
+                .ok_or(ReservationError::InvalidJob(job))?;
+            materials.add_reservation(material, self.0)?
+        };
 
         match surplus {
             MaterialReservation::ConsumeAll(n) => {
-                debug!("reserving material for build job"; "material" => material, "job" => ?job, "n" => n, "remaining" => remaining);
+                debug!("reserving material for job"; "material" => material, "job" => ?job, "n" => n, "remaining" => remaining);
             }
             MaterialReservation::Surplus { surplus, reserved } => {
                 // drop surplus
-                debug!("reserving {n} material for build job with {surplus} surplus to be split into new stack",
+                debug!("reserving {n} material for job with {surplus} surplus to be split into new stack",
                     n = reserved, surplus = surplus; "job" => ?job);
                 let new_stack = self
                     .0
@@ -137,7 +142,7 @@ impl EcsExtComponents<'_> {
                         .expect("transform expected");
 
                     let pos = {
-                        let mut rand = thread_rng();
+                        let mut rand = random::get();
                         transform
                             .position
                             .modify_x(|x| x + rand.gen_range(-1.0, 1.0))
@@ -184,7 +189,7 @@ pub enum ReservationError {
     #[error("Job used for society job material reservation not found: {0:?}")]
     JobNotFound(SocietyJobHandle),
 
-    #[error("Job used for society material reservation is not a build job: {0:?}")]
+    #[error("Job used for society material reservation does not require materials: {0:?}")]
     InvalidJob(SocietyJobHandle),
 
     #[error("Failed to reserve material: {0}")]
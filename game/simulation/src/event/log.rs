@@ -0,0 +1,29 @@
+use crate::event::EntityEvent;
+
+/// Copy of every entity event, for observers outside of the simulation such as the headless
+/// backend. Disabled by default to avoid cloning every event
+#[derive(Default)]
+pub struct EntityEventLog {
+    enabled: bool,
+    events: Vec<EntityEvent>,
+}
+
+impl EntityEventLog {
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.events.clear();
+        }
+    }
+
+    pub(crate) fn log<'a>(&mut self, events: impl Iterator<Item = &'a EntityEvent>) {
+        if self.enabled {
+            self.events.extend(events.cloned());
+        }
+    }
+
+    /// All events logged since the last call
+    pub fn drain(&mut self) -> impl Iterator<Item = EntityEvent> + '_ {
+        self.events.drain(..)
+    }
+}
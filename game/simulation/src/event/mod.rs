@@ -1,3 +1,4 @@
+pub use log::EntityEventLog;
 pub use queue::EntityEventQueue;
 #[cfg(feature = "testing")]
 pub use subscription::debug_events::{EntityEventDebugPayload, TaskResultSummary};
@@ -7,6 +8,7 @@ pub use subscription::{
 };
 pub use timer::{Timer, TimerToken, Timers};
 
+mod log;
 mod queue;
 mod subscription;
 mod timer;
@@ -90,6 +90,31 @@ pub enum DeathReason {
     /// it was used in a build
     CompletedBuild,
 
+    /// it was used as an input to a craft
+    CompletedCraft,
+
+    /// it died of thirst
+    Dehydration,
+
+    /// it fell from a great height
+    Fall,
+
+    /// it bled to death
+    BloodLoss,
+
+    /// it was killed by the given entity
+    #[display(fmt = "Killed by {}", _0)]
+    Killed(Entity),
+
+    /// it was drunk up
+    Consumed,
+
+    /// it was butchered into meat
+    Butchered,
+
+    /// it was harvested for its produce
+    Harvested,
+
     /// the containing item stack was destroyed
     ParentStackDestroyed,
 
@@ -0,0 +1,254 @@
+use std::rc::Rc;
+
+use common::*;
+use world::block::BlockType;
+
+use crate::ecs::*;
+use crate::simulation::Tick;
+use crate::string::StringCache;
+use crate::{TransformComponent, WorldRef};
+
+/// Growth is applied every this many ticks rather than every tick
+const GROWTH_INTERVAL: u32 = 20;
+
+/// Plant that grows through a number of stages over time, the last of which is ripe
+#[derive(Component, EcsComponent, Clone, Debug)]
+#[storage(DenseVecStorage)]
+#[name("growth")]
+#[interactive]
+#[clone(disallow)]
+#[save]
+pub struct GrowthComponent {
+    /// Current stage, starting at 0
+    stage: u8,
+
+    /// Total number of stages including the ripe stage
+    stages: u8,
+
+    /// Ticks spent in each stage before advancing to the next
+    stage_ticks: u32,
+
+    /// Ticks spent in the current stage so far
+    progress: u32,
+
+    /// Only grows while planted on one of these blocks, or anywhere if empty
+    soil: Vec<BlockType>,
+}
+
+/// Advances plant growth for plants on suitable soil
+pub struct GrowthSystem;
+
+impl GrowthComponent {
+    pub fn new(stages: u8, stage_ticks: u32, soil: Vec<BlockType>) -> Self {
+        Self {
+            stage: 0,
+            stages: stages.max(1),
+            stage_ticks,
+            progress: 0,
+            soil,
+        }
+    }
+
+    pub fn stage(&self) -> u8 {
+        self.stage
+    }
+
+    pub fn is_ripe(&self) -> bool {
+        self.stage + 1 >= self.stages
+    }
+
+    pub fn grows_on(&self, block: BlockType) -> bool {
+        self.soil.is_empty() || self.soil.contains(&block)
+    }
+
+    pub fn soil(&self) -> &[BlockType] {
+        &self.soil
+    }
+
+    /// Back to the first stage, e.g. after being harvested
+    pub fn reset(&mut self) {
+        self.stage = 0;
+        self.progress = 0;
+    }
+
+    /// Returns true if advanced to the next stage
+    fn grow(&mut self, ticks: u32) -> bool {
+        if self.is_ripe() {
+            return false;
+        }
+
+        self.progress += ticks;
+        if self.progress >= self.stage_ticks {
+            self.progress = 0;
+            self.stage += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<'a> System<'a> for GrowthSystem {
+    type SystemData = (
+        Read<'a, WorldRef>,
+        Read<'a, EntitiesRes>,
+        ReadStorage<'a, TransformComponent>,
+        WriteStorage<'a, GrowthComponent>,
+    );
+
+    fn run(&mut self, (world_ref, entities, transform, mut growth): Self::SystemData) {
+        if Tick::fetch().value() % GROWTH_INTERVAL != 0 {
+            return;
+        }
+
+        let world = world_ref.borrow();
+        for (e, transform, growth) in (&entities, &transform, &mut growth).join() {
+            if growth.is_ripe() {
+                continue;
+            }
+
+            let soil = world
+                .block(transform.position.floor().below())
+                .map(|b| b.block_type());
+            if !soil.map(|bt| growth.grows_on(bt)).unwrap_or(false) {
+                continue;
+            }
+
+            if growth.grow(GROWTH_INTERVAL) {
+                let e = Entity::from(e);
+                trace!("plant has grown"; e, "stage" => growth.stage(), "ripe" => growth.is_ripe());
+            }
+        }
+    }
+}
+
+impl SaveComponent for GrowthComponent {
+    /// (stage, progress), the rest comes from the definition
+    type Saved = (u8, u32);
+
+    fn save(&self, _: &SaveContext) -> Option<Self::Saved> {
+        Some((self.stage, self.progress))
+    }
+
+    fn load(
+        (stage, progress): Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        _: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let mut comp = world.component_mut::<Self>(entity)?;
+        comp.stage = stage.min(comp.stages - 1);
+        comp.progress = progress;
+        Ok(())
+    }
+}
+
+impl<V: Value> ComponentTemplate<V> for GrowthComponent {
+    fn construct(
+        values: &mut Map<V>,
+        _: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let stages = values.get_int("stages")?;
+        let stage_ticks = values.get_int("stage_ticks")?;
+        let soil = match values.get_string("soil") {
+            Ok(s) => s
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    s.parse::<BlockType>().map_err(|_| {
+                        ComponentBuildError::TemplateSpecific(format!("invalid soil {:?}", s))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            Err(ComponentBuildError::KeyNotFound(_)) => Vec::new(), // grows anywhere
+            Err(e) => return Err(e),
+        };
+
+        if stages == 0 {
+            return Err(ComponentBuildError::TemplateSpecific(
+                "growth stages should be positive".to_owned(),
+            ));
+        }
+
+        Ok(Rc::new(Self::new(stages, stage_ticks, soil)))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
+impl InteractiveComponent for GrowthComponent {
+    fn as_debug(&self) -> Option<&dyn Debug> {
+        Some(self)
+    }
+}
+
+register_component_template!("growth", GrowthComponent);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grows_until_ripe() {
+        let mut growth = GrowthComponent::new(3, 50, vec![]);
+        assert!(!growth.grow(40));
+        assert!(growth.grow(20));
+        assert_eq!(growth.stage(), 1);
+
+        assert!(growth.grow(50));
+        assert!(growth.is_ripe());
+
+        // stays ripe
+        assert!(!growth.grow(100));
+        assert_eq!(growth.stage(), 2);
+
+        growth.reset();
+        assert_eq!(growth.stage(), 0);
+        assert!(!growth.is_ripe());
+    }
+
+    #[test]
+    fn soil_restrictions() {
+        let anywhere = GrowthComponent::new(2, 10, vec![]);
+        assert!(anywhere.grows_on(BlockType::Stone));
+
+        let picky = GrowthComponent::new(2, 10, vec![BlockType::Grass, BlockType::Dirt]);
+        assert!(picky.grows_on(BlockType::Dirt));
+        assert!(!picky.grows_on(BlockType::Stone));
+    }
+
+    #[test]
+    fn growth_is_saved() {
+        let world = EcsWorld::new();
+        let src: Entity = world.create_entity().build().into();
+        let dst: Entity = world.create_entity().build().into();
+
+        let mut growth = GrowthComponent::new(3, 50, vec![]);
+        growth.grow(60);
+        growth.grow(20);
+        let _ = world.add_now(src, growth);
+
+        // destination spawned from its definition with no growth yet
+        let _ = world.add_now(dst, GrowthComponent::new(3, 50, vec![]));
+
+        let ctx = SaveContext::default();
+        for (name, serialized) in world.save_components_for(src, &ctx) {
+            let serialized = serialized.expect("failed to save");
+            world
+                .load_component_for(name, dst, &serialized, &LoadContext::default())
+                .expect("failed to load");
+        }
+
+        let growth = world.component::<GrowthComponent>(dst).unwrap();
+        assert_eq!(growth.stage(), 1);
+        assert_eq!(growth.progress, 20);
+    }
+}
@@ -0,0 +1,115 @@
+use std::rc::Rc;
+
+use common::*;
+use unit::world::WorldPosition;
+
+use crate::ecs::*;
+use crate::event::DeathReason;
+use crate::farming::GrowthComponent;
+use crate::item::spawn_item_stack;
+use crate::string::{CachedStr, StringCache};
+use crate::TransformComponent;
+
+/// Can be harvested for a number of produce items once ripe
+#[derive(Component, EcsComponent, Clone, Debug)]
+#[storage(DenseVecStorage)]
+#[name("harvestable")]
+#[clone(disallow)]
+pub struct HarvestableComponent {
+    /// Definition of the produce item
+    pub produce: CachedStr,
+    pub count: u16,
+    /// Time taken to harvest
+    pub ticks: u32,
+    /// Plant goes back to its first growth stage when harvested instead of being destroyed
+    pub regrows: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum HarvestError {
+    #[error("Plant is not ripe")]
+    NotRipe,
+}
+
+/// Spawns a new plant of the given crop definition on top of the given soil block
+pub fn sow_crop(
+    world: &EcsWorld,
+    crop: CachedStr,
+    soil: WorldPosition,
+) -> Result<Entity, Box<dyn Error>> {
+    let plant = world
+        .build_entity(crop.as_ref())?
+        .with_position(soil.above().centred())
+        .spawn()?;
+
+    debug!("sowed crop"; plant, "crop" => %crop, "position" => %soil.above());
+    Ok(plant)
+}
+
+/// Spawns the plant's produce in a stack in its place, and either resets its growth or destroys it
+pub fn harvest_plant(world: &EcsWorld, plant: Entity) -> Result<Entity, Box<dyn Error>> {
+    let (produce, count, regrows, pos) = {
+        let harvestable = world.component::<HarvestableComponent>(plant)?;
+        let transform = world.component::<TransformComponent>(plant)?;
+        (
+            harvestable.produce,
+            harvestable.count,
+            harvestable.regrows,
+            transform.position,
+        )
+    };
+
+    match world.component_mut::<GrowthComponent>(plant) {
+        Ok(growth) if !growth.is_ripe() => return Err(HarvestError::NotRipe.into()),
+        Ok(mut growth) if regrows => growth.reset(),
+        _ => {}
+    }
+
+    if !regrows {
+        world.kill_entity(plant, DeathReason::Harvested);
+    }
+
+    let stack = spawn_item_stack(world, produce, count, pos)?;
+    debug!("harvested plant"; plant, "produce" => %produce, "count" => count);
+    Ok(stack)
+}
+
+impl<V: Value> ComponentTemplate<V> for HarvestableComponent {
+    fn construct(
+        values: &mut Map<V>,
+        string_cache: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let produce = values.get_string("produce")?;
+        let count = values.get_int("count")?;
+        let ticks = values.get_int("ticks")?;
+        let regrows = match values.get_bool("regrows") {
+            Ok(b) => b,
+            Err(ComponentBuildError::KeyNotFound(_)) => false,
+            Err(e) => return Err(e),
+        };
+
+        if count == 0 {
+            return Err(ComponentBuildError::TemplateSpecific(
+                "harvestable produce count should be positive".to_owned(),
+            ));
+        }
+
+        Ok(Rc::new(Self {
+            produce: string_cache.get(&produce),
+            count,
+            ticks,
+            regrows,
+        }))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
+register_component_template!("harvestable", HarvestableComponent);
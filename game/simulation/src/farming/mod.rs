@@ -0,0 +1,5 @@
+mod growth;
+mod harvest;
+
+pub use growth::{GrowthComponent, GrowthSystem};
+pub use harvest::{harvest_plant, sow_crop, HarvestableComponent};
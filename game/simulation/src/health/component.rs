@@ -0,0 +1,221 @@
+use std::rc::Rc;
+
+use common::*;
+
+use crate::ecs::*;
+use crate::event::DeathReason;
+use crate::StringCache;
+
+/// Blocks that can be fallen without injury
+const SAFE_FALL_BLOCKS: u32 = 2;
+
+/// Damage for each block fallen beyond the safe distance
+const FALL_DAMAGE_PER_BLOCK: f32 = 15.0;
+
+/// Proportion of fall damage that is lost again per tick as bleeding
+const FALL_BLEEDING: f32 = 0.002;
+
+/// Bleeding rate reduction per tick
+const CLOTTING: f32 = 0.0005;
+
+/// Hit points that drain with injuries and bleeding, and slowly heal when not bleeding
+#[derive(Component, EcsComponent, Debug, Clone)]
+#[storage(DenseVecStorage)]
+#[name("health")]
+#[interactive]
+#[clone(disallow)]
+#[save]
+pub struct HealthComponent {
+    max: f32,
+    current: f32,
+
+    /// Health lost per tick, reduces over time as the wound clots
+    bleeding: f32,
+
+    /// Health gained per tick while not bleeding
+    healing: f32,
+
+    /// Cause of the most recent health loss, used as the reason of death
+    last_injury: DeathReason,
+}
+
+/// (damage, bleeding per tick) from falling the given number of blocks, if any
+pub fn fall_damage(fallen: u32) -> Option<(f32, f32)> {
+    let blocks = fallen.checked_sub(SAFE_FALL_BLOCKS).filter(|n| *n > 0)?;
+    let damage = blocks as f32 * FALL_DAMAGE_PER_BLOCK;
+    Some((damage, damage * FALL_BLEEDING))
+}
+
+impl HealthComponent {
+    /// Defaults to full health. Heal ticks are the number needed to go from the brink of death to
+    /// full health
+    pub fn new(max: f32, heal_ticks: u32) -> Self {
+        Self {
+            max,
+            current: max,
+            bleeding: 0.0,
+            healing: max / heal_ticks.max(1) as f32,
+            last_injury: DeathReason::Unknown,
+        }
+    }
+
+    pub fn health(&self) -> NormalizedFloat {
+        NormalizedFloat::clamped(self.current / self.max)
+    }
+
+    pub fn set_health(&mut self, health: NormalizedFloat) {
+        self.current = self.max * health.value();
+    }
+
+    pub fn bleeding(&self) -> f32 {
+        self.bleeding
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+
+    pub fn cause_of_death(&self) -> DeathReason {
+        self.last_injury
+    }
+
+    /// Immediately loses the given damage and starts bleeding on top of any existing bleeding
+    pub fn injure(&mut self, damage: f32, bleeding: f32, cause: DeathReason) {
+        debug_assert!(damage >= 0.0 && bleeding >= 0.0);
+        self.current = (self.current - damage).max(0.0);
+        self.bleeding += bleeding;
+        self.last_injury = cause;
+    }
+
+    /// Bleeds or heals for a single tick
+    pub fn tick(&mut self) {
+        if self.bleeding > 0.0 {
+            self.current = (self.current - self.bleeding).max(0.0);
+            self.bleeding = (self.bleeding - CLOTTING).max(0.0);
+
+            // bleeding out from an attack is still the attacker's doing
+            if !matches!(self.last_injury, DeathReason::Killed(_)) {
+                self.last_injury = DeathReason::BloodLoss;
+            }
+        } else {
+            self.current = (self.current + self.healing).min(self.max);
+        }
+    }
+}
+
+impl SaveComponent for HealthComponent {
+    /// (health, bleeding), the rest comes from the definition
+    type Saved = (f32, f32);
+
+    fn save(&self, _: &SaveContext) -> Option<Self::Saved> {
+        Some((self.health().value(), self.bleeding))
+    }
+
+    fn load(
+        (health, bleeding): Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        _: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let mut comp = world.component_mut::<Self>(entity)?;
+        comp.set_health(NormalizedFloat::clamped(health));
+        comp.bleeding = bleeding.max(0.0);
+        Ok(())
+    }
+}
+
+impl<V: Value> ComponentTemplate<V> for HealthComponent {
+    fn construct(
+        values: &mut Map<V>,
+        _: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let max: f32 = values.get_float("max")?;
+        if !(max.is_finite() && max > 0.0) {
+            return Err(ComponentBuildError::TemplateSpecific(format!(
+                "max health should be positive but is {}",
+                max
+            )));
+        }
+
+        let heal_ticks = values.get_int("heal_ticks")?;
+        Ok(Rc::new(Self::new(max, heal_ticks)))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
+impl InteractiveComponent for HealthComponent {
+    fn as_debug(&self) -> Option<&dyn Debug> {
+        Some(self)
+    }
+}
+
+register_component_template!("health", HealthComponent);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fall_damage_beyond_safe_distance() {
+        assert!(fall_damage(0).is_none());
+        assert!(fall_damage(SAFE_FALL_BLOCKS).is_none());
+
+        let (small, _) = fall_damage(SAFE_FALL_BLOCKS + 1).unwrap();
+        let (big, bleeding) = fall_damage(SAFE_FALL_BLOCKS + 4).unwrap();
+        assert!(big > small);
+        assert!(bleeding > 0.0);
+    }
+
+    #[test]
+    fn bleeding_clots_then_heals() {
+        let mut health = HealthComponent::new(100.0, 100);
+        health.injure(50.0, CLOTTING * 10.0, DeathReason::Fall);
+        assert!(!health.is_dead());
+
+        // bleeds for a while
+        let mut ticks = 0;
+        while health.bleeding() > 0.0 {
+            health.tick();
+            ticks += 1;
+            assert!(ticks < 100, "bleeding should clot");
+        }
+        assert!(health.health().value() < 0.5);
+
+        // then heals
+        let before = health.health();
+        health.tick();
+        assert!(health.health() > before);
+    }
+
+    #[test]
+    fn death_by_injury() {
+        let mut health = HealthComponent::new(10.0, 100);
+        health.injure(20.0, 0.0, DeathReason::Fall);
+        assert!(health.is_dead());
+        assert!(matches!(health.cause_of_death(), DeathReason::Fall));
+
+        let mut health = HealthComponent::new(10.0, 100);
+        health.injure(5.0, 10.0, DeathReason::Fall);
+        health.tick();
+        assert!(health.is_dead());
+        assert!(matches!(health.cause_of_death(), DeathReason::BloodLoss));
+    }
+
+    #[test]
+    fn bleeding_out_from_attack_blames_attacker() {
+        let attacker = Entity::from(specs::World::new().create_entity().build());
+        let mut health = HealthComponent::new(10.0, 100);
+        health.injure(5.0, 10.0, DeathReason::Killed(attacker));
+        health.tick();
+        assert!(health.is_dead());
+        assert!(matches!(health.cause_of_death(), DeathReason::Killed(e) if e == attacker));
+    }
+}
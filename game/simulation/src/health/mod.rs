@@ -0,0 +1,5 @@
+mod component;
+mod system;
+
+pub use component::{fall_damage, HealthComponent};
+pub use system::HealthSystem;
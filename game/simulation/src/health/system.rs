@@ -0,0 +1,32 @@
+use common::*;
+
+use crate::ecs::*;
+use crate::health::HealthComponent;
+use crate::simulation::EcsWorldRef;
+
+/// Applies bleeding and healing over time, killing entities that run out of health
+pub struct HealthSystem;
+
+impl<'a> System<'a> for HealthSystem {
+    type SystemData = (
+        Read<'a, EntitiesRes>,
+        Read<'a, EcsWorldRef>,
+        WriteStorage<'a, HealthComponent>,
+    );
+
+    fn run(&mut self, (entities, ecs_world, mut health): Self::SystemData) {
+        for (e, health) in (&entities, &mut health).join() {
+            // may have already been killed outright e.g. by a fall
+            if !health.is_dead() {
+                health.tick();
+            }
+
+            if health.is_dead() {
+                let e = Entity::from(e);
+                let reason = health.cause_of_death();
+                debug!("entity has died of its injuries"; e, "reason" => %reason);
+                ecs_world.kill_entity(e, reason);
+            }
+        }
+    }
+}
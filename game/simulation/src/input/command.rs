@@ -7,7 +7,7 @@ use crate::{AiAction, Exit, SocietyHandle};
 use common::*;
 
 use crate::backend::GameSpeedChange;
-use crate::job::SocietyJobHandle;
+use crate::job::{JobCategory, JobPriority, SocietyJobHandle};
 use std::borrow::Cow;
 use std::path::PathBuf;
 use std::rc::Rc;
@@ -33,6 +33,15 @@ pub enum UiRequest {
 
     CancelJob(SocietyJobHandle),
 
+    SetJobPriority(SocietyJobHandle, JobPriority),
+
+    /// Weight of 0 disables the category for the entity
+    SetWorkPreference {
+        entity: Entity,
+        category: JobCategory,
+        weight: f32,
+    },
+
     SetContainerOwnership {
         container: Entity,
         owner: Option<Option<Entity>>,
@@ -42,6 +51,12 @@ pub enum UiRequest {
     /// Eval the script at the given path
     ExecuteScript(PathBuf),
 
+    /// Save the game state to the given path
+    SaveGame(PathBuf),
+
+    /// Replace the current game state with that loaded from the given path
+    LoadGame(PathBuf),
+
     ToggleEntityLogging {
         entity: Entity,
         enabled: bool,
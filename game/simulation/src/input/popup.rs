@@ -111,22 +111,32 @@ mod content {
     use unit::world::{WorldPoint, WorldPositionRange};
 
     use crate::ai::AiComponent;
+    use crate::definitions::{DefinitionNameComponent, DefinitionRegistry};
     use crate::ecs::*;
     use crate::input::popup::{PopupContentType, RenderedPopupContent};
     use crate::input::{SelectedEntities, SelectedTiles, UiRequest, UiResponse};
-    use crate::item::HaulableItemComponent;
-    use crate::job::{SocietyCommand, SocietyJobHandle};
+    use crate::item::{HaulableItemComponent, ItemFilter};
+    use crate::job::{JobCategory, JobPriority, SocietyCommand, SocietyJobHandle};
+    use crate::society::{Societies, WorkPreferencesComponent, STOCKPILE_FILTERS};
+    use crate::string::CachedStr;
     use crate::{
-        AiAction, BuildTemplate, ContainedInComponent, FollowPathComponent, HaulPurpose,
-        HaulSource, HaulTarget, PlayerSociety, SocietyComponent, SocietyHandle, UiElementComponent,
-        WorldRef,
+        AiAction, BedComponent, BuildTemplate, ContainedInComponent, ContainerComponent,
+        CraftRecipe, FollowPathComponent, HaulPurpose, HaulSource, HaulTarget, HealthComponent,
+        IntoEnumIterator, PlayerSociety, SocietyComponent, SocietyHandle, UiElementComponent,
+        WorkshopComponent, WorldRef,
     };
 
     pub enum ButtonType {
         GoTo(WorldPoint),
         Follow(Entity),
-        // TODO prioritise job
+        Attack(Entity),
         CancelJobs(SmallVec<[SocietyJobHandle; 1]>),
+        SetJobPriority(SmallVec<[SocietyJobHandle; 1]>, JobPriority),
+        ToggleWork {
+            entity: Entity,
+            category: JobCategory,
+            enable: bool,
+        },
         CancelDivineCommand,
         /// Society command or divine command to all subjects
         Command(Option<SocietyHandle>, ButtonCommand),
@@ -139,6 +149,28 @@ mod content {
             display: Rc<dyn Display>,
             outline_only: bool,
         },
+        Craft {
+            society: SocietyHandle,
+            workshop: Entity,
+            recipe: Rc<CraftRecipe>,
+            display: Rc<dyn Display>,
+        },
+        Farm {
+            society: SocietyHandle,
+            range: WorldPositionRange,
+            crop: CachedStr,
+            display: Rc<dyn Display>,
+        },
+        CreateStockpile {
+            society: SocietyHandle,
+            range: WorldPositionRange,
+            filter: ItemFilter,
+            display: &'static str,
+        },
+        RemoveStockpiles(SocietyHandle, WorldPositionRange),
+        ClaimBed(Entity, Entity),
+        MakeBedCommunal(Entity, SocietyHandle),
+        ClearBedOwner(Entity),
     }
 
     /// Individual divine command or society
@@ -165,6 +197,7 @@ mod content {
         subjects_contain_self: bool,
         subjects_are_controllable: bool,
         target_has_path_finding: bool,
+        target_has_health: bool,
         target_is_haulable: bool,
         subjects_are_haulable: bool,
 
@@ -188,11 +221,21 @@ mod content {
                 ReadStorage<'a, SocietyComponent>,
                 ReadStorage<'a, AiComponent>,
                 ReadStorage<'a, FollowPathComponent>,
+                ReadStorage<'a, HealthComponent>,
                 ReadStorage<'a, HaulableItemComponent>,
                 ReadStorage<'a, ContainedInComponent>,
             );
-            let (world_sel, entity_sel, player_soc, socs, ais, paths, haulables, containeds) =
-                <Query as SystemData>::fetch(world);
+            let (
+                world_sel,
+                entity_sel,
+                player_soc,
+                socs,
+                ais,
+                paths,
+                healths,
+                haulables,
+                containeds,
+            ) = <Query as SystemData>::fetch(world);
 
             let subjects = entity_sel.iter();
 
@@ -210,6 +253,9 @@ mod content {
             let target_has_path_finding = target_entity
                 .map(|target| target.has(&paths))
                 .unwrap_or_default();
+            let target_has_health = target_entity
+                .map(|target| target.has(&healths))
+                .unwrap_or_default();
 
             let is_haulable = |e: Entity| {
                 e.has(&haulables)
@@ -233,6 +279,7 @@ mod content {
                 subjects_contain_self,
                 subjects_are_controllable,
                 target_has_path_finding,
+                target_has_health,
                 target_is_haulable,
                 subjects_are_haulable,
                 player_society: player_soc,
@@ -254,9 +301,15 @@ mod content {
             Read<'a, WorldRef>,
             ReadStorage<'a, AiComponent>,
             ReadStorage<'a, UiElementComponent>,
+            ReadStorage<'a, WorkshopComponent>,
+            ReadStorage<'a, DefinitionNameComponent>,
+            ReadStorage<'a, BedComponent>,
+            ReadStorage<'a, ContainerComponent>,
+            ReadStorage<'a, WorkPreferencesComponent>,
         );
 
-        let (voxel_world, ais, uis) = <Query as SystemData>::fetch(world);
+        let (voxel_world, ais, uis, workshops, def_names, beds, containers, work_prefs) =
+            <Query as SystemData>::fetch(world);
 
         let state = State::fetch(world, ty);
         let mut buttons = Buttons::default();
@@ -281,6 +334,19 @@ mod content {
                     None
                 });
 
+                // attack target entity
+                buttons.add(|| {
+                    if state.subjects_are_controllable
+                        && !state.subjects_contain_self
+                        && state.subjects_have_ai
+                        && state.target_has_health
+                    {
+                        return Some(ButtonType::Attack(target_entity));
+                    }
+
+                    None
+                });
+
                 // cancel divine command
                 buttons.add(|| {
                     if state.subjects_are_controllable
@@ -327,34 +393,129 @@ mod content {
                     }
                 });
 
+                // jobs of selected + target
+                let jobs = if state.player_society.has() {
+                    let include_target = if state.subjects().contains(&target_entity) {
+                        None // dont duplicate
+                    } else {
+                        Some(target_entity)
+                    };
+
+                    state
+                        .subjects()
+                        .iter()
+                        .copied()
+                        .chain(include_target.into_iter())
+                        .filter_map(|e| {
+                            e.get(&uis)
+                                .map(|ui| ui.build_job)
+                                .filter(|job| *state.player_society == job.society())
+                        })
+                        .collect::<SmallVec<[_; 1]>>()
+                } else {
+                    SmallVec::new()
+                };
+
                 // cancel job
                 buttons.add(|| {
-                    // cancel all selected + target job
-                    if state.player_society.has() {
-                        let include_target = if state.subjects().contains(&target_entity) {
-                            None // dont duplicate
-                        } else {
-                            Some(target_entity)
+                    if !jobs.is_empty() {
+                        return Some(ButtonType::CancelJobs(jobs.clone()));
+                    }
+
+                    None
+                });
+
+                // prioritise job
+                buttons.add_multiple(|add| {
+                    if !jobs.is_empty() {
+                        for priority in JobPriority::iter() {
+                            add(ButtonType::SetJobPriority(jobs.clone(), priority));
+                        }
+                    }
+                });
+
+                // toggle work preferences
+                buttons.add_multiple(|add| {
+                    let prefs = match target_entity.get(&work_prefs) {
+                        Some(prefs) => prefs,
+                        None => return,
+                    };
+
+                    let society = world
+                        .component::<SocietyComponent>(target_entity)
+                        .ok()
+                        .map(|comp| comp.handle());
+                    if *state.player_society != society {
+                        return;
+                    }
+
+                    for category in JobCategory::iter() {
+                        add(ButtonType::ToggleWork {
+                            entity: target_entity,
+                            category,
+                            enable: !prefs.is_enabled(category),
+                        });
+                    }
+                });
+
+                // craft at workshop
+                buttons.add_multiple(|add| {
+                    if let Some(soc) = state.player_society.get() {
+                        if !target_entity.has(&workshops) {
+                            return;
+                        }
+
+                        let workshop_def = match target_entity.get(&def_names) {
+                            Some(def) => def.0,
+                            None => return,
                         };
 
-                        let jobs = state
-                            .subjects()
-                            .iter()
-                            .copied()
-                            .chain(include_target.into_iter())
-                            .filter_map(|e| {
-                                e.get(&uis)
-                                    .map(|ui| ui.build_job)
-                                    .filter(|job| *state.player_society == job.society())
-                            })
-                            .collect::<SmallVec<[_; 1]>>();
+                        for (def, recipe, name) in world.recipes() {
+                            if recipe.workshop() != workshop_def {
+                                continue;
+                            }
 
-                        if !jobs.is_empty() {
-                            return Some(ButtonType::CancelJobs(jobs));
+                            let name = match name {
+                                Some(s) => Rc::new(s.clone()) as Rc<dyn Display>,
+                                None => Rc::new(*def) as Rc<dyn Display>,
+                            };
+
+                            add(ButtonType::Craft {
+                                society: soc,
+                                workshop: target_entity,
+                                recipe: recipe.clone(),
+                                display: name,
+                            });
                         }
                     }
+                });
 
-                    None
+                // bed ownership, shared with its container
+                buttons.add_multiple(|add| {
+                    let bed = match (target_entity.get(&beds), target_entity.get(&containers)) {
+                        (Some(_), Some(container)) => container,
+                        _ => return,
+                    };
+
+                    if state.subjects_are_controllable
+                        && state.single_subject
+                        && !state.subjects_contain_self
+                    {
+                        let subject = state.subjects()[0]; // checked single
+                        if bed.owner != Some(subject) {
+                            add(ButtonType::ClaimBed(target_entity, subject));
+                        }
+                    }
+
+                    if let Some(soc) = state.player_society.get() {
+                        if bed.communal() != Some(soc) {
+                            add(ButtonType::MakeBedCommunal(target_entity, soc));
+                        }
+                    }
+
+                    if bed.owner.is_some() {
+                        add(ButtonType::ClearBedOwner(target_entity));
+                    }
                 });
             }
             PopupContentType::TargetPoint(target_pos) => {
@@ -423,7 +584,14 @@ mod content {
                                     None => Rc::new(*def) as Rc<dyn Display>,
                                 };
 
-                                if let Some(range) = selection.range().above() {
+                                // floors replace the selected blocks, everything else is built on top
+                                let range = if template.replaces_floor() {
+                                    Some(selection.range().clone())
+                                } else {
+                                    selection.range().above()
+                                };
+
+                                if let Some(range) = range {
                                     let outline = if template.supports_outline()
                                         && range.iter_outline().is_some()
                                     {
@@ -453,6 +621,59 @@ mod content {
                             }
                         }
                     });
+
+                    // farm zone
+                    buttons.add_multiple(|add| {
+                        if let Some(soc) = state.player_society.get() {
+                            let definitions = world.resource::<DefinitionRegistry>();
+                            for (crop, definition) in definitions.iter_category("crops") {
+                                let name = match definition
+                                    .find_component("kind")
+                                    .and_then(|any| any.downcast_ref::<KindComponent>())
+                                {
+                                    Some(kind) => Rc::new(kind.to_string()) as Rc<dyn Display>,
+                                    None => Rc::new(crop) as Rc<dyn Display>,
+                                };
+
+                                add(ButtonType::Farm {
+                                    society: soc,
+                                    range: selection.range().clone(),
+                                    crop,
+                                    display: name,
+                                });
+                            }
+                        }
+                    });
+
+                    // stockpile zone
+                    buttons.add_multiple(|add| {
+                        if let Some(soc) = state.player_society.get() {
+                            let range = selection.range();
+                            for (name, filter) in STOCKPILE_FILTERS.iter() {
+                                add(ButtonType::CreateStockpile {
+                                    society: soc,
+                                    range: range.clone(),
+                                    filter: *filter,
+                                    display: *name,
+                                });
+                            }
+
+                            let overlaps = world
+                                .resource::<Societies>()
+                                .society_by_handle(soc)
+                                .map(|society| {
+                                    society
+                                        .stockpiles()
+                                        .iter()
+                                        .any(|stockpile| stockpile.range().intersects(range))
+                                })
+                                .unwrap_or(false);
+
+                            if overlaps {
+                                add(ButtonType::RemoveStockpiles(soc, range.clone()));
+                            }
+                        }
+                    });
                 }
             }
         }
@@ -499,6 +720,7 @@ mod content {
                 Follow(target) => {
                     UiRequest::IssueDivineCommand(AiAction::Follow { target, radius: 3 })
                 }
+                Attack(target) => UiRequest::IssueDivineCommand(AiAction::Attack(target)),
                 Command(Some(soc), command) => {
                     // command to player's society
                     let cmd = match command {
@@ -543,6 +765,20 @@ mod content {
                         issue_req(UiRequest::CancelJob(job));
                     }
                 }
+                SetJobPriority(jobs, priority) => {
+                    return for job in jobs.into_iter() {
+                        issue_req(UiRequest::SetJobPriority(job, priority));
+                    }
+                }
+                ToggleWork {
+                    entity,
+                    category,
+                    enable,
+                } => UiRequest::SetWorkPreference {
+                    entity,
+                    category,
+                    weight: if enable { 1.0 } else { 0.0 },
+                },
                 Build {
                     society,
                     range,
@@ -566,6 +802,47 @@ mod content {
                         };
                     }
                 }
+                Craft {
+                    society,
+                    workshop,
+                    recipe,
+                    ..
+                } => {
+                    UiRequest::IssueSocietyCommand(society, SocietyCommand::Craft(workshop, recipe))
+                }
+                Farm {
+                    society,
+                    range,
+                    crop,
+                    ..
+                } => UiRequest::IssueSocietyCommand(society, SocietyCommand::Farm(range, crop)),
+                CreateStockpile {
+                    society,
+                    range,
+                    filter,
+                    ..
+                } => UiRequest::IssueSocietyCommand(
+                    society,
+                    SocietyCommand::CreateStockpile(range, filter),
+                ),
+                RemoveStockpiles(society, range) => {
+                    UiRequest::IssueSocietyCommand(society, SocietyCommand::RemoveStockpiles(range))
+                }
+                ClaimBed(bed, owner) => UiRequest::SetContainerOwnership {
+                    container: bed,
+                    owner: Some(Some(owner)),
+                    communal: None,
+                },
+                MakeBedCommunal(bed, society) => UiRequest::SetContainerOwnership {
+                    container: bed,
+                    owner: None,
+                    communal: Some(Some(society)),
+                },
+                ClearBedOwner(bed) => UiRequest::SetContainerOwnership {
+                    container: bed,
+                    owner: Some(None),
+                    communal: None,
+                },
             };
 
             issue_req(req);
@@ -585,8 +862,25 @@ mod content {
             let s = match self {
                 GoTo(_) => "Go here",
                 Follow(_) => "Follow",
+                Attack(_) => "Attack",
                 CancelJobs(jobs) if jobs.len() == 1 => "Cancel job",
                 CancelJobs(jobs) => return write!(f, "Cancel {} jobs", jobs.len()),
+                SetJobPriority(jobs, priority) if jobs.len() == 1 => {
+                    return write!(f, "Set job priority: {}", priority)
+                }
+                SetJobPriority(jobs, priority) => {
+                    return write!(f, "Set {} jobs priority: {}", jobs.len(), priority)
+                }
+                ToggleWork {
+                    category, enable, ..
+                } => {
+                    return write!(
+                        f,
+                        "{} {} work",
+                        if *enable { "Enable" } else { "Disable" },
+                        category
+                    )
+                }
                 CancelDivineCommand => "Cancel divine command",
                 Command(soc, cmd) => {
                     // special case
@@ -618,6 +912,13 @@ mod content {
                         if *outline_only { " (outline)" } else { "" }
                     )
                 }
+                Craft { display, .. } => return write!(f, "Craft: {}", display),
+                Farm { display, .. } => return write!(f, "Farm: {}", display),
+                CreateStockpile { display, .. } => return write!(f, "Stockpile: {}", display),
+                RemoveStockpiles(_, _) => "Remove stockpiles",
+                ClaimBed(_, _) => "Claim bed",
+                MakeBedCommunal(_, _) => "Make bed communal",
+                ClearBedOwner(_) => "Clear bed owner",
             };
             f.write_str(s)
         }
@@ -96,7 +96,7 @@ impl<'a> System<'a> for InputSystem<'a> {
             // fallback to looking for normal entities
             ui_elem.or_else(|| {
                 spatial
-                    .query_in_radius(&ecs_world, point, DISTANCE_THRESHOLD)
+                    .query_nearest(&ecs_world, point, 1, DISTANCE_THRESHOLD)
                     .next()
                     .map(|(e, _, _)| e)
             })
@@ -288,7 +288,7 @@ mod selected_tiles {
     use unit::world::SlabLocation;
     use world::block::BlockType;
 
-    use crate::input::SelectionModification;
+    use crate::input::{BlockPlacement, SelectionModification};
     use crate::InnerWorldRef;
 
     use super::*;
@@ -366,6 +366,20 @@ mod selected_tiles {
             }
         }
 
+        /// Completely selects the exact given range, for input that doesn't come from the mouse
+        pub fn select_range(&mut self, range: WorldPositionRange, world: &WorldRef) {
+            let (a, b) = range.bounds();
+            debug!("selecting tiles"; "min" => %a, "max" => %b);
+
+            self.last = Some((a, b, SelectionProgress::Complete));
+            self.current = Some(CurrentSelection::new(
+                range,
+                SelectionProgress::Complete,
+                self.current.take(),
+                &world.borrow(),
+            ));
+        }
+
         pub fn clear(&mut self) {
             self.current = None;
         }
@@ -388,6 +402,18 @@ mod selected_tiles {
             }
         }
 
+        /// Range of blocks to fill with the given placement, if there is a complete selection
+        pub fn fill_range(&self, placement: BlockPlacement) -> Option<WorldPositionRange> {
+            let (mut from, mut to) = self.current_selected()?.range().bounds();
+            if let BlockPlacement::PlaceAbove = placement {
+                // move the range up 1 block
+                from = from.above();
+                to = to.above();
+            }
+
+            Some(WorldPositionRange::with_inclusive_range(from, to))
+        }
+
         pub fn modify(&mut self, modification: SelectionModification, world: &WorldRef) {
             if let Some(
                 sel @ CurrentSelection {
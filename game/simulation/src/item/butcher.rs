@@ -0,0 +1,238 @@
+use std::rc::Rc;
+
+use common::*;
+use unit::world::WorldPoint;
+
+use crate::ecs::*;
+use crate::event::DeathReason;
+use crate::item::spawn_item_stack;
+use crate::string::{CachedStr, StringCache};
+use crate::TransformComponent;
+
+/// Leaves behind a corpse entity of the given definition when killed
+#[derive(Component, EcsComponent, Clone, Debug)]
+#[storage(DenseVecStorage)]
+#[name("leaves-corpse")]
+#[clone(disallow)]
+pub struct LeavesCorpseComponent {
+    corpse: CachedStr,
+}
+
+/// Can be butchered into a number of meat items
+#[derive(Component, EcsComponent, Clone, Debug)]
+#[storage(DenseVecStorage)]
+#[name("butcherable")]
+#[clone(disallow)]
+pub struct ButcherableComponent {
+    /// Definition of the meat item
+    pub meat: CachedStr,
+    pub count: u16,
+    /// Time taken to butcher
+    pub ticks: u32,
+}
+
+/// Corpses to be spawned for the given dying entities, must be called before they are deleted
+pub fn find_corpses(
+    world: &EcsWorld,
+    dying: impl Iterator<Item = Entity>,
+) -> Vec<(CachedStr, WorldPoint)> {
+    let corpses = world.read_storage::<LeavesCorpseComponent>();
+    let transforms = world.read_storage::<TransformComponent>();
+    dying
+        .unique()
+        .filter_map(|e| {
+            world
+                .components(e, (&corpses, &transforms))
+                .map(|(corpse, transform)| (corpse.corpse, transform.position))
+        })
+        .collect()
+}
+
+pub fn spawn_corpses(world: &EcsWorld, corpses: Vec<(CachedStr, WorldPoint)>) {
+    for (definition, pos) in corpses {
+        let result = world.build_entity(definition.as_ref()).map(|builder| {
+            builder
+                .with_position(pos)
+                .doesnt_need_to_be_accessible()
+                .spawn()
+        });
+
+        match result {
+            Ok(Ok(corpse)) => debug!("spawned corpse"; corpse, "definition" => %definition),
+            Ok(Err(err)) => {
+                warn!("failed to spawn corpse"; "definition" => %definition, "error" => %err)
+            }
+            Err(err) => {
+                warn!("invalid corpse definition"; "definition" => %definition, "error" => %err)
+            }
+        }
+    }
+}
+
+/// Destroys the corpse and spawns its meat in a stack in its place
+pub fn butcher_corpse(world: &EcsWorld, corpse: Entity) -> Result<Entity, Box<dyn Error>> {
+    let (meat, count, pos) = {
+        let butcherable = world.component::<ButcherableComponent>(corpse)?;
+        let transform = world.component::<TransformComponent>(corpse)?;
+        (butcherable.meat, butcherable.count, transform.position)
+    };
+
+    // spawn first so the corpse is left intact if the meat can't be created
+    let stack = spawn_item_stack(world, meat, count, pos)?;
+    world.kill_entity(corpse, DeathReason::Butchered);
+
+    debug!("butchered corpse"; corpse, "meat" => %meat, "count" => count);
+    Ok(stack)
+}
+
+impl<V: Value> ComponentTemplate<V> for LeavesCorpseComponent {
+    fn construct(
+        values: &mut Map<V>,
+        string_cache: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let corpse = values.get_string("definition")?;
+        Ok(Rc::new(Self {
+            corpse: string_cache.get(&corpse),
+        }))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
+impl<V: Value> ComponentTemplate<V> for ButcherableComponent {
+    fn construct(
+        values: &mut Map<V>,
+        string_cache: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let meat = values.get_string("meat")?;
+        let count = values.get_int("count")?;
+        let ticks = values.get_int("ticks")?;
+        if count == 0 {
+            return Err(ComponentBuildError::TemplateSpecific(
+                "butcherable meat count should be positive".to_owned(),
+            ));
+        }
+
+        Ok(Rc::new(Self {
+            meat: string_cache.get(&meat),
+            count,
+            ticks,
+        }))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
+register_component_template!("leaves-corpse", LeavesCorpseComponent);
+register_component_template!("butcherable", ButcherableComponent);
+
+#[cfg(test)]
+mod tests {
+    use unit::world::WorldPosition;
+
+    use super::*;
+    use crate::event::EntityEventQueue;
+    use crate::item::ItemStackComponent;
+    use crate::WorldRef;
+
+    const DEFINITIONS: &str = r#"[
+    (
+        uid: "test_meat",
+        components: [
+            {"physical": (size: (1, 1, 1), volume: 1)},
+            {"stackable": (max_count: 10)},
+        ],
+    ),
+    (
+        uid: "test_corpse",
+        components: [
+            {"physical": (size: (2, 2, 1), volume: 10)},
+            {"butcherable": (meat: "test_meat", count: 3, ticks: 10)},
+        ],
+    ),
+    (
+        uid: "test_bad_corpse",
+        components: [
+            {"physical": (size: (2, 2, 1), volume: 10)},
+            {"butcherable": (meat: "nonsense", count: 3, ticks: 10)},
+        ],
+    ),
+]"#;
+
+    fn setup() -> EcsWorld {
+        let definitions = crate::definitions::load_from_str(DEFINITIONS).expect("bad definitions");
+        let mut world = EcsWorld::with_definitions(definitions).expect("bad definitions");
+        world.insert(WorldRef::default());
+        world.insert(EntityEventQueue::default());
+        world.insert(EntitiesToKill::default());
+        world
+    }
+
+    fn spawn_corpse(world: &EcsWorld, definition: &str) -> (Entity, WorldPoint) {
+        let pos = WorldPosition::from((2, 3, 1)).centred();
+        let corpse = world
+            .build_entity(definition)
+            .unwrap()
+            .with_position(pos)
+            .doesnt_need_to_be_accessible()
+            .spawn()
+            .unwrap();
+        (corpse, pos)
+    }
+
+    fn to_kill(world: &EcsWorld) -> Vec<Entity> {
+        world
+            .resource::<EntitiesToKill>()
+            .iter()
+            .map(|(e, _)| e)
+            .collect()
+    }
+
+    #[test]
+    fn corpse_becomes_meat() {
+        let world = setup();
+        let (corpse, pos) = spawn_corpse(&world, "test_corpse");
+
+        let stack = butcher_corpse(&world, corpse).expect("butchering failed");
+        assert_eq!(to_kill(&world), vec![corpse]);
+
+        let meat = world.component::<ItemStackComponent>(stack).unwrap();
+        assert_eq!(meat.stack.total_count(), 3);
+
+        let transform = world.component::<TransformComponent>(stack).unwrap();
+        assert_eq!(transform.position, pos);
+    }
+
+    #[test]
+    fn corpse_survives_failed_butchering() {
+        let world = setup();
+        let (corpse, _) = spawn_corpse(&world, "test_bad_corpse");
+
+        assert!(butcher_corpse(&world, corpse).is_err());
+        assert!(to_kill(&world).is_empty());
+        assert!(world.has_component::<ButcherableComponent>(corpse));
+    }
+
+    #[test]
+    fn not_butcherable() {
+        let world = setup();
+        let (corpse, _) = spawn_corpse(&world, "test_meat");
+
+        assert!(butcher_corpse(&world, corpse).is_err());
+        assert!(to_kill(&world).is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 use std::rc::Rc;
 
 use common::derive_more::*;
+use common::NormalizedFloat;
+use unit::drink::Hydration;
 use unit::food::Nutrition;
 
 use crate::ecs::*;
@@ -12,6 +14,7 @@ use crate::string::StringCache;
 #[derive(Component, EcsComponent, Constructor, Clone, Debug)]
 #[storage(DenseVecStorage)]
 #[name("condition")]
+#[save]
 pub struct ConditionComponent(pub ItemCondition);
 
 #[derive(Component, EcsComponent, Constructor, Clone, Debug)]
@@ -35,10 +38,38 @@ pub struct EdibleItemComponent {
 #[name("throwable")]
 pub struct ThrowableItemComponent;
 
-// TODO drinkable
+/// Holds water that can be drunk, e.g. a waterskin
+#[derive(Component, EcsComponent, Constructor, Clone, Debug)]
+#[name("drinkable")]
+#[storage(DenseVecStorage)]
+pub struct DrinkableItemComponent {
+    /// Total hydration available from this item. Multiplied by item condition to get remaining
+    /// hydration
+    pub total_hydration: Hydration,
+}
+
 // TODO splatterable (after throw, if walked on)
 // TODO weapon (damage to target per hit, damage to own condition per hit, attack speed, cooldown)
 
+impl SaveComponent for ConditionComponent {
+    type Saved = f32;
+
+    fn save(&self, _: &SaveContext) -> Option<Self::Saved> {
+        Some(self.0.value().value())
+    }
+
+    fn load(
+        condition: Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        _: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let mut comp = world.component_mut::<Self>(entity)?;
+        comp.0.set(NormalizedFloat::clamped(condition));
+        Ok(())
+    }
+}
+
 impl<V: Value> ComponentTemplate<V> for ConditionComponent {
     fn construct(
         _: &mut Map<V>,
@@ -86,6 +117,22 @@ impl<V: Value> ComponentTemplate<V> for EdibleItemComponent {
     crate::as_any!();
 }
 
+impl<V: Value> ComponentTemplate<V> for DrinkableItemComponent {
+    fn construct(
+        values: &mut Map<V>,
+        _: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError> {
+        let total_hydration = Hydration::new(values.get_int("total_hydration")?);
+        Ok(Rc::new(Self { total_hydration }))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
 impl<V: Value> ComponentTemplate<V> for ThrowableItemComponent {
     fn construct(
         _: &mut Map<V>,
@@ -103,4 +150,5 @@ impl<V: Value> ComponentTemplate<V> for ThrowableItemComponent {
 
 register_component_template!("breakable", ConditionComponent);
 register_component_template!("edible", EdibleItemComponent);
+register_component_template!("drinkable", DrinkableItemComponent);
 register_component_template!("throwable", ThrowableItemComponent);
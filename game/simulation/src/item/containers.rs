@@ -49,6 +49,7 @@ pub enum ContainersError {
 #[name("contained")]
 #[storage(DenseVecStorage)]
 #[clone(disallow)]
+#[save]
 pub enum ContainedInComponent {
     Container(Entity),
     InventoryOf(Entity),
@@ -457,6 +458,46 @@ impl ContainedInComponent {
     }
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum SavedContainedIn {
+    Container(SavedEntity),
+    InventoryOf(SavedEntity),
+    StackOf(SavedEntity),
+}
+
+impl SaveComponent for ContainedInComponent {
+    type Saved = SavedContainedIn;
+
+    fn save(&self, ctx: &SaveContext) -> Option<Self::Saved> {
+        match self {
+            ContainedInComponent::Container(e) => ctx.entity(*e).map(SavedContainedIn::Container),
+            ContainedInComponent::InventoryOf(e) => {
+                ctx.entity(*e).map(SavedContainedIn::InventoryOf)
+            }
+            ContainedInComponent::StackOf(e) => ctx.entity(*e).map(SavedContainedIn::StackOf),
+        }
+    }
+
+    fn load(
+        saved: Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        ctx: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let comp = match saved {
+            SavedContainedIn::Container(e) => ContainedInComponent::Container(ctx.entity(e)?),
+            SavedContainedIn::InventoryOf(e) => ContainedInComponent::InventoryOf(ctx.entity(e)?),
+            SavedContainedIn::StackOf(e) => ContainedInComponent::StackOf(ctx.entity(e)?),
+        };
+
+        // contained items have no transform. hauls are not saved, so hauled items are just held
+        // after loading
+        let _ = world.remove_now::<TransformComponent>(entity);
+        let _ = world.add_now(entity, comp);
+        Ok(())
+    }
+}
+
 impl Display for ContainedInComponent {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
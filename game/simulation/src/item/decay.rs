@@ -0,0 +1,201 @@
+use std::rc::Rc;
+
+use common::*;
+
+use crate::ecs::*;
+use crate::item::{
+    ButcherableComponent, ConditionComponent, ContainedInComponent, EdibleItemComponent,
+};
+use crate::needs::food::BeingEatenComponent;
+use crate::simulation::Tick;
+use crate::string::StringCache;
+
+/// Decay is applied every this many ticks rather than every tick
+const DECAY_INTERVAL: u32 = 20;
+
+/// Condition of the item degrades over time, e.g. food spoiling
+#[derive(Component, EcsComponent, Clone, Debug)]
+#[storage(DenseVecStorage)]
+#[name("decay")]
+#[clone(disallow)]
+#[save]
+pub struct DecayComponent {
+    /// Condition lost per tick
+    rate: f32,
+
+    /// Multiplier applied to rate while stored inside a container
+    contained_multiplier: f32,
+}
+
+/// Degrades item condition over time. Food that decays completely becomes inedible
+pub struct DecaySystem;
+
+impl DecayComponent {
+    /// Ticks is the number needed to go from perfect condition to broken, outside of a container
+    pub fn new(ticks: u32, contained_multiplier: f32) -> Self {
+        Self {
+            rate: 1.0 / ticks.max(1) as f32,
+            contained_multiplier,
+        }
+    }
+
+    fn decay_for(&self, ticks: u32, contained: Option<&ContainedInComponent>) -> NormalizedFloat {
+        let multiplier = match contained {
+            Some(ContainedInComponent::Container(_)) => self.contained_multiplier,
+            _ => 1.0,
+        };
+
+        NormalizedFloat::clamped(self.rate * multiplier * ticks as f32)
+    }
+}
+
+impl<'a> System<'a> for DecaySystem {
+    type SystemData = (
+        Read<'a, EntitiesRes>,
+        Read<'a, LazyUpdate>,
+        ReadStorage<'a, DecayComponent>,
+        ReadStorage<'a, ContainedInComponent>,
+        ReadStorage<'a, EdibleItemComponent>,
+        ReadStorage<'a, BeingEatenComponent>,
+        WriteStorage<'a, ConditionComponent>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, lazy, decay, contained, edible, being_eaten, mut condition): Self::SystemData,
+    ) {
+        if Tick::fetch().value() % DECAY_INTERVAL != 0 {
+            return;
+        }
+
+        // food that is currently being eaten is left alone until it's finished
+        for (e, decay, condition, contained, edible, _) in (
+            &entities,
+            &decay,
+            &mut condition,
+            contained.maybe(),
+            edible.maybe(),
+            !&being_eaten,
+        )
+            .join()
+        {
+            condition.0 -= decay.decay_for(DECAY_INTERVAL, contained);
+
+            if condition.0.is_broken() {
+                let e = Entity::from(e);
+                lazy.remove::<DecayComponent>(e.into());
+
+                // nothing worth butchering from a rotten corpse
+                lazy.remove::<ButcherableComponent>(e.into());
+
+                if edible.is_some() {
+                    debug!("food has rotted away"; e);
+                    lazy.remove::<EdibleItemComponent>(e.into());
+                } else {
+                    debug!("item has decayed"; e);
+                }
+            }
+        }
+    }
+}
+
+impl SaveComponent for DecayComponent {
+    /// (rate, contained multiplier)
+    type Saved = (f32, f32);
+
+    fn save(&self, _: &SaveContext) -> Option<Self::Saved> {
+        Some((self.rate, self.contained_multiplier))
+    }
+
+    fn load(
+        (rate, contained_multiplier): Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        _: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let _ = world.add_now(
+            entity,
+            Self {
+                rate,
+                contained_multiplier,
+            },
+        );
+        Ok(())
+    }
+}
+
+impl<V: Value> ComponentTemplate<V> for DecayComponent {
+    fn construct(
+        values: &mut Map<V>,
+        _: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let ticks = values.get_int("ticks")?;
+        let contained_multiplier = match values.get_float("contained_multiplier") {
+            Ok(f) => f,
+            Err(ComponentBuildError::KeyNotFound(_)) => 1.0, // containers don't help by default
+            Err(e) => return Err(e),
+        };
+
+        Ok(Rc::new(Self::new(ticks, contained_multiplier)))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
+register_component_template!("decay", DecayComponent);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::ItemCondition;
+
+    #[test]
+    fn containers_slow_decay() {
+        let decay = DecayComponent::new(100, 0.25);
+        let container = ContainedInComponent::Container(Entity::from(
+            specs::World::new().create_entity().build(),
+        ));
+
+        let outside = decay.decay_for(10, None);
+        let inside = decay.decay_for(10, Some(&container));
+        assert!(inside < outside);
+        assert!((outside.value() - 0.1).abs() < 0.0001);
+    }
+
+    #[test]
+    fn decay_and_condition_are_saved() {
+        let world = EcsWorld::new();
+        let src: Entity = world.create_entity().build().into();
+        let dst: Entity = world.create_entity().build().into();
+        let _ = world.add_now(src, DecayComponent::new(100, 0.25));
+        let _ = world.add_now(
+            src,
+            ConditionComponent::new(ItemCondition::new(NormalizedFloat::new(0.4))),
+        );
+
+        // destination spawned from its definition in perfect condition
+        let _ = world.add_now(dst, ConditionComponent::new(ItemCondition::perfect()));
+
+        let ctx = SaveContext::default();
+        for (name, serialized) in world.save_components_for(src, &ctx) {
+            let serialized = serialized.expect("failed to save");
+            world
+                .load_component_for(name, dst, &serialized, &LoadContext::default())
+                .expect("failed to load");
+        }
+
+        let condition = world.component::<ConditionComponent>(dst).unwrap();
+        assert!((condition.0.value().value() - 0.4).abs() < 0.0001);
+
+        let decay = world.component::<DecayComponent>(dst).unwrap();
+        assert!((decay.rate - 0.01).abs() < 0.0001);
+        assert!((decay.contained_multiplier - 0.25).abs() < 0.0001);
+    }
+}
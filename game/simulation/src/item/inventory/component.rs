@@ -14,6 +14,7 @@ use crate::item::inventory::equip::EquipSlot;
 use crate::item::inventory::{Container, HeldEntity};
 use crate::item::{ItemFilter, ItemFilterable};
 use crate::string::StringCache;
+use crate::PhysicalComponent;
 
 /// Temporary dumb component to hold equip slots and containers. Will eventually be a view on top of
 /// the physical body tree
@@ -21,6 +22,7 @@ use crate::string::StringCache;
 #[storage(DenseVecStorage)]
 #[name("inventory")]
 #[clone(disallow)]
+#[save]
 pub struct InventoryComponent {
     equip_slots: Vec<EquipSlot>,
 
@@ -33,6 +35,7 @@ pub struct InventoryComponent {
 #[storage(HashMapStorage)]
 #[name("container")]
 #[clone(disallow)]
+#[save]
 pub struct ContainerComponent {
     pub container: Container,
 
@@ -657,6 +660,15 @@ impl ContainerComponent {
         self.communal
     }
 
+    /// Whether the given entity of the given society may use this, e.g. to sleep in a bed. Only
+    /// the owner if there is one, otherwise any member of the communal society
+    pub fn can_be_used_by(&self, user: Entity, society: Option<SocietyHandle>) -> bool {
+        match self.owner {
+            Some(owner) => owner == user,
+            None => self.communal.is_some() && self.communal == society,
+        }
+    }
+
     /// Must be kept in sync with society
     pub(in crate::item) fn make_communal(
         &mut self,
@@ -666,6 +678,158 @@ impl ContainerComponent {
     }
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SavedContainer {
+    contents: Vec<SavedEntity>,
+    owner: Option<SavedEntity>,
+    communal: Option<SavedSociety>,
+}
+
+impl SaveComponent for ContainerComponent {
+    type Saved = SavedContainer;
+
+    fn save(&self, ctx: &SaveContext) -> Option<Self::Saved> {
+        Some(SavedContainer {
+            contents: self
+                .container
+                .contents()
+                .filter_map(|held| ctx.entity(held.entity))
+                .collect(),
+            owner: self.owner.and_then(|e| ctx.entity(e)),
+            communal: self.communal.and_then(|soc| ctx.society(soc)),
+        })
+    }
+
+    fn load(
+        saved: Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        ctx: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        {
+            let physicals = world.read_storage::<PhysicalComponent>();
+            let mut container = world.component_mut::<Self>(entity)?;
+
+            for item in saved.contents {
+                let item = ctx.entity(item)?;
+                let physical = item.get(&physicals).ok_or_else(|| {
+                    ComponentSaveError::Specific(format!("contained item {} has no physical", item))
+                })?;
+
+                container
+                    .container
+                    .add(&HeldEntity {
+                        entity: item,
+                        volume: physical.volume,
+                        size: physical.size,
+                    })
+                    .map_err(|err| ComponentSaveError::Specific(err.to_string()))?;
+            }
+
+            container.owner = saved.owner.map(|e| ctx.entity(e)).transpose()?;
+        }
+
+        if let Some(society) = saved.communal {
+            let society = ctx.society(society)?;
+            world
+                .helpers_containers()
+                .set_container_communal(entity, Some(society))
+                .map_err(|err| ComponentSaveError::Specific(err.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SavedInventory {
+    equip_slots: Vec<SavedEquipSlot>,
+    containers: Vec<SavedEntity>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum SavedEquipSlot {
+    Empty,
+    Occupied(SavedEntity),
+    Overflow(SavedEntity),
+}
+
+impl SaveComponent for InventoryComponent {
+    type Saved = SavedInventory;
+
+    /// Items that aren't saved leave an empty slot behind
+    fn save(&self, ctx: &SaveContext) -> Option<Self::Saved> {
+        let slot = |e: Entity, f: fn(SavedEntity) -> SavedEquipSlot| {
+            ctx.entity(e).map(f).unwrap_or(SavedEquipSlot::Empty)
+        };
+
+        Some(SavedInventory {
+            equip_slots: self
+                .equip_slots
+                .iter()
+                .map(|s| match s {
+                    EquipSlot::Empty => SavedEquipSlot::Empty,
+                    EquipSlot::Occupied(held) => slot(held.entity, SavedEquipSlot::Occupied),
+                    EquipSlot::Overflow(e) => slot(*e, SavedEquipSlot::Overflow),
+                })
+                .collect(),
+            containers: self
+                .containers
+                .iter()
+                .filter_map(|e| ctx.entity(*e))
+                .collect(),
+        })
+    }
+
+    fn load(
+        saved: Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        ctx: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let physicals = world.read_storage::<PhysicalComponent>();
+        let mut inventory = world.component_mut::<Self>(entity)?;
+
+        if saved.equip_slots.len() != inventory.equip_slots.len() {
+            return Err(ComponentSaveError::Specific(format!(
+                "expected {} equip slots but {} were saved",
+                inventory.equip_slots.len(),
+                saved.equip_slots.len()
+            )));
+        }
+
+        for (slot, saved) in inventory.equip_slots.iter_mut().zip(saved.equip_slots) {
+            *slot = match saved {
+                SavedEquipSlot::Empty => EquipSlot::Empty,
+                SavedEquipSlot::Occupied(item) => {
+                    let item = ctx.entity(item)?;
+                    let physical = item.get(&physicals).ok_or_else(|| {
+                        ComponentSaveError::Specific(format!(
+                            "equipped item {} has no physical",
+                            item
+                        ))
+                    })?;
+
+                    EquipSlot::Occupied(HeldEntity {
+                        entity: item,
+                        volume: physical.volume,
+                        size: physical.size,
+                    })
+                }
+                SavedEquipSlot::Overflow(item) => EquipSlot::Overflow(ctx.entity(item)?),
+            };
+        }
+
+        inventory.containers = saved
+            .containers
+            .into_iter()
+            .map(|e| ctx.entity(e))
+            .collect::<Result<_, _>>()?;
+
+        Ok(())
+    }
+}
+
 impl<V: Value> ComponentTemplate<V> for InventoryComponentTemplate {
     fn construct(
         values: &mut Map<V>,
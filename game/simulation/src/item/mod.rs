@@ -1,24 +1,34 @@
 pub use self::inventory::{
     Container, ContainerComponent, ContainerError, ContainerResolver, FoundSlot, InventoryComponent,
 };
-pub use component::{ConditionComponent, EdibleItemComponent, ThrowableItemComponent};
+pub use butcher::{
+    butcher_corpse, find_corpses, spawn_corpses, ButcherableComponent, LeavesCorpseComponent,
+};
+pub use component::{
+    ConditionComponent, DrinkableItemComponent, EdibleItemComponent, ThrowableItemComponent,
+};
 pub use condition::{ItemCondition, ItemConditionGrade};
 pub use containers::{ContainedInComponent, ContainersError, StackableComponent};
+pub use decay::{DecayComponent, DecaySystem};
 pub use filter::{ItemFilter, ItemFilterable};
 pub use haul::{
     EndHaulBehaviour, HaulSystem, HaulType, HaulableItemComponent, HauledItemComponent,
 };
+pub use spawn::spawn_item_stack;
 pub use stack::ItemStackComponent;
 
 pub type ItemStack = stack::ItemStack<crate::EcsWorld>;
 pub type ItemStackError = stack::ItemStackError<crate::Entity>;
 
+mod butcher;
 mod component;
 mod condition;
 mod containers;
+mod decay;
 mod filter;
 mod haul;
 mod inventory;
+mod spawn;
 mod stack;
 
 #[cfg(debug_assertions)]
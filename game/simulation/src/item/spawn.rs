@@ -0,0 +1,38 @@
+use common::*;
+use unit::world::WorldPoint;
+
+use crate::ecs::*;
+use crate::string::CachedStr;
+
+/// Spawns `count` items of the given definition at the given position, combined into a single
+/// stack if the item is stackable. Returns the stack, or the first item if not stackable
+pub fn spawn_item_stack(
+    world: &EcsWorld,
+    definition: CachedStr,
+    count: u16,
+    pos: WorldPoint,
+) -> Result<Entity, Box<dyn Error>> {
+    let mut stack = None;
+    for _ in 0..count {
+        let item = world
+            .build_entity(definition.as_ref())?
+            .with_position(pos)
+            .doesnt_need_to_be_accessible()
+            .spawn()?;
+
+        let containers = world.helpers_containers();
+        match stack {
+            None => {
+                // first item becomes a stack if possible
+                stack = Some(containers.convert_to_stack(item).unwrap_or(item));
+            }
+            Some(stack) => {
+                if let Err(err) = containers.add_to_stack(stack, item) {
+                    debug!("could not add item to stack, leaving it separate"; "error" => %err);
+                }
+            }
+        }
+    }
+
+    stack.ok_or_else(|| "no items to spawn".into())
+}
@@ -3,12 +3,13 @@ use std::collections::VecDeque;
 use std::num::NonZeroU16;
 
 use common::*;
+use unit::space::volume::Volume;
 
 use crate::definitions::DefinitionNameComponent;
 use crate::ecs::*;
 
 use crate::string::CachedStr;
-use crate::Tick;
+use crate::{PhysicalComponent, Tick};
 
 #[derive(Debug, Error, Eq, PartialEq, Clone)]
 pub enum ItemStackError<E: Debug + Display + Eq + Clone> {
@@ -45,12 +46,83 @@ pub enum ItemStackError<E: Debug + Display + Eq + Clone> {
 #[name("item-stack")]
 #[storage(DenseVecStorage)]
 #[clone(disallow)]
+#[save]
 pub struct ItemStackComponent {
     pub stack: crate::item::ItemStack,
     /// Stack that this stack was spawned from
     pub split_from: Option<(Entity, Tick)>,
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SavedItemStack {
+    max_count: u16,
+    /// (item, Some(count) if copyable)
+    contents: Vec<(SavedEntity, Option<u16>)>,
+}
+
+impl SaveComponent for ItemStackComponent {
+    type Saved = SavedItemStack;
+
+    fn save(&self, ctx: &SaveContext) -> Option<Self::Saved> {
+        Some(SavedItemStack {
+            max_count: self.stack.capacity().get(),
+            contents: self
+                .stack
+                .contents_with_copyability()
+                .filter_map(|(e, n)| ctx.entity(e).map(|e| (e, n.map(NonZeroU16::get))))
+                .collect(),
+        })
+    }
+
+    fn load(
+        saved: Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        ctx: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let max_count = NonZeroU16::new(saved.max_count)
+            .ok_or_else(|| ComponentSaveError::Specific("zero stack size".to_owned()))?;
+
+        let contents = saved
+            .contents
+            .into_iter()
+            .map(|(e, n)| ctx.entity(e).map(|e| (e, n.and_then(NonZeroU16::new))))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let stack = ItemStack::restore(max_count, contents.into_iter(), world)
+            .map_err(|err| ComponentSaveError::Specific(err.to_string()))?;
+
+        // stack volume is the sum of its contents
+        {
+            let mut physicals = world.write_storage::<PhysicalComponent>();
+            let volume = stack
+                .contents()
+                .filter_map(|(e, n)| {
+                    e.get(&physicals)
+                        .map(|p| p.volume.get().saturating_mul(n.get()))
+                })
+                .fold(0u16, u16::saturating_add);
+
+            if let Some(physical) = entity.get_mut(&mut physicals) {
+                physical.volume = Volume::new(volume);
+            }
+        }
+
+        if let Ok(mut kind) = world.component_mut::<KindComponent>(entity) {
+            kind.make_stack();
+        }
+
+        let _ = world.add_now(
+            entity,
+            ItemStackComponent {
+                stack,
+                split_from: None,
+            },
+        );
+        Ok(())
+    }
+}
+
 pub trait World {
     type Entity: Debug + Display + Copy + Eq;
     type Homogeneity: Clone;
@@ -155,6 +227,43 @@ impl<W: World> ItemStack<W> {
         Ok(stack)
     }
 
+    /// Restores a stack from contents previously returned from [contents_with_copyability].
+    /// Homogeneity is taken from the first item and not checked for the rest
+    pub fn restore(
+        max_size: NonZeroU16,
+        contents: impl Iterator<Item = (W::Entity, Option<NonZeroU16>)>,
+        world: &W,
+    ) -> Result<Self, ItemStackError<W::Entity>> {
+        let mut contents = contents.peekable();
+        let first = contents.peek().ok_or(ItemStackError::Empty)?.0;
+        let homogeneity = world
+            .homogeneity_for(first)
+            .ok_or(ItemStackError::CantGetHomogeneity(first))?;
+
+        let mut stack = ItemStack {
+            contents: VecDeque::with_capacity(max_size.get() as usize),
+            total_count: 0,
+            max_count: max_size,
+            homogeneity,
+        };
+
+        for (entity, count) in contents {
+            let count = match count {
+                Some(n) => StackedEntityCount::Copyable(n),
+                None => StackedEntityCount::Distinct,
+            };
+
+            let stacked = StackedEntity { entity, count };
+            stack.total_count = stack
+                .total_count
+                .checked_add(stacked.count().get())
+                .ok_or(ItemStackError::Overflow(entity))?;
+            stack.contents.push_back(stacked);
+        }
+
+        Ok(stack)
+    }
+
     fn empty_from_other(other: &Self) -> Self {
         Self {
             contents: VecDeque::with_capacity(other.contents.capacity()),
@@ -338,11 +447,21 @@ impl<W: World> ItemStack<W> {
         self.contents.iter().map(|e| (e.entity, e.count()))
     }
 
+    /// (entity, Some(count) if copyable or None if distinct)
+    pub fn contents_with_copyability(
+        &self,
+    ) -> impl Iterator<Item = (W::Entity, Option<NonZeroU16>)> + '_ {
+        self.contents.iter().map(|e| match e.count {
+            StackedEntityCount::Distinct => (e.entity, None),
+            StackedEntityCount::Copyable(n) => (e.entity, Some(n)),
+        })
+    }
+
     pub fn total_count(&self) -> u16 {
         self.total_count
     }
 
-    fn capacity(&self) -> NonZeroU16 {
+    pub fn capacity(&self) -> NonZeroU16 {
         self.max_count
     }
 }
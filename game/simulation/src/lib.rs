@@ -31,6 +31,7 @@ pub use crate::backend::{
     state, BackendData, Exit, GameSpeedChange, InitializedSimulationBackend,
     PersistentSimulationBackend, TickResponse,
 };
+pub use crate::calendar::{Calendar, Season};
 pub use crate::render::{RenderComponent, Renderer, Shape2d, UiElementComponent};
 pub use crate::simulation::{
     AssociatedBlockData, AssociatedBlockDataType, Simulation, SimulationRef, SimulationRefLite,
@@ -41,7 +42,7 @@ pub use activity::{
     ActivityComponent, EntityLoggingComponent, HaulPurpose, HaulSource, HaulTarget,
     LoggedEntityDecision, LoggedEntityEvent,
 };
-pub use definitions::EntityPosition;
+pub use definitions::{load_block_types, EntityPosition};
 
 #[cfg(feature = "utils")]
 pub use definitions::{load as load_definitions, Definition};
@@ -50,25 +51,31 @@ pub use ecs::{
     Component, ComponentRef, ComponentRefMut, ComponentWorld, EcsWorld, Entity, KindComponent,
     NameComponent,
 };
-pub use event::{DeathReason, EntityEvent, EntityEventPayload};
+pub use event::{DeathReason, EntityEvent, EntityEventLog, EntityEventPayload};
 #[cfg(feature = "testing")]
 pub use event::{EntityEventDebugPayload, TaskResultSummary};
 
 pub use interact::herd::{HerdedComponent, Herds};
 
 pub use build::{BuildMaterial, BuildTemplate};
+pub use craft::{CraftRecipe, WorkshopComponent};
+pub use health::HealthComponent;
 #[cfg(debug_assertions)]
 pub use item::validation::validate_all_inventories;
 pub use item::{
     ConditionComponent, ContainedInComponent, Container, ContainerComponent, ContainersError,
-    EdibleItemComponent, InventoryComponent, ItemCondition, ItemStack, ItemStackComponent,
-    ItemStackError, StackableComponent,
+    DrinkableItemComponent, EdibleItemComponent, InventoryComponent, ItemCondition, ItemStack,
+    ItemStackComponent, ItemStackError, StackableComponent,
 };
 pub use needs::food::HungerComponent;
+pub use needs::sleep::{BedComponent, EnergyComponent};
+pub use needs::thirst::ThirstComponent;
 pub use path::FollowPathComponent;
 pub use perf::{Perf, PerfAvg, Timing};
 pub use queued_update::QueuedUpdates;
+pub use replay::{ReplayError, ReplayHeader, ReplayPlayer, ReplayRecorder};
 pub use runtime::Runtime;
+pub use scripting::parse_entity_id;
 pub use society::{
     job, NameGeneration, PlayerSociety, Societies, SocietyComponent, SocietyHandle,
     SocietyVisibility,
@@ -110,10 +117,15 @@ mod ai;
 mod alloc;
 mod backend;
 mod build;
+mod calendar;
+mod combat;
+mod craft;
 mod definitions;
 pub mod dev;
 mod ecs;
 mod event;
+mod farming;
+mod health;
 pub mod input;
 mod interact;
 mod item;
@@ -124,7 +136,9 @@ mod perf;
 mod physics;
 mod queued_update;
 mod render;
+mod replay;
 mod runtime;
+mod save;
 mod scripting;
 mod senses;
 mod simulation;
@@ -14,7 +14,13 @@ use crate::string::StringCache;
 #[storage(DenseVecStorage)]
 #[name("desired-movement")]
 #[clone(disallow)]
-pub struct DesiredMovementComponent(pub ContextMap);
+pub struct DesiredMovementComponent {
+    pub steering: ContextMap,
+
+    /// Vertical movement in the range [-1, 1] while deliberately climbing, only possible on
+    /// climbable blocks
+    pub climb: Option<f32>,
+}
 
 /// Converts *desired* movement from context steering map to *practical* movement.
 /// this will depend on the entity's health and presence of necessary limbs -
@@ -30,10 +36,8 @@ impl<'a> System<'a> for MovementFulfilmentSystem {
 
     fn run(&mut self, (desired, config, mut physics): Self::SystemData) {
         for (desired, config, physics) in (&desired, &config, &mut physics).join() {
-            let DesiredMovementComponent(context_map) = desired;
-
             // resolve context map to a direction
-            let (angle, speed) = context_map.resolve();
+            let (angle, speed) = desired.steering.resolve();
             let direction = forward_angle(angle);
 
             // TODO actually use body health to determine how much movement is allowed
@@ -44,11 +48,12 @@ impl<'a> System<'a> for MovementFulfilmentSystem {
 
             // TODO scale max speed based on applied effects?
             physics.max_speed = config.max_speed;
+            physics.climb = desired.climb;
         }
     }
 }
 
-/// Movement speeds
+/// Movement speeds and abilities
 #[derive(Clone, Component, EcsComponent, Debug)]
 #[storage(DenseVecStorage)]
 #[name("movement-cfg")]
@@ -56,6 +61,12 @@ impl<'a> System<'a> for MovementFulfilmentSystem {
 pub struct MovementConfigComponent {
     pub max_speed: f32,
     pub acceleration: f32,
+
+    /// Can path through doors, defaults to false
+    pub can_open_doors: bool,
+
+    /// Paths around blocks crowded with other agents, defaults to false
+    pub avoid_congestion: bool,
 }
 
 impl<V: Value> ComponentTemplate<V> for MovementConfigComponent {
@@ -66,9 +77,23 @@ impl<V: Value> ComponentTemplate<V> for MovementConfigComponent {
     where
         Self: Sized,
     {
+        let can_open_doors = match values.get_bool("can_open_doors") {
+            Ok(b) => b,
+            Err(ComponentBuildError::KeyNotFound(_)) => false,
+            Err(err) => return Err(err),
+        };
+
+        let avoid_congestion = match values.get_bool("avoid_congestion") {
+            Ok(b) => b,
+            Err(ComponentBuildError::KeyNotFound(_)) => false,
+            Err(err) => return Err(err),
+        };
+
         Ok(Rc::new(Self {
             max_speed: values.get_float("max_speed")?,
             acceleration: values.get_float("acceleration")?,
+            can_open_doors,
+            avoid_congestion,
         }))
     }
 
@@ -13,6 +13,7 @@ use crate::StringCache;
 #[name("hunger")]
 #[interactive]
 #[clone(disallow)]
+#[save]
 pub struct HungerComponent {
     hunger: Hunger,
     metabolism: Metabolism,
@@ -64,6 +65,26 @@ impl HungerComponent {
     }
 }
 
+impl SaveComponent for HungerComponent {
+    /// Only satiety, the rest comes from the definition
+    type Saved = f32;
+
+    fn save(&self, _: &SaveContext) -> Option<Self::Saved> {
+        Some(self.hunger.satiety().value())
+    }
+
+    fn load(
+        satiety: Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        _: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let mut hunger = world.component_mut::<Self>(entity)?;
+        hunger.hunger.set_satiety(NormalizedFloat::clamped(satiety));
+        Ok(())
+    }
+}
+
 impl<V: Value> ComponentTemplate<V> for HungerComponent {
     fn construct(
         values: &mut Map<V>,
@@ -1 +1,3 @@
 pub mod food;
+pub mod sleep;
+pub mod thirst;
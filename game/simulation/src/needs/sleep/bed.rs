@@ -0,0 +1,49 @@
+use std::rc::Rc;
+
+use common::*;
+
+use crate::ecs::*;
+use crate::StringCache;
+
+/// Somewhere comfortable to sleep. Beds are also containers and share their ownership, so only
+/// the [owner](crate::ContainerComponent::owner) or members of its communal society sleep here
+#[derive(Component, EcsComponent, Debug, Clone)]
+#[storage(HashMapStorage)]
+#[name("bed")]
+#[clone(disallow)]
+pub struct BedComponent {
+    /// Recovery multiplier while sleeping here
+    comfort: NormalizedFloat,
+}
+
+impl BedComponent {
+    pub fn comfort(&self) -> NormalizedFloat {
+        self.comfort
+    }
+}
+
+impl<V: Value> ComponentTemplate<V> for BedComponent {
+    fn construct(
+        values: &mut Map<V>,
+        _: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let comfort = match values.get_float("comfort") {
+            Ok(f) => f,
+            Err(ComponentBuildError::KeyNotFound(_)) => NormalizedFloat::one(),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Rc::new(Self { comfort }))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
+register_component_template!("bed", BedComponent);
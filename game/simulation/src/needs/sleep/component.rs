@@ -0,0 +1,131 @@
+use std::rc::Rc;
+
+use common::*;
+
+use crate::ecs::*;
+use crate::StringCache;
+
+/// Energy that drains with exertion while awake and recovers while sleeping
+#[derive(Component, EcsComponent, Debug, Clone)]
+#[storage(DenseVecStorage)]
+#[name("energy")]
+#[interactive]
+#[clone(disallow)]
+#[save]
+pub struct EnergyComponent {
+    /// 0=exhausted, 1=fully rested
+    energy: NormalizedFloat,
+
+    /// Energy lost per tick at an exertion of 1.0
+    drain: f32,
+
+    /// Energy gained per tick while sleeping somewhere perfectly comfortable
+    recovery: f32,
+}
+
+impl EnergyComponent {
+    /// Defaults to fully rested. Ticks are the number needed to go from fully rested to exhausted
+    /// at an exertion of 1.0, and vice versa while sleeping
+    pub fn new(awake_ticks: u32, sleep_ticks: u32) -> Self {
+        Self {
+            energy: NormalizedFloat::one(),
+            drain: 1.0 / awake_ticks.max(1) as f32,
+            recovery: 1.0 / sleep_ticks.max(1) as f32,
+        }
+    }
+
+    pub fn energy(&self) -> NormalizedFloat {
+        self.energy
+    }
+
+    pub fn set_energy(&mut self, energy: NormalizedFloat) {
+        self.energy = energy;
+    }
+
+    /// Panics on invalid exertion
+    pub fn drain(&mut self, exertion: f32) {
+        let drained = self.drain * exertion;
+        if !(drained.is_finite() && drained.is_sign_positive()) {
+            panic!(
+                "invalid exertion {exertion} for energy drain {}",
+                self.drain
+            );
+        }
+        self.energy -= drained;
+    }
+
+    /// Recovers energy for the given number of ticks of sleep, scaled by comfort. Returns true if
+    /// now fully rested
+    pub fn recover(&mut self, ticks: u32, comfort: NormalizedFloat) -> bool {
+        let recovered = self.recovery * ticks as f32 * comfort.value();
+        self.energy = NormalizedFloat::clamped(self.energy.value() + recovered);
+        self.energy >= NormalizedFloat::one()
+    }
+}
+
+impl SaveComponent for EnergyComponent {
+    /// Only energy, the rest comes from the definition
+    type Saved = f32;
+
+    fn save(&self, _: &SaveContext) -> Option<Self::Saved> {
+        Some(self.energy.value())
+    }
+
+    fn load(
+        energy: Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        _: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let mut comp = world.component_mut::<Self>(entity)?;
+        comp.set_energy(NormalizedFloat::clamped(energy));
+        Ok(())
+    }
+}
+
+impl<V: Value> ComponentTemplate<V> for EnergyComponent {
+    fn construct(
+        values: &mut Map<V>,
+        _: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let awake_ticks = values.get_int("awake_ticks")?;
+        let sleep_ticks = values.get_int("sleep_ticks")?;
+        Ok(Rc::new(Self::new(awake_ticks, sleep_ticks)))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
+impl InteractiveComponent for EnergyComponent {
+    fn as_debug(&self) -> Option<&dyn Debug> {
+        Some(self)
+    }
+}
+
+register_component_template!("energy", EnergyComponent);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drain_and_recover() {
+        let mut energy = EnergyComponent::new(100, 10);
+        for _ in 0..50 {
+            energy.drain(1.0);
+        }
+        assert!((energy.energy().value() - 0.5).abs() < 0.001);
+
+        // half as comfortable takes twice as long
+        assert!(!energy.recover(5, NormalizedFloat::new(0.5)));
+        assert!(energy.recover(5, NormalizedFloat::new(0.5)));
+        assert_eq!(energy.energy(), NormalizedFloat::one());
+    }
+}
@@ -0,0 +1,7 @@
+mod bed;
+mod component;
+mod system;
+
+pub use bed::BedComponent;
+pub use component::EnergyComponent;
+pub use system::EnergySystem;
@@ -0,0 +1,19 @@
+use crate::ecs::*;
+use crate::needs::sleep::EnergyComponent;
+use crate::ActivityComponent;
+
+/// Drains energy over time based on exertion
+pub struct EnergySystem;
+
+impl<'a> System<'a> for EnergySystem {
+    type SystemData = (
+        WriteStorage<'a, EnergyComponent>,
+        ReadStorage<'a, ActivityComponent>, // for current exertion TODO moving average
+    );
+
+    fn run(&mut self, (mut energy, activity): Self::SystemData) {
+        for (energy, activity) in (&mut energy, &activity).join() {
+            energy.drain(activity.exertion());
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::rc::Rc;
+
+use common::*;
+use unit::drink::Hydration;
+use unit::food::Metabolism;
+use unit::world::WorldPosition;
+
+use crate::ecs::*;
+use crate::needs::thirst::Thirst;
+use crate::StringCache;
+
+#[derive(Component, EcsComponent, Debug, Clone)]
+#[storage(DenseVecStorage)]
+#[name("thirst")]
+#[interactive]
+#[clone(disallow)]
+#[save]
+pub struct ThirstComponent {
+    thirst: Thirst,
+    metabolism: Metabolism,
+}
+
+/// Something to drink from
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum DrinkSource {
+    /// A water block
+    Block(WorldPosition),
+
+    /// An item with a `DrinkableItemComponent`
+    Item(Entity),
+}
+
+impl ThirstComponent {
+    /// Defaults to fully hydrated
+    pub fn new(max: Hydration, metabolism: Metabolism) -> Self {
+        Self {
+            thirst: Thirst::new(max),
+            metabolism,
+        }
+    }
+
+    pub fn thirst(&self) -> &Thirst {
+        &self.thirst
+    }
+
+    pub fn thirst_mut(&mut self) -> &mut Thirst {
+        &mut self.thirst
+    }
+
+    pub fn metabolism(&self) -> Metabolism {
+        self.metabolism
+    }
+}
+
+impl SaveComponent for ThirstComponent {
+    /// Only hydration, the rest comes from the definition
+    type Saved = f32;
+
+    fn save(&self, _: &SaveContext) -> Option<Self::Saved> {
+        Some(self.thirst.hydration().value())
+    }
+
+    fn load(
+        hydration: Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        _: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let mut thirst = world.component_mut::<Self>(entity)?;
+        thirst
+            .thirst
+            .set_hydration(NormalizedFloat::clamped(hydration));
+        Ok(())
+    }
+}
+
+impl<V: Value> ComponentTemplate<V> for ThirstComponent {
+    fn construct(
+        values: &mut Map<V>,
+        _: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let max = Hydration::new(values.get_int("max")?);
+        let metabolism = Metabolism::new(values.get_float("metabolism")?).ok_or_else(|| {
+            ComponentBuildError::TemplateSpecific("invalid metabolism".to_string())
+        })?;
+        Ok(Rc::new(Self::new(max, metabolism)))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
+impl InteractiveComponent for ThirstComponent {
+    fn as_debug(&self) -> Option<&dyn Debug> {
+        Some(self)
+    }
+}
+
+impl Display for DrinkSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            DrinkSource::Block(pos) => write!(f, "water at {}", pos),
+            DrinkSource::Item(item) => write!(f, "{}", item),
+        }
+    }
+}
+
+register_component_template!("thirst", ThirstComponent);
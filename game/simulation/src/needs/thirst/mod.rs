@@ -0,0 +1,7 @@
+mod component;
+mod system;
+mod thirst;
+
+pub use component::{DrinkSource, ThirstComponent};
+pub use system::ThirstSystem;
+pub use thirst::Thirst;
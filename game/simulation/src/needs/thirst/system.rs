@@ -0,0 +1,38 @@
+use common::*;
+
+use crate::ecs::*;
+use crate::event::DeathReason;
+use crate::needs::thirst::ThirstComponent;
+use crate::simulation::EcsWorldRef;
+use crate::ActivityComponent;
+
+/// Decreases hydration over time, killing entities that become fully dehydrated
+pub struct ThirstSystem;
+
+impl<'a> System<'a> for ThirstSystem {
+    type SystemData = (
+        Read<'a, EntitiesRes>,
+        Read<'a, EcsWorldRef>,
+        WriteStorage<'a, ThirstComponent>,
+        ReadStorage<'a, ActivityComponent>, // for current exertion TODO moving average
+    );
+
+    fn run(&mut self, (entities, ecs_world, mut thirst, activity): Self::SystemData) {
+        for (e, thirst, activity) in (&entities, &mut thirst, &activity).join() {
+            let metabolism = thirst.metabolism();
+            let thirst = thirst.thirst_mut();
+            if thirst.is_dehydrated() {
+                // already dying
+                continue;
+            }
+
+            thirst.burn(metabolism, activity.exertion());
+
+            if thirst.is_dehydrated() {
+                let e = Entity::from(e);
+                debug!("entity has died of dehydration"; e);
+                ecs_world.kill_entity(e, DeathReason::Dehydration);
+            }
+        }
+    }
+}
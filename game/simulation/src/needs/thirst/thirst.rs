@@ -0,0 +1,85 @@
+use common::newtype::AccumulativeInt;
+use common::NormalizedFloat;
+use unit::drink::Hydration;
+use unit::food::Metabolism;
+
+#[derive(Debug, Clone)]
+pub struct Thirst {
+    max: Hydration,
+    current: AccumulativeInt<Hydration>,
+}
+
+impl Thirst {
+    /// Defaults to fully hydrated
+    pub fn new(max: Hydration) -> Self {
+        Thirst {
+            current: AccumulativeInt::new(max),
+            max,
+        }
+    }
+
+    pub fn hydration(&self) -> NormalizedFloat {
+        self.current.value().proportion_of(self.max)
+    }
+
+    pub fn set_hydration(&mut self, hydration: NormalizedFloat) {
+        self.current = AccumulativeInt::new(self.max * hydration);
+    }
+
+    pub fn is_dehydrated(&self) -> bool {
+        self.current.value().is_zero()
+    }
+
+    /// Amount that can be drunk before being full
+    pub fn remaining(&self) -> Hydration {
+        self.current.value().remaining(self.max)
+    }
+
+    /// Drinks up to the given amount, limited by how much can be drunk. Returns the amount drunk
+    pub fn drink(&mut self, amount: Hydration) -> Hydration {
+        let drunk = amount.min(self.remaining());
+        self.current.add(drunk);
+        debug_assert!(
+            self.current.value() <= self.max,
+            "thirst exceeds maximum hydration"
+        );
+        drunk
+    }
+
+    /// Panics on invalid exertion
+    pub fn burn(&mut self, metabolism: Metabolism, exertion: f32) {
+        let burned = metabolism.value() * exertion;
+        if !(burned.is_finite() && burned.is_sign_positive()) {
+            panic!("invalid exertion {exertion} for metabolism {metabolism:?}");
+        }
+        self.current -= burned;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drink_is_limited_by_thirst() {
+        let mut thirst = Thirst::new(Hydration::new(100));
+        thirst.set_hydration(NormalizedFloat::new(0.8));
+
+        assert_eq!(thirst.drink(Hydration::new(50)), Hydration::new(20));
+        assert_eq!(thirst.hydration(), NormalizedFloat::one());
+        assert_eq!(thirst.drink(Hydration::new(50)), Hydration::new(0));
+    }
+
+    #[test]
+    fn dehydration() {
+        let mut thirst = Thirst::new(Hydration::new(10));
+        let metabolism = Metabolism::new(2.5).unwrap();
+        for _ in 0..3 {
+            thirst.burn(metabolism, 1.0);
+        }
+        assert!(!thirst.is_dehydrated());
+
+        thirst.burn(metabolism, 1.0);
+        assert!(thirst.is_dehydrated());
+    }
+}
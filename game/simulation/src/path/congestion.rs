@@ -0,0 +1,31 @@
+use crate::ecs::*;
+use crate::path::FollowPathComponent;
+use crate::{Tick, TransformComponent, WorldRef};
+
+/// Congestion is refreshed every this many ticks
+const UPDATE_FREQUENCY: u32 = 20;
+
+/// Counts path followers in each block so congestion-averse agents can route around crowds
+pub struct PathCongestionSystem;
+
+impl<'a> System<'a> for PathCongestionSystem {
+    type SystemData = (
+        Read<'a, WorldRef>,
+        ReadStorage<'a, TransformComponent>,
+        ReadStorage<'a, FollowPathComponent>,
+    );
+
+    fn run(&mut self, (world, transforms, paths): Self::SystemData) {
+        // only update occasionally
+        let tick = Tick::fetch();
+        if tick.value() % UPDATE_FREQUENCY != 0 {
+            return;
+        }
+
+        let agents = (&transforms, &paths)
+            .join()
+            .map(|(transform, _)| transform.accessible_position());
+
+        world.borrow_mut().set_congestion(agents);
+    }
+}
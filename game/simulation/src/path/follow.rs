@@ -41,10 +41,12 @@ impl PathFollowing {
         }
     }
 
+    /// (waypoint, cost of the edge leading to it)
     pub fn next_waypoint(&mut self) -> Option<(WorldPoint, EdgeCost)> {
-        let path_len = self.path.path().len();
+        let path = self.path.path();
+        let path_len = path.len();
 
-        let node = self.path.path().get(self.next)?;
+        let node = path.get(self.next)?;
 
         let waypoint = if self.next == path_len - 1 {
             // last waypoint, use exact target point instead of waypoint block pos
@@ -53,8 +55,13 @@ impl PathFollowing {
             node.block.centred()
         };
 
+        let cost = match self.next.checked_sub(1) {
+            Some(prev) => path[prev].exit_cost,
+            None => EdgeCost::Walk,
+        };
+
         self.next += 1;
-        Some((waypoint, node.exit_cost))
+        Some((waypoint, cost))
     }
 
     pub const fn target(&self) -> WorldPoint {
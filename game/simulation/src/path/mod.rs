@@ -1,6 +1,9 @@
+pub use congestion::PathCongestionSystem;
 pub use debug::{NavigationAreaDebugRenderer, PathDebugRenderer};
-pub use system::{FollowPathComponent, PathSteeringSystem, PathToken};
+pub(crate) use system::navigation_profile;
+pub use system::{FollowPathComponent, PathSteeringSystem, PathToken, Pathfinder};
 
+mod congestion;
 mod debug;
 mod follow;
 mod system;
@@ -1,14 +1,19 @@
 use std::iter::once;
 
 use common::*;
-use unit::world::WorldPoint;
-use world::{NavigationError, SearchGoal};
+use unit::world::{WorldPoint, BLOCKS_PER_METRE};
+use world::{NavigationError, NavigationProfile, SearchGoal, WorldPath};
 
 use crate::ecs::*;
 use crate::event::{EntityEvent, EntityEventPayload, EntityEventQueue};
+use crate::movement::MovementConfigComponent;
 use crate::path::follow::{PathFollowing, PathRequest};
+use crate::simulation::WorldContext;
 use crate::steer::{SteeringBehaviour, SteeringComponent};
-use crate::{TransformComponent, WorldRef};
+use crate::{PhysicalComponent, SocietyComponent, TransformComponent};
+
+/// Finds paths for entities asynchronously, results are delivered to [PathSteeringSystem]
+pub type Pathfinder = world::AsyncPathfinder<WorldContext, Entity, PathToken>;
 
 /// Holds the current path to follow
 #[derive(Component, EcsComponent)]
@@ -23,6 +28,16 @@ pub struct FollowPathComponent {
     /// If set, will be popped in next tick and `path` updated
     request: Option<PathRequest>,
     next_token: u64,
+
+    /// Path for `current_token` is still being searched for
+    pending: Option<PendingPath>,
+}
+
+/// Details of a request needed to follow its path once found
+struct PendingPath {
+    target: WorldPoint,
+    goal: SearchGoal,
+    speed: NormalizedFloat,
 }
 
 /// Entity-specific opaque unique token to differentiate path requests
@@ -35,19 +50,66 @@ pub struct PathSteeringSystem;
 impl<'a> System<'a> for PathSteeringSystem {
     type SystemData = (
         Read<'a, EntitiesRes>,
-        Read<'a, WorldRef>,
+        WriteExpect<'a, Pathfinder>,
         Write<'a, EntityEventQueue>,
         WriteStorage<'a, TransformComponent>,
         WriteStorage<'a, FollowPathComponent>,
         WriteStorage<'a, SteeringComponent>,
+        ReadStorage<'a, PhysicalComponent>,
+        ReadStorage<'a, MovementConfigComponent>,
+        ReadStorage<'a, SocietyComponent>,
     );
 
     fn run(
         &mut self,
-        (entities, world, mut event_queue, mut transform, mut path, mut steer): Self::SystemData,
+        (
+            entities,
+            mut pathfinder,
+            mut event_queue,
+            mut transform,
+            mut path,
+            mut steer,
+            physical,
+            movement,
+            society,
+        ): Self::SystemData,
     ) {
-        for (e, transform, mut path, steer) in
-            (&entities, &mut transform, &mut path, &mut steer).join()
+        // start following paths found since last tick
+        pathfinder.poll_completed(|e, token, result| {
+            let path = match path.get_mut(e.into()) {
+                Some(path) if path.current_token == Some(token) => path,
+                _ => return,
+            };
+
+            let pending = match path.pending.take() {
+                Some(pending) => pending,
+                None => return,
+            };
+
+            match result {
+                Err(err) => {
+                    warn!("failed to find path"; e, "target" => ?pending.target, "error" => %err);
+                    path.current_token = None;
+
+                    event_queue.post(EntityEvent {
+                        subject: e,
+                        payload: EntityEventPayload::Arrived(token, Err(err)),
+                    });
+                }
+                Ok(new_path) => path.follow(e, new_path, pending),
+            }
+        });
+
+        for (e, transform, mut path, steer, physical, movement, society) in (
+            &entities,
+            &mut transform,
+            &mut path,
+            &mut steer,
+            physical.maybe(),
+            movement.maybe(),
+            society.maybe(),
+        )
+            .join()
         {
             let e = Entity::from(e);
             log_scope!(o!("system" => "path steering", e));
@@ -56,7 +118,7 @@ impl<'a> System<'a> for PathSteeringSystem {
             if let Some(req) = path.pop_request() {
                 trace!("new path request"; "request" => ?req);
 
-                // send failed arrived event for previous target
+                // send failed arrived event for previous target, whether found yet or not
                 if let Some(current) = path.current_token {
                     trace!("aborting previous path"; "token" => ?current, "target" => ?path.target());
 
@@ -71,12 +133,13 @@ impl<'a> System<'a> for PathSteeringSystem {
 
                 // clobber current path
                 path.path = None;
+                path.pending = None;
                 path.current_token = None;
 
-                let new_path = match req {
+                match req {
                     PathRequest::ClearCurrent => {
                         debug!("clearing current path by request");
-                        None
+                        pathfinder.cancel(e);
                     }
                     PathRequest::NavigateTo {
                         target,
@@ -84,42 +147,25 @@ impl<'a> System<'a> for PathSteeringSystem {
                         speed,
                         token,
                     } => {
-                        let world = world.borrow();
-                        Some((
-                            world.find_path_with_goal(
-                                transform.accessible_position(),
-                                target.floor(),
-                                goal,
-                            ),
-                            speed,
+                        // replaces any search still running for the previous token
+                        let profile = navigation_profile(physical, movement, society);
+                        pathfinder.request(
+                            e,
                             token,
+                            transform.accessible_position(),
+                            target.floor(),
                             goal,
-                            Some(target),
-                        ))
-                    }
-                };
-
-                match new_path {
-                    Some((Err(err), _, token, _, target)) => {
-                        warn!("failed to find path"; "target" => ?target, "error" => %err);
+                            profile,
+                        );
 
-                        event_queue.post(EntityEvent {
-                            subject: e,
-                            payload: EntityEventPayload::Arrived(token, Err(err)),
-                        });
-                    }
-                    Some((Ok(new_path), speed, token, goal, _)) => {
-                        let path_len = new_path.path().len();
-                        let target = new_path.target().centred(); // TODO return random target point for unspecified too
-                        let new_following = PathFollowing::new(new_path, target, goal);
-                        debug!("following new path"; "target" => ?new_following.target(), "path_nodes" => path_len);
-
-                        path.path = Some(new_following);
-                        path.follow_speed = speed;
                         path.current_token = Some(token);
+                        path.pending = Some(PendingPath {
+                            target,
+                            goal,
+                            speed,
+                        });
                     }
-                    None => {}
-                };
+                }
             }
 
             let following = match path.path.as_mut() {
@@ -144,7 +190,11 @@ impl<'a> System<'a> for PathSteeringSystem {
                     }
                     Some((next_block, cost)) => {
                         trace!("next waypoint"; "waypoint" => ?next_block, "cost" => ?cost);
-                        steer.behaviour = SteeringBehaviour::seek(next_block, path.follow_speed);
+                        steer.behaviour = if cost.is_climb() {
+                            SteeringBehaviour::climb(next_block, path.follow_speed)
+                        } else {
+                            SteeringBehaviour::seek(next_block, path.follow_speed)
+                        };
                     }
                 }
             }
@@ -152,7 +202,37 @@ impl<'a> System<'a> for PathSteeringSystem {
     }
 }
 
+/// Passability attributes of an agent for path finding
+pub(crate) fn navigation_profile(
+    physical: Option<&PhysicalComponent>,
+    movement: Option<&MovementConfigComponent>,
+    society: Option<&SocietyComponent>,
+) -> NavigationProfile {
+    // partially covered blocks don't count, so only things at least 2 blocks wide are too big
+    // for 1 block gaps
+    let size = physical
+        .map(|physical| physical.size.xy_max().metres() * BLOCKS_PER_METRE as f32)
+        .map_or(1, |blocks| (blocks as u8).max(1));
+
+    NavigationProfile {
+        size,
+        can_open_doors: movement.map_or(false, |movement| movement.can_open_doors),
+        owner: society.map(|society| society.handle().into()),
+        avoid_congestion: movement.map_or(false, |movement| movement.avoid_congestion),
+    }
+}
+
 impl FollowPathComponent {
+    fn follow(&mut self, e: Entity, path: WorldPath, pending: PendingPath) {
+        let path_len = path.path().len();
+        let target = path.target().centred(); // TODO return random target point for unspecified too
+        let new_following = PathFollowing::new(path, target, pending.goal);
+        debug!("following new path"; e, "target" => ?new_following.target(), "path_nodes" => path_len);
+
+        self.path = Some(new_following);
+        self.follow_speed = pending.speed;
+    }
+
     fn set_request(&mut self, req: PathRequest) {
         if let Some(prev @ PathRequest::NavigateTo { .. }) = self.request.as_ref() {
             warn!("follow path target was overwritten before it could be used";
@@ -225,6 +305,7 @@ impl Default for FollowPathComponent {
             follow_speed: NormalizedFloat::one(),
             next_token: 0x1000,
             current_token: None,
+            pending: None,
         }
     }
 }
@@ -234,3 +315,85 @@ impl Debug for PathToken {
         write!(f, "PathToken({:#x})", self.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use world::block::BlockType;
+    use world::loader::AsyncWorkerPool;
+    use world::ChunkBuilder;
+
+    use super::*;
+
+    #[test]
+    fn superseded_path_never_arrives() {
+        let voxel_world = world::helpers::context_world_from_chunks_blocking::<WorldContext>(vec![
+            ChunkBuilder::new()
+                .fill_slice(0, BlockType::Stone)
+                .build((0, 0)),
+        ]);
+        let pool = AsyncWorkerPool::new(2).unwrap();
+
+        let mut world = EcsWorld::new();
+        world.insert(Pathfinder::new(voxel_world, &pool));
+        world.insert(EntityEventQueue::default());
+
+        let e = Entity::from(
+            world
+                .create_entity()
+                .with(TransformComponent::new(WorldPoint::new_unchecked(
+                    2.5, 2.5, 1.0,
+                )))
+                .with(FollowPathComponent::default())
+                .with(SteeringComponent::default())
+                .build(),
+        );
+
+        let request = |target: WorldPoint| {
+            let mut paths = world.write_storage::<FollowPathComponent>();
+            let path = paths.get_mut(e.into()).unwrap();
+            path.request_navigation(target, NormalizedFloat::one())
+        };
+
+        // superseded before it's delivered
+        let first = request(WorldPoint::new_unchecked(12.5, 12.5, 1.0));
+        PathSteeringSystem.run_now(&world);
+
+        let second_target = WorldPoint::new_unchecked(4.5, 10.5, 1.0);
+        let second = request(second_target);
+
+        // results arrive once found, so keep ticking until it's followed
+        let start = std::time::Instant::now();
+        let is_following = || {
+            let paths = world.read_storage::<FollowPathComponent>();
+            paths.get(e.into()).unwrap().target().is_some()
+        };
+        while !is_following() {
+            assert!(
+                start.elapsed() < std::time::Duration::from_secs(5),
+                "path timed out"
+            );
+            PathSteeringSystem.run_now(&world);
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        // nothing moves the entity so the second path is still being followed
+        let paths = world.read_storage::<FollowPathComponent>();
+        let path = paths.get(e.into()).unwrap();
+        assert_eq!(path.current_token(), Some(second));
+        assert_eq!(path.target(), Some(second_target));
+
+        // the first only gets the abort from being superseded, never its own result
+        let events = world.resource::<EntityEventQueue>();
+        let arrivals = events
+            .events()
+            .filter_map(|evt| match &evt.payload {
+                EntityEventPayload::Arrived(token, result) => Some((*token, result)),
+                _ => None,
+            })
+            .collect_vec();
+        assert_eq!(arrivals.len(), 1);
+        let (token, result) = &arrivals[0];
+        assert_eq!(*token, first);
+        assert!(matches!(result, Err(NavigationError::Aborted)));
+    }
+}
@@ -2,7 +2,7 @@ use common::Vector2;
 
 use crate::World;
 use unit::world::{WorldPoint, WorldPointRange, WorldPosition, WorldPositionRange, WorldRange};
-use world::block::BlockOpacity;
+use world::block::{BlockOpacity, BlockType};
 
 #[derive(Clone, Debug)]
 pub struct Bounds {
@@ -112,11 +112,20 @@ impl BoundsOverlap {
     }
 }
 
+/// Doors are physically passable by everyone, who can open them is enforced by pathfinding
+fn collision_opacity(block_type: BlockType) -> BlockOpacity {
+    if block_type.is_door() {
+        BlockOpacity::Transparent
+    } else {
+        block_type.opacity()
+    }
+}
+
 impl BoundsCheck for World {
     fn all(&self, range: &WorldPositionRange) -> Option<BlockOpacity> {
         let mut opacities = self
             .iterate_blocks(range.clone())
-            .map(|(block, _)| block.block_type().opacity());
+            .map(|(block, _)| collision_opacity(block.block_type()));
 
         opacities.next().and_then(|opacity| {
             if opacities.all(|o| o == opacity) {
@@ -133,7 +142,7 @@ impl BoundsCheck for World {
         out.extend(
             self.iterate_blocks(range.clone())
                 .filter_map(|(block, pos)| {
-                    if block.opacity().solid() {
+                    if collision_opacity(block.block_type()).solid() {
                         Some(pos)
                     } else {
                         None
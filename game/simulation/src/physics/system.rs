@@ -1,14 +1,21 @@
 use crate::ecs::*;
 
+use crate::event::DeathReason;
+use crate::health::{fall_damage, HealthComponent};
 use crate::item::HauledItemComponent;
 use crate::transform::PhysicalComponent;
 use crate::TransformComponent;
+use crate::World;
 use crate::WorldRef;
 use common::*;
+use unit::world::WorldPosition;
 
 const STOP_LIMIT: f32 = 0.01;
 const FALL_SLOWDOWN: f32 = 0.5;
 
+/// Blocks climbed per tick at full climbing speed
+const CLIMB_SPEED: f32 = 0.08;
+
 pub struct PhysicsSystem;
 
 #[derive(Debug, Clone, Component, EcsComponent)]
@@ -23,6 +30,10 @@ pub struct PhysicsComponent {
 
     /// Max speed limit to apply this frame
     pub max_speed: f32,
+
+    /// Vertical movement to apply this frame in the range [-1, 1] if deliberately climbing. Only
+    /// climbing entities hold onto climbable blocks, everything else falls past them
+    pub climb: Option<f32>,
 }
 
 impl Default for PhysicsComponent {
@@ -31,6 +42,7 @@ impl Default for PhysicsComponent {
             fallen: 0,
             acceleration: Vector2::zero(),
             max_speed: 0.0,
+            climb: None,
         }
     }
 }
@@ -43,11 +55,12 @@ impl<'a> System<'a> for PhysicsSystem {
         WriteStorage<'a, TransformComponent>,
         WriteStorage<'a, PhysicsComponent>,
         ReadStorage<'a, HauledItemComponent>,
+        WriteStorage<'a, HealthComponent>,
     );
 
     fn run(
         &mut self,
-        (world_ref, entities, physical, mut transform, mut physics, hauled): Self::SystemData,
+        (world_ref, entities, physical, mut transform, mut physics, hauled, mut health): Self::SystemData,
     ) {
         let mut friction = config::get().simulation.friction;
 
@@ -91,8 +104,20 @@ impl<'a> System<'a> for PhysicsSystem {
                 transform.position = new_pos;
             }
 
-            // apply gravity
-            if bounds.check_ground(&*world).is_all_air() {
+            // apply gravity, unless holding onto a climbable block
+            let floor_pos = transform.position.floor();
+            let climb = physics
+                .climb
+                .take()
+                .and_then(|climb| climb_speed(&*world, floor_pos).map(|speed| climb * speed));
+            if let Some(climb) = climb {
+                transform.position.modify_z(|z| z + climb);
+
+                // catching hold breaks a fall
+                physics.fallen = 0;
+            } else if bounds.check_ground(&*world).is_all_air()
+                && !is_on_top_of_climbable(&*world, floor_pos)
+            {
                 // floating!
 
                 // slow down even more horizontally
@@ -110,10 +135,14 @@ impl<'a> System<'a> for PhysicsSystem {
             } else {
                 // on the ground
                 let fallen = std::mem::take(&mut physics.fallen);
-                if fallen > 0 {
-                    // TODO apply fall damage if applicable
-                    if fallen > 1 {
-                        debug!("fell {fallen} blocks", fallen = fallen);
+                if fallen > 1 {
+                    debug!("fell {fallen} blocks", fallen = fallen);
+                }
+
+                if let Some((damage, bleeding)) = fall_damage(fallen) {
+                    if let Some(health) = e.get_mut(&mut health) {
+                        debug!("injured by fall"; "damage" => damage, "bleeding" => bleeding);
+                        health.injure(damage, bleeding, DeathReason::Fall);
                     }
                 }
             }
@@ -143,3 +172,27 @@ impl<'a> System<'a> for PhysicsSystem {
         }
     }
 }
+
+/// Vertical speed when climbing in or on top of a climbable block, if any. Blocks that are
+/// cheaper to move across are also quicker to climb, e.g. stairs compared to a ladder
+fn climb_speed(world: &World, pos: WorldPosition) -> Option<f32> {
+    [pos, pos.below()].iter().find_map(|&pos| {
+        world
+            .block(pos)
+            .map(|block| block.block_type())
+            .filter(|bt| bt.is_climbable())
+            .map(|bt| CLIMB_SPEED / bt.movement_cost())
+    })
+}
+
+/// Standing on the top of a climbable column, which can be stood on like the ground
+fn is_on_top_of_climbable(world: &World, pos: WorldPosition) -> bool {
+    let is_climbable = |pos: WorldPosition| {
+        world
+            .block(pos)
+            .map(|block| block.block_type().is_climbable())
+            .unwrap_or(false)
+    };
+
+    !is_climbable(pos) && is_climbable(pos.below())
+}
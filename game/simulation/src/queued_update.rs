@@ -8,6 +8,8 @@ use world::loader::{TerrainUpdatesRes, WorldTerrainUpdate};
 use world::BlockDamageResult;
 
 use crate::ecs::EcsWorld;
+use crate::item::spawn_item_stack;
+use crate::string::CachedStr;
 use crate::ComponentWorld;
 
 pub type QueuedUpdates = RawQueuedUpdates<naive::NaiveImpl>;
@@ -86,14 +88,28 @@ impl<Q: QueuedUpdatesImpl> RawQueuedUpdates<Q> {
     pub fn queue_block_damage(&self, block: WorldPosition, damage: BlockDurability) {
         self.queue("damage block", move |world| {
             let world_ref = world.voxel_world();
-            let mut voxel_world = world_ref.borrow_mut();
+            let broken = {
+                let mut voxel_world = world_ref.borrow_mut();
+                // ignore damage to a block that's already broken but not yet replaced, so it only
+                // drops items once
+                let block_type = voxel_world
+                    .block(block)
+                    .filter(|b| !b.is_destroyed())
+                    .map(|b| b.block_type());
+                match voxel_world.damage_block(block, damage) {
+                    Some(BlockDamageResult::Broken) => block_type,
+                    _ => None,
+                }
+            };
 
-            if let Some(BlockDamageResult::Broken) = voxel_world.damage_block(block, damage) {
+            if let Some(block_type) = broken {
                 let terrain_updates = world.resource_mut::<TerrainUpdatesRes>();
                 terrain_updates.push(WorldTerrainUpdate::new(
                     WorldPositionRange::with_single(block),
                     BlockType::Air,
-                ))
+                ));
+
+                spawn_block_drops(&world, block, block_type);
             }
 
             Ok(())
@@ -101,6 +117,25 @@ impl<Q: QueuedUpdatesImpl> RawQueuedUpdates<Q> {
     }
 }
 
+/// Spawns the items dropped by the given broken block type at its position, stacked where possible
+fn spawn_block_drops(world: &EcsWorld, block: WorldPosition, block_type: BlockType) {
+    for (definition, count) in block_type.drops() {
+        match spawn_item_stack(
+            world,
+            CachedStr::from(definition.as_str()),
+            *count,
+            block.centred(),
+        ) {
+            Ok(item) => {
+                debug!("spawned block drop"; item, "count" => count, "block" => %block, "block_type" => ?block_type)
+            }
+            Err(err) => {
+                warn!("failed to spawn block drop"; "definition" => definition, "error" => %err)
+            }
+        }
+    }
+}
+
 mod naive {
     use super::*;
 
@@ -150,7 +185,11 @@ mod naive {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ecs::*;
+    use crate::event::EntityEventQueue;
+    use crate::item::ItemStackComponent;
     use crate::queued_update::naive::NaiveImpl;
+    use crate::TransformComponent;
 
     fn do_basic<Q: QueuedUpdatesImpl>() {
         let mut updates = RawQueuedUpdates::<Q>::default();
@@ -187,4 +226,50 @@ mod tests {
         // logging::for_tests();
         do_basic::<NaiveImpl>()
     }
+
+    #[test]
+    fn block_drops_are_stacked() {
+        let definitions = crate::definitions::load_from_str(
+            r#"[
+            (
+                uid: "core_item_stone_rubble",
+                components: [
+                    {"stackable": (max_count: 8)},
+                    {"physical": (size: (4, 4, 3), volume: 30)},
+                ],
+            ),
+        ]"#,
+        )
+        .expect("bad definitions");
+
+        let ecs = {
+            let mut world = EcsWorld::with_definitions(definitions).expect("bad definitions");
+            world.insert(crate::WorldRef::default());
+            world.insert(EntityEventQueue::default());
+            world
+        };
+
+        // stone drops 2 rubble in the core definitions
+        let (definition, count) = BlockType::Stone
+            .drops()
+            .iter()
+            .exactly_one()
+            .expect("stone should drop rubble");
+        assert_eq!((definition.as_str(), *count), ("core_item_stone_rubble", 2));
+
+        spawn_block_drops(&ecs, WorldPosition::from((1, 2, 3)), BlockType::Stone);
+
+        let stacks = ecs.read_storage::<ItemStackComponent>();
+        let transforms = ecs.read_storage::<TransformComponent>();
+        let (stack, transform) = (&stacks, &transforms)
+            .join()
+            .exactly_one()
+            .ok()
+            .expect("drops should be in a single stack");
+        assert_eq!(stack.stack.total_count(), 2);
+        assert_eq!(transform.position.floor(), WorldPosition::from((1, 2, 3)));
+
+        // only the stack is free in the world
+        assert_eq!(transforms.join().count(), 1);
+    }
 }
@@ -9,6 +9,7 @@ pub use debug::{
     DebugRenderers, DebugRenderersState,
 };
 pub use renderer::Renderer;
+pub(crate) use shape::RenderHexColor;
 pub use shape::Shape2d;
 pub use system::{RenderComponent, RenderSystem};
 pub use ui::{UiElementComponent, UiElementPruneSystem};
@@ -11,11 +11,11 @@ use crate::ecs::*;
 use crate::input::{SelectedComponent, SelectedEntities, SelectedTiles, SelectionProgress};
 
 use crate::render::renderer::Renderer;
-use crate::render::shape::RenderHexColor;
+use crate::render::RenderHexColor;
 use crate::render::UiElementComponent;
 use crate::string::StringCache;
 use crate::transform::{PhysicalComponent, TransformRenderDescription};
-use crate::{PlayerSociety, Shape2d, SliceRange, TransformComponent};
+use crate::{PlayerSociety, Shape2d, SliceRange, Societies, TransformComponent};
 
 #[derive(Debug, Clone, Component, EcsComponent)]
 #[storage(VecStorage)]
@@ -38,6 +38,7 @@ pub struct RenderSystem<'a, R: Renderer> {
 impl<'a, R: Renderer> System<'a> for RenderSystem<'a, R> {
     type SystemData = (
         Read<'a, PlayerSociety>,
+        Read<'a, Societies>,
         Read<'a, SelectedTiles>,
         Read<'a, SelectedEntities>,
         Read<'a, EntitiesRes>,
@@ -55,6 +56,7 @@ impl<'a, R: Renderer> System<'a> for RenderSystem<'a, R> {
         &mut self,
         (
             player_soc,
+            societies,
             block_sel,
             entity_sel,
             entities,
@@ -108,6 +110,20 @@ impl<'a, R: Renderer> System<'a> for RenderSystem<'a, R> {
             }
         }
 
+        // render player's stockpiles
+        if let Some(society) = player_soc
+            .get()
+            .and_then(|h| societies.society_by_handle(h))
+        {
+            let color = Color::rgb(160, 140, 110);
+            for stockpile in society.stockpiles().iter() {
+                let (from, to) = stockpile.range().bounds();
+                if self.slices.contains(from.2) || self.slices.contains(to.2) {
+                    self.renderer.selection(from.into(), to.into(), color, true);
+                }
+            }
+        }
+
         // render player's selections
         if let Some(sel) = block_sel.current() {
             let (from, to) = sel.bounds();
@@ -0,0 +1,148 @@
+//! Recording and playback of player input for reproducing bugs from long sessions.
+//!
+//! All gameplay-affecting [UiRequest](crate::input::UiRequest)s are recorded with the tick they
+//! were applied on, along with the random seed and config used to start the game. A checksum of
+//! all entity transforms is recorded periodically so desyncs during playback can be detected.
+//!
+//! The file is line based, with the [ReplayHeader] on the first line and a [ReplayEntry] on each
+//! subsequent line, so a recording is still usable if the game crashes.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+
+use common::*;
+
+use crate::ecs::*;
+use crate::{Tick, TransformComponent};
+
+pub use player::ReplayPlayer;
+pub use recorder::ReplayRecorder;
+pub use request::{Recording, ReplayEntity, ReplayRequest, ReplayedRequest};
+
+mod player;
+mod recorder;
+mod request;
+
+/// Bumped on every incompatible change to the replay format
+pub const REPLAY_VERSION: u32 = 1;
+
+/// Ticks between transform checksums
+pub const CHECKSUM_INTERVAL: u32 = 100;
+
+#[derive(Serialize, Deserialize)]
+pub struct ReplayHeader {
+    version: u32,
+    pub seed: u64,
+
+    /// Contents of the config file the game was started with
+    pub config: String,
+
+    pub scenario: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+enum ReplayEntry {
+    Request {
+        tick: u32,
+        request: ReplayRequest,
+    },
+
+    /// Recorded at the end of the tick
+    Checksum { tick: u32, checksum: u64 },
+}
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to serialize replay: {0}")]
+    Serialize(#[from] ron::Error),
+
+    #[error("Failed to parse line {0} of replay: {1}")]
+    Parse(usize, #[source] ron::Error),
+
+    #[error("Replay file is empty")]
+    MissingHeader,
+
+    #[error("Unsupported replay version {0}, expected {}", REPLAY_VERSION)]
+    UnsupportedVersion(u32),
+
+    #[error("Invalid entity {0:?}")]
+    InvalidEntity(ReplayEntity),
+
+    #[error("Invalid point {0:?}")]
+    InvalidPoint((f32, f32, f32)),
+
+    #[error("Invalid block type {0:?}")]
+    InvalidBlockType(String),
+
+    #[error("Unknown build template {0:?}")]
+    UnknownBuildTemplate(String),
+
+    #[error("Unknown recipe {0:?}")]
+    UnknownRecipe(String),
+
+    #[error("Unknown stockpile filter {0:?}")]
+    UnknownStockpileFilter(String),
+}
+
+/// Current replay state of the simulation
+pub enum Replay {
+    Recording(ReplayRecorder),
+    Playing(ReplayPlayer),
+}
+
+impl ReplayHeader {
+    pub fn new(seed: u64, config: String, scenario: Option<String>) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            config,
+            scenario,
+        }
+    }
+}
+
+impl Replay {
+    /// Called at the end of every tick that wasn't paused
+    pub fn on_tick_end(&mut self, tick: Tick, world: &EcsWorld) -> Result<(), ReplayError> {
+        match self {
+            Replay::Recording(recorder) => {
+                if tick.value() % CHECKSUM_INTERVAL == 0 {
+                    recorder.record_checksum(tick, transform_checksum(world))?;
+                }
+            }
+            Replay::Playing(player) => {
+                if let Some(expected) = player.take_checksum(tick)? {
+                    let actual = transform_checksum(world);
+                    player.verify_checksum(tick, expected, actual);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Hash of the position of every entity with a transform, in entity order
+pub fn transform_checksum(world: &EcsWorld) -> u64 {
+    let entities = world.read_resource::<EntitiesRes>();
+    let transforms = world.read_storage::<TransformComponent>();
+
+    // default hasher uses fixed keys so is deterministic between runs
+    let mut hasher = DefaultHasher::new();
+    for (e, transform) in (&entities, &transforms).join() {
+        e.id().hash(&mut hasher);
+        e.gen().id().hash(&mut hasher);
+
+        let (x, y, z) = transform.position.xyz();
+        x.to_bits().hash(&mut hasher);
+        y.to_bits().hash(&mut hasher);
+        z.to_bits().hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
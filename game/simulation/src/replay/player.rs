@@ -0,0 +1,232 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Lines};
+use std::path::Path;
+
+use common::*;
+
+use crate::replay::{ReplayEntry, ReplayError, ReplayHeader, ReplayRequest, REPLAY_VERSION};
+use crate::Tick;
+
+/// Reads recorded player input from a replay file tick by tick
+pub struct ReplayPlayer {
+    header: ReplayHeader,
+    lines: Lines<BufReader<File>>,
+    line_number: usize,
+
+    /// Next entry that has been read but not yet reached
+    next: Option<ReplayEntry>,
+    desynced: bool,
+}
+
+impl ReplayPlayer {
+    /// Reads the header only
+    pub fn open(path: &Path) -> Result<Self, ReplayError> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let header = lines.next().ok_or(ReplayError::MissingHeader)??;
+        let header: ReplayHeader =
+            ron::de::from_str(&header).map_err(|err| ReplayError::Parse(1, err))?;
+
+        if header.version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(header.version));
+        }
+
+        let mut player = Self {
+            header,
+            lines,
+            line_number: 1,
+            next: None,
+            desynced: false,
+        };
+        player.next = player.read_entry()?;
+
+        info!("opened replay"; "path" => %path.display(), "seed" => player.header.seed);
+        Ok(player)
+    }
+
+    pub fn header(&self) -> &ReplayHeader {
+        &self.header
+    }
+
+    /// No more entries to play back
+    pub fn is_finished(&self) -> bool {
+        self.next.is_none()
+    }
+
+    /// All requests recorded up to and including the given tick
+    pub fn take_requests(&mut self, tick: Tick) -> Result<Vec<ReplayRequest>, ReplayError> {
+        let mut requests = Vec::new();
+        loop {
+            match self.next {
+                Some(ReplayEntry::Request { tick: t, .. }) if t <= tick.value() => {
+                    if let Some(ReplayEntry::Request { request, .. }) = self.advance()? {
+                        requests.push(request);
+                    }
+                }
+                Some(ReplayEntry::Checksum { tick: t, .. }) if t < tick.value() => {
+                    debug!("skipping stale replay checksum"; "tick" => t);
+                    self.advance()?;
+                }
+                _ => break,
+            }
+        }
+
+        Ok(requests)
+    }
+
+    /// The checksum recorded for the given tick, if any
+    pub fn take_checksum(&mut self, tick: Tick) -> Result<Option<u64>, ReplayError> {
+        match self.next {
+            Some(ReplayEntry::Checksum { tick: t, checksum }) if t <= tick.value() => {
+                self.advance()?;
+                Ok(Some(checksum).filter(|_| t == tick.value()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Only logs the first desync at error level, as everything after will likely differ too
+    pub fn verify_checksum(&mut self, tick: Tick, expected: u64, actual: u64) {
+        if expected == actual {
+            trace!("replay checksum matches"; "tick" => tick.value());
+        } else if !self.desynced {
+            self.desynced = true;
+            error!("replay desync detected"; "tick" => tick.value(),
+                "expected" => expected, "actual" => actual);
+        } else {
+            debug!("replay checksum mismatch"; "tick" => tick.value());
+        }
+    }
+
+    /// Returns the current next entry and reads the following one
+    fn advance(&mut self) -> Result<Option<ReplayEntry>, ReplayError> {
+        let next = self.read_entry()?;
+        Ok(std::mem::replace(&mut self.next, next))
+    }
+
+    fn read_entry(&mut self) -> Result<Option<ReplayEntry>, ReplayError> {
+        for line in self.lines.by_ref() {
+            let line = line?;
+            self.line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            return ron::de::from_str(&line)
+                .map(Some)
+                .map_err(|err| ReplayError::Parse(self.line_number, err));
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn write_replay(name: &str, entries: &[ReplayEntry]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("nn-test-replay-{}", name));
+        let mut file = File::create(&path).expect("failed to create replay");
+
+        let header = ReplayHeader::new(1234, String::new(), None);
+        writeln!(file, "{}", ron::ser::to_string(&header).unwrap()).unwrap();
+        for entry in entries {
+            writeln!(file, "{}", ron::ser::to_string(entry).unwrap()).unwrap();
+        }
+
+        path
+    }
+
+    fn request(tick: u32, script: &str) -> ReplayEntry {
+        ReplayEntry::Request {
+            tick,
+            request: ReplayRequest::ExecuteScript(script.into()),
+        }
+    }
+
+    fn scripts(requests: Vec<ReplayRequest>) -> Vec<String> {
+        requests
+            .into_iter()
+            .map(|req| match req {
+                ReplayRequest::ExecuteScript(path) => path.display().to_string(),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn requests_and_checksums_in_tick_order() {
+        let path = write_replay(
+            "order",
+            &[
+                request(1, "a"),
+                request(1, "b"),
+                ReplayEntry::Checksum {
+                    tick: 2,
+                    checksum: 20,
+                },
+                request(3, "c"),
+                ReplayEntry::Checksum {
+                    tick: 3,
+                    checksum: 30,
+                },
+                request(5, "d"),
+            ],
+        );
+
+        let mut player = ReplayPlayer::open(&path).unwrap();
+        assert_eq!(player.header().seed, 1234);
+
+        let mut tick = |t| {
+            let tick = Tick::with(t);
+            let requests = scripts(player.take_requests(tick).unwrap());
+            let checksum = player.take_checksum(tick).unwrap();
+            (requests, checksum)
+        };
+
+        assert_eq!(tick(1), (vec!["a".to_owned(), "b".to_owned()], None));
+        assert_eq!(tick(2), (vec![], Some(20)));
+        assert_eq!(tick(3), (vec!["c".to_owned()], Some(30)));
+        assert_eq!(tick(4), (vec![], None));
+        assert_eq!(tick(5), (vec!["d".to_owned()], None));
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn stale_checksums_are_skipped() {
+        let path = write_replay(
+            "stale",
+            &[
+                ReplayEntry::Checksum {
+                    tick: 2,
+                    checksum: 20,
+                },
+                request(3, "a"),
+                ReplayEntry::Checksum {
+                    tick: 4,
+                    checksum: 40,
+                },
+                ReplayEntry::Checksum {
+                    tick: 6,
+                    checksum: 60,
+                },
+            ],
+        );
+
+        let mut player = ReplayPlayer::open(&path).unwrap();
+
+        // tick 2 was missed, its checksum must not block later requests
+        assert_eq!(
+            scripts(player.take_requests(Tick::with(3)).unwrap()),
+            vec!["a"]
+        );
+
+        // checksum for an earlier tick is consumed but not returned
+        assert_eq!(player.take_checksum(Tick::with(5)).unwrap(), None);
+        assert_eq!(player.take_checksum(Tick::with(6)).unwrap(), Some(60));
+        assert!(player.is_finished());
+    }
+}
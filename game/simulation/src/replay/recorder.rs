@@ -0,0 +1,62 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use common::*;
+
+use crate::input::UiRequest;
+use crate::replay::{Recording, ReplayEntry, ReplayError, ReplayHeader, ReplayRequest};
+use crate::{EcsWorld, Tick};
+
+/// Writes player input to a replay file as it happens
+pub struct ReplayRecorder {
+    writer: BufWriter<File>,
+}
+
+impl ReplayRecorder {
+    /// Creates or truncates the file at the given path and writes the header
+    pub fn create(path: &Path, header: &ReplayHeader) -> Result<Self, ReplayError> {
+        let mut recorder = Self {
+            writer: BufWriter::new(File::create(path)?),
+        };
+
+        recorder.write_line(header)?;
+        recorder.writer.flush()?;
+        info!("recording replay"; "path" => %path.display(), "seed" => header.seed);
+        Ok(recorder)
+    }
+
+    /// Should be called before the request is applied, as it depends on the current selection
+    pub fn record(&mut self, req: &UiRequest, world: &EcsWorld) -> Result<(), ReplayError> {
+        match ReplayRequest::record(req, world) {
+            Recording::Recorded(request) => {
+                let tick = Tick::fetch().value();
+                trace!("recording request"; "tick" => tick, "request" => ?request);
+                self.write_line(&ReplayEntry::Request { tick, request })?;
+            }
+            Recording::NotGameplay => {}
+            Recording::Unsupported(what) => {
+                warn!("can't record {what} in replay, replay will likely desync", what = what);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn record_checksum(&mut self, tick: Tick, checksum: u64) -> Result<(), ReplayError> {
+        self.write_line(&ReplayEntry::Checksum {
+            tick: tick.value(),
+            checksum,
+        })?;
+
+        // flush regularly so a crash loses as little as possible
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn write_line(&mut self, value: &impl serde::Serialize) -> Result<(), ReplayError> {
+        let line = ron::ser::to_string(value)?;
+        writeln!(self.writer, "{}", line)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,540 @@
+use std::num::NonZeroI32;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use unit::world::{WorldPoint, WorldPosition, WorldPositionRange};
+use world::block::BlockType;
+
+use crate::activity::{HaulPurpose, HaulSource, HaulTarget};
+use crate::ecs::{EcsWorld, Entity, EntityWrapper};
+use crate::input::{SelectedEntities, SelectedTiles, UiRequest};
+use crate::job::{JobCategory, JobPriority, SocietyCommand, SocietyJobHandle};
+use crate::replay::ReplayError;
+use crate::society::{stockpile_filter_by_name, stockpile_filter_name};
+use crate::string::StringCache;
+use crate::{AiAction, ComponentWorld, SocietyHandle};
+
+/// Entity as its raw index and generation. These are stable between a recording and its replay
+/// as long as the game is deterministic
+#[derive(Serialize, Deserialize, Copy, Clone, Debug)]
+pub struct ReplayEntity(u32, i32);
+
+type Position = (i32, i32, i32);
+type Point = (f32, f32, f32);
+
+/// A [UiRequest] that affects the game state, with any implicit state it depends on such as the
+/// current selection resolved at the time it was issued
+#[derive(Serialize, Deserialize, Debug)]
+pub enum ReplayRequest {
+    FillTiles {
+        from: Position,
+        to: Position,
+        block: String,
+    },
+    DivineCommand {
+        subjects: Vec<ReplayEntity>,
+        command: ReplayDivineCommand,
+    },
+    CancelDivineCommand {
+        subjects: Vec<ReplayEntity>,
+    },
+    SocietyCommand {
+        society: SocietyHandle,
+        command: ReplaySocietyCommand,
+    },
+    CancelJob(SocietyJobHandle),
+    SetJobPriority(SocietyJobHandle, JobPriority),
+    SetWorkPreference {
+        entity: ReplayEntity,
+        category: JobCategory,
+        weight: f32,
+    },
+    SetContainerOwnership {
+        container: ReplayEntity,
+        owner: Option<Option<ReplayEntity>>,
+        communal: Option<Option<SocietyHandle>>,
+    },
+    ExecuteScript(PathBuf),
+    LoadGame(PathBuf),
+    Kill(ReplayEntity),
+}
+
+/// The subset of [AiAction]s that can be issued as divine commands from the UI
+#[derive(Serialize, Deserialize, Debug)]
+pub enum ReplayDivineCommand {
+    Goto(Point),
+    Follow { target: ReplayEntity, radius: u8 },
+    Attack(ReplayEntity),
+    Haul { thing: ReplayEntity, target: Point },
+    BreakBlock(Position),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum ReplaySocietyCommand {
+    BreakBlocks {
+        from: Position,
+        to: Position,
+    },
+    Build {
+        from: Position,
+        to: Position,
+        template: String,
+    },
+    HaulToPosition {
+        thing: ReplayEntity,
+        target: Point,
+    },
+    HaulIntoContainer {
+        thing: ReplayEntity,
+        container: ReplayEntity,
+    },
+    Craft {
+        workshop: ReplayEntity,
+        recipe: String,
+    },
+    Farm {
+        from: Position,
+        to: Position,
+        crop: String,
+    },
+    CreateStockpile {
+        from: Position,
+        to: Position,
+        filter: String,
+    },
+    RemoveStockpiles {
+        from: Position,
+        to: Position,
+    },
+}
+
+pub enum Recording {
+    Recorded(ReplayRequest),
+
+    /// Request doesn't affect the game state, e.g. debug renderers or selection
+    NotGameplay,
+
+    /// Request affects the game state but can't be recorded
+    Unsupported(&'static str),
+}
+
+/// A recorded request ready to be applied to the game
+pub enum ReplayedRequest {
+    /// Bypasses the tile selection
+    FillTiles(WorldPositionRange, BlockType),
+
+    /// Bypasses the entity selection. None to cancel
+    DivineCommand(Vec<Entity>, Option<AiAction>),
+
+    Ui(UiRequest),
+}
+
+impl ReplayEntity {
+    fn resolve(self) -> Result<Entity, ReplayError> {
+        let gen = NonZeroI32::new(self.1).ok_or(ReplayError::InvalidEntity(self))?;
+        Ok(Entity::from(EntityWrapper(self.0, gen)))
+    }
+}
+
+impl From<Entity> for ReplayEntity {
+    fn from(e: Entity) -> Self {
+        Self(e.id(), e.gen().id())
+    }
+}
+
+fn from_position(pos: WorldPosition) -> Position {
+    (pos.0, pos.1, pos.2.slice())
+}
+
+fn to_position((x, y, z): Position) -> WorldPosition {
+    WorldPosition::from((x, y, z))
+}
+
+fn from_range(range: &WorldPositionRange) -> (Position, Position) {
+    let (from, to) = range.bounds();
+    (from_position(from), from_position(to))
+}
+
+fn to_range(from: Position, to: Position) -> WorldPositionRange {
+    WorldPositionRange::with_inclusive_range(to_position(from), to_position(to))
+}
+
+fn to_point((x, y, z): Point) -> Result<WorldPoint, ReplayError> {
+    WorldPoint::new(x, y, z).ok_or(ReplayError::InvalidPoint((x, y, z)))
+}
+
+impl ReplayRequest {
+    /// Resolves the request against the current game state so it can be recorded
+    pub fn record(req: &UiRequest, world: &EcsWorld) -> Recording {
+        use Recording::*;
+
+        let selected_entities = || {
+            world
+                .resource::<SelectedEntities>()
+                .iter()
+                .map(|e| ReplayEntity::from(*e))
+                .collect()
+        };
+
+        let request = match req {
+            UiRequest::FillSelectedTiles(placement, block) => {
+                match world.resource::<SelectedTiles>().fill_range(*placement) {
+                    Some(range) => {
+                        let (from, to) = from_range(&range);
+                        ReplayRequest::FillTiles {
+                            from,
+                            to,
+                            block: block.name().to_owned(),
+                        }
+                    }
+                    None => return NotGameplay, // nothing selected so nothing will happen
+                }
+            }
+            UiRequest::IssueDivineCommand(action) => {
+                let command = match ReplayDivineCommand::record(action) {
+                    Some(cmd) => cmd,
+                    None => return Unsupported("divine command"),
+                };
+
+                ReplayRequest::DivineCommand {
+                    subjects: selected_entities(),
+                    command,
+                }
+            }
+            UiRequest::CancelDivineCommand => ReplayRequest::CancelDivineCommand {
+                subjects: selected_entities(),
+            },
+            UiRequest::IssueSocietyCommand(society, command) => {
+                match ReplaySocietyCommand::record(command, world) {
+                    Some(command) => ReplayRequest::SocietyCommand {
+                        society: *society,
+                        command,
+                    },
+                    None => return Unsupported("society command"),
+                }
+            }
+            UiRequest::CancelJob(job) => ReplayRequest::CancelJob(*job),
+            UiRequest::SetJobPriority(job, priority) => {
+                ReplayRequest::SetJobPriority(*job, *priority)
+            }
+            UiRequest::SetWorkPreference {
+                entity,
+                category,
+                weight,
+            } => ReplayRequest::SetWorkPreference {
+                entity: (*entity).into(),
+                category: *category,
+                weight: *weight,
+            },
+            UiRequest::SetContainerOwnership {
+                container,
+                owner,
+                communal,
+            } => ReplayRequest::SetContainerOwnership {
+                container: (*container).into(),
+                owner: owner.map(|o| o.map(Into::into)),
+                communal: *communal,
+            },
+            UiRequest::ExecuteScript(path) => ReplayRequest::ExecuteScript(path.clone()),
+            UiRequest::LoadGame(path) => ReplayRequest::LoadGame(path.clone()),
+            UiRequest::Kill(e) => ReplayRequest::Kill((*e).into()),
+
+            UiRequest::ExitGame(_)
+            | UiRequest::DisableAllDebugRenderers
+            | UiRequest::SetDebugRendererEnabled { .. }
+            | UiRequest::SaveGame(_)
+            | UiRequest::ToggleEntityLogging { .. }
+            | UiRequest::ModifySelection(_)
+            | UiRequest::CancelPopup
+            | UiRequest::CancelSelection
+            | UiRequest::TogglePaused
+            | UiRequest::ChangeGameSpeed(_) => return NotGameplay,
+        };
+
+        Recorded(request)
+    }
+
+    pub fn resolve(self, world: &EcsWorld) -> Result<ReplayedRequest, ReplayError> {
+        let resolve_all = |entities: Vec<ReplayEntity>| {
+            entities
+                .into_iter()
+                .map(ReplayEntity::resolve)
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let resolve_owner = |owner: Option<Option<ReplayEntity>>| {
+            owner
+                .map(|o| o.map(ReplayEntity::resolve).transpose())
+                .transpose()
+        };
+
+        let req = match self {
+            ReplayRequest::FillTiles { from, to, block } => {
+                let block = block
+                    .parse::<BlockType>()
+                    .map_err(|_| ReplayError::InvalidBlockType(block))?;
+                return Ok(ReplayedRequest::FillTiles(to_range(from, to), block));
+            }
+            ReplayRequest::DivineCommand { subjects, command } => {
+                return Ok(ReplayedRequest::DivineCommand(
+                    resolve_all(subjects)?,
+                    Some(command.resolve()?),
+                ));
+            }
+            ReplayRequest::CancelDivineCommand { subjects } => {
+                return Ok(ReplayedRequest::DivineCommand(resolve_all(subjects)?, None));
+            }
+            ReplayRequest::SocietyCommand { society, command } => {
+                UiRequest::IssueSocietyCommand(society, command.resolve(world)?)
+            }
+            ReplayRequest::CancelJob(job) => UiRequest::CancelJob(job),
+            ReplayRequest::SetJobPriority(job, priority) => {
+                UiRequest::SetJobPriority(job, priority)
+            }
+            ReplayRequest::SetWorkPreference {
+                entity,
+                category,
+                weight,
+            } => UiRequest::SetWorkPreference {
+                entity: entity.resolve()?,
+                category,
+                weight,
+            },
+            ReplayRequest::SetContainerOwnership {
+                container,
+                owner,
+                communal,
+            } => UiRequest::SetContainerOwnership {
+                container: container.resolve()?,
+                owner: resolve_owner(owner)?,
+                communal,
+            },
+            ReplayRequest::ExecuteScript(path) => UiRequest::ExecuteScript(path),
+            ReplayRequest::LoadGame(path) => UiRequest::LoadGame(path),
+            ReplayRequest::Kill(e) => UiRequest::Kill(e.resolve()?),
+        };
+
+        Ok(ReplayedRequest::Ui(req))
+    }
+}
+
+impl ReplayDivineCommand {
+    fn record(action: &AiAction) -> Option<Self> {
+        Some(match action {
+            AiAction::Goto(target) => Self::Goto(target.xyz()),
+            AiAction::Follow { target, radius } => Self::Follow {
+                target: (*target).into(),
+                radius: *radius,
+            },
+            AiAction::Attack(target) => Self::Attack((*target).into()),
+            AiAction::Haul(
+                thing,
+                HaulSource::PickUp,
+                HaulTarget::Drop(target),
+                HaulPurpose::JustBecause,
+            ) => Self::Haul {
+                thing: (*thing).into(),
+                target: target.xyz(),
+            },
+            AiAction::GoBreakBlock(pos) => Self::BreakBlock(from_position(*pos)),
+            _ => return None,
+        })
+    }
+
+    fn resolve(self) -> Result<AiAction, ReplayError> {
+        Ok(match self {
+            Self::Goto(target) => AiAction::Goto(to_point(target)?),
+            Self::Follow { target, radius } => AiAction::Follow {
+                target: target.resolve()?,
+                radius,
+            },
+            Self::Attack(target) => AiAction::Attack(target.resolve()?),
+            Self::Haul { thing, target } => AiAction::Haul(
+                thing.resolve()?,
+                HaulSource::PickUp,
+                HaulTarget::Drop(to_point(target)?),
+                HaulPurpose::JustBecause,
+            ),
+            Self::BreakBlock(pos) => AiAction::GoBreakBlock(to_position(pos)),
+        })
+    }
+}
+
+impl ReplaySocietyCommand {
+    fn record(command: &SocietyCommand, world: &EcsWorld) -> Option<Self> {
+        Some(match command {
+            SocietyCommand::BreakBlocks(range) => {
+                let (from, to) = from_range(range);
+                Self::BreakBlocks { from, to }
+            }
+            SocietyCommand::Build(range, template) => {
+                let (from, to) = from_range(range);
+                let template = world
+                    .build_templates()
+                    .iter()
+                    .find(|(_, t, _)| Rc::ptr_eq(t, template))
+                    .map(|(name, _, _)| name.as_ref().to_owned())?;
+                Self::Build { from, to, template }
+            }
+            SocietyCommand::HaulToPosition(thing, target) => Self::HaulToPosition {
+                thing: (*thing).into(),
+                target: target.xyz(),
+            },
+            SocietyCommand::HaulIntoContainer(thing, container) => Self::HaulIntoContainer {
+                thing: (*thing).into(),
+                container: (*container).into(),
+            },
+            SocietyCommand::Craft(workshop, recipe) => {
+                let recipe = world
+                    .recipes()
+                    .iter()
+                    .find(|(_, r, _)| Rc::ptr_eq(r, recipe))
+                    .map(|(name, _, _)| name.as_ref().to_owned())?;
+                Self::Craft {
+                    workshop: (*workshop).into(),
+                    recipe,
+                }
+            }
+            SocietyCommand::Farm(range, crop) => {
+                let (from, to) = from_range(range);
+                Self::Farm {
+                    from,
+                    to,
+                    crop: crop.as_ref().to_owned(),
+                }
+            }
+            SocietyCommand::CreateStockpile(range, filter) => {
+                let (from, to) = from_range(range);
+                Self::CreateStockpile {
+                    from,
+                    to,
+                    filter: stockpile_filter_name(*filter)?.to_owned(),
+                }
+            }
+            SocietyCommand::RemoveStockpiles(range) => {
+                let (from, to) = from_range(range);
+                Self::RemoveStockpiles { from, to }
+            }
+        })
+    }
+
+    fn resolve(self, world: &EcsWorld) -> Result<SocietyCommand, ReplayError> {
+        Ok(match self {
+            Self::BreakBlocks { from, to } => SocietyCommand::BreakBlocks(to_range(from, to)),
+            Self::Build { from, to, template } => {
+                let template = world
+                    .find_build_template(&template)
+                    .ok_or(ReplayError::UnknownBuildTemplate(template))?;
+                SocietyCommand::Build(to_range(from, to), template)
+            }
+            Self::HaulToPosition { thing, target } => {
+                SocietyCommand::HaulToPosition(thing.resolve()?, to_point(target)?)
+            }
+            Self::HaulIntoContainer { thing, container } => {
+                SocietyCommand::HaulIntoContainer(thing.resolve()?, container.resolve()?)
+            }
+            Self::Craft { workshop, recipe } => {
+                let recipe = world
+                    .find_recipe(&recipe)
+                    .ok_or(ReplayError::UnknownRecipe(recipe))?;
+                SocietyCommand::Craft(workshop.resolve()?, recipe)
+            }
+            Self::Farm { from, to, crop } => {
+                let crop = world.resource::<StringCache>().get(&crop);
+                SocietyCommand::Farm(to_range(from, to), crop)
+            }
+            Self::CreateStockpile { from, to, filter } => {
+                let filter = stockpile_filter_by_name(&filter)
+                    .ok_or(ReplayError::UnknownStockpileFilter(filter))?;
+                SocietyCommand::CreateStockpile(to_range(from, to), filter)
+            }
+            Self::RemoveStockpiles { from, to } => {
+                SocietyCommand::RemoveStockpiles(to_range(from, to))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::BlockPlacement;
+
+    fn record(req: &UiRequest, world: &EcsWorld) -> String {
+        match ReplayRequest::record(req, world) {
+            Recording::Recorded(request) => {
+                ron::ser::to_string(&request).expect("failed to serialize request")
+            }
+            _ => panic!("request should be recorded"),
+        }
+    }
+
+    fn resolve(line: &str, world: &EcsWorld) -> ReplayedRequest {
+        let request: ReplayRequest = ron::de::from_str(line).expect("failed to parse request");
+        request.resolve(world).expect("failed to resolve request")
+    }
+
+    #[test]
+    fn record_resolve_round_trip() {
+        let mut world = EcsWorld::new();
+        world.insert(SelectedEntities::default());
+        let a = Entity::from(world.create_entity().build());
+        let b = Entity::from(world.create_entity().build());
+
+        match resolve(&record(&UiRequest::Kill(b), &world), &world) {
+            ReplayedRequest::Ui(UiRequest::Kill(e)) => assert_eq!(e, b),
+            _ => unreachable!(),
+        }
+
+        let req = UiRequest::SetContainerOwnership {
+            container: a,
+            owner: Some(None),
+            communal: None,
+        };
+        match resolve(&record(&req, &world), &world) {
+            ReplayedRequest::Ui(UiRequest::SetContainerOwnership {
+                container,
+                owner,
+                communal,
+            }) => {
+                assert_eq!(container, a);
+                assert_eq!(owner, Some(None));
+                assert!(communal.is_none());
+            }
+            _ => unreachable!(),
+        }
+
+        // selection is captured when recorded, not when resolved
+        world.resource_mut::<SelectedEntities>().select(&world, a);
+        let target = WorldPoint::new(1.0, 2.0, 3.0).unwrap();
+        let line = record(
+            &UiRequest::IssueDivineCommand(AiAction::Goto(target)),
+            &world,
+        );
+        world
+            .resource_mut::<SelectedEntities>()
+            .unselect_all(&world);
+
+        match resolve(&line, &world) {
+            ReplayedRequest::DivineCommand(subjects, Some(AiAction::Goto(pos))) => {
+                assert_eq!(subjects, vec![a]);
+                assert_eq!(pos, target);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn nothing_selected_is_not_gameplay() {
+        let mut world = EcsWorld::new();
+        world.insert(SelectedTiles::default());
+
+        let req = UiRequest::FillSelectedTiles(BlockPlacement::Set, BlockType::Stone);
+        assert!(matches!(
+            ReplayRequest::record(&req, &world),
+            Recording::NotGameplay
+        ));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::ecs::*;
-use crate::event::{EntityEventQueue, RuntimeTimers};
+use crate::event::{EntityEventLog, EntityEventQueue, RuntimeTimers};
 use crate::runtime::Runtime;
 use crate::{ActivityComponent, EntityLoggingComponent, Tick};
 use common::*;
@@ -11,6 +11,7 @@ impl<'a> System<'a> for RuntimeSystem {
     type SystemData = (
         Write<'a, EntityEventQueue>,
         Write<'a, RuntimeTimers>,
+        Write<'a, EntityEventLog>,
         Read<'a, Runtime>,
         WriteStorage<'a, EntityLoggingComponent>,
         WriteStorage<'a, ActivityComponent>,
@@ -18,7 +19,7 @@ impl<'a> System<'a> for RuntimeSystem {
 
     fn run(
         &mut self,
-        (mut events, mut timers, runtime, mut logging, mut activities): Self::SystemData,
+        (mut events, mut timers, mut event_log, runtime, mut logging, mut activities): Self::SystemData,
     ) {
         // consume timers
         for (timer_token, task) in timers.maintain(Tick::fetch()) {
@@ -43,6 +44,8 @@ impl<'a> System<'a> for RuntimeSystem {
             logging.log_events(events.map(|e| &e.payload));
         }
 
+        event_log.log(events.events());
+
         #[cfg(feature = "testing")]
         {
             runtime.post_events(events.events().cloned());
@@ -0,0 +1,466 @@
+//! Saving and loading of the game state to a versioned file.
+//!
+//! Terrain is not saved in full, only slabs that were modified during the game, so a save must be
+//! loaded into a world that was generated from the same terrain source.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use common::*;
+use unit::world::{SlabLocation, WorldPoint, WorldPosition};
+use world::loader::WorldTerrainUpdate;
+use world::LiquidLevel;
+
+use crate::build::ConsumedMaterialForJobComponent;
+use crate::definitions::{BuilderError, DefinitionErrorKind, DefinitionNameComponent};
+use crate::ecs::*;
+use crate::item::ContainedInComponent;
+use crate::save::society::SavedSocietyState;
+use crate::save::terrain::SavedSlab;
+use crate::{AssociatedBlockData, PlayerSociety, Societies, TransformComponent, World};
+
+mod society;
+mod terrain;
+
+/// Bumped on every incompatible change to the save format
+pub const SAVE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct SaveGame {
+    version: u32,
+    tick: u32,
+    terrain: Vec<SavedSlab>,
+    societies: Vec<SavedSocietyState>,
+    player_society: Option<SavedSociety>,
+
+    /// Indexed by [SavedEntity]
+    entities: Vec<SavedEntityState>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedEntityState {
+    /// Definition to spawn the entity from before applying saved components
+    definition: String,
+
+    /// Position to spawn at, None if the entity has no transform
+    position: Option<(f32, f32, f32)>,
+
+    /// Block this entity is associated with, e.g. for chests
+    block: Option<(i32, i32, i32)>,
+
+    /// (component name, serialized component)
+    components: Vec<(String, String)>,
+}
+
+#[derive(Debug, Error)]
+pub enum SaveError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to (de)serialize save: {0}")]
+    Ron(#[from] ron::Error),
+
+    #[error("Unsupported save version {0}, expected {}", SAVE_VERSION)]
+    UnsupportedVersion(u32),
+
+    #[error("Invalid block type {0:?}")]
+    InvalidBlockType(String),
+
+    #[error("Too many blocks saved for slab {0}")]
+    SlabOverflow(SlabLocation),
+
+    #[error("Failed to create entity: {0}")]
+    Definition(#[from] DefinitionErrorKind),
+
+    #[error("Failed to spawn entity: {0}")]
+    Builder(#[from] BuilderError),
+
+    #[error("Failed to load component {0:?}: {1}")]
+    ComponentLoad(String, #[source] ComponentSaveError),
+
+    #[error("Component error: {0}")]
+    Component(#[from] ComponentSaveError),
+
+    #[error("Failed to recreate society {0:?}")]
+    Society(String),
+
+    #[error("Unknown build template {0:?}")]
+    UnknownBuildTemplate(String),
+
+    #[error("Unknown recipe {0:?}")]
+    UnknownRecipe(String),
+
+    #[error("Unknown stockpile filter {0:?}")]
+    UnknownStockpileFilter(String),
+
+    #[error("Failed to resubmit job: {0}")]
+    JobSubmission(String),
+}
+
+impl SaveGame {
+    /// Collects the current game state. Terrain is only saved for the given modified slabs
+    pub fn collect(
+        ecs: &EcsWorld,
+        world: &World,
+        modified_slabs: impl Iterator<Item = SlabLocation>,
+        tick: u32,
+    ) -> Self {
+        let mut ctx = SaveContext::default();
+
+        // register all entities up front so references between them can be resolved regardless
+        // of order
+        let to_save = {
+            let entities = ecs.read_resource::<EntitiesRes>();
+            let definitions = ecs.read_storage::<DefinitionNameComponent>();
+            let consumed = ecs.read_storage::<ConsumedMaterialForJobComponent>();
+            (&entities, &definitions, !&consumed)
+                .join()
+                .map(|(e, def, _)| (Entity::from(e), def.0.clone()))
+                .collect_vec()
+        };
+        for (e, _) in to_save.iter() {
+            ctx.register_entity(*e);
+        }
+
+        let societies = ecs.resource::<Societies>();
+        for society in societies.iter() {
+            ctx.register_society(society.handle());
+        }
+
+        let entities = to_save
+            .into_iter()
+            .map(|(e, definition)| SavedEntityState::save(e, definition.as_ref(), ecs, world, &ctx))
+            .collect();
+
+        let societies = societies
+            .iter()
+            .map(|society| SavedSocietyState::save(society, ecs, &ctx))
+            .collect();
+
+        let player_society = ecs
+            .resource::<PlayerSociety>()
+            .get()
+            .and_then(|soc| ctx.society(soc));
+
+        let terrain = modified_slabs
+            .filter_map(|slab| SavedSlab::save(world, slab))
+            .collect();
+
+        Self {
+            version: SAVE_VERSION,
+            tick,
+            terrain,
+            societies,
+            player_society,
+            entities,
+        }
+    }
+
+    pub fn write_to(&self, path: &Path) -> Result<(), SaveError> {
+        let writer = BufWriter::new(File::create(path)?);
+        ron::ser::to_writer_pretty(writer, self, Default::default())?;
+        Ok(())
+    }
+
+    pub fn read_from(path: &Path) -> Result<Self, SaveError> {
+        let reader = BufReader::new(File::open(path)?);
+        let save: Self = ron::de::from_reader(reader)?;
+        if save.version != SAVE_VERSION {
+            return Err(SaveError::UnsupportedVersion(save.version));
+        }
+
+        Ok(save)
+    }
+
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+
+    pub fn modified_slabs(&self) -> impl Iterator<Item = SlabLocation> + '_ {
+        self.terrain.iter().map(|slab| slab.location())
+    }
+
+    /// Restores all saved state into the given world, which should have no entities or societies.
+    /// Terrain and liquid changes are queued into `terrain_updates` and `liquid_updates` to be
+    /// applied on the next tick.
+    pub fn restore(
+        &self,
+        ecs: &EcsWorld,
+        terrain_updates: &mut HashSet<WorldTerrainUpdate>,
+        liquid_updates: &mut Vec<(WorldPosition, LiquidLevel)>,
+    ) -> Result<(), SaveError> {
+        let mut ctx = LoadContext::default();
+
+        // societies first so components can reference them
+        {
+            let societies = ecs.resource_mut::<Societies>();
+            for society in self.societies.iter() {
+                let handle = societies
+                    .new_society(society.name().to_owned())
+                    .ok_or_else(|| SaveError::Society(society.name().to_owned()))?;
+                ctx.register_society(handle);
+            }
+
+            let player_society = match self.player_society {
+                Some(soc) => PlayerSociety::with_society(ctx.society(soc)?),
+                None => PlayerSociety::default(),
+            };
+            *ecs.resource_mut::<PlayerSociety>() = player_society;
+        }
+
+        // spawn all entities before applying components so they can reference each other
+        for saved in self.entities.iter() {
+            let entity = saved.spawn(ecs)?;
+            ctx.register_entity(entity);
+        }
+
+        for (saved, entity) in self.entities.iter().zip(0..) {
+            let entity = ctx.entity(SavedEntity(entity))?;
+            saved.restore(entity, ecs, &ctx)?;
+        }
+
+        // resubmit jobs now that entities exist
+        {
+            let societies = ecs.resource::<Societies>();
+            for (saved, idx) in self.societies.iter().zip(0..) {
+                let handle = ctx.society(SavedSociety(idx))?;
+                let society = societies
+                    .society_by_handle(handle)
+                    .ok_or_else(|| SaveError::Society(saved.name().to_owned()))?;
+                saved.restore(society, ecs, &ctx)?;
+            }
+        }
+
+        let world = ecs.voxel_world();
+        let world = world.borrow();
+        for slab in self.terrain.iter() {
+            slab.restore(&world, terrain_updates, liquid_updates)?;
+        }
+
+        info!(
+            "loaded save";
+            "entities" => self.entities.len(),
+            "societies" => self.societies.len(),
+            "slabs" => self.terrain.len(),
+        );
+        Ok(())
+    }
+}
+
+impl SavedEntityState {
+    fn save(
+        entity: Entity,
+        definition: &str,
+        ecs: &EcsWorld,
+        world: &World,
+        ctx: &SaveContext,
+    ) -> Self {
+        let transform = ecs.component::<TransformComponent>(entity).ok();
+        let position = match transform {
+            Some(transform) => Some(transform.position),
+            None => match ecs.component::<ContainedInComponent>(entity).ok() {
+                // held items have no transform of their own, so spawn them at the holder's feet in
+                // case the holder isn't saved
+                Some(contained) if matches!(*contained, ContainedInComponent::InventoryOf(_)) => {
+                    ecs.component::<TransformComponent>(contained.entity())
+                        .ok()
+                        .map(|t| t.position)
+                }
+                _ => None,
+            },
+        };
+
+        let block = position.map(|pos| pos.floor()).filter(|pos| {
+            matches!(world.associated_block_data(*pos), Some(AssociatedBlockData::Container(e)) if *e == entity)
+        });
+
+        let components = ecs
+            .save_components_for(entity, ctx)
+            .filter_map(|(name, res)| match res {
+                Ok(serialized) => Some((name.to_owned(), serialized)),
+                Err(err) => {
+                    warn!("failed to save component"; entity, "component" => name, "error" => %err);
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            definition: definition.to_owned(),
+            position: position.map(|pos| pos.xyz()),
+            block: block.map(|pos| (pos.0, pos.1, pos.2.slice())),
+            components,
+        }
+    }
+
+    fn spawn(&self, ecs: &EcsWorld) -> Result<Entity, SaveError> {
+        let builder = ecs.build_entity(&self.definition)?;
+        let entity = match self.position.and_then(|(x, y, z)| WorldPoint::new(x, y, z)) {
+            Some(pos) => builder
+                .with_position(pos)
+                .doesnt_need_to_be_accessible()
+                .spawn()?,
+            None => {
+                // position is required if the definition has a transform, so use a placeholder
+                // and remove it again
+                let entity = builder
+                    .with_position(WorldPosition::from((0, 0, 0)))
+                    .doesnt_need_to_be_accessible()
+                    .spawn()?;
+                let _ = ecs.remove_now::<TransformComponent>(entity);
+                entity
+            }
+        };
+
+        if let Some(block) = self.block {
+            let world = ecs.voxel_world();
+            let _ = world.borrow_mut().set_associated_block_data(
+                WorldPosition::from(block),
+                AssociatedBlockData::Container(entity),
+            );
+        }
+
+        Ok(entity)
+    }
+
+    fn restore(&self, entity: Entity, ecs: &EcsWorld, ctx: &LoadContext) -> Result<(), SaveError> {
+        for (name, serialized) in self.components.iter() {
+            ecs.load_component_for(name, entity, serialized, ctx)
+                .map_err(|err| SaveError::ComponentLoad(name.clone(), err))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::definitions::DefinitionNameComponent;
+    use crate::{ContainerComponent, InventoryComponent, WorldRef};
+
+    use super::*;
+
+    const DEFINITIONS: &str = r#"[
+    (
+        uid: "test_holder",
+        components: [
+            {"physical": (size: (4, 4, 6), volume: 100)},
+            {"inventory": (equip_slots: 2)},
+        ],
+    ),
+    (
+        uid: "test_item",
+        components: [
+            {"physical": (size: (1, 1, 1), volume: 1)},
+            {"haulable": (extra_hands: 0)},
+        ],
+    ),
+    (
+        uid: "core_storage_backpack",
+        components: [
+            {"haulable": (extra_hands: 0)},
+            {"container": (size: (10, 10, 20), volume: 100)},
+        ],
+    ),
+]"#;
+
+    fn new_ecs() -> EcsWorld {
+        let definitions = crate::definitions::load_from_str(DEFINITIONS).expect("bad definitions");
+        let mut ecs = EcsWorld::with_definitions(definitions).expect("bad definitions");
+        ecs.insert(WorldRef::default());
+        ecs.insert(Societies::default());
+        ecs.insert(PlayerSociety::default());
+        ecs
+    }
+
+    fn entities_by_definition(ecs: &EcsWorld) -> HashMap<String, Vec<Entity>> {
+        let entities = ecs.read_resource::<EntitiesRes>();
+        let definitions = ecs.read_storage::<DefinitionNameComponent>();
+        (&entities, &definitions)
+            .join()
+            .map(|(e, def)| (def.0.as_ref().to_owned(), Entity::from(e)))
+            .into_group_map()
+    }
+
+    #[test]
+    fn held_items_round_trip() {
+        let ecs = new_ecs();
+        let spawn = |def: &str| {
+            ecs.build_entity(def)
+                .expect("no definition")
+                .with_position(WorldPosition::from((2, 2, 2)))
+                .doesnt_need_to_be_accessible()
+                .spawn()
+                .expect("failed to spawn")
+        };
+
+        let holder = spawn("test_holder");
+        let held = spawn("test_item");
+        let bagged = spawn("test_item");
+
+        let mut dev = ecs.helpers_dev();
+        dev.give_bag(holder);
+        dev.give_item(holder, held).expect("failed to give item");
+        dev.put_food_in_container(bagged, holder);
+
+        let save = {
+            let world = ecs.voxel_world();
+            let world = world.borrow();
+            SaveGame::collect(&ecs, &world, empty(), 0)
+        };
+
+        // through the file format too
+        let serialized = ron::to_string(&save).expect("failed to serialize");
+        let save: SaveGame = ron::from_str(&serialized).expect("failed to deserialize");
+
+        let loaded = new_ecs();
+        save.restore(&loaded, &mut HashSet::new(), &mut Vec::new())
+            .expect("failed to load");
+
+        let by_def = entities_by_definition(&loaded);
+        let holder = by_def["test_holder"][0];
+        let bag = by_def["core_storage_backpack"][0];
+        assert_eq!(by_def["test_item"].len(), 2);
+
+        let inventory = loaded
+            .component::<InventoryComponent>(holder)
+            .expect("no inventory");
+        let held = inventory
+            .all_equipped_items()
+            .exactly_one()
+            .ok()
+            .expect("should be holding 1 item");
+        assert_eq!(
+            inventory.containers_unresolved().copied().collect_vec(),
+            vec![bag]
+        );
+
+        for item in [held, bag] {
+            assert!(matches!(
+                *loaded.component::<ContainedInComponent>(item).unwrap(),
+                ContainedInComponent::InventoryOf(e) if e == holder
+            ));
+            assert!(!loaded.has_component::<TransformComponent>(item));
+        }
+
+        let container = loaded.component::<ContainerComponent>(bag).unwrap();
+        let bagged = container
+            .container
+            .contents()
+            .exactly_one()
+            .ok()
+            .expect("bag should have 1 item")
+            .entity;
+        assert_ne!(bagged, held);
+        assert!(matches!(
+            *loaded.component::<ContainedInComponent>(bagged).unwrap(),
+            ContainedInComponent::Container(e) if e == bag
+        ));
+    }
+}
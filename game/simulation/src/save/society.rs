@@ -0,0 +1,276 @@
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use common::*;
+use unit::world::{WorldPoint, WorldPosition, WorldPositionRange};
+use world::DoorOwner;
+
+use crate::activity::HaulTarget;
+use crate::ecs::*;
+use crate::job::{
+    BreakBlocksJob, BuildThingJob, CraftJob, FarmJob, HaulJob, JobPriority, SocietyCommand,
+};
+use crate::save::SaveError;
+use crate::society::{stockpile_filter_by_name, stockpile_filter_name, Society};
+use crate::string::StringCache;
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedSocietyState {
+    name: String,
+    /// Task reservations aren't saved, the AI decision that made them isn't either. Reserving
+    /// entities re-reserve their tasks when they next choose them
+    jobs: Vec<SavedJob>,
+
+    #[serde(default)]
+    stockpiles: Vec<SavedStockpile>,
+
+    /// (job index, priority) for jobs without the default priority
+    #[serde(default)]
+    priorities: Vec<(u32, JobPriority)>,
+
+    /// Positions of doors owned by this society
+    #[serde(default)]
+    doors: Vec<(i32, i32, i32)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedStockpile {
+    from: (i32, i32, i32),
+    to: (i32, i32, i32),
+    filter: String,
+}
+
+#[derive(Serialize, Deserialize)]
+enum SavedJob {
+    BreakBlocks {
+        from: (i32, i32, i32),
+        to: (i32, i32, i32),
+    },
+    Build {
+        pos: (i32, i32, i32),
+        template: String,
+    },
+    Craft {
+        workshop: SavedEntity,
+        recipe: String,
+    },
+    HaulToPosition {
+        thing: SavedEntity,
+        target: (f32, f32, f32),
+    },
+    HaulIntoContainer {
+        thing: SavedEntity,
+        container: SavedEntity,
+    },
+    Farm {
+        from: (i32, i32, i32),
+        to: (i32, i32, i32),
+        crop: String,
+    },
+}
+
+fn from_position(pos: WorldPosition) -> (i32, i32, i32) {
+    (pos.0, pos.1, pos.2.slice())
+}
+
+fn to_position((x, y, z): (i32, i32, i32)) -> WorldPosition {
+    WorldPosition::from((x, y, z))
+}
+
+impl SavedSocietyState {
+    pub fn save(society: &Society, world: &EcsWorld, ctx: &SaveContext) -> Self {
+        let jobs = society.jobs();
+        let mut saved_jobs = Vec::new();
+        let mut priorities = Vec::new();
+
+        for job_ref in jobs.iter_all() {
+            let job = job_ref.borrow();
+            let saved = SavedJob::from_job(&job, world, ctx);
+            if saved.is_none() {
+                debug!("skipping unsaveable job"; "job" => ?job_ref);
+                continue;
+            }
+
+            let job_idx = saved_jobs.len() as u32;
+            if job.priority() != JobPriority::default() {
+                priorities.push((job_idx, job.priority()));
+            }
+
+            saved_jobs.extend(saved);
+        }
+
+        let stockpiles = society
+            .stockpiles()
+            .iter()
+            .filter_map(|stockpile| {
+                let (from, to) = stockpile.range().bounds();
+                let filter = stockpile_filter_name(stockpile.filter())?;
+                Some(SavedStockpile {
+                    from: from_position(from),
+                    to: from_position(to),
+                    filter: filter.to_owned(),
+                })
+            })
+            .collect();
+
+        let owner = DoorOwner::from(society.handle());
+        let doors = world
+            .voxel_world()
+            .borrow()
+            .owned_doors()
+            .filter(|(_, door_owner)| *door_owner == owner)
+            .map(|(pos, _)| from_position(pos))
+            .collect();
+
+        Self {
+            name: society.name().to_owned(),
+            jobs: saved_jobs,
+            stockpiles,
+            priorities,
+            doors,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Resubmits all jobs to the given freshly created society, then restores job priorities,
+    /// stockpiles and door ownership
+    pub fn restore(
+        &self,
+        society: &Society,
+        world: &EcsWorld,
+        ctx: &LoadContext,
+    ) -> Result<(), SaveError> {
+        for job in self.jobs.iter() {
+            let command = job.to_command(world, ctx)?;
+            if let Err(command) = command.submit_job_to_society(society, world) {
+                return Err(SaveError::JobSubmission(format!("{:?}", command)));
+            }
+        }
+
+        let mut jobs = society.jobs_mut();
+        for &(job_idx, priority) in self.priorities.iter() {
+            match jobs.by_index(job_idx as usize) {
+                Some(job) => job.borrow_mut().set_priority(priority),
+                None => {
+                    debug!("saved job priority no longer exists"; "job" => job_idx);
+                }
+            }
+        }
+
+        let mut stockpiles = society.stockpiles_mut();
+        for stockpile in self.stockpiles.iter() {
+            let filter = stockpile_filter_by_name(&stockpile.filter)
+                .ok_or_else(|| SaveError::UnknownStockpileFilter(stockpile.filter.clone()))?;
+            stockpiles.add(
+                WorldPositionRange::with_inclusive_range(
+                    to_position(stockpile.from),
+                    to_position(stockpile.to),
+                ),
+                filter,
+            );
+        }
+
+        let owner = DoorOwner::from(society.handle());
+        let world_ref = world.voxel_world();
+        let mut voxel_world = world_ref.borrow_mut();
+        for &door in self.doors.iter() {
+            voxel_world.set_door_owner(to_position(door), Some(owner));
+        }
+
+        Ok(())
+    }
+}
+
+impl SavedJob {
+    fn from_job(job: &crate::job::SocietyJob, world: &EcsWorld, ctx: &SaveContext) -> Option<Self> {
+        if let Some(job) = job.cast::<BreakBlocksJob>() {
+            let (from, to) = job.range().bounds();
+            Some(SavedJob::BreakBlocks {
+                from: from_position(from),
+                to: from_position(to),
+            })
+        } else if let Some(job) = job.cast::<BuildThingJob>() {
+            let template = world
+                .build_templates()
+                .iter()
+                .find(|(_, template, _)| Rc::ptr_eq(template, job.template()))
+                .map(|(name, _, _)| name.as_ref().to_owned())?;
+
+            Some(SavedJob::Build {
+                pos: from_position(job.details().pos),
+                template,
+            })
+        } else if let Some(job) = job.cast::<CraftJob>() {
+            let recipe = world
+                .recipes()
+                .iter()
+                .find(|(_, recipe, _)| Rc::ptr_eq(recipe, job.recipe()))
+                .map(|(name, _, _)| name.as_ref().to_owned())?;
+
+            Some(SavedJob::Craft {
+                workshop: ctx.entity(job.workshop())?,
+                recipe,
+            })
+        } else if let Some(job) = job.cast::<HaulJob>() {
+            let thing = ctx.entity(job.entity())?;
+            Some(match job.target() {
+                HaulTarget::Drop(point) => SavedJob::HaulToPosition {
+                    thing,
+                    target: point.xyz(),
+                },
+                HaulTarget::Container(container) => SavedJob::HaulIntoContainer {
+                    thing,
+                    container: ctx.entity(container)?,
+                },
+            })
+        } else if let Some(job) = job.cast::<FarmJob>() {
+            let (from, to) = job.range().bounds();
+            Some(SavedJob::Farm {
+                from: from_position(from),
+                to: from_position(to),
+                crop: job.crop().as_ref().to_owned(),
+            })
+        } else {
+            None
+        }
+    }
+
+    fn to_command(&self, world: &EcsWorld, ctx: &LoadContext) -> Result<SocietyCommand, SaveError> {
+        Ok(match self {
+            SavedJob::BreakBlocks { from, to } => SocietyCommand::BreakBlocks(
+                WorldPositionRange::with_inclusive_range(to_position(*from), to_position(*to)),
+            ),
+            SavedJob::Build { pos, template } => {
+                let template = world
+                    .find_build_template(template)
+                    .ok_or_else(|| SaveError::UnknownBuildTemplate(template.clone()))?;
+                SocietyCommand::Build(WorldPositionRange::with_single(to_position(*pos)), template)
+            }
+            SavedJob::Craft { workshop, recipe } => {
+                let recipe = world
+                    .find_recipe(recipe)
+                    .ok_or_else(|| SaveError::UnknownRecipe(recipe.clone()))?;
+                SocietyCommand::Craft(ctx.entity(*workshop)?, recipe)
+            }
+            SavedJob::HaulToPosition {
+                thing,
+                target: (x, y, z),
+            } => {
+                let target = WorldPoint::new(*x, *y, *z)
+                    .ok_or_else(|| SaveError::JobSubmission("invalid haul target".to_owned()))?;
+                SocietyCommand::HaulToPosition(ctx.entity(*thing)?, target)
+            }
+            SavedJob::HaulIntoContainer { thing, container } => {
+                SocietyCommand::HaulIntoContainer(ctx.entity(*thing)?, ctx.entity(*container)?)
+            }
+            SavedJob::Farm { from, to, crop } => SocietyCommand::Farm(
+                WorldPositionRange::with_inclusive_range(to_position(*from), to_position(*to)),
+                world.resource::<StringCache>().get(crop),
+            ),
+        })
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use common::*;
+use unit::world::{
+    ChunkLocation, LocalSliceIndex, SlabIndex, SlabLocation, SlabPosition, WorldPosition,
+    WorldPositionRange, CHUNK_SIZE,
+};
+use world::block::BlockType;
+use world::loader::WorldTerrainUpdate;
+use world::LiquidLevel;
+
+use crate::save::SaveError;
+use crate::World;
+
+/// All blocks in a slab that was modified during the game
+#[derive(Serialize, Deserialize)]
+pub struct SavedSlab {
+    chunk: (i32, i32),
+    slab: i32,
+
+    /// Run-length encoded block types, in the iteration order of the slab's block range
+    blocks: Vec<(String, u16)>,
+
+    /// Run-length encoded liquid levels in the same order as `blocks`, empty if there is none
+    #[serde(default)]
+    liquid: Vec<(u8, u16)>,
+}
+
+fn slab_range(slab: SlabLocation) -> WorldPositionRange {
+    let max = CHUNK_SIZE.as_block_coord() - 1;
+    let from = SlabPosition::new_unchecked(0, 0, LocalSliceIndex::bottom()).to_world_position(slab);
+    let to = SlabPosition::new_unchecked(max, max, LocalSliceIndex::top()).to_world_position(slab);
+    WorldPositionRange::with_inclusive_range(from, to)
+}
+
+impl SavedSlab {
+    /// None if slab is not loaded
+    pub fn save(world: &World, slab: SlabLocation) -> Option<Self> {
+        if !world.has_slab(slab) {
+            return None;
+        }
+
+        let mut blocks: Vec<(String, u16)> = Vec::new();
+        let mut liquid: Vec<(u8, u16)> = Vec::new();
+        for pos in slab_range(slab).iter_blocks() {
+            let block = world.block(pos)?;
+            let name = format!("{:?}", block.block_type());
+            match blocks.last_mut() {
+                Some((last, n)) if *last == name && *n < u16::MAX => *n += 1,
+                _ => blocks.push((name, 1)),
+            }
+
+            let level = block.liquid().value();
+            match liquid.last_mut() {
+                Some((last, n)) if *last == level && *n < u16::MAX => *n += 1,
+                _ => liquid.push((level, 1)),
+            }
+        }
+
+        if liquid.iter().all(|(level, _)| *level == 0) {
+            liquid.clear();
+        }
+
+        Some(Self {
+            chunk: (slab.chunk.0, slab.chunk.1),
+            slab: slab.slab.as_i32(),
+            blocks,
+            liquid,
+        })
+    }
+
+    pub fn location(&self) -> SlabLocation {
+        SlabLocation::new(
+            SlabIndex(self.slab),
+            ChunkLocation(self.chunk.0, self.chunk.1),
+        )
+    }
+
+    /// Queues terrain updates for all blocks that differ from the current world, and liquid
+    /// updates for all blocks with liquid in them
+    pub fn restore(
+        &self,
+        world: &World,
+        updates: &mut HashSet<WorldTerrainUpdate>,
+        liquid_updates: &mut Vec<(WorldPosition, LiquidLevel)>,
+    ) -> Result<(), SaveError> {
+        let slab = self.location();
+        let mut positions = slab_range(slab).iter_blocks();
+        for (name, n) in self.blocks.iter() {
+            let block_type = name
+                .parse::<BlockType>()
+                .map_err(|_| SaveError::InvalidBlockType(name.clone()))?;
+
+            for _ in 0..*n {
+                let pos = positions.next().ok_or(SaveError::SlabOverflow(slab))?;
+                let current = world.block(pos).map(|b| b.block_type());
+                if current != Some(block_type) {
+                    updates.insert(WorldTerrainUpdate::new(
+                        WorldPositionRange::with_single(pos),
+                        block_type,
+                    ));
+                }
+            }
+        }
+
+        let mut positions = slab_range(slab).iter_blocks();
+        for &(level, n) in self.liquid.iter() {
+            for _ in 0..n {
+                let pos = positions.next().ok_or(SaveError::SlabOverflow(slab))?;
+                if level != 0 {
+                    liquid_updates.push((pos, LiquidLevel::new(level)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
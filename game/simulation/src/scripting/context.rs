@@ -1,3 +1,5 @@
+use crate::activity::EquipItemError;
+use crate::definitions::{BuilderError, DefinitionErrorKind};
 use crate::ecs::EntityWrapper;
 use crate::EcsWorld;
 use common::*;
@@ -18,6 +20,36 @@ pub enum ScriptingError {
 
     #[error("Entity is not alive: {0}")]
     DeadEntity(EntityWrapper),
+
+    #[error("Entity {0} is missing component {1}")]
+    MissingComponent(EntityWrapper, &'static str),
+
+    #[error("Failed to create entity: {0}")]
+    Definition(#[from] DefinitionErrorKind),
+
+    #[error("Failed to spawn entity: {0}")]
+    Builder(#[from] BuilderError),
+
+    #[error("Failed to give item: {0}")]
+    GiveItem(#[from] EquipItemError),
+
+    #[error("Invalid block type: {0}")]
+    InvalidBlockType(String),
+
+    #[error("Invalid position: {0:?}")]
+    InvalidPoint((f32, f32, f32)),
+
+    #[error("No such society: {0}")]
+    InvalidSociety(String),
+
+    #[error("No such build template: {0}")]
+    UnknownBuildTemplate(String),
+
+    #[error("No such recipe: {0}")]
+    UnknownRecipe(String),
+
+    #[error("Society rejected command: {0}")]
+    RejectedCommand(String),
 }
 
 #[derive(Default)]
@@ -29,6 +61,12 @@ pub trait Scripting: Sized {
     fn new() -> ScriptingResult<Self>;
 
     fn run(&mut self, script: &[u8], ecs: &EcsWorld) -> ScriptingResult<ScriptingOutput>;
+
+    /// Runs all callbacks registered by previous scripts, called once per game tick
+    fn tick(&mut self, ecs: &EcsWorld) -> ScriptingResult<ScriptingOutput>;
+
+    /// Forgets everything registered by previous scripts, e.g. when loading a different game
+    fn reset(&mut self) -> ScriptingResult<()>;
 }
 
 pub struct ScriptingContext<S: Scripting> {
@@ -46,6 +84,14 @@ impl<S: Scripting> ScriptingContext<S> {
         self.eval_bytes(&bytes, ecs)
     }
 
+    pub fn tick(&mut self, ecs: &EcsWorld) -> ScriptingResult<ScriptingOutput> {
+        self.inner.tick(ecs)
+    }
+
+    pub fn reset(&mut self) -> ScriptingResult<()> {
+        self.inner.reset()
+    }
+
     fn eval_bytes(&mut self, bytes: &[u8], ecs: &EcsWorld) -> ScriptingResult<ScriptingOutput> {
         self.inner.run(bytes, ecs)
     }
@@ -75,6 +121,10 @@ impl ScriptingOutput {
         self.0.push('\n');
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn into_string(self) -> String {
         self.0
     }
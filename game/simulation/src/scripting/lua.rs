@@ -1,12 +1,22 @@
 use rlua::prelude::*;
-use rlua::{Context, MetaMethod, StdLib, UserData, UserDataMethods, Variadic};
+use rlua::{Context, FromLua, MetaMethod, StdLib, UserData, UserDataMethods, Variadic};
 
-use crate::ecs::EntityWrapper;
+use unit::world::{WorldPoint, WorldPosition, WorldPositionRange};
+use world::block::BlockType;
+use world::loader::{TerrainUpdatesRes, WorldTerrainUpdate};
+
+use crate::ecs::{Component, ComponentRef, Entity, EntityWrapper};
 use crate::input::SelectedEntities;
+use crate::job::SocietyCommand;
 use crate::scripting::context::{
     parse_entity_id, Scripting, ScriptingError, ScriptingOutput, ScriptingResult,
 };
-use crate::{ComponentWorld, EcsWorld, PlayerSociety, SocietyHandle};
+use crate::simulation::Tick;
+use crate::spatial::Spatial;
+use crate::{
+    ComponentWorld, DeathReason, EcsWorld, HungerComponent, InventoryComponent, PlayerSociety,
+    Societies, SocietyHandle, TransformComponent,
+};
 use common::*;
 
 pub struct LuaScripting {
@@ -16,6 +26,15 @@ pub struct LuaScripting {
 /// Key used in lua registry
 const GAME_STATE_KEY: &str = "game-state";
 
+/// Key used in lua registry for the sequence of functions registered with `OnTick`
+const TICK_CALLBACKS_KEY: &str = "tick-callbacks";
+
+/// Block position passed from lua as a `{x, y, z}` table
+struct LuaPosition(WorldPosition);
+
+/// Point passed from lua as a `{x, y, z}` table
+struct LuaPoint(WorldPoint);
+
 /// Temporary references to game state for use in scripts. Only one script runs at a time
 #[derive(Clone)]
 struct LuaGameState<'a> {
@@ -38,7 +57,11 @@ impl UserData for LuaGameState<'_> {}
 //  - scripts can't access or store these references
 unsafe impl Send for LuaGameState<'static> {}
 
-impl UserData for EntityWrapper {}
+impl UserData for EntityWrapper {
+    fn add_methods<'lua, T: UserDataMethods<'lua, Self>>(methods: &mut T) {
+        methods.add_meta_function(MetaMethod::ToString, |_, this: Self| Ok(this.to_string()));
+    }
+}
 impl UserData for SocietyHandle {
     fn add_methods<'lua, T: UserDataMethods<'lua, Self>>(methods: &mut T) {
         methods.add_meta_function(MetaMethod::ToString, |_, this: Self| {
@@ -58,19 +81,18 @@ impl Scripting for LuaScripting {
         runtime.set_memory_limit(Some(10 * 1024 * 1024));
         // TODO configure lua GC
 
-        runtime.context(populate_globals)?;
+        runtime.context(|ctx| {
+            populate_globals(ctx)?;
+            ctx.set_named_registry_value(TICK_CALLBACKS_KEY, ctx.create_table()?)?;
+            Ok::<_, ScriptingError>(())
+        })?;
 
         Ok(Self { runtime })
     }
 
     fn run(&mut self, script: &[u8], ecs: &EcsWorld) -> ScriptingResult<ScriptingOutput> {
         let mut output = ScriptingOutput::default();
-        let state = LuaGameState {
-            ecs,
-            output: &mut output as *mut _,
-            #[cfg(debug_assertions)]
-            safety_guard: Default::default(),
-        };
+        let state = LuaGameState::new(ecs, &mut output);
 
         self.runtime.context(|ctx| {
             let guard = state.install(ctx)?;
@@ -81,6 +103,51 @@ impl Scripting for LuaScripting {
 
         Ok(output)
     }
+
+    fn tick(&mut self, ecs: &EcsWorld) -> ScriptingResult<ScriptingOutput> {
+        let mut output = ScriptingOutput::default();
+        let state = LuaGameState::new(ecs, &mut output);
+
+        self.runtime.context(|ctx| {
+            let callbacks: LuaTable = ctx.named_registry_value(TICK_CALLBACKS_KEY)?;
+            if callbacks.raw_len() == 0 {
+                return Ok(());
+            }
+
+            let tick = Tick::fetch().value();
+            let guard = state.install(ctx)?;
+            let result = callbacks
+                .clone()
+                .sequence_values::<LuaValue>()
+                .enumerate()
+                .try_for_each(|(i, callback)| -> rlua::Result<()> {
+                    let callback = match callback? {
+                        LuaValue::Function(f) => f,
+                        _ => return Ok(()), // unregistered
+                    };
+
+                    if let Err(err) = callback.call::<_, ()>(tick) {
+                        warn!("lua tick callback errored, unregistering it"; "error" => %err);
+
+                        // replaced rather than removed to avoid a hole in the sequence
+                        callbacks.raw_set(i + 1, false)?;
+                    }
+
+                    Ok(())
+                });
+            guard.uninstall(ctx)?;
+            result
+        })?;
+
+        Ok(output)
+    }
+
+    fn reset(&mut self) -> ScriptingResult<()> {
+        self.runtime
+            .context(|ctx| ctx.set_named_registry_value(TICK_CALLBACKS_KEY, ctx.create_table()?))?;
+
+        Ok(())
+    }
 }
 
 fn populate_globals(ctx: Context) -> ScriptingResult<()> {
@@ -158,10 +225,223 @@ fn populate_globals(ctx: Context) -> ScriptingResult<()> {
         Ok(society.get())
     });
 
+    // ---- entities
+
+    define!(fn SpawnEntity |ctx, (uid, x, y, z): (String, i32, i32, Option<i32>)| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        let builder = state.ecs.build_entity(&uid).map_err(ScriptingError::from);
+        let result = builder.and_then(|builder| {
+            // z is optional, defaulting to the highest accessible block in the column
+            let builder = match z {
+                Some(z) => builder.with_position(WorldPosition::from((x, y, z))),
+                None => builder.with_position((x, y)),
+            };
+
+            builder.spawn().map_err(ScriptingError::from)
+        });
+
+        result
+            .map(EntityWrapper::from)
+            .map_err(rlua::Error::external)
+    });
+
+    define!(fn KillEntity |ctx, eid: EntityWrapper| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        let entity = state.entity(eid)?;
+        state.ecs.kill_entity(entity, DeathReason::Unknown);
+        Ok(())
+    });
+
+    define!(fn GetPosition |ctx, eid: EntityWrapper| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        let entity = state.entity(eid)?;
+        let transform = state.component::<TransformComponent>(entity, "transform")?;
+        Ok(transform.position.xyz())
+    });
+
+    define!(fn FindEntitiesNear |ctx, (centre, radius, component): (LuaPoint, f32, Option<String>)| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        let nearby = state
+            .ecs
+            .resource::<Spatial>()
+            .query_in_radius(state.ecs, centre.0, radius)
+            .map(|(e, _, _)| e)
+            .collect_vec();
+
+        Ok(nearby
+            .into_iter()
+            .filter(|e| match component.as_ref() {
+                Some(comp) => state.ecs.has_component_by_name(comp, *e),
+                None => true,
+            })
+            .map(EntityWrapper::from)
+            .collect_vec())
+    });
+
+    // ---- needs and inventory
+
+    define!(fn GetHunger |ctx, eid: EntityWrapper| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        let entity = state.entity(eid)?;
+        let hunger = state.component::<HungerComponent>(entity, "hunger")?;
+        Ok(hunger.hunger().satiety().value())
+    });
+
+    define!(fn SetHunger |ctx, (eid, satiety): (EntityWrapper, f32)| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        let entity = state.entity(eid)?;
+        let mut hunger = state
+            .ecs
+            .component_mut::<HungerComponent>(entity)
+            .map_err(|_| rlua::Error::external(ScriptingError::MissingComponent(eid, "hunger")))?;
+        hunger.hunger_mut().set_satiety(NormalizedFloat::clamped(satiety));
+        Ok(())
+    });
+
+    define!(fn GetInventory |ctx, eid: EntityWrapper| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        let entity = state.entity(eid)?;
+        let inventory = state.component::<InventoryComponent>(entity, "inventory")?;
+
+        // held items first, then the contents of any held containers
+        let mut items = inventory.all_equipped_items().collect_vec();
+        for (_, container) in inventory.containers(state.ecs) {
+            items.extend(container.contents().map(|held| held.entity));
+        }
+
+        Ok(items.into_iter().map(EntityWrapper::from).collect_vec())
+    });
+
+    define!(fn GiveItem |ctx, (holder, item): (EntityWrapper, EntityWrapper)| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        let holder = state.entity(holder)?;
+        let item = state.entity(item)?;
+        state
+            .ecs
+            .helpers_dev()
+            .give_item(holder, item)
+            .map_err(|err| rlua::Error::external(ScriptingError::from(err)))
+    });
+
+    // ---- society commands
+
+    define!(fn BreakBlocks |ctx, (society, from, to): (SocietyHandle, LuaPosition, LuaPosition)| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        state.submit_command(society, SocietyCommand::BreakBlocks(to_range(from, to)))
+    });
+
+    define!(fn Build |ctx, (society, template, from, to): (SocietyHandle, String, LuaPosition, LuaPosition)| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        let template = state
+            .ecs
+            .find_build_template(&template)
+            .ok_or(ScriptingError::UnknownBuildTemplate(template))
+            .map_err(rlua::Error::external)?;
+        state.submit_command(society, SocietyCommand::Build(to_range(from, to), template))
+    });
+
+    define!(fn HaulToPosition |ctx, (society, thing, target): (SocietyHandle, EntityWrapper, LuaPoint)| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        let thing = state.entity(thing)?;
+        state.submit_command(society, SocietyCommand::HaulToPosition(thing, target.0))
+    });
+
+    define!(fn HaulIntoContainer |ctx, (society, thing, container): (SocietyHandle, EntityWrapper, EntityWrapper)| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        let thing = state.entity(thing)?;
+        let container = state.entity(container)?;
+        state.submit_command(society, SocietyCommand::HaulIntoContainer(thing, container))
+    });
+
+    define!(fn Craft |ctx, (society, workshop, recipe): (SocietyHandle, EntityWrapper, String)| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        let workshop = state.entity(workshop)?;
+        let recipe = state
+            .ecs
+            .find_recipe(&recipe)
+            .ok_or(ScriptingError::UnknownRecipe(recipe))
+            .map_err(rlua::Error::external)?;
+        state.submit_command(society, SocietyCommand::Craft(workshop, recipe))
+    });
+
+    // ---- terrain
+
+    define!(fn SetBlocks |ctx, (block, from, to): (String, LuaPosition, LuaPosition)| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        let block_type = block
+            .parse::<BlockType>()
+            .map_err(|_| rlua::Error::external(ScriptingError::InvalidBlockType(block)))?;
+        state.update_terrain(to_range(from, to), block_type);
+        Ok(())
+    });
+
+    define!(fn RemoveBlocks |ctx, (from, to): (LuaPosition, LuaPosition)| {
+        let state: LuaGameState = ctx.named_registry_value(GAME_STATE_KEY)?;
+
+        state.update_terrain(to_range(from, to), BlockType::Air);
+        Ok(())
+    });
+
+    // ---- callbacks
+
+    define!(fn OnTick |ctx, callback: LuaFunction| {
+        let callbacks: LuaTable = ctx.named_registry_value(TICK_CALLBACKS_KEY)?;
+        callbacks.set(callbacks.raw_len() + 1, callback)
+    });
+
+    define!(fn ClearTickCallbacks |ctx, _: ()| {
+        ctx.set_named_registry_value(TICK_CALLBACKS_KEY, ctx.create_table()?)
+    });
+
     Ok(())
 }
 
+fn to_range(from: LuaPosition, to: LuaPosition) -> WorldPositionRange {
+    WorldPositionRange::with_inclusive_range(from.0, to.0)
+}
+
+impl<'lua> FromLua<'lua> for LuaPosition {
+    fn from_lua(value: LuaValue<'lua>, ctx: Context<'lua>) -> rlua::Result<Self> {
+        let table = LuaTable::from_lua(value, ctx)?;
+        let xyz: (i32, i32, i32) = (table.get(1)?, table.get(2)?, table.get(3)?);
+        Ok(Self(WorldPosition::from(xyz)))
+    }
+}
+
+impl<'lua> FromLua<'lua> for LuaPoint {
+    fn from_lua(value: LuaValue<'lua>, ctx: Context<'lua>) -> rlua::Result<Self> {
+        let table = LuaTable::from_lua(value, ctx)?;
+        let (x, y, z) = (table.get(1)?, table.get(2)?, table.get(3)?);
+        WorldPoint::new(x, y, z)
+            .map(Self)
+            .ok_or_else(|| rlua::Error::external(ScriptingError::InvalidPoint((x, y, z))))
+    }
+}
+
 impl<'a> LuaGameState<'a> {
+    fn new(ecs: &'a EcsWorld, output: &mut ScriptingOutput) -> Self {
+        Self {
+            ecs,
+            output: output as *mut _,
+            #[cfg(debug_assertions)]
+            safety_guard: Default::default(),
+        }
+    }
+
     fn install(self, context: Context) -> rlua::Result<LuaGameStateGuard> {
         // safety: registry value is "static" in that it lives for the lifetime of the script, it's
         // removed when the returned guard is dropped
@@ -177,6 +457,45 @@ impl<'a> LuaGameState<'a> {
             Err(ScriptingError::DeadEntity(entity))
         }
     }
+
+    /// Alive entity or lua error
+    fn entity(&self, entity: EntityWrapper) -> rlua::Result<Entity> {
+        self.ensure_alive(entity)
+            .map(Entity::from)
+            .map_err(rlua::Error::external)
+    }
+
+    fn component<T: Component>(
+        &self,
+        entity: Entity,
+        name: &'static str,
+    ) -> rlua::Result<ComponentRef<'a, T>> {
+        self.ecs.component::<T>(entity).map_err(|_| {
+            rlua::Error::external(ScriptingError::MissingComponent(entity.into(), name))
+        })
+    }
+
+    fn submit_command(&self, society: SocietyHandle, command: SocietyCommand) -> rlua::Result<()> {
+        let societies = self.ecs.resource::<Societies>();
+        let society_ref = societies
+            .society_by_handle(society)
+            .ok_or_else(|| ScriptingError::InvalidSociety(format!("{:?}", society)))
+            .map_err(rlua::Error::external)?;
+
+        debug!("lua: submitting command to society"; "society" => ?society, "command" => ?command);
+        command
+            .submit_job_to_society(society_ref, self.ecs)
+            .map_err(|cmd| {
+                rlua::Error::external(ScriptingError::RejectedCommand(format!("{:?}", cmd)))
+            })
+    }
+
+    fn update_terrain(&self, range: WorldPositionRange, block_type: BlockType) {
+        debug!("lua: updating terrain"; "range" => ?range, "block_type" => ?block_type);
+        self.ecs
+            .resource_mut::<TerrainUpdatesRes>()
+            .push(WorldTerrainUpdate::new(range, block_type));
+    }
 }
 
 impl LuaGameStateGuard {
@@ -184,3 +503,103 @@ impl LuaGameStateGuard {
         ctx.unset_named_registry_value(GAME_STATE_KEY)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(lua: &mut LuaScripting, ecs: &EcsWorld, script: &str) -> ScriptingResult<String> {
+        lua.run(script.as_bytes(), ecs)
+            .map(|output| output.into_string())
+    }
+
+    #[test]
+    fn print_output() {
+        let ecs = EcsWorld::new();
+        let mut lua = LuaScripting::new().unwrap();
+
+        let output = run(&mut lua, &ecs, r#"print("hello", 5)"#).unwrap();
+        assert_eq!(output, "hello, 5\n");
+
+        // output is per script
+        let output = run(&mut lua, &ecs, "local x = 2").unwrap();
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn entity_api() {
+        let ecs = EcsWorld::new();
+        let mut lua = LuaScripting::new().unwrap();
+
+        let entity = Entity::from(
+            ecs.create_entity()
+                .with(TransformComponent::new(
+                    WorldPoint::new(1.5, 2.5, 3.5).unwrap(),
+                ))
+                .build(),
+        );
+
+        let script = format!(r#"print(GetPosition(GetEntityById("{}")))"#, entity);
+        let output = run(&mut lua, &ecs, &script).unwrap();
+        assert_eq!(output, "1.5, 2.5, 3.5\n");
+
+        assert!(run(&mut lua, &ecs, r#"GetEntityById("nope")"#).is_err());
+
+        // no hunger component
+        let script = format!(r#"GetHunger(GetEntityById("{}"))"#, entity);
+        assert!(run(&mut lua, &ecs, &script).is_err());
+    }
+
+    #[test]
+    fn erroring_tick_callbacks_are_unregistered() {
+        let ecs = EcsWorld::new();
+        let mut lua = LuaScripting::new().unwrap();
+
+        let script = r#"
+            count = 0
+            OnTick(function(tick) count = count + 1 end)
+            OnTick(function(tick) error("oops") end)
+            OnTick(function(tick) print("tick") end)
+        "#;
+        run(&mut lua, &ecs, script).unwrap();
+
+        // later callbacks still run after one errors
+        for _ in 0..2 {
+            let output = lua.tick(&ecs).unwrap().into_string();
+            assert_eq!(output, "tick\n");
+        }
+
+        assert_eq!(run(&mut lua, &ecs, "print(count)").unwrap(), "2\n");
+
+        // new callbacks don't fill the gap
+        run(&mut lua, &ecs, r#"OnTick(function(tick) print("new") end)"#).unwrap();
+        let output = lua.tick(&ecs).unwrap().into_string();
+        assert_eq!(output, "tick\nnew\n");
+    }
+
+    #[test]
+    fn reset_clears_tick_callbacks() {
+        let ecs = EcsWorld::new();
+        let mut lua = LuaScripting::new().unwrap();
+
+        run(
+            &mut lua,
+            &ecs,
+            r#"OnTick(function(tick) print("tick") end)"#,
+        )
+        .unwrap();
+        assert_eq!(lua.tick(&ecs).unwrap().into_string(), "tick\n");
+
+        lua.reset().unwrap();
+        assert_eq!(lua.tick(&ecs).unwrap().into_string(), "");
+
+        // can still register after reset
+        run(
+            &mut lua,
+            &ecs,
+            r#"OnTick(function(tick) print("again") end)"#,
+        )
+        .unwrap();
+        assert_eq!(lua.tick(&ecs).unwrap().into_string(), "again\n");
+    }
+}
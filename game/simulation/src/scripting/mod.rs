@@ -1,6 +1,6 @@
 mod context;
 
-pub use context::{Scripting, ScriptingError};
+pub use context::{parse_entity_id, Scripting, ScriptingError};
 
 #[cfg(feature = "scripting")]
 mod lua;
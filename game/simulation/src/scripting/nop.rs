@@ -11,4 +11,12 @@ impl Scripting for NopScripting {
     fn run(&mut self, _script: &[u8], _ecs: &EcsWorld) -> ScriptingResult<ScriptingOutput> {
         Ok(ScriptingOutput::default())
     }
+
+    fn tick(&mut self, _ecs: &EcsWorld) -> ScriptingResult<ScriptingOutput> {
+        Ok(ScriptingOutput::default())
+    }
+
+    fn reset(&mut self) -> ScriptingResult<()> {
+        Ok(())
+    }
 }
@@ -1,6 +1,8 @@
 mod debug;
+mod noise;
 mod sense;
 mod system;
 
 pub use debug::SensesDebugRenderer;
+pub use noise::{Noise, NoiseKind, Noises, VocalComponent, VocalSystem};
 pub use system::{MagicalSenseComponent, SensesComponent, SensesSystem};
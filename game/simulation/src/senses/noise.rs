@@ -0,0 +1,187 @@
+use crate::ecs::*;
+use crate::simulation::Tick;
+use crate::string::StringCache;
+use crate::TransformComponent;
+use common::*;
+use std::rc::Rc;
+use unit::world::WorldPoint;
+
+/// Ticks a noise lingers for after it was last emitted
+const NOISE_DURATION: u32 = 10;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum NoiseKind {
+    BreakBlock,
+    Build,
+    Death,
+    Vocalisation,
+    Fight,
+}
+
+/// A sound made by an entity, heard through walls by anything with hearing in range. Loudness is a
+/// multiplier on the listener's hearing radius, so a noise with loudness 2 can be heard twice as
+/// far as a normal one
+#[derive(Debug, Clone)]
+pub struct Noise {
+    pub source: Entity,
+    pub position: WorldPoint,
+    pub kind: NoiseKind,
+    pub loudness: f32,
+    expires: Tick,
+}
+
+/// All noises currently being made
+#[derive(Default)]
+pub struct Noises(Vec<Noise>);
+
+/// Randomly makes noise e.g. barking
+#[derive(Component, EcsComponent, Debug, Clone)]
+#[name("vocal")]
+#[storage(DenseVecStorage)]
+#[clone(disallow)]
+pub struct VocalComponent {
+    pub loudness: f32,
+    /// Chance of making a noise each tick
+    pub chance: f32,
+}
+
+pub struct VocalSystem;
+
+impl NoiseKind {
+    /// Default loudness of this kind of noise
+    pub fn loudness(self) -> f32 {
+        match self {
+            NoiseKind::BreakBlock => 1.5,
+            NoiseKind::Build => 1.0,
+            NoiseKind::Death => 2.0,
+            NoiseKind::Vocalisation => 1.0,
+            NoiseKind::Fight => 1.5,
+        }
+    }
+}
+
+impl Noises {
+    pub fn emit(&mut self, source: Entity, position: WorldPoint, kind: NoiseKind) {
+        self.emit_with_loudness(source, position, kind, kind.loudness())
+    }
+
+    /// Repeatedly emitting the same kind of noise from the same source prolongs the existing noise
+    pub fn emit_with_loudness(
+        &mut self,
+        source: Entity,
+        position: WorldPoint,
+        kind: NoiseKind,
+        loudness: f32,
+    ) {
+        let expires = Tick::fetch() + NOISE_DURATION;
+        if let Some(existing) = self
+            .0
+            .iter_mut()
+            .find(|n| n.source == source && n.kind == kind)
+        {
+            existing.position = position;
+            existing.loudness = loudness;
+            existing.expires = expires;
+        } else {
+            trace!("new noise"; "source" => source, "kind" => ?kind, "position" => %position);
+            self.0.push(Noise {
+                source,
+                position,
+                kind,
+                loudness,
+                expires,
+            });
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Noise> + '_ {
+        self.0.iter()
+    }
+
+    pub(in crate::senses) fn remove_expired(&mut self) {
+        let now = Tick::fetch().value();
+        self.0.retain(|n| n.expires.value() > now);
+    }
+}
+
+impl<'a> System<'a> for VocalSystem {
+    type SystemData = (
+        Read<'a, EntitiesRes>,
+        ReadStorage<'a, VocalComponent>,
+        ReadStorage<'a, TransformComponent>,
+        Write<'a, Noises>,
+    );
+
+    fn run(&mut self, (entities, vocals, transforms, mut noises): Self::SystemData) {
+        let mut rng = random::get();
+        for (e, vocal, transform) in (&entities, &vocals, &transforms).join() {
+            if rng.gen_bool(vocal.chance as f64) {
+                noises.emit_with_loudness(
+                    e.into(),
+                    transform.position,
+                    NoiseKind::Vocalisation,
+                    vocal.loudness,
+                );
+            }
+        }
+    }
+}
+
+impl<V: Value> ComponentTemplate<V> for VocalComponent {
+    fn construct(
+        values: &mut Map<V>,
+        _: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let loudness = values.get_float("loudness")?;
+        let chance: f32 = values.get_float("chance")?;
+        if !(0.0..=1.0).contains(&chance) {
+            return Err(ComponentBuildError::TemplateSpecific(format!(
+                "vocal chance should be 0-1 but is {}",
+                chance
+            )));
+        }
+
+        Ok(Rc::new(Self { loudness, chance }))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
+register_component_template!("vocal", VocalComponent);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::senses::sense::HearingSphere;
+
+    #[test]
+    fn noises() {
+        let mut world = specs::World::new();
+        let dog = Entity::from(world.create_entity().build());
+        let human = Entity::from(world.create_entity().build());
+
+        let pos = WorldPoint::new(2.0, 3.0, 4.0).unwrap();
+        let mut noises = Noises::default();
+        noises.emit(dog, pos, NoiseKind::Vocalisation);
+        noises.emit(dog, pos + (1.0, 0.0, 0.0), NoiseKind::Vocalisation);
+        noises.emit(human, pos, NoiseKind::BreakBlock);
+        noises.emit(dog, pos, NoiseKind::Death);
+
+        // repeated noise is prolonged instead of duplicated
+        assert_eq!(noises.iter().count(), 3);
+        let bark = noises.iter().find(|n| n.source == dog).unwrap();
+        assert_eq!(bark.position, pos + (1.0, 0.0, 0.0));
+
+        let hearing = HearingSphere { radius: 4.0 };
+        let break_block = noises.iter().find(|n| n.source == human).unwrap();
+        assert!(hearing.hears(break_block, 5.0)); // louder than normal
+        assert!(!hearing.hears(bark, 5.0));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::senses::noise::Noise;
 use bitflags::bitflags;
 use common::{InnerSpace, OrderedFloat, Rad, Vector2, Zero};
 use std::hash::{Hash, Hasher};
@@ -16,7 +17,7 @@ pub struct VisionCone {
 
 #[derive(Debug, Default, Clone)]
 pub struct HearingSphere {
-    /// Radius of the hearing sphere
+    /// Radius within which a noise of normal loudness can be heard
     pub radius: f32,
 }
 
@@ -74,8 +75,8 @@ impl VisionCone {
 }
 
 impl HearingSphere {
-    #[allow(dead_code)] // currently unused
-    pub fn senses(&self, distance: f32) -> bool {
-        distance <= self.radius
+    /// Not blocked by terrain
+    pub fn hears(&self, noise: &Noise, distance: f32) -> bool {
+        distance <= self.radius * noise.loudness
     }
 }
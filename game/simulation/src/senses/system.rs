@@ -1,4 +1,5 @@
 use crate::ecs::*;
+use crate::senses::noise::Noises;
 use crate::senses::sense::{HearingSphere, Sense, VisionCone};
 use crate::simulation::EcsWorldRef;
 use crate::spatial::Spatial;
@@ -43,7 +44,7 @@ struct SensedEntity {
 #[clone(disallow)]
 pub struct MagicalSenseComponent {
     pub vision: VisionCone,
-    // pub hearing: HearingSphere,
+    pub hearing: Option<HearingSphere>,
 }
 
 pub struct SensesSystem;
@@ -53,6 +54,7 @@ impl<'a> System<'a> for SensesSystem {
         Read<'a, Spatial>,
         Read<'a, EntitiesRes>,
         Read<'a, EcsWorldRef>,
+        Write<'a, Noises>,
         ReadStorage<'a, MagicalSenseComponent>,
         ReadStorage<'a, TransformComponent>,
         WriteStorage<'a, SensesComponent>,
@@ -60,7 +62,7 @@ impl<'a> System<'a> for SensesSystem {
 
     fn run(
         &mut self,
-        (spatial, entities, world, providers, transforms, mut senses): Self::SystemData,
+        (spatial, entities, world, mut noises, providers, transforms, mut senses): Self::SystemData,
     ) {
         log_scope!(o!("system" => "senses"));
 
@@ -75,13 +77,15 @@ impl<'a> System<'a> for SensesSystem {
 
             // no calculation needed atm, just copy the sense definition directly into the senses
             senses.vision.push(provider.vision.clone());
-            // senses.hearing.push(provider.hearing.clone());
+            senses.hearing.extend(provider.hearing.clone());
 
             if senses.debug_hash() != prev_hash {
                 debug!("senses updated"; e, "senses" => ?senses)
             }
         }
 
+        noises.remove_expired();
+
         // use senses
         for (e, senses, transform) in (&entities, &mut senses, &transforms).join() {
             let e = Entity::from(e);
@@ -89,27 +93,31 @@ impl<'a> System<'a> for SensesSystem {
 
             senses.decay_sensed_entities();
 
-            // do a single query for all senses
-            let max_radius = match senses.max_radius() {
-                Some(f) => f,
-                None => {
-                    // no senses
-                    trace!("no senses");
-                    continue;
-                }
-            };
-
-            // TODO specialize query e.g. only detect those with a given component combo e.g. Transform + Render (+ Visible/!Invisible?)
+            // do a single query for all vision
+            if let Some(max_radius) = senses.max_vision_radius() {
+                // TODO specialize query e.g. only detect those with a given component combo e.g. Transform + Render (+ Visible/!Invisible?)
+
+                spatial
+                    .query_in_radius(&world, transform.position, max_radius)
+                    .filter(|(entity, _, _)| *entity != e) // TODO self is probably the first in the list
+                    .for_each(|(entity, pos, dist)| {
+                        let sensed = senses.senses(transform, &pos, dist);
+                        if !sensed.is_empty() {
+                            senses.add_sensed_entity(entity, sensed);
+                        }
+                    });
+            }
 
-            spatial
-                .query_in_radius(&world, transform.position, max_radius)
-                .filter(|(entity, _, _)| *entity != e) // TODO self is probably the first in the list
-                .for_each(|(entity, pos, dist)| {
-                    let sensed = senses.senses(transform, &pos, dist);
-                    if !sensed.is_empty() {
-                        senses.add_sensed_entity(entity, sensed);
+            // hear noises made by others, even through walls
+            if !senses.hearing.is_empty() {
+                for noise in noises.iter().filter(|n| n.source != e) {
+                    let distance = noise.position.distance2(transform.position).sqrt();
+                    if senses.hearing.iter().any(|h| h.hears(noise, distance)) {
+                        trace!("heard noise"; "source" => noise.source, "kind" => ?noise.kind);
+                        senses.add_sensed_entity(noise.source, Sense::HEARING);
                     }
-                });
+                }
+            }
 
             trace!("senses {count} entities", count = senses.sensed.len());
         }
@@ -122,11 +130,12 @@ impl SensesComponent {
         self.hearing.clear();
     }
 
-    fn max_radius(&self) -> Option<f32> {
-        let vision = self.vision.iter().map(|v| v.length);
-        let hearing = self.hearing.iter().map(|h| h.radius);
-
-        vision.chain(hearing).max_by_key(|f| OrderedFloat(*f))
+    /// Hearing is excluded, as it only senses noises rather than all entities in range
+    fn max_vision_radius(&self) -> Option<f32> {
+        self.vision
+            .iter()
+            .map(|v| v.length)
+            .max_by_key(|f| OrderedFloat(*f))
     }
 
     fn debug_hash(&self) -> u64 {
@@ -157,10 +166,6 @@ impl SensesComponent {
             result.insert(Sense::VISION);
         }
 
-        // if self.hearing.iter().any(|h| h.senses(distance)) {
-        //     result.insert(Sense::HEARING);
-        // }
-
         result
     }
 
@@ -228,13 +233,17 @@ impl<V: Value> ComponentTemplate<V> for MagicalSenseComponent {
             angle_offset: f32,
         }
 
-        // #[derive(Deserialize)]
-        // struct DeHearing {
-        //     radius: f32,
-        // }
+        #[derive(Deserialize)]
+        struct DeHearing {
+            radius: f32,
+        }
 
         let vision: DeVision = values.get("vision").and_then(|v| v.into_type())?;
-        // let hearing: DeHearing = values.get("hearing").and_then(|v| v.into_type())?;
+        let hearing: Option<DeHearing> = match values.get("hearing") {
+            Ok(v) => Some(v.into_type()?),
+            Err(ComponentBuildError::KeyNotFound(_)) => None, // deaf
+            Err(e) => return Err(e),
+        };
 
         Ok(Rc::new(Self {
             vision: VisionCone {
@@ -242,9 +251,9 @@ impl<V: Value> ComponentTemplate<V> for MagicalSenseComponent {
                 angle: deg(vision.angle).into(),
                 angle_offset: deg(vision.angle_offset).into(),
             },
-            // hearing: HearingSphere {
-            //     radius: hearing.radius,
-            // },
+            hearing: hearing.map(|hearing| HearingSphere {
+                radius: hearing.radius,
+            }),
         }))
     }
 
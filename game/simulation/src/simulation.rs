@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 use std::ops::{Add, Deref};
+use std::path::Path;
 use std::pin::Pin;
 
 use strum::EnumDiscriminants;
@@ -7,10 +8,10 @@ use strum::EnumDiscriminants;
 use common::*;
 use resources::Resources;
 
-use unit::world::{WorldPosition, WorldPositionRange};
+use unit::world::{ChunkLocation, SlabLocation, WorldPosition, WorldPositionRange};
 use world::block::BlockType;
 use world::loader::{TerrainUpdatesRes, WorldTerrainUpdate};
-use world::WorldChangeEvent;
+use world::{LiquidLevel, LiquidSimulation, WorldChangeEvent};
 use world_types::EntityDescription;
 
 use crate::activity::ActivitySystem;
@@ -18,16 +19,23 @@ use crate::ai::{AiComponent, AiSystem};
 use crate::alloc::FrameAllocator;
 use crate::backend::TickResponse;
 use crate::ecs::*;
-use crate::event::{DeathReason, EntityEventQueue, RuntimeTimers};
+use crate::event::{DeathReason, EntityEventLog, EntityEventQueue, RuntimeTimers};
+use crate::farming::GrowthSystem;
+use crate::health::HealthSystem;
 use crate::input::{
-    BlockPlacement, InputEvent, InputSystem, MouseLocation, SelectedEntities, SelectedTiles,
-    UiCommand, UiPopup, UiRequest, UiResponsePayload,
+    InputEvent, InputSystem, MouseLocation, SelectedEntities, SelectedTiles, UiCommand, UiPopup,
+    UiRequest, UiResponse, UiResponsePayload,
 };
 use crate::interact::herd::{HerdDebugRenderer, HerdJoiningSystem, Herds};
-use crate::item::{ContainerComponent, HaulSystem};
+use crate::item::{find_corpses, spawn_corpses, ContainerComponent, DecaySystem, HaulSystem};
 use crate::movement::MovementFulfilmentSystem;
 use crate::needs::food::{EatingSystem, HungerSystem};
-use crate::path::{NavigationAreaDebugRenderer, PathDebugRenderer, PathSteeringSystem};
+use crate::needs::sleep::EnergySystem;
+use crate::needs::thirst::ThirstSystem;
+use crate::path::{
+    NavigationAreaDebugRenderer, PathCongestionSystem, PathDebugRenderer, PathSteeringSystem,
+    Pathfinder,
+};
 use crate::physics::PhysicsSystem;
 use crate::queued_update::QueuedUpdates;
 use crate::render::{
@@ -35,19 +43,23 @@ use crate::render::{
     DebugRenderersState, UiElementPruneSystem,
 };
 use crate::render::{RenderSystem, Renderer};
+use crate::replay::{
+    Recording, Replay, ReplayPlayer, ReplayRecorder, ReplayRequest, ReplayedRequest,
+};
 use crate::runtime::{Runtime, RuntimeSystem};
+use crate::save::{SaveError, SaveGame};
 use crate::scripting::ScriptingContext;
-use crate::senses::{SensesDebugRenderer, SensesSystem};
-use crate::society::{NameGeneration, PlayerSociety};
+use crate::senses::{Noises, SensesDebugRenderer, SensesSystem, VocalSystem};
+use crate::society::{NameGeneration, PlayerSociety, WorkPreferencesComponent};
 use crate::spatial::{Spatial, SpatialSystem};
 use crate::steer::{SteeringDebugRenderer, SteeringSystem};
 use crate::string::StringCache;
 use crate::world_debug::FeatureBoundaryDebugRenderer;
 use crate::{
     definitions, BackendData, EntityEvent, EntityEventPayload, EntityLoggingComponent,
-    ThreadedWorldLoader, WorldRef, WorldViewer,
+    ThreadedWorldLoader, TransformComponent, WorldRef, WorldViewer,
 };
-use crate::{ComponentWorld, Societies, SocietyHandle};
+use crate::{AiAction, Calendar, ComponentWorld, Societies, SocietyHandle};
 
 #[derive(Debug, EnumDiscriminants)]
 #[strum_discriminants(name(AssociatedBlockDataType))]
@@ -62,6 +74,9 @@ pub struct WorldContext;
 /// produced in tick()
 static mut TICK: u32 = 0;
 
+/// Liquids flow once every this many ticks
+const LIQUID_FLOW_INTERVAL: u32 = 4;
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
 /// Represents a game tick
 pub struct Tick(u32);
@@ -89,11 +104,22 @@ pub struct Simulation<R: Renderer> {
     /// World change events populated during terrain updates, consumed every tick
     change_events: Vec<WorldChangeEvent>,
 
+    liquids: LiquidSimulation,
+
+    /// Liquid level changes from the liquid simulation or a loaded save, applied every tick
+    liquid_changes: Vec<(WorldPosition, LiquidLevel)>,
+
+    /// Slabs that have been modified since the game started, to be persisted in saves
+    modified_slabs: HashSet<SlabLocation>,
+
     debug_renderers: DebugRenderers<R>,
     scripting: ScriptingContext,
 
     /// One off system that caches some allocations
     display_text_system: DisplayTextSystem,
+
+    /// Recording or playing back player input, if any
+    replay: Option<Replay>,
 }
 
 /// A little bundle of references to the game state without the generic [Renderer] param
@@ -138,6 +164,7 @@ impl<R: Renderer> Simulation<R> {
         let mut ecs_world = EcsWorld::with_definitions(definitions)?;
         ecs_world.insert(voxel_world.clone());
         ecs_world.insert(string_cache);
+        ecs_world.insert::<Pathfinder>(world_loader.pathfinder());
         register_resources(&mut ecs_world, resources)?;
 
         // get a self referential ecs world resource pointing to itself
@@ -166,8 +193,12 @@ impl<R: Renderer> Simulation<R> {
             debug_renderers,
             terrain_changes: HashSet::with_capacity(1024),
             change_events: Vec::with_capacity(1024),
+            liquids: LiquidSimulation::default(),
+            liquid_changes: Vec::new(),
+            modified_slabs: HashSet::new(),
             scripting: ScriptingContext::new()?,
             display_text_system: DisplayTextSystem::default(),
+            replay: None,
         })
     }
 
@@ -183,6 +214,9 @@ impl<R: Renderer> Simulation<R> {
         // update tick
         if !self.is_paused() {
             increment_tick();
+            self.ecs_world
+                .resource_mut::<Calendar>()
+                .update(Tick::fetch());
         }
 
         // TODO sort out systems so they all have an ecs_world reference and can keep state
@@ -213,6 +247,11 @@ impl<R: Renderer> Simulation<R> {
         // tick game logic
         self.tick_systems();
 
+        // run script callbacks
+        if !self.is_paused() {
+            self.tick_scripts();
+        }
+
         // per tick maintenance
         // must remove resource from world first so we can use &mut ecs_world
         let mut updates = self.ecs_world.remove::<QueuedUpdates>().unwrap();
@@ -221,9 +260,50 @@ impl<R: Renderer> Simulation<R> {
 
         self.delete_queued_entities();
         self.ecs_world.maintain();
+
+        if !self.is_paused() {
+            self.on_replay_tick_end();
+        }
+    }
+
+    /// Records all gameplay-affecting player input from now on. Should be started before the
+    /// first tick
+    pub fn start_recording(&mut self, recorder: ReplayRecorder) {
+        self.replay = Some(Replay::Recording(recorder));
+    }
+
+    /// Feeds recorded player input back in tick-for-tick, ignoring any real player input that
+    /// would affect the game. Should be started before the first tick, in a game started with the
+    /// same seed, config and scenario as the recording
+    pub fn start_replay(&mut self, player: ReplayPlayer) {
+        self.replay = Some(Replay::Playing(player));
     }
 
-    fn is_paused(&self) -> bool {
+    fn on_replay_tick_end(&mut self) {
+        if let Some(replay) = self.replay.as_mut() {
+            if let Err(err) = replay.on_tick_end(Tick::fetch(), &self.ecs_world) {
+                error!("replay failed, stopping"; "error" => %err);
+                self.replay = None;
+            } else if let Replay::Playing(player) = replay {
+                if player.is_finished() {
+                    info!("replay finished");
+                    self.replay = None;
+                }
+            }
+        }
+    }
+
+    fn tick_scripts(&mut self) {
+        match self.scripting.tick(&*self.ecs_world) {
+            Ok(output) if !output.is_empty() => {
+                info!("script tick output"; "output" => %output.into_string())
+            }
+            Ok(_) => {}
+            Err(err) => warn!("script tick callback errored"; "error" => %err),
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
         matches!(self.running, RunStatus::Paused)
     }
 
@@ -250,8 +330,17 @@ impl<R: Renderer> Simulation<R> {
             // needs
             run!(HungerSystem);
             run!(EatingSystem);
+            run!(ThirstSystem);
+            run!(EnergySystem);
+
+            // item condition
+            run!(DecaySystem);
 
-            // update senses
+            // plant growth
+            run!(GrowthSystem);
+
+            // make noise and update senses
+            run!(VocalSystem);
             run!(SensesSystem);
 
             // update herds
@@ -268,7 +357,8 @@ impl<R: Renderer> Simulation<R> {
                 self.ecs_world.resource::<Runtime>().tick();
             }
 
-            // follow paths with steering
+            // count agents in each block for congestion avoidance, then follow paths with steering
+            run!(PathCongestionSystem);
             run!(PathSteeringSystem);
 
             // apply steering
@@ -283,6 +373,9 @@ impl<R: Renderer> Simulation<R> {
             // apply physics
             run!(PhysicsSystem);
 
+            // apply injuries, bleeding and healing
+            run!(HealthSystem);
+
             // sync hauled item positions
             run!(HaulSystem);
 
@@ -312,6 +405,9 @@ impl<R: Renderer> Simulation<R> {
             // take out of resource so we can get a mutable world ref
             let deathlist = deathlist_ref.replace_entities(Vec::new());
 
+            // note corpses to leave behind while the dying still have their components
+            let corpses = find_corpses(&self.ecs_world, deathlist.iter().map(|e| Entity::from(*e)));
+
             if let Err(err) = self.ecs_world.delete_entities(&deathlist) {
                 error!("failed to kill entities"; "entities" => ?deathlist, "error" => %err);
             }
@@ -331,6 +427,8 @@ impl<R: Renderer> Simulation<R> {
 
             debug!("killed {} entities", n);
 
+            spawn_corpses(&self.ecs_world, corpses);
+
             deathlist_ref.clear();
         }
     }
@@ -403,6 +501,25 @@ impl<R: Renderer> Simulation<R> {
             }
         }
 
+        // flow liquids every few ticks, along with any restored from a save
+        if Tick::fetch().value() % LIQUID_FLOW_INTERVAL == 0 {
+            let world = self.voxel_world.borrow();
+            self.liquids.step(&world, &mut self.liquid_changes);
+        }
+
+        self.world_loader
+            .apply_liquid_updates(&mut self.liquid_changes, &mut self.change_events);
+        self.liquids.on_world_changes(&self.change_events);
+
+        // remember modified slabs for saving
+        self.modified_slabs
+            .extend(self.change_events.iter().map(|event| {
+                SlabLocation::new(
+                    event.pos.slice().slab_index(),
+                    ChunkLocation::from(event.pos),
+                )
+            }));
+
         // consume change events
         let mut events = std::mem::take(&mut self.change_events);
         self.on_world_changes(&events);
@@ -463,183 +580,288 @@ impl<R: Renderer> Simulation<R> {
         commands: impl Iterator<Item = UiCommand>,
         tick: &mut TickResponse,
     ) {
+        if let Some(Replay::Playing(_)) = self.replay {
+            self.apply_replayed_requests(tick);
+        }
+
         for cmd in commands {
             let (req, resp) = cmd.consume();
-            match req {
-                UiRequest::DisableAllDebugRenderers => {
-                    self.debug_renderers.disable_all(&self.ecs_world);
+            match self.replay.as_mut() {
+                Some(Replay::Recording(recorder)) => {
+                    if let Err(err) = recorder.record(&req, &self.ecs_world) {
+                        error!("failed to record replay, stopping recording"; "error" => %err);
+                        self.replay = None;
+                    }
                 }
-
-                UiRequest::SetDebugRendererEnabled { ident, enabled } => {
-                    if let Err(e) =
-                        self.debug_renderers
-                            .set_enabled(ident, enabled, &self.ecs_world)
+                Some(Replay::Playing(_)) => {
+                    if let Recording::Recorded(_) | Recording::Unsupported(_) =
+                        ReplayRequest::record(&req, &self.ecs_world)
                     {
-                        warn!("failed to set debug renderer state"; "error" => %e);
-                        if cfg!(debug_assertions) {
-                            panic!("unknown debug renderer: {}", e)
-                        }
+                        debug!("ignoring player input during replay");
+                        continue;
                     }
                 }
+                None => {}
+            }
 
-                UiRequest::FillSelectedTiles(placement, block_type) => {
-                    let selection = self.ecs_world.resource::<SelectedTiles>();
-                    if let Some((mut from, mut to)) =
-                        selection.current_selected().map(|sel| sel.range().bounds())
-                    {
-                        if let BlockPlacement::PlaceAbove = placement {
-                            // move the range up 1 block
-                            from = from.above();
-                            to = to.above();
-                        }
-                        let range = WorldPositionRange::with_inclusive_range(from, to);
-                        debug!("filling in block range"; "range" => ?range, "block_type" => ?block_type);
+            self.process_ui_request(req, resp, tick);
+        }
+    }
 
-                        self.terrain_changes
-                            .insert(WorldTerrainUpdate::new(range, block_type));
-                    }
+    fn apply_replayed_requests(&mut self, tick: &mut TickResponse) {
+        let player = match self.replay.as_mut() {
+            Some(Replay::Playing(player)) => player,
+            _ => return,
+        };
+
+        let requests = match player.take_requests(Tick::fetch()) {
+            Ok(requests) => requests,
+            Err(err) => {
+                error!("failed to read replay, stopping playback"; "error" => %err);
+                self.replay = None;
+                return;
+            }
+        };
+
+        for request in requests {
+            match request.resolve(&self.ecs_world) {
+                Ok(ReplayedRequest::FillTiles(range, block_type)) => {
+                    self.fill_tiles(range, block_type)
                 }
-                UiRequest::IssueDivineCommand(_) | UiRequest::CancelDivineCommand => {
-                    let mut ais = self.ecs_world.write_storage::<AiComponent>();
-                    let selected_entities = self.ecs_world.resource_mut::<SelectedEntities>();
-                    for selected in selected_entities.iter() {
-                        if let Some(ai) = selected.get_mut(&mut ais) {
-                            if let UiRequest::IssueDivineCommand(ref command) = req {
-                                ai.add_divine_command(command.clone());
-                            } else {
-                                ai.remove_divine_command();
-                            }
-                        }
+                Ok(ReplayedRequest::DivineCommand(subjects, command)) => {
+                    self.issue_divine_command(&subjects, command)
+                }
+                Ok(ReplayedRequest::Ui(req)) => {
+                    let (req, resp) = UiCommand::new(req).consume();
+                    self.process_ui_request(req, resp, tick);
+                }
+                Err(err) => warn!("failed to apply replayed request"; "error" => %err),
+            }
+        }
+    }
+
+    fn process_ui_request(&mut self, req: UiRequest, resp: UiResponse, tick: &mut TickResponse) {
+        match req {
+            UiRequest::DisableAllDebugRenderers => {
+                self.debug_renderers.disable_all(&self.ecs_world);
+            }
+
+            UiRequest::SetDebugRendererEnabled { ident, enabled } => {
+                if let Err(e) = self
+                    .debug_renderers
+                    .set_enabled(ident, enabled, &self.ecs_world)
+                {
+                    warn!("failed to set debug renderer state"; "error" => %e);
+                    if cfg!(debug_assertions) {
+                        panic!("unknown debug renderer: {}", e)
                     }
                 }
-                UiRequest::IssueSocietyCommand(society, command) => {
-                    let society = match self
-                        .world()
-                        .resource::<Societies>()
-                        .society_by_handle(society)
-                    {
-                        Some(s) => s,
-                        None => {
-                            warn!("invalid society while issuing command"; "society" => ?society, "command" => ?command);
-                            continue;
-                        }
-                    };
+            }
 
-                    debug!("submitting command to society"; "society" => ?society, "command" => ?command);
-                    if let Err(command) = command.submit_job_to_society(society, &self.ecs_world) {
-                        warn!("failed to issue society command"; "command" => ?command);
-                        continue;
+            UiRequest::FillSelectedTiles(placement, block_type) => {
+                let range = self
+                    .ecs_world
+                    .resource::<SelectedTiles>()
+                    .fill_range(placement);
+                if let Some(range) = range {
+                    self.fill_tiles(range, block_type);
+                }
+            }
+            UiRequest::IssueDivineCommand(command) => {
+                let selected = self.ecs_world.resource::<SelectedEntities>();
+                self.issue_divine_command(selected.iter(), Some(command));
+            }
+            UiRequest::CancelDivineCommand => {
+                let selected = self.ecs_world.resource::<SelectedEntities>();
+                self.issue_divine_command(selected.iter(), None);
+            }
+            UiRequest::IssueSocietyCommand(society, command) => {
+                let society = match self
+                    .world()
+                    .resource::<Societies>()
+                    .society_by_handle(society)
+                {
+                    Some(s) => s,
+                    None => {
+                        warn!("invalid society while issuing command"; "society" => ?society, "command" => ?command);
+                        return;
                     }
+                };
+
+                debug!("submitting command to society"; "society" => ?society, "command" => ?command);
+                if let Err(command) = command.submit_job_to_society(society, &self.ecs_world) {
+                    warn!("failed to issue society command"; "command" => ?command);
+                    return;
                 }
+            }
 
-                UiRequest::CancelJob(job) => {
-                    if let Some(society) = self
-                        .world()
-                        .resource::<Societies>()
-                        .society_by_handle(job.society())
-                    {
-                        society.jobs_mut().cancel(job);
+            UiRequest::CancelJob(job) => {
+                if let Some(society) = self
+                    .world()
+                    .resource::<Societies>()
+                    .society_by_handle(job.society())
+                {
+                    society.jobs_mut().cancel(job);
+                }
+            }
+
+            UiRequest::SetJobPriority(job, priority) => {
+                if let Some(society) = self
+                    .world()
+                    .resource::<Societies>()
+                    .society_by_handle(job.society())
+                {
+                    if !society.jobs_mut().set_priority(job, priority) {
+                        warn!("failed to set priority of missing job"; "job" => ?job);
                     }
                 }
+            }
 
-                UiRequest::SetContainerOwnership {
-                    container,
-                    owner,
-                    communal,
-                } => {
-                    match self
-                        .ecs_world
-                        .component_mut::<ContainerComponent>(container)
-                    {
-                        Err(e) => {
-                            warn!("invalid container entity"; "entity" => container, "error" => %e);
-                            continue;
+            UiRequest::SetWorkPreference {
+                entity,
+                category,
+                weight,
+            } => match self
+                .ecs_world
+                .component_mut::<WorkPreferencesComponent>(entity)
+            {
+                Err(e) => {
+                    warn!("invalid entity for work preferences"; "entity" => entity, "error" => %e);
+                }
+                Ok(mut prefs) => {
+                    prefs.set_weight(category, weight);
+                    info!("set work preference"; "entity" => entity, "category" => %category, "weight" => weight);
+                }
+            },
+
+            UiRequest::SetContainerOwnership {
+                container,
+                owner,
+                communal,
+            } => {
+                match self
+                    .ecs_world
+                    .component_mut::<ContainerComponent>(container)
+                {
+                    Err(e) => {
+                        warn!("invalid container entity"; "entity" => container, "error" => %e);
+                        return;
+                    }
+                    Ok(mut c) => {
+                        if let Some(owner) = owner {
+                            c.owner = owner;
+                            info!("set container owner"; "container" => container, "owner" => owner)
                         }
-                        Ok(mut c) => {
-                            if let Some(owner) = owner {
-                                c.owner = owner;
-                                info!("set container owner"; "container" => container, "owner" => owner)
-                            }
 
-                            if let Some(communal) = communal {
-                                if let Err(e) = self
-                                    .ecs_world
-                                    .helpers_containers()
-                                    .set_container_communal(container, communal)
-                                {
-                                    warn!("failed to set container society"; "container" => container, "society" => ?communal, "error" => %e);
-                                }
+                        if let Some(communal) = communal {
+                            if let Err(e) = self
+                                .ecs_world
+                                .helpers_containers()
+                                .set_container_communal(container, communal)
+                            {
+                                warn!("failed to set container society"; "container" => container, "society" => ?communal, "error" => %e);
                             }
                         }
                     }
                 }
-                UiRequest::ExitGame(ex) => tick.exit = Some(ex),
-                UiRequest::ExecuteScript(path) => {
-                    info!("executing script"; "path" => %path.display());
-                    let result = self
-                        .scripting
-                        .eval_path(&path, &*self.ecs_world)
-                        .map(|output| output.into_string());
-
-                    if let Err(err) = result.as_ref() {
-                        warn!("script errored"; "error" => %err);
-                    }
+            }
+            UiRequest::ExitGame(ex) => tick.exit = Some(ex),
+            UiRequest::ExecuteScript(path) => {
+                info!("executing script"; "path" => %path.display());
+                let result = self
+                    .scripting
+                    .eval_path(&path, &*self.ecs_world)
+                    .map(|output| output.into_string());
+
+                if let Err(err) = result.as_ref() {
+                    warn!("script errored"; "error" => %err);
+                }
 
-                    resp.set_response(UiResponsePayload::ScriptOutput(result));
+                resp.set_response(UiResponsePayload::ScriptOutput(result));
+            }
+            UiRequest::SaveGame(path) => {
+                info!("saving game"; "path" => %path.display());
+                if let Err(err) = self.save_game(&path) {
+                    warn!("failed to save game"; "path" => %path.display(), "error" => %err);
                 }
-                UiRequest::ToggleEntityLogging { entity, enabled } => {
-                    if enabled {
-                        let _ = self
-                            .ecs_world
-                            .add_now::<EntityLoggingComponent>(entity, Default::default());
-                    } else {
-                        let _ = self.ecs_world.remove_now::<EntityLoggingComponent>(entity);
-                    }
+            }
+            UiRequest::LoadGame(path) => {
+                info!("loading game"; "path" => %path.display());
+                if let Err(err) = self.load_game(&path) {
+                    warn!("failed to load game"; "path" => %path.display(), "error" => %err);
                 }
+            }
+            UiRequest::ToggleEntityLogging { entity, enabled } => {
+                if enabled {
+                    let _ = self
+                        .ecs_world
+                        .add_now::<EntityLoggingComponent>(entity, Default::default());
+                } else {
+                    let _ = self.ecs_world.remove_now::<EntityLoggingComponent>(entity);
+                }
+            }
 
-                UiRequest::ModifySelection(modification) => {
-                    let sel = self.ecs_world.resource_mut::<SelectedTiles>();
-                    sel.modify(modification, &self.voxel_world);
+            UiRequest::ModifySelection(modification) => {
+                let sel = self.ecs_world.resource_mut::<SelectedTiles>();
+                sel.modify(modification, &self.voxel_world);
+            }
+
+            UiRequest::CancelSelection => {
+                // close current popup if there is one
+                let popup = self.ecs_world.resource_mut::<UiPopup>();
+                if !popup.close() {
+                    // fallback to clearing tile and entity selections
+                    let tiles = self.ecs_world.resource_mut::<SelectedTiles>();
+                    let entities = self.ecs_world.resource_mut::<SelectedEntities>();
+
+                    tiles.clear();
+                    entities.unselect_all(&self.ecs_world);
                 }
+            }
+            UiRequest::CancelPopup => {
+                // close current popup only
+                self.ecs_world.resource_mut::<UiPopup>().close();
+            }
 
-                UiRequest::CancelSelection => {
-                    // close current popup if there is one
-                    let popup = self.ecs_world.resource_mut::<UiPopup>();
-                    if !popup.close() {
-                        // fallback to clearing tile and entity selections
-                        let tiles = self.ecs_world.resource_mut::<SelectedTiles>();
-                        let entities = self.ecs_world.resource_mut::<SelectedEntities>();
+            UiRequest::TogglePaused => {
+                self.running = match self.running {
+                    RunStatus::Running => RunStatus::Paused,
+                    RunStatus::Paused => RunStatus::Running,
+                };
 
-                        tiles.clear();
-                        entities.unselect_all(&self.ecs_world);
+                debug!(
+                    "{} gameplay",
+                    if self.is_paused() {
+                        "paused"
+                    } else {
+                        "resumed"
                     }
-                }
-                UiRequest::CancelPopup => {
-                    // close current popup only
-                    self.ecs_world.resource_mut::<UiPopup>().close();
-                }
+                )
+            }
+            UiRequest::ChangeGameSpeed(change) => {
+                tick.speed_change = Some(change);
+            }
+            UiRequest::Kill(e) => {
+                debug!("killing entity with god powers"; e);
+                self.ecs_world.kill_entity(e, DeathReason::Unknown);
+            }
+        }
+    }
 
-                UiRequest::TogglePaused => {
-                    self.running = match self.running {
-                        RunStatus::Running => RunStatus::Paused,
-                        RunStatus::Paused => RunStatus::Running,
-                    };
-
-                    debug!(
-                        "{} gameplay",
-                        if self.is_paused() {
-                            "paused"
-                        } else {
-                            "resumed"
-                        }
-                    )
-                }
-                UiRequest::ChangeGameSpeed(change) => {
-                    tick.speed_change = Some(change);
-                }
-                UiRequest::Kill(e) => {
-                    debug!("killing entity with god powers"; e);
-                    self.ecs_world.kill_entity(e, DeathReason::Unknown);
+    fn fill_tiles(&mut self, range: WorldPositionRange, block_type: BlockType) {
+        debug!("filling in block range"; "range" => ?range, "block_type" => ?block_type);
+        self.terrain_changes
+            .insert(WorldTerrainUpdate::new(range, block_type));
+    }
+
+    /// None to cancel
+    fn issue_divine_command(&self, subjects: &[Entity], command: Option<AiAction>) {
+        let mut ais = self.ecs_world.write_storage::<AiComponent>();
+        for subject in subjects {
+            if let Some(ai) = subject.get_mut(&mut ais) {
+                match command {
+                    Some(ref command) => ai.add_divine_command(command.clone()),
+                    None => ai.remove_divine_command(),
                 }
             }
         }
@@ -715,9 +937,19 @@ impl<R: Renderer> Simulation<R> {
         let selection = self.ecs_world.resource_mut::<SelectedTiles>();
         let mut selection_modified = false;
 
-        for &WorldChangeEvent { pos, prev, new } in events {
+        for &WorldChangeEvent { pos, prev, new, .. } in events {
             match (prev, new) {
                 (a, b) if a == b => continue,
+                (_, BlockType::Chest)
+                    if self
+                        .voxel_world
+                        .borrow()
+                        .associated_block_data(pos)
+                        .is_some() =>
+                {
+                    // chest entity already exists, e.g. restored from a save
+                    continue;
+                }
                 (_, BlockType::Chest) => {
                     // new chest placed
                     if let Err(err) = self
@@ -762,6 +994,88 @@ impl<R: Renderer> Simulation<R> {
         }
     }
 
+    fn save_game(&self, path: &Path) -> Result<(), SaveError> {
+        let save = {
+            let world = self.voxel_world.borrow();
+            SaveGame::collect(
+                &self.ecs_world,
+                &world,
+                self.modified_slabs.iter().copied(),
+                current_tick(),
+            )
+        };
+
+        save.write_to(path)?;
+        info!("saved game"; "path" => %path.display(), "tick" => save.tick());
+        Ok(())
+    }
+
+    /// Replaces all entities and societies with those in the save. On error the game is left
+    /// in a partially loaded state
+    fn load_game(&mut self, path: &Path) -> Result<(), SaveError> {
+        let save = SaveGame::read_from(path)?;
+
+        // remove all existing entities, along with their associated blocks
+        {
+            let all_entities = {
+                let entities = self.ecs_world.read_resource::<EntitiesRes>();
+                (&entities).join().collect_vec()
+            };
+
+            let mut world = self.voxel_world.borrow_mut();
+            let containers = self.ecs_world.read_storage::<ContainerComponent>();
+            let transforms = self.ecs_world.read_storage::<TransformComponent>();
+            for (_, transform) in (&containers, &transforms).join() {
+                let _ = world.remove_associated_block_data(transform.position.floor());
+            }
+            drop((containers, transforms, world));
+
+            if let Err(err) = self.ecs_world.delete_entities(&all_entities) {
+                error!("failed to delete entities before loading"; "error" => %err);
+            }
+
+            self.ecs_world.maintain();
+        }
+
+        // reset resources that refer to entities or societies
+        self.ecs_world.insert(QueuedUpdates::default());
+        self.ecs_world.insert(EntitiesToKill::default());
+        self.ecs_world.insert(SelectedEntities::default());
+        self.ecs_world.insert(SelectedTiles::default());
+        self.ecs_world.insert(Societies::default());
+        self.ecs_world.insert(PlayerSociety::default());
+        self.ecs_world.insert(EntityEventQueue::default());
+        self.ecs_world.insert(Spatial::default());
+        self.ecs_world
+            .insert::<Pathfinder>(self.world_loader.pathfinder());
+        self.ecs_world.insert(Noises::default());
+        self.ecs_world.insert(RuntimeTimers::default());
+        self.ecs_world.insert(Runtime::default());
+        self.ecs_world.insert(UiPopup::default());
+        self.ecs_world.insert(Herds::default());
+
+        // callbacks registered by scripts refer to entities in the old game
+        if let Err(err) = self.scripting.reset() {
+            warn!("failed to reset scripting state"; "error" => %err);
+        }
+
+        save.restore(
+            &self.ecs_world,
+            &mut self.terrain_changes,
+            &mut self.liquid_changes,
+        )?;
+
+        self.modified_slabs.clear();
+        self.modified_slabs.extend(save.modified_slabs());
+
+        set_tick(save.tick());
+        self.ecs_world
+            .resource_mut::<Calendar>()
+            .update(Tick::fetch());
+        info!("loaded game"; "path" => %path.display(), "tick" => save.tick());
+        Ok(())
+    }
+
     pub fn as_lite_ref(&self) -> SimulationRefLite {
         SimulationRefLite {
             ecs: &*self.ecs_world,
@@ -795,6 +1109,13 @@ fn reset_tick() {
     }
 }
 
+fn set_tick(tick: u32) {
+    // safety: called between ticks
+    unsafe {
+        TICK = tick;
+    }
+}
+
 pub fn current_tick() -> u32 {
     // safety: only modified between ticks
     unsafe { TICK }
@@ -837,7 +1158,9 @@ fn register_resources(world: &mut EcsWorld, resources: Resources) -> BoxedResult
     world.insert(Societies::default());
     world.insert(PlayerSociety::default());
     world.insert(EntityEventQueue::default());
+    world.insert(EntityEventLog::default());
     world.insert(Spatial::default());
+    world.insert(Noises::default());
     world.insert(RuntimeTimers::default());
     world.insert(Runtime::default());
     world.insert(MouseLocation::default());
@@ -845,6 +1168,7 @@ fn register_resources(world: &mut EcsWorld, resources: Resources) -> BoxedResult
     world.insert(FrameAllocator::default());
     world.insert(UiPopup::default());
     world.insert(Herds::default());
+    world.insert(Calendar::default());
 
     Ok(())
 }
@@ -8,6 +8,7 @@ use crate::Societies;
 #[derive(Component, EcsComponent, Clone)]
 #[storage(DenseVecStorage)]
 #[name("society")]
+#[save]
 pub struct SocietyComponent(SocietyHandle);
 
 impl SocietyComponent {
@@ -27,3 +28,21 @@ impl SocietyComponent {
         })
     }
 }
+
+impl SaveComponent for SocietyComponent {
+    type Saved = SavedSociety;
+
+    fn save(&self, ctx: &SaveContext) -> Option<Self::Saved> {
+        ctx.society(self.0)
+    }
+
+    fn load(
+        society: Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        ctx: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let _ = world.add_now(entity, Self(ctx.society(society)?));
+        Ok(())
+    }
+}
@@ -0,0 +1,417 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use common::*;
+use unit::space::volume::Volume;
+use unit::world::WorldPoint;
+
+use crate::build::ReservedMaterialComponent;
+use crate::ecs::*;
+use crate::item::{
+    ContainedInComponent, DecayComponent, HaulableItemComponent, HauledItemComponent,
+};
+use crate::job::{HaulJob, SocietyJobHandle, SocietyJobList};
+use crate::simulation::Tick;
+use crate::society::Stockpiles;
+use crate::{ContainerComponent, PhysicalComponent, TransformComponent};
+
+/// Loose items are checked for hauls into communal containers every this many ticks
+const CONTAINER_HAUL_INTERVAL: u32 = 100;
+
+/// Max number of generated container haul jobs in progress per society at once
+const MAX_CONTAINER_HAULS: usize = 6;
+
+/// Generates jobs to haul loose items into a society's communal containers
+#[derive(Default)]
+pub struct ContainerHauls {
+    in_progress: HashMap<Entity, InProgressHaul>,
+}
+
+struct InProgressHaul {
+    job: SocietyJobHandle,
+    container: Entity,
+
+    /// Reserved in the container until the job finishes
+    volume: Volume,
+}
+
+/// Haulable items lying in the world, i.e. not contained, being hauled or reserved as a build
+/// material
+pub(in crate::society) fn items_on_ground(world: &EcsWorld) -> Vec<(Entity, WorldPoint)> {
+    let entities = world.read_resource::<EntitiesRes>();
+    let transforms = world.read_storage::<TransformComponent>();
+    let haulables = world.read_storage::<HaulableItemComponent>();
+    let containeds = world.read_storage::<ContainedInComponent>();
+    let hauleds = world.read_storage::<HauledItemComponent>();
+    let reserveds = world.read_storage::<ReservedMaterialComponent>();
+
+    (
+        &entities,
+        &transforms,
+        &haulables,
+        !&containeds,
+        !&hauleds,
+        !&reserveds,
+    )
+        .join()
+        .map(|(e, transform, _, _, _, _)| (Entity::from(e), transform.position))
+        .collect()
+}
+
+/// Items that are already the subject of a haul job in this society
+pub(in crate::society) fn items_with_haul_jobs(jobs: &SocietyJobList) -> HashSet<Entity> {
+    jobs.iter_all()
+        .filter_map(|job| job.borrow().cast::<HaulJob>().map(|haul| haul.entity()))
+        .collect()
+}
+
+impl ContainerHauls {
+    /// Periodically submits jobs to haul loose items into communal containers with space for
+    /// them. Items that will decay are prioritised, then those closest to a container. Items
+    /// already placed on a stockpile are left alone
+    pub(in crate::society) fn generate_jobs(
+        &mut self,
+        containers: &HashSet<Entity>,
+        stockpiles: &Stockpiles,
+        world: &EcsWorld,
+        jobs: &mut SocietyJobList,
+    ) {
+        if containers.is_empty() || Tick::fetch().value() % CONTAINER_HAUL_INTERVAL != 0 {
+            return;
+        }
+
+        self.in_progress
+            .retain(|_, haul| jobs.find_job(haul.job).is_some());
+
+        let mut capacity = MAX_CONTAINER_HAULS.saturating_sub(self.in_progress.len());
+        if capacity == 0 {
+            return;
+        }
+
+        let mut to_submit = Vec::new();
+        {
+            let transforms = world.read_storage::<TransformComponent>();
+            let physicals = world.read_storage::<PhysicalComponent>();
+            let decays = world.read_storage::<DecayComponent>();
+            let container_comps = world.read_storage::<ContainerComponent>();
+
+            // (container, position, volume promised to in progress hauls)
+            let mut targets = containers
+                .iter()
+                .filter_map(|&container| {
+                    let pos = transforms.get(container.into())?.position;
+                    let reserved = self
+                        .in_progress
+                        .values()
+                        .filter(|haul| haul.container == container)
+                        .fold(Volume::new(0), |acc, haul| acc + haul.volume);
+                    Some((container, pos, reserved))
+                })
+                .collect_vec();
+
+            if targets.is_empty() {
+                return;
+            }
+
+            let hauled = items_with_haul_jobs(jobs);
+            let mut candidates = items_on_ground(world)
+                .into_iter()
+                .filter(|(item, pos)| {
+                    !hauled.contains(item) && !stockpiles.is_stockpiled(pos.floor().below())
+                })
+                .map(|(item, pos)| {
+                    let decays = decays.get(item.into()).is_some();
+                    let distance = targets
+                        .iter()
+                        .map(|(_, container_pos, _)| container_pos.distance2(pos))
+                        .fold(f32::MAX, f32::min);
+                    (item, pos, decays, distance)
+                })
+                .collect_vec();
+
+            candidates.sort_unstable_by(|(_, _, decays_a, dist_a), (_, _, decays_b, dist_b)| {
+                decays_b
+                    .cmp(decays_a)
+                    .then_with(|| dist_a.partial_cmp(dist_b).unwrap_or(Ordering::Equal))
+            });
+
+            for (item, pos, _, _) in candidates {
+                let physical = match physicals.get(item.into()) {
+                    Some(physical) => physical,
+                    None => continue,
+                };
+
+                // choose the closest container that it fits in
+                let target = targets
+                    .iter_mut()
+                    .filter(|(container, _, reserved)| {
+                        container_comps
+                            .get((*container).into())
+                            .map(|comp| {
+                                comp.container
+                                    .fits(physical.size, physical.volume + *reserved)
+                                    .is_ok()
+                            })
+                            .unwrap_or(false)
+                    })
+                    .min_by(|(_, a, _), (_, b, _)| {
+                        a.distance2(pos)
+                            .partial_cmp(&b.distance2(pos))
+                            .unwrap_or(Ordering::Equal)
+                    });
+
+                if let Some((container, _, reserved)) = target {
+                    *reserved += physical.volume;
+                    to_submit.push((item, *container, physical.volume));
+
+                    capacity -= 1;
+                    if capacity == 0 {
+                        break;
+                    }
+                }
+            }
+        }
+
+        for (item, container, volume) in to_submit {
+            let job = match HaulJob::with_target_container(item, container, world) {
+                Some(job) => job,
+                None => continue,
+            };
+
+            let job = jobs.submit(world, job);
+            trace!("generated container haul job"; "item" => item, "container" => container, "job" => ?job);
+            self.in_progress.insert(
+                item,
+                InProgressHaul {
+                    job,
+                    container,
+                    volume,
+                },
+            );
+        }
+    }
+
+    /// Cancels generated jobs targeting a container that is no longer communal
+    pub(in crate::society) fn on_container_removed(
+        &mut self,
+        container: Entity,
+        jobs: &mut SocietyJobList,
+    ) {
+        self.in_progress.retain(|_, haul| {
+            if haul.container == container {
+                jobs.cancel(haul.job);
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use unit::world::WorldPosition;
+
+    use super::*;
+    use crate::activity::HaulTarget;
+    use crate::event::EntityEventQueue;
+    use crate::society::Societies;
+    use crate::WorldRef;
+
+    const DEFINITIONS: &str = r#"[
+    (
+        uid: "test_chest",
+        components: [
+            {"physical": (size: (5, 5, 5), volume: 20)},
+            {"container": (size: (5, 5, 5), volume: 10)},
+        ],
+    ),
+    (
+        uid: "test_big_chest",
+        components: [
+            {"physical": (size: (5, 5, 5), volume: 20)},
+            {"container": (size: (5, 5, 5), volume: 1000)},
+        ],
+    ),
+    (
+        uid: "test_rock",
+        components: [
+            {"physical": (size: (1, 1, 1), volume: 4)},
+            {"haulable": (extra_hands: 0)},
+        ],
+    ),
+    (
+        uid: "test_apple",
+        components: [
+            {"physical": (size: (1, 1, 1), volume: 4)},
+            {"haulable": (extra_hands: 0)},
+            {"decay": (ticks: 1000)},
+        ],
+    ),
+]"#;
+
+    struct Setup {
+        world: EcsWorld,
+        societies: Societies,
+        society: crate::SocietyHandle,
+        hauls: ContainerHauls,
+        containers: HashSet<Entity>,
+    }
+
+    impl Setup {
+        fn new() -> Self {
+            let definitions =
+                crate::definitions::load_from_str(DEFINITIONS).expect("bad definitions");
+            let mut world = EcsWorld::with_definitions(definitions).expect("bad definitions");
+            world.insert(WorldRef::default());
+            world.insert(EntityEventQueue::default());
+
+            let mut societies = Societies::default();
+            let society = societies.new_society("test".to_owned()).unwrap();
+            Self {
+                world,
+                societies,
+                society,
+                hauls: ContainerHauls::default(),
+                containers: HashSet::new(),
+            }
+        }
+
+        fn spawn(&self, definition: &str, pos: (i32, i32, i32)) -> Entity {
+            self.world
+                .build_entity(definition)
+                .unwrap()
+                .with_position(WorldPosition::from(pos).centred())
+                .doesnt_need_to_be_accessible()
+                .spawn()
+                .unwrap()
+        }
+
+        fn add_container(&mut self, definition: &str, pos: (i32, i32, i32)) -> Entity {
+            let container = self.spawn(definition, pos);
+            self.containers.insert(container);
+            container
+        }
+
+        fn generate(&mut self) {
+            let society = self.societies.society_by_handle(self.society).unwrap();
+            self.hauls.generate_jobs(
+                &self.containers,
+                &Stockpiles::default(),
+                &self.world,
+                &mut society.jobs_mut(),
+            );
+        }
+
+        /// (item, target container) of all haul jobs
+        fn jobs(&self) -> Vec<(Entity, Entity)> {
+            let society = self.societies.society_by_handle(self.society).unwrap();
+            society
+                .jobs()
+                .iter_all()
+                .filter_map(|job| {
+                    let job = job.borrow();
+                    let haul = job.cast::<HaulJob>()?;
+                    match haul.target() {
+                        HaulTarget::Container(container) => Some((haul.entity(), container)),
+                        _ => None,
+                    }
+                })
+                .collect()
+        }
+
+        fn jobs_for(&self, container: Entity) -> Vec<Entity> {
+            self.jobs()
+                .into_iter()
+                .filter(|(_, c)| *c == container)
+                .map(|(item, _)| item)
+                .sorted()
+                .collect()
+        }
+    }
+
+    #[test]
+    fn volume_reserved_across_items() {
+        let mut setup = Setup::new();
+        let near = setup.add_container("test_chest", (0, 0, 1));
+        let far = setup.add_container("test_chest", (20, 0, 1));
+        let rocks = (1..=3)
+            .map(|x| setup.spawn("test_rock", (x, 0, 1)))
+            .collect_vec();
+
+        setup.generate();
+
+        // only 2 fit in the closest container, the rest goes to the next one
+        assert_eq!(setup.jobs_for(near), vec![rocks[0], rocks[1]]);
+        assert_eq!(setup.jobs_for(far), vec![rocks[2]]);
+
+        // in progress hauls are still reserved
+        let extra = setup.spawn("test_rock", (4, 0, 1));
+        setup.generate();
+        assert_eq!(setup.jobs_for(near).len(), 2);
+        assert_eq!(setup.jobs_for(far), vec![rocks[2], extra]);
+    }
+
+    #[test]
+    fn hauls_are_capped() {
+        let mut setup = Setup::new();
+        setup.add_container("test_big_chest", (0, 0, 1));
+        for x in 1..=MAX_CONTAINER_HAULS as i32 + 4 {
+            setup.spawn("test_rock", (x, 0, 1));
+        }
+
+        setup.generate();
+        assert_eq!(setup.jobs().len(), MAX_CONTAINER_HAULS);
+
+        setup.generate();
+        assert_eq!(setup.jobs().len(), MAX_CONTAINER_HAULS);
+    }
+
+    #[test]
+    fn decaying_items_first() {
+        let mut setup = Setup::new();
+        let chest = setup.add_container("test_big_chest", (0, 0, 1));
+        let rocks = (1..=MAX_CONTAINER_HAULS as i32)
+            .map(|x| setup.spawn("test_rock", (x, 0, 1)))
+            .collect_vec();
+        let apple = setup.spawn("test_apple", (30, 0, 1));
+
+        setup.generate();
+        let hauled = setup.jobs_for(chest);
+        assert_eq!(hauled.len(), MAX_CONTAINER_HAULS);
+        assert!(hauled.contains(&apple));
+
+        // the furthest rock misses out
+        assert!(!hauled.contains(rocks.last().unwrap()));
+    }
+
+    #[test]
+    fn removed_container_cancels_hauls() {
+        let mut setup = Setup::new();
+        let near = setup.add_container("test_chest", (0, 0, 1));
+        let far = setup.add_container("test_chest", (20, 0, 1));
+        for x in 1..=3 {
+            setup.spawn("test_rock", (x, 0, 1));
+        }
+
+        setup.generate();
+        assert_eq!(setup.jobs_for(near).len(), 2);
+        assert_eq!(setup.jobs_for(far).len(), 1);
+
+        {
+            let society = setup.societies.society_by_handle(setup.society).unwrap();
+            let mut jobs = society.jobs_mut();
+            setup.hauls.on_container_removed(near, &mut jobs);
+            jobs.refresh_jobs(&setup.world);
+        }
+        setup.containers.remove(&near);
+
+        assert!(setup.jobs_for(near).is_empty());
+        assert_eq!(setup.jobs_for(far).len(), 1);
+        assert!(setup
+            .hauls
+            .in_progress
+            .values()
+            .all(|haul| haul.container == far));
+    }
+}
@@ -8,9 +8,12 @@ use common::*;
 use unit::world::WorldPoint;
 
 use crate::build::BuildTemplate;
+use crate::craft::CraftRecipe;
+use crate::item::ItemFilter;
 use crate::job::list::SocietyJobHandle;
-use crate::job::SocietyTask;
+use crate::job::{JobMaterials, JobPriority, SocietyTask};
 use crate::society::Society;
+use crate::string::CachedStr;
 use crate::{EcsWorld, Entity, WorldPositionRange};
 
 /// A high-level society job that produces a number of [SocietyTask]s. Unsized but it lives in an
@@ -21,6 +24,8 @@ pub struct SocietyJob<J: ?Sized = dyn SocietyJobImpl> {
 
     pending_complete: SmallVec<[(SocietyTask, SocietyTaskResult); 1]>,
 
+    priority: JobPriority,
+
     inner: J,
 }
 
@@ -58,6 +63,17 @@ pub trait SocietyJobImpl: Display + Debug {
         completions: CompletedTasks,
     ) -> Option<SocietyTaskResult>;
 
+    /// Materials to be gathered and reserved for this job, if it requires any
+    fn materials_mut(&mut self) -> Option<&mut JobMaterials> {
+        None
+    }
+
+    /// Persistent jobs are not finished by running out of tasks, e.g. a farm waiting for its
+    /// crops to ripen. They only end with an explicit result or by being cancelled
+    fn is_persistent(&self) -> bool {
+        false
+    }
+
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
@@ -71,6 +87,18 @@ pub enum SocietyCommand {
 
     /// (thing, container)
     HaulIntoContainer(Entity, Entity),
+
+    /// (workshop, recipe)
+    Craft(Entity, Rc<CraftRecipe>),
+
+    /// (soil range, crop definition)
+    Farm(WorldPositionRange, CachedStr),
+
+    /// (solid floor blocks, items to accept)
+    CreateStockpile(WorldPositionRange, ItemFilter),
+
+    /// Removes all stockpiles overlapping the range
+    RemoveStockpiles(WorldPositionRange),
 }
 
 impl SocietyCommand {
@@ -82,16 +110,16 @@ impl SocietyCommand {
         let jobs = jobs.borrow_mut();
 
         macro_rules! job {
-            ($job:expr) => {
-                jobs.submit(world, $job)
-            };
+            ($job:expr) => {{
+                jobs.submit(world, $job);
+            }};
         }
 
         match self {
             BreakBlocks(range) => job!(BreakBlocksJob::new(range)),
             Build(pos, template) => {
                 for block in pos.iter_blocks() {
-                    jobs.submit(world, BuildThingJob::new(block, template.clone()))
+                    jobs.submit(world, BuildThingJob::new(block, template.clone()));
                 }
             }
             HaulToPosition(e, pos) => {
@@ -100,6 +128,18 @@ impl SocietyCommand {
             HaulIntoContainer(e, container) => {
                 job!(HaulJob::with_target_container(e, container, world).ok_or(self)?)
             }
+            Craft(workshop, ref recipe) => {
+                job!(CraftJob::new(workshop, recipe.clone(), world).ok_or(self)?)
+            }
+            Farm(ref range, crop) => {
+                job!(FarmJob::new(range.clone(), crop, world).ok_or(self)?)
+            }
+            CreateStockpile(range, filter) => {
+                society.stockpiles_mut().add(range, filter);
+            }
+            RemoveStockpiles(ref range) => {
+                society.stockpiles_mut().remove_overlapping(range, jobs);
+            }
         }
 
         Ok(())
@@ -119,6 +159,7 @@ impl SocietyJob<dyn SocietyJobImpl> {
             Rc::new(RefCell::new(SocietyJob {
                 tasks,
                 pending_complete: SmallVec::new(),
+                priority: JobPriority::default(),
                 inner: job,
             })),
             handle,
@@ -132,7 +173,7 @@ impl SocietyJob<dyn SocietyJobImpl> {
         let ret = self
             .inner
             .refresh_tasks(world, &mut self.tasks, &mut self.pending_complete)
-            .or(if self.tasks.is_empty() {
+            .or(if self.tasks.is_empty() && !self.inner.is_persistent() {
                 // no tasks left and no specific result returned
                 Some(SocietyTaskResult::Success)
             } else {
@@ -147,10 +188,22 @@ impl SocietyJob<dyn SocietyJobImpl> {
         self.tasks.iter()
     }
 
+    pub fn priority(&self) -> JobPriority {
+        self.priority
+    }
+
+    pub fn set_priority(&mut self, priority: JobPriority) {
+        self.priority = priority;
+    }
+
     pub fn notify_completion(&mut self, task: SocietyTask, result: SocietyTaskResult) {
         self.pending_complete.push((task, result));
     }
 
+    pub fn materials_mut(&mut self) -> Option<&mut JobMaterials> {
+        self.inner.materials_mut()
+    }
+
     pub fn cast<J: SocietyJobImpl + 'static>(&self) -> Option<&J> {
         self.inner.as_any().downcast_ref()
     }
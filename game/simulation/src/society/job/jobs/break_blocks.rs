@@ -10,6 +10,12 @@ use crate::{BlockType, ComponentWorld, WorldPositionRange};
 #[derive(Constructor, Debug)]
 pub struct BreakBlocksJob(WorldPositionRange);
 
+impl BreakBlocksJob {
+    pub fn range(&self) -> &WorldPositionRange {
+        &self.0
+    }
+}
+
 impl SocietyJobImpl for BreakBlocksJob {
     fn populate_initial_tasks(
         &mut self,
@@ -23,7 +29,7 @@ impl SocietyJobImpl for BreakBlocksJob {
         // only queue blocks that are not air and are reachable
         out.extend(
             voxel_world
-                .filter_reachable_blocks_in_range(&self.0, |bt| bt != BlockType::Air)
+                .filter_reachable_blocks_in_range(&self.0, |b| b.block_type() != BlockType::Air)
                 .map(SocietyTask::BreakBlock),
         );
     }
@@ -1,24 +1,15 @@
-use std::collections::{HashMap, HashSet};
-use std::num::NonZeroU16;
 use std::rc::Rc;
 
-use specs::BitSet;
-
 use common::*;
 
-use crate::build::{
-    BuildMaterial, BuildTemplate, ConsumedMaterialForJobComponent, ReservedMaterialComponent,
-};
-use crate::definitions::{DefinitionNameComponent, DefinitionRegistry};
-use crate::ecs::{EcsWorld, Join, WorldExt};
-use crate::item::HaulableItemComponent;
+use crate::build::{BuildMaterial, BuildTemplate};
+use crate::ecs::EcsWorld;
 use crate::job::job::{CompletedTasks, SocietyJobImpl};
+use crate::job::jobs::materials::JobMaterials;
 use crate::job::SocietyTaskResult;
 use crate::society::job::{SocietyJobHandle, SocietyTask};
-use crate::string::{CachedStr, CachedStringHasher};
-use crate::{
-    BlockType, ComponentWorld, Entity, ItemStackComponent, TransformComponent, WorldPosition,
-};
+use crate::string::CachedStr;
+use crate::{BlockType, Entity, WorldPosition};
 
 // TODO build requirement engine for generic material combining
 //      each job owns an instance, lends it to UI for rendering
@@ -31,21 +22,11 @@ pub struct BuildThingJob {
     // TODO support builds spanning multiple blocks/range
     position: WorldPosition,
     build: Rc<BuildTemplate>,
-    required_materials: Vec<BuildMaterial>,
-    reserved_materials: HashSet<Entity>,
-
-    /// Cache of extra hands needed for hauling each material
-    hands_needed: HashMap<CachedStr, u16, CachedStringHasher>,
+    materials: JobMaterials,
 
     /// Steps completed so far
     progress: u32,
 
-    /// Gross temporary way of tracking remaining materials
-    materials_remaining: HashMap<CachedStr, NonZeroU16, CachedStringHasher>,
-
-    /// Set if any material types are invalid e.g. not haulable
-    missing_any_requirements: bool,
-
     /// Set in first call to [populate_initial_tasks]
     this_job: Option<SocietyJobHandle>,
 
@@ -58,6 +39,8 @@ pub struct BuildThingJob {
 pub struct BuildDetails {
     pub pos: WorldPosition,
     pub target: BlockType,
+    /// Replaces the floor block at `pos` rather than filling the air
+    pub replaces_floor: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -77,184 +60,49 @@ pub enum BuildThingError {
 
     #[error("Material '{0}' is not required")]
     MaterialNotRequired(CachedStr),
-}
 
-pub enum MaterialReservation {
-    /// Whole stack of this size is reserved
-    ConsumeAll(u16),
-    /// Stack has too many materials, should be split from original stack
-    Surplus { surplus: u16, reserved: u16 },
+    #[error("More of material '{0}' is reserved than required")]
+    OverReserved(CachedStr),
 }
 
 impl BuildThingJob {
     pub fn new(block: WorldPosition, build: Rc<BuildTemplate>) -> Self {
-        let required_materials = build.materials().to_vec();
-        let count = required_materials.len();
+        let materials = JobMaterials::new(build.materials().to_vec());
         Self {
             position: block,
             build,
             progress: 0,
-            required_materials,
-            hands_needed: HashMap::with_capacity_and_hasher(count, CachedStringHasher::default()),
-            reserved_materials: HashSet::new(),
-            materials_remaining: HashMap::with_hasher(CachedStringHasher::default()), // replaced on each call
-            missing_any_requirements: false,
+            materials,
             this_job: None,
             ui_element: None,
         }
     }
 
-    // TODO fewer temporary allocations
-    fn check_materials(
-        &mut self,
-        world: &EcsWorld,
-    ) -> HashMap<CachedStr, NonZeroU16, CachedStringHasher> {
-        let this_job = self.this_job.unwrap(); // set before this is called
-
-        let job_pos = self.position.centred();
-        let reserveds = world.read_storage::<ReservedMaterialComponent>();
-        let transforms = world.read_storage::<TransformComponent>();
-        let consumeds = world.read_storage::<ConsumedMaterialForJobComponent>();
-
-        // clear out now-invalid reserved materials
-        self.reserved_materials.retain(|e| {
-            let unreserve_reason = match world.components(*e, (&reserveds, transforms.maybe(), consumeds.maybe())) {
-                Some((reserved, Some(transform), None)) =>  {
-                    if reserved.build_job != this_job {
-                        Some("reservation changed")
-                    }
-                    else if !transform.position.is_almost(&job_pos, 3.0) {
-                        Some("too far away")
-                    } else {
-                        // reservation is still fine
-                        None
-                    }
-                },
-                Some((_reserved, None, Some(_))) => {
-                    // material is still reserved as it's consumed
-                    None
-                }
-                _ => {
-                    Some("material is kill")
-                },
-            };
-
-            match unreserve_reason {
-                Some(reason) => {
-                    debug!("removing now-invalid reservation for build job"; "material" => e, "reason" => reason);
-                    false
-                }
-                None => true,
-            }
-        });
-
-        let mut remaining_materials = self
-            .required_materials
-            .iter()
-            .map(|mat| (mat.definition(), mat.quantity().get()))
-            .collect::<HashMap<_, _>>();
-
-        let reservations_bitset = self
-            .reserved_materials
-            .iter()
-            .map(|e| e.id())
-            .collect::<BitSet>();
-
-        let def_names = world.read_storage::<DefinitionNameComponent>();
-        let stacks = world.read_storage::<ItemStackComponent>();
-        for (e, def, stack_opt) in (&reservations_bitset, &def_names, stacks.maybe()).join() {
-            let entry = remaining_materials
-                .get_mut(&def.0)
-                .unwrap_or_else(|| panic!("invalid reservation {:?}", def.0));
-            // TODO ensure this doesn't happen, or just handle it properly
-
-            let quantity = stack_opt.map(|comp| comp.stack.total_count()).unwrap_or(1);
-            *entry = entry.checked_sub(quantity).unwrap_or_else(|| {
-                unreachable!(
-                    "tried to over-reserve {}/{} of remaining requirement '{}' in job {:?} (material = {})",
-                    quantity, *entry, def.0, this_job, e
-                )
-            });
-        }
-
-        // collect the remaining unsatisfied requirements
-        remaining_materials
-            .iter()
-            .filter_map(|(def, n)| NonZeroU16::new(*n).map(|n| (*def, n)))
-            .collect()
-    }
-
-    /// Returns (Surplus(n), _) if there is a surplus of material to drop in a NEW stack.
-    /// Second return val is the now-remaining requirement for this material.
-    /// Entity should get a ReservedMaterialComponent on success
-    pub fn add_reservation(
-        &mut self,
-        reservee: Entity,
-        world: &EcsWorld,
-    ) -> Result<(MaterialReservation, u16), BuildThingError> {
-        let stacks = world.read_storage::<ItemStackComponent>();
-        let defs = world.read_storage::<DefinitionNameComponent>();
-
-        let def = reservee
-            .get(&defs)
-            .ok_or(BuildThingError::MissingDefinition(reservee))?;
-
-        let n_required_ref = self
-            .materials_remaining
-            .get_mut(&def.0)
-            .ok_or(BuildThingError::MaterialNotRequired(def.0))?;
-        let n_required = n_required_ref.get();
-
-        let n_actual = {
-            reservee
-                .get(&stacks)
-                .map(|stack| stack.stack.total_count())
-                .unwrap_or(1)
-        };
-
-        let n_to_reserve;
-        let result = if n_actual > n_required {
-            // trying to reserve too many, keep the original stack but split off some
-            let to_drop = n_actual - n_required;
-            n_to_reserve = n_required;
-            MaterialReservation::Surplus {
-                surplus: to_drop,
-                reserved: n_required,
-            }
-        } else {
-            n_to_reserve = n_actual;
-            MaterialReservation::ConsumeAll(n_actual)
-        };
-
-        // reserve material entity
-        let _ = self.reserved_materials.insert(reservee);
-
-        // reduce requirement count
-        let remaining = match NonZeroU16::new(n_required - n_to_reserve) {
-            Some(n) => {
-                *n_required_ref = n;
-                n.get()
-            }
-            None => {
-                self.materials_remaining.remove(&def.0);
-                0
-            }
-        };
-
-        Ok((result, remaining))
-    }
-
     pub fn reserved_materials(&self) -> impl Iterator<Item = Entity> + '_ {
-        self.reserved_materials.iter().copied()
+        self.materials.reserved_materials()
     }
 
     pub fn details(&self) -> BuildDetails {
         BuildDetails {
             pos: self.position,
             target: self.build.output(),
+            replaces_floor: self.build.replaces_floor(),
+        }
+    }
+
+    /// Materials can't be gathered inside the floor block being replaced, so use the block on top
+    fn gather_position(&self) -> WorldPosition {
+        if self.build.replaces_floor() {
+            self.position.above()
+        } else {
+            self.position
         }
     }
 
+    pub fn template(&self) -> &Rc<BuildTemplate> {
+        &self.build
+    }
+
     pub fn progress(&self) -> BuildProgressDetails {
         let (total_steps_needed, progress_rate) = self.build.progression();
         BuildProgressDetails {
@@ -270,9 +118,7 @@ impl BuildThingJob {
     }
 
     pub fn remaining_requirements(&self) -> impl Iterator<Item = BuildMaterial> + '_ {
-        self.materials_remaining
-            .iter()
-            .map(|(s, n)| BuildMaterial::new(*s, *n))
+        self.materials.remaining_requirements()
     }
 
     /// Returns new progress
@@ -294,44 +140,9 @@ impl SocietyJobImpl for BuildThingJob {
         // TODO allow "building" of a non-air block, and automatically emit a break task first?
         //  maybe that should be at a higher level than this
 
-        // preprocess materials to get hands needed for hauling
-        let definitions = world.resource::<DefinitionRegistry>();
-        for mat in self.required_materials.iter() {
-            let def = definitions
-                .lookup_definition(mat.definition())
-                .and_then(|def| def.find_component("haulable"));
-            let hands = match def {
-                Some(any) => {
-                    let haulable = any
-                        .downcast_ref::<HaulableItemComponent>()
-                        .expect("bad type for haulable template");
-                    debug!("{:?} needs {} hands", mat, haulable.extra_hands);
-                    haulable.extra_hands
-                }
-                None => {
-                    // TODO job is destined to fail...
-                    warn!("build material is not haulable"; "material" => ?mat);
-                    self.missing_any_requirements = true;
-                    return;
-                }
-            };
-
-            self.hands_needed.insert(mat.definition(), hands);
-        }
-
-        // gather materials first
-        out.extend(self.required_materials.iter().cloned().flat_map(|mat| {
-            let extra_hands = *self.hands_needed.get(&mat.definition()).unwrap(); // just inserted
-
-            Some(SocietyTask::GatherMaterials {
-                build_pos: self.position,
-                material: mat,
-                job: this_job,
-                extra_hands_needed_for_haul: extra_hands,
-            })
-        }));
-
-        self.missing_any_requirements = out.len() != self.required_materials.len();
+        let gather_pos = self.gather_position();
+        self.materials
+            .populate_initial_tasks(world, out, this_job, gather_pos);
     }
 
     fn refresh_tasks(
@@ -340,7 +151,7 @@ impl SocietyJobImpl for BuildThingJob {
         tasks: &mut Vec<SocietyTask>,
         completions: CompletedTasks,
     ) -> Option<SocietyTaskResult> {
-        if self.missing_any_requirements {
+        if self.materials.missing_any_requirements() {
             return Some(SocietyTaskResult::Failure(
                 BuildThingError::InvalidMaterials.into(),
             ));
@@ -356,30 +167,18 @@ impl SocietyJobImpl for BuildThingJob {
             return Some(result);
         }
 
-        // TODO dont run this every tick, only when something changes or intermittently
-        let outstanding_requirements = self.check_materials(world);
-
-        // recreate tasks for outstanding materials
-        // TODO this changes the order
         tasks.clear();
         let this_job = self.this_job.unwrap(); // set unconditionally
-        for (def, count) in outstanding_requirements.iter() {
-            let extra_hands = *self.hands_needed.get(def).unwrap(); // already inserted
-
-            let task = SocietyTask::GatherMaterials {
-                build_pos: self.position,
-                material: BuildMaterial::new(*def, *count),
-                job: this_job,
-                extra_hands_needed_for_haul: extra_hands,
-            };
-
-            tasks.push(task);
-        }
-
-        // store this to show in the ui
-        self.materials_remaining = outstanding_requirements;
+        let gather_pos = self.gather_position();
+        let gathered = match self
+            .materials
+            .refresh_gather_tasks(world, tasks, this_job, gather_pos)
+        {
+            Ok(gathered) => gathered,
+            Err(err) => return Some(SocietyTaskResult::Failure(err.into())),
+        };
 
-        if tasks.is_empty() {
+        if gathered {
             // all gather requirements are satisfied, do the build
             // TODO some builds could have multiple workers
 
@@ -389,6 +188,10 @@ impl SocietyJobImpl for BuildThingJob {
         None // use number of tasks to determine completion
     }
 
+    fn materials_mut(&mut self) -> Option<&mut JobMaterials> {
+        Some(&mut self.materials)
+    }
+
     crate::as_any_impl!();
 }
 
@@ -0,0 +1,190 @@
+use std::rc::Rc;
+
+use common::*;
+
+use crate::craft::{CraftRecipe, WorkshopComponent};
+use crate::definitions::DefinitionNameComponent;
+use crate::ecs::*;
+use crate::job::job::{CompletedTasks, SocietyJobImpl};
+use crate::job::jobs::materials::JobMaterials;
+use crate::job::SocietyTaskResult;
+use crate::society::job::{SocietyJobHandle, SocietyTask};
+use crate::string::CachedStr;
+use crate::{TransformComponent, WorldPosition};
+
+/// Craft a recipe at a workshop, gathering its inputs to the workshop first
+#[derive(Debug)]
+pub struct CraftJob {
+    workshop: Entity,
+    /// Position of the workshop when the job was created, inputs are gathered here
+    position: WorldPosition,
+    recipe: Rc<CraftRecipe>,
+    materials: JobMaterials,
+
+    /// Steps completed so far
+    progress: u32,
+
+    /// Set in first call to [populate_initial_tasks]
+    this_job: Option<SocietyJobHandle>,
+}
+
+/// Lightweight struct of end goals for a craft, to be used for deciding whether to work on it
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CraftDetails {
+    pub workshop: Entity,
+    pub pos: WorldPosition,
+    pub output: CachedStr,
+}
+
+#[derive(Debug, Error)]
+pub enum CraftError {
+    #[error("Recipe inputs are invalid")]
+    InvalidMaterials,
+
+    #[error("Workshop {0} no longer exists")]
+    MissingWorkshop(Entity),
+
+    #[error("Workshop {0} has moved")]
+    WorkshopMoved(Entity),
+}
+
+impl CraftJob {
+    /// Returns None if the workshop is not valid for this recipe
+    pub fn new(
+        workshop: Entity,
+        recipe: Rc<CraftRecipe>,
+        world: &impl ComponentWorld,
+    ) -> Option<Self> {
+        if !world.has_component::<WorkshopComponent>(workshop) {
+            debug!("craft target is not a workshop"; "workshop" => workshop);
+            return None;
+        }
+
+        let definition = world.component::<DefinitionNameComponent>(workshop).ok()?;
+        if definition.0 != recipe.workshop() {
+            debug!("recipe cannot be crafted at this workshop"; "workshop" => workshop,
+                "required" => %recipe.workshop(), "actual" => %definition.0);
+            return None;
+        }
+
+        let position = world
+            .component::<TransformComponent>(workshop)
+            .ok()?
+            .position
+            .floor();
+
+        let materials = JobMaterials::new(recipe.inputs().to_vec());
+        Some(Self {
+            workshop,
+            position,
+            recipe,
+            materials,
+            progress: 0,
+            this_job: None,
+        })
+    }
+
+    pub fn workshop(&self) -> Entity {
+        self.workshop
+    }
+
+    pub fn recipe(&self) -> &Rc<CraftRecipe> {
+        &self.recipe
+    }
+
+    pub fn reserved_materials(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.materials.reserved_materials()
+    }
+
+    pub fn details(&self) -> CraftDetails {
+        CraftDetails {
+            workshop: self.workshop,
+            pos: self.position,
+            output: self.recipe.output().0,
+        }
+    }
+
+    /// Returns new progress
+    pub fn make_progress(&mut self) -> u32 {
+        self.progress += 1;
+        self.progress
+    }
+
+    fn check_workshop(&self, world: &EcsWorld) -> Result<(), CraftError> {
+        match world.component::<TransformComponent>(self.workshop) {
+            Ok(transform) if transform.position.floor() == self.position => Ok(()),
+            Ok(_) => Err(CraftError::WorkshopMoved(self.workshop)),
+            Err(_) => Err(CraftError::MissingWorkshop(self.workshop)),
+        }
+    }
+}
+
+impl SocietyJobImpl for CraftJob {
+    fn populate_initial_tasks(
+        &mut self,
+        world: &EcsWorld,
+        out: &mut Vec<SocietyTask>,
+        this_job: SocietyJobHandle,
+    ) {
+        self.this_job = Some(this_job);
+        self.materials
+            .populate_initial_tasks(world, out, this_job, self.position);
+    }
+
+    fn refresh_tasks(
+        &mut self,
+        world: &EcsWorld,
+        tasks: &mut Vec<SocietyTask>,
+        completions: CompletedTasks,
+    ) -> Option<SocietyTaskResult> {
+        if self.materials.missing_any_requirements() {
+            return Some(SocietyTaskResult::Failure(
+                CraftError::InvalidMaterials.into(),
+            ));
+        }
+
+        // ignore completions for gathering, only use for checking the craft outcome
+        if let Some((_, result)) = completions
+            .iter_mut()
+            .find(|(t, _)| matches!(t, SocietyTask::Craft(_, _)))
+        {
+            let result = std::mem::replace(result, SocietyTaskResult::Success);
+            return Some(result);
+        }
+
+        if let Err(err) = self.check_workshop(world) {
+            return Some(SocietyTaskResult::Failure(err.into()));
+        }
+
+        tasks.clear();
+        let this_job = self.this_job.unwrap(); // set unconditionally
+        let gathered =
+            match self
+                .materials
+                .refresh_gather_tasks(world, tasks, this_job, self.position)
+            {
+                Ok(gathered) => gathered,
+                Err(err) => return Some(SocietyTaskResult::Failure(err.into())),
+            };
+
+        if gathered {
+            // all inputs are present, do the craft
+            tasks.push(SocietyTask::Craft(this_job, self.details()));
+        }
+
+        None // use number of tasks to determine completion
+    }
+
+    fn materials_mut(&mut self) -> Option<&mut JobMaterials> {
+        Some(&mut self.materials)
+    }
+
+    crate::as_any_impl!();
+}
+
+impl Display for CraftJob {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let (output, count) = self.recipe.output();
+        write!(f, "Craft {}x{} at {}", count, output, self.workshop)
+    }
+}
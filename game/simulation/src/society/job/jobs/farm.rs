@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use common::*;
+use world::block::BlockType;
+
+use crate::definitions::DefinitionRegistry;
+use crate::ecs::*;
+use crate::farming::{GrowthComponent, HarvestableComponent};
+use crate::job::job::{CompletedTasks, SocietyJobImpl};
+use crate::job::{SocietyJobHandle, SocietyTaskResult};
+use crate::simulation::Tick;
+use crate::society::job::SocietyTask;
+use crate::string::CachedStr;
+use crate::{TransformComponent, WorldPositionRange};
+
+/// Tasks are recalculated every this many ticks, rather than every tick
+const FARM_REFRESH_INTERVAL: u32 = 40;
+
+/// Farm zone on a range of soil blocks, continually sowing the crop on empty tiles and
+/// harvesting ripe plants. Only ends when cancelled.
+// TODO require seed items to sow
+#[derive(Debug)]
+pub struct FarmJob {
+    range: WorldPositionRange,
+    crop: CachedStr,
+
+    /// Blocks the crop can grow on, any if empty
+    soil: Vec<BlockType>,
+}
+
+impl FarmJob {
+    /// None if the crop definition doesn't exist or isn't a harvestable plant that grows
+    pub fn new(range: WorldPositionRange, crop: CachedStr, world: &EcsWorld) -> Option<Self> {
+        let definitions = world.resource::<DefinitionRegistry>();
+        let definition = definitions.lookup_definition(crop)?;
+        let growth = definition.find_component_ref::<GrowthComponent>("growth")?;
+        let _harvestable = definition.find_component_ref::<HarvestableComponent>("harvestable")?;
+
+        Some(Self {
+            range,
+            crop,
+            soil: growth.soil().to_vec(),
+        })
+    }
+
+    pub fn range(&self) -> &WorldPositionRange {
+        &self.range
+    }
+
+    pub fn crop(&self) -> CachedStr {
+        self.crop
+    }
+
+    fn find_tasks(&self, world: &EcsWorld, out: &mut Vec<SocietyTask>) {
+        out.clear();
+
+        // harvest ripe plants already in the farm
+        let mut planted = HashSet::new();
+        let entities = world.read_resource::<EntitiesRes>();
+        let growths = world.read_storage::<GrowthComponent>();
+        let transforms = world.read_storage::<TransformComponent>();
+        let harvestables = world.read_storage::<HarvestableComponent>();
+        for (e, growth, transform, harvestable) in
+            (&entities, &growths, &transforms, harvestables.maybe()).join()
+        {
+            let soil = transform.position.floor().below();
+            if !self.range.contains(&soil) {
+                continue;
+            }
+
+            planted.insert(soil);
+            if growth.is_ripe() && harvestable.is_some() {
+                out.push(SocietyTask::Harvest(Entity::from(e)));
+            }
+        }
+
+        // sow on suitable empty soil
+        let voxel_world = world.voxel_world();
+        let voxel_world = voxel_world.borrow();
+        out.extend(
+            self.range
+                .iter_blocks()
+                .filter(|soil| !planted.contains(soil))
+                .filter(|soil| {
+                    let suitable = voxel_world
+                        .block(*soil)
+                        .map(|b| self.soil.is_empty() || self.soil.contains(&b.block_type()))
+                        .unwrap_or(false);
+
+                    // must be able to stand on it
+                    suitable && voxel_world.area(soil.above()).ok().is_some()
+                })
+                .map(|soil| SocietyTask::Sow {
+                    soil,
+                    crop: self.crop,
+                }),
+        );
+    }
+}
+
+impl SocietyJobImpl for FarmJob {
+    fn populate_initial_tasks(
+        &mut self,
+        world: &EcsWorld,
+        out: &mut Vec<SocietyTask>,
+        _: SocietyJobHandle,
+    ) {
+        self.find_tasks(world, out);
+    }
+
+    fn refresh_tasks(
+        &mut self,
+        world: &EcsWorld,
+        tasks: &mut Vec<SocietyTask>,
+        completions: CompletedTasks,
+    ) -> Option<SocietyTaskResult> {
+        if Tick::fetch().value() % FARM_REFRESH_INTERVAL == 0 {
+            self.find_tasks(world, tasks);
+        } else {
+            // drop completed tasks until the next full refresh
+            tasks.retain(|task| !completions.iter().any(|(t, _)| t == task));
+        }
+
+        None
+    }
+
+    fn is_persistent(&self) -> bool {
+        true
+    }
+
+    crate::as_any_impl!();
+}
+
+impl Display for FarmJob {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "Farm {} in range {}", self.crop, self.range)
+    }
+}
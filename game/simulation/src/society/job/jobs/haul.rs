@@ -56,6 +56,14 @@ impl HaulJob {
             target,
         })
     }
+
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    pub fn target(&self) -> HaulTarget {
+        self.target
+    }
 }
 
 impl SocietyJobImpl for HaulJob {
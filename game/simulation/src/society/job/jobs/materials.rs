@@ -0,0 +1,462 @@
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroU16;
+
+use specs::BitSet;
+
+use common::*;
+use unit::world::WorldPosition;
+
+use crate::build::{BuildMaterial, ConsumedMaterialForJobComponent, ReservedMaterialComponent};
+use crate::definitions::{DefinitionNameComponent, DefinitionRegistry};
+use crate::ecs::{EcsWorld, Join, WorldExt};
+use crate::item::HaulableItemComponent;
+use crate::job::BuildThingError;
+use crate::society::job::{SocietyJobHandle, SocietyTask};
+use crate::string::{CachedStr, CachedStringHasher};
+use crate::{ComponentWorld, Entity, ItemStackComponent, TransformComponent};
+
+/// Material requirements of a job, and the materials that have been gathered and reserved for it
+/// so far. Materials are gathered to a single position.
+#[derive(Debug)]
+pub struct JobMaterials {
+    required_materials: Vec<BuildMaterial>,
+    reserved_materials: HashSet<Entity>,
+
+    /// Cache of extra hands needed for hauling each material
+    hands_needed: HashMap<CachedStr, u16, CachedStringHasher>,
+
+    /// Gross temporary way of tracking remaining materials
+    materials_remaining: HashMap<CachedStr, NonZeroU16, CachedStringHasher>,
+
+    /// Set if any material types are invalid e.g. not haulable
+    missing_any_requirements: bool,
+}
+
+pub enum MaterialReservation {
+    /// Whole stack of this size is reserved
+    ConsumeAll(u16),
+    /// Stack has too many materials, should be split from original stack
+    Surplus { surplus: u16, reserved: u16 },
+}
+
+impl JobMaterials {
+    pub fn new(required_materials: Vec<BuildMaterial>) -> Self {
+        let count = required_materials.len();
+        Self {
+            required_materials,
+            reserved_materials: HashSet::new(),
+            hands_needed: HashMap::with_capacity_and_hasher(count, CachedStringHasher::default()),
+            materials_remaining: HashMap::with_hasher(CachedStringHasher::default()), // replaced on each call
+            missing_any_requirements: false,
+        }
+    }
+
+    /// Looks up the hands needed to haul each material and populates the initial gather tasks
+    pub fn populate_initial_tasks(
+        &mut self,
+        world: &EcsWorld,
+        out: &mut Vec<SocietyTask>,
+        this_job: SocietyJobHandle,
+        gather_pos: WorldPosition,
+    ) {
+        // preprocess materials to get hands needed for hauling
+        let definitions = world.resource::<DefinitionRegistry>();
+        for mat in self.required_materials.iter() {
+            let haulable = definitions
+                .lookup_definition(mat.definition())
+                .and_then(|def| def.find_component("haulable"))
+                .and_then(|any| any.downcast_ref::<HaulableItemComponent>());
+            let hands = match haulable {
+                Some(haulable) => {
+                    debug!("{:?} needs {} hands", mat, haulable.extra_hands);
+                    haulable.extra_hands
+                }
+                None => {
+                    // TODO job is destined to fail...
+                    warn!("job material is not haulable"; "material" => ?mat);
+                    self.missing_any_requirements = true;
+                    return;
+                }
+            };
+
+            self.hands_needed.insert(mat.definition(), hands);
+        }
+
+        // gather materials first
+        let len_before = out.len();
+        out.extend(self.required_materials.iter().cloned().map(|mat| {
+            let extra_hands = *self.hands_needed.get(&mat.definition()).unwrap(); // just inserted
+
+            SocietyTask::GatherMaterials {
+                target: gather_pos,
+                material: mat,
+                job: this_job,
+                extra_hands_needed_for_haul: extra_hands,
+            }
+        }));
+
+        self.missing_any_requirements = out.len() - len_before != self.required_materials.len();
+    }
+
+    /// Set if any materials are invalid and so the job can never be completed
+    pub fn missing_any_requirements(&self) -> bool {
+        self.missing_any_requirements
+    }
+
+    /// Recreates gather tasks for materials that are still outstanding. Returns true if all
+    /// requirements are satisfied and no gather tasks were added, or an error if the reserved
+    /// materials no longer match the requirements.
+    pub fn refresh_gather_tasks(
+        &mut self,
+        world: &EcsWorld,
+        tasks: &mut Vec<SocietyTask>,
+        this_job: SocietyJobHandle,
+        gather_pos: WorldPosition,
+    ) -> Result<bool, BuildThingError> {
+        // TODO dont run this every tick, only when something changes or intermittently
+        let outstanding_requirements = self.check_materials(world, this_job, gather_pos)?;
+
+        // recreate tasks for outstanding materials
+        // TODO this changes the order
+        let len_before = tasks.len();
+        for (def, count) in outstanding_requirements.iter() {
+            let extra_hands = *self.hands_needed.get(def).unwrap(); // already inserted
+
+            let task = SocietyTask::GatherMaterials {
+                target: gather_pos,
+                material: BuildMaterial::new(*def, *count),
+                job: this_job,
+                extra_hands_needed_for_haul: extra_hands,
+            };
+
+            tasks.push(task);
+        }
+
+        // store this to show in the ui
+        self.materials_remaining = outstanding_requirements;
+
+        Ok(tasks.len() == len_before)
+    }
+
+    // TODO fewer temporary allocations
+    fn check_materials(
+        &mut self,
+        world: &EcsWorld,
+        this_job: SocietyJobHandle,
+        gather_pos: WorldPosition,
+    ) -> Result<HashMap<CachedStr, NonZeroU16, CachedStringHasher>, BuildThingError> {
+        let job_pos = gather_pos.centred();
+        let reserveds = world.read_storage::<ReservedMaterialComponent>();
+        let transforms = world.read_storage::<TransformComponent>();
+        let consumeds = world.read_storage::<ConsumedMaterialForJobComponent>();
+
+        // clear out now-invalid reserved materials
+        self.reserved_materials.retain(|e| {
+            let unreserve_reason = match world.components(*e, (&reserveds, transforms.maybe(), consumeds.maybe())) {
+                Some((reserved, Some(transform), None)) =>  {
+                    if reserved.build_job != this_job {
+                        Some("reservation changed")
+                    }
+                    else if !transform.position.is_almost(&job_pos, 3.0) {
+                        Some("too far away")
+                    } else {
+                        // reservation is still fine
+                        None
+                    }
+                },
+                Some((_reserved, None, Some(_))) => {
+                    // material is still reserved as it's consumed
+                    None
+                }
+                _ => {
+                    Some("material is kill")
+                },
+            };
+
+            match unreserve_reason {
+                Some(reason) => {
+                    debug!("removing now-invalid reservation for job"; "material" => e, "reason" => reason);
+                    false
+                }
+                None => true,
+            }
+        });
+
+        let mut remaining_materials = self
+            .required_materials
+            .iter()
+            .map(|mat| (mat.definition(), mat.quantity().get()))
+            .collect::<HashMap<_, _>>();
+
+        let reservations_bitset = self
+            .reserved_materials
+            .iter()
+            .map(|e| e.id())
+            .collect::<BitSet>();
+
+        let def_names = world.read_storage::<DefinitionNameComponent>();
+        let stacks = world.read_storage::<ItemStackComponent>();
+        for (e, def, stack_opt) in (&reservations_bitset, &def_names, stacks.maybe()).join() {
+            let entry = remaining_materials
+                .get_mut(&def.0)
+                .ok_or(BuildThingError::MaterialNotRequired(def.0))?;
+
+            let quantity = stack_opt.map(|comp| comp.stack.total_count()).unwrap_or(1);
+            *entry = entry.checked_sub(quantity).ok_or_else(|| {
+                debug!("over-reserved material for job"; "material" => e, "job" => ?this_job, "quantity" => quantity, "remaining" => *entry);
+                BuildThingError::OverReserved(def.0)
+            })?;
+        }
+
+        // collect the remaining unsatisfied requirements
+        Ok(remaining_materials
+            .iter()
+            .filter_map(|(def, n)| NonZeroU16::new(*n).map(|n| (*def, n)))
+            .collect())
+    }
+
+    /// Returns (Surplus(n), _) if there is a surplus of material to drop in a NEW stack.
+    /// Second return val is the now-remaining requirement for this material.
+    /// Entity should get a ReservedMaterialComponent on success
+    pub fn add_reservation(
+        &mut self,
+        reservee: Entity,
+        world: &EcsWorld,
+    ) -> Result<(MaterialReservation, u16), BuildThingError> {
+        let stacks = world.read_storage::<ItemStackComponent>();
+        let defs = world.read_storage::<DefinitionNameComponent>();
+
+        let def = reservee
+            .get(&defs)
+            .ok_or(BuildThingError::MissingDefinition(reservee))?;
+
+        let n_required_ref = self
+            .materials_remaining
+            .get_mut(&def.0)
+            .ok_or(BuildThingError::MaterialNotRequired(def.0))?;
+        let n_required = n_required_ref.get();
+
+        let n_actual = {
+            reservee
+                .get(&stacks)
+                .map(|stack| stack.stack.total_count())
+                .unwrap_or(1)
+        };
+
+        let n_to_reserve;
+        let result = if n_actual > n_required {
+            // trying to reserve too many, keep the original stack but split off some
+            let to_drop = n_actual - n_required;
+            n_to_reserve = n_required;
+            MaterialReservation::Surplus {
+                surplus: to_drop,
+                reserved: n_required,
+            }
+        } else {
+            n_to_reserve = n_actual;
+            MaterialReservation::ConsumeAll(n_actual)
+        };
+
+        // reserve material entity
+        let _ = self.reserved_materials.insert(reservee);
+
+        // reduce requirement count
+        let remaining = match NonZeroU16::new(n_required - n_to_reserve) {
+            Some(n) => {
+                *n_required_ref = n;
+                n.get()
+            }
+            None => {
+                self.materials_remaining.remove(&def.0);
+                0
+            }
+        };
+
+        Ok((result, remaining))
+    }
+
+    pub fn reserved_materials(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.reserved_materials.iter().copied()
+    }
+
+    pub fn remaining_requirements(&self) -> impl Iterator<Item = BuildMaterial> + '_ {
+        self.materials_remaining
+            .iter()
+            .map(|(s, n)| BuildMaterial::new(*s, *n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event::EntityEventQueue;
+    use crate::item::spawn_item_stack;
+    use crate::job::job::{CompletedTasks, SocietyJobImpl};
+    use crate::job::SocietyTaskResult;
+    use crate::society::Societies;
+    use crate::WorldRef;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct DummyJob;
+
+    impl Display for DummyJob {
+        fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+            write!(f, "Dummy")
+        }
+    }
+
+    impl SocietyJobImpl for DummyJob {
+        fn populate_initial_tasks(
+            &mut self,
+            _: &EcsWorld,
+            _: &mut Vec<SocietyTask>,
+            _: SocietyJobHandle,
+        ) {
+        }
+
+        fn refresh_tasks(
+            &mut self,
+            _: &EcsWorld,
+            _: &mut Vec<SocietyTask>,
+            _: CompletedTasks,
+        ) -> Option<SocietyTaskResult> {
+            None
+        }
+
+        crate::as_any_impl!();
+    }
+
+    const DEFINITIONS: &str = r#"[
+    (
+        uid: "test_plank",
+        components: [
+            {"physical": (size: (1, 1, 1), volume: 1)},
+            {"haulable": (extra_hands: 1)},
+            {"stackable": (max_count: 10)},
+        ],
+    ),
+    (
+        uid: "test_rock",
+        components: [
+            {"physical": (size: (1, 1, 1), volume: 1)},
+        ],
+    ),
+]"#;
+
+    const GATHER_POS: (i32, i32, i32) = (2, 2, 2);
+
+    fn setup() -> (EcsWorld, SocietyJobHandle) {
+        let definitions = crate::definitions::load_from_str(DEFINITIONS).expect("bad definitions");
+        let mut world = EcsWorld::with_definitions(definitions).expect("bad definitions");
+        world.insert(WorldRef::default());
+        world.insert(EntityEventQueue::default());
+        world.insert(Societies::default());
+
+        let job = {
+            let societies = world.resource_mut::<Societies>();
+            let society = societies.new_society("test".to_owned()).unwrap();
+            let society = societies.society_by_handle(society).unwrap();
+            society.jobs_mut().submit(&world, DummyJob)
+        };
+
+        (world, job)
+    }
+
+    fn material(definition: &str, n: u16) -> BuildMaterial {
+        BuildMaterial::new(CachedStr::from(definition), NonZeroU16::new(n).unwrap())
+    }
+
+    fn populate(world: &EcsWorld, job: SocietyJobHandle, materials: &mut JobMaterials) {
+        let mut tasks = vec![];
+        materials.populate_initial_tasks(world, &mut tasks, job, GATHER_POS.into());
+    }
+
+    #[test]
+    fn gather_tasks_need_hauling_hands() {
+        let (world, job) = setup();
+        let mut materials = JobMaterials::new(vec![material("test_plank", 3)]);
+
+        let mut tasks = vec![];
+        materials.populate_initial_tasks(&world, &mut tasks, job, GATHER_POS.into());
+        assert!(!materials.missing_any_requirements());
+        assert!(matches!(
+            tasks.as_slice(),
+            [SocietyTask::GatherMaterials {
+                extra_hands_needed_for_haul: 1,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn unhaulable_material_is_invalid() {
+        let (world, job) = setup();
+        let mut materials =
+            JobMaterials::new(vec![material("test_plank", 1), material("test_rock", 1)]);
+
+        populate(&world, job, &mut materials);
+        assert!(materials.missing_any_requirements());
+    }
+
+    #[test]
+    fn reserve_with_surplus() {
+        let (world, job) = setup();
+        let mut materials = JobMaterials::new(vec![material("test_plank", 3)]);
+        populate(&world, job, &mut materials);
+
+        let mut tasks = vec![];
+        let gathered = materials
+            .refresh_gather_tasks(&world, &mut tasks, job, GATHER_POS.into())
+            .expect("refresh failed");
+        assert!(!gathered);
+        assert_eq!(tasks.len(), 1);
+
+        let pos = WorldPosition::from(GATHER_POS).centred();
+        let stack = spawn_item_stack(&world, CachedStr::from("test_plank"), 5, pos).unwrap();
+        let (reservation, remaining) = materials
+            .add_reservation(stack, &world)
+            .expect("reservation failed");
+        assert!(matches!(
+            reservation,
+            MaterialReservation::Surplus {
+                surplus: 2,
+                reserved: 3
+            }
+        ));
+        assert_eq!(remaining, 0);
+        assert_eq!(materials.remaining_requirements().count(), 0);
+
+        // no longer needed
+        let another = spawn_item_stack(&world, CachedStr::from("test_plank"), 1, pos).unwrap();
+        assert!(matches!(
+            materials.add_reservation(another, &world),
+            Err(BuildThingError::MaterialNotRequired(_))
+        ));
+    }
+
+    #[test]
+    fn unrequired_reservation_fails_job() {
+        let (world, job) = setup();
+        let mut materials = JobMaterials::new(vec![material("test_plank", 1)]);
+        populate(&world, job, &mut materials);
+
+        let rock = world
+            .build_entity("test_rock")
+            .unwrap()
+            .with_position(WorldPosition::from(GATHER_POS))
+            .doesnt_need_to_be_accessible()
+            .spawn()
+            .unwrap();
+        world
+            .add_now(rock, ReservedMaterialComponent { build_job: job })
+            .expect("failed to reserve");
+        materials.reserved_materials.insert(rock);
+
+        let result = materials.refresh_gather_tasks(&world, &mut vec![], job, GATHER_POS.into());
+        assert!(matches!(
+            result,
+            Err(BuildThingError::MaterialNotRequired(def)) if def.as_ref() == "test_rock"
+        ));
+    }
+}
@@ -1,9 +1,13 @@
 pub use break_blocks::BreakBlocksJob;
-pub use build::{
-    BuildDetails, BuildProgressDetails, BuildThingError, BuildThingJob, MaterialReservation,
-};
+pub use build::{BuildDetails, BuildProgressDetails, BuildThingError, BuildThingJob};
+pub use craft::{CraftDetails, CraftError, CraftJob};
+pub use farm::FarmJob;
 pub use haul::HaulJob;
+pub use materials::{JobMaterials, MaterialReservation};
 
 mod break_blocks;
 mod build;
+mod craft;
+mod farm;
 mod haul;
+mod materials;
@@ -1,10 +1,12 @@
 use std::collections::HashMap;
 use std::num::NonZeroU16;
 
+use serde::{Deserialize, Serialize};
+
 use common::*;
 
 use crate::job::job::SocietyJobImpl;
-use crate::job::{BuildThingJob, SocietyJob, SocietyTask};
+use crate::job::{BuildThingJob, JobPriority, SocietyJob, SocietyTask};
 
 use crate::society::job::job::SocietyJobRef;
 use crate::{EcsWorld, Entity, Societies, SocietyHandle};
@@ -42,7 +44,7 @@ pub enum Reservation {
 }
 
 /// Unique job id per society
-#[derive(Copy, Clone, Hash, Eq, PartialEq)]
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SocietyJobHandle {
     society: SocietyHandle,
     idx: u32,
@@ -106,7 +108,11 @@ impl SocietyJobList {
         }
     }
 
-    pub fn submit(&mut self, world: &EcsWorld, job: impl SocietyJobImpl + 'static) {
+    pub fn submit(
+        &mut self,
+        world: &EcsWorld,
+        job: impl SocietyJobImpl + 'static,
+    ) -> SocietyJobHandle {
         let handle = {
             let this = self.next_handle;
             self.next_handle.idx += 1;
@@ -114,7 +120,8 @@ impl SocietyJobList {
         };
 
         let job = SocietyJob::create(world, handle, job);
-        self.submit_internal(world, job)
+        self.submit_internal(world, job);
+        handle
     }
 
     /// Without generic parameter to reduce code size
@@ -175,7 +182,7 @@ impl SocietyJobList {
     pub fn filter_applicable_tasks(
         &self,
         entity: Entity,
-        mut add_task: impl FnMut(SocietyTask, JobIndex, Reservation),
+        mut add_task: impl FnMut(SocietyTask, JobIndex, Reservation, JobPriority),
     ) {
         for (i, job) in self.jobs.iter().enumerate() {
             let job = job.borrow();
@@ -187,8 +194,20 @@ impl SocietyJobList {
                     Unavailable => continue,
                     other => other,
                 };
-                add_task(task.clone(), i, reservations);
+                add_task(task.clone(), i, reservations, job.priority());
+            }
+        }
+    }
+
+    /// Returns false if the job was not found
+    pub fn set_priority(&mut self, job: SocietyJobHandle, priority: JobPriority) -> bool {
+        match self.find_job(job) {
+            Some(job_ref) => {
+                debug!("setting job priority"; "job" => ?job, "priority" => ?priority);
+                job_ref.borrow_mut().set_priority(priority);
+                true
             }
+            None => false,
         }
     }
 
@@ -250,6 +269,14 @@ impl SocietyJobList {
     pub fn iter_all(&self) -> impl Iterator<Item = &SocietyJobRef> + '_ {
         self.jobs.iter()
     }
+
+    /// All (task, reserver) pairs
+    pub fn iter_reservations(&self) -> impl Iterator<Item = (&SocietyTask, Entity)> + '_ {
+        self.reservations
+            .reservations
+            .iter()
+            .map(|(task, e)| (task, *e))
+    }
 }
 
 impl<T> Default for Reservations<T> {
@@ -3,8 +3,10 @@ pub use task::SocietyTask;
 
 pub use self::job::{SocietyCommand, SocietyJob, SocietyJobRef, SocietyTaskResult};
 pub use list::{JobIndex, Reservation, SocietyJobHandle, SocietyJobList};
+pub use priority::{JobCategory, JobPriority};
 
 mod job;
 mod jobs;
 mod list;
+mod priority;
 mod task;
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumCount, EnumIter, EnumString};
+
+use common::*;
+
+/// Set per job, scales the weight of all its tasks in the AI
+#[derive(
+    Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, EnumIter,
+)]
+pub enum JobPriority {
+    Low,
+    Normal,
+    High,
+    Urgent,
+}
+
+/// Broad kind of work a [SocietyTask](crate::job::SocietyTask) falls under, for per-entity work
+/// preferences. Displayed and parsed in kebab-case
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Hash,
+    Serialize,
+    Deserialize,
+    EnumIter,
+    EnumString,
+    EnumCount,
+    AsRefStr,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum JobCategory {
+    Build,
+    Haul,
+    Break,
+    Craft,
+    Farm,
+}
+
+impl JobPriority {
+    pub fn weight(self) -> f32 {
+        match self {
+            JobPriority::Low => 0.75,
+            JobPriority::Normal => 1.0,
+            JobPriority::High => 1.25,
+            JobPriority::Urgent => 1.5,
+        }
+    }
+}
+
+impl JobCategory {
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Default for JobPriority {
+    fn default() -> Self {
+        JobPriority::Normal
+    }
+}
+
+impl Display for JobPriority {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Debug::fmt(self, f)
+    }
+}
+
+impl Display for JobCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.as_ref())
+    }
+}
@@ -1,18 +1,25 @@
 use std::num::NonZeroU16;
 
-use ai::WeightedDse;
+use ai::{DecisionWeight, Dse, WeightedDse};
 use common::*;
 use unit::world::WorldPosition;
 
 use crate::activity::HaulTarget;
-use crate::ai::dse::{BreakBlockDse, BuildDse, GatherMaterialsDse, HaulDse};
+use crate::ai::dse::{
+    BreakBlockDse, BuildDse, CraftDse, GatherMaterialsDse, HarvestDse, HaulDse, SowDse,
+};
 use crate::ai::AiContext;
 use crate::build::BuildMaterial;
 use crate::ecs::{EcsWorld, Entity};
 use crate::item::HaulableItemComponent;
-use crate::job::{BuildDetails, Reservation, SocietyJobHandle};
+use crate::job::{BuildDetails, CraftDetails, JobCategory, Reservation, SocietyJobHandle};
+use crate::string::CachedStr;
 use crate::{ComponentWorld, HaulSource};
 
+/// Society tasks are capped at this proportion of [DecisionWeight::BasicNeeds], so no job
+/// priority or work preference can make them more important than basic needs or emergencies
+const MAX_TASK_WEIGHT: f32 = 0.95;
+
 #[derive(Debug, Hash, Clone, Eq, PartialEq)]
 pub struct HaulSocietyTask {
     pub item: Entity,
@@ -30,9 +37,12 @@ pub enum SocietyTask {
     /// Work on a build job
     Build(SocietyJobHandle, BuildDetails),
 
-    /// Gather materials for a build at the given position
+    /// Work on a craft job at a workshop
+    Craft(SocietyJobHandle, CraftDetails),
+
+    /// Gather materials for a job to the given position, e.g. a build or workshop
     GatherMaterials {
-        build_pos: WorldPosition,
+        target: WorldPosition,
         material: BuildMaterial,
         job: SocietyJobHandle,
         extra_hands_needed_for_haul: u16,
@@ -41,6 +51,15 @@ pub enum SocietyTask {
     /// Haul something.
     /// Boxed as this variant is much larger than the rest
     Haul(Box<HaulSocietyTask>),
+
+    /// Sow the crop on top of the given soil block
+    Sow {
+        soil: WorldPosition,
+        crop: CachedStr,
+    },
+
+    /// Harvest the given ripe plant
+    Harvest(Entity),
 }
 
 impl SocietyTask {
@@ -49,12 +68,14 @@ impl SocietyTask {
     }
 
     // TODO temporary box allocation is gross, use dynstack for dses
-    /// More reservations = lower weight
+    /// More reservations = lower weight. Final weight is scaled by `multiplier`, e.g. for job
+    /// priority and work preferences, but is always less than that of basic needs
     #[allow(unused_variables)] // used in macro
     pub fn as_dse(
         &self,
         world: &EcsWorld,
         reservation: Reservation,
+        multiplier: f32,
     ) -> Option<WeightedDse<AiContext>> {
         use Reservation::*;
         use SocietyTask::*;
@@ -66,12 +87,14 @@ impl SocietyTask {
             ReservedButShareable(3) => 0.75,
             ReservedButShareable(4) => 0.7,
             ReservedButShareable(_) | Unavailable => return None, // don't even bother
-        };
+        } * multiplier;
 
         macro_rules! dse {
-            ($dse:expr) => {
-                Some(WeightedDse::new($dse, weight))
-            };
+            ($dse:expr) => {{
+                let dse = $dse;
+                let weight = clamp_task_weight(weight, dse.weight());
+                Some(WeightedDse::new(dse, weight))
+            }};
         }
 
         match self {
@@ -83,13 +106,17 @@ impl SocietyTask {
                     details: details.clone(),
                 })
             }
+            Craft(job, details) => dse!(CraftDse {
+                job: *job,
+                details: details.clone(),
+            }),
             GatherMaterials {
-                build_pos: target,
+                target,
                 material,
                 job,
                 extra_hands_needed_for_haul,
             } => dse!(GatherMaterialsDse {
-                build_pos: *target,
+                target: *target,
                 material: material.clone(),
                 job: *job,
                 extra_hands_for_haul: *extra_hands_needed_for_haul
@@ -108,6 +135,11 @@ impl SocietyTask {
                     destination: pos,
                 })
             }
+            Sow { soil, crop } => dse!(SowDse {
+                soil: *soil,
+                crop: *crop,
+            }),
+            Harvest(plant) => dse!(HarvestDse(*plant)),
         }
     }
 
@@ -116,14 +148,33 @@ impl SocietyTask {
         let n = match self {
             BreakBlock(_) => 1,
             Build(_, _) => 1,
+            Craft(_, _) => 1,
             // TODO some types of hauling will be shareable
             // TODO depends on work item
             Haul(_) => 1,
             GatherMaterials { .. } => 3,
+            Sow { .. } => 1,
+            Harvest(_) => 1,
         };
 
         NonZeroU16::new(n).unwrap()
     }
+
+    pub fn category(&self) -> JobCategory {
+        use SocietyTask::*;
+        match self {
+            BreakBlock(_) => JobCategory::Break,
+            Build(_, _) => JobCategory::Build,
+            Craft(_, _) => JobCategory::Craft,
+            Haul(_) | GatherMaterials { .. } => JobCategory::Haul,
+            Sow { .. } | Harvest(_) => JobCategory::Farm,
+        }
+    }
+}
+
+fn clamp_task_weight(weight: f32, dse: DecisionWeight) -> f32 {
+    let max = DecisionWeight::BasicNeeds.multiplier() * MAX_TASK_WEIGHT / dse.multiplier();
+    weight.min(max)
 }
 
 impl Display for SocietyTask {
@@ -132,9 +183,12 @@ impl Display for SocietyTask {
         match self {
             BreakBlock(b) => write!(f, "Break block at {}", b),
             Build(_, details) => write!(f, "Build {:?} at {}", details.target, details.pos),
+            Craft(_, details) => write!(f, "Craft {}", details.output),
             Haul(haul) => Display::fmt(haul, f),
             // TODO include a description field for proper description e.g. "cutting log", "building wall"
             GatherMaterials { material, .. } => write!(f, "Gather {:?}", material),
+            Sow { soil, crop } => write!(f, "Sow {} at {}", crop, soil),
+            Harvest(plant) => write!(f, "Harvest {}", plant),
         }
     }
 }
@@ -144,3 +198,36 @@ impl Display for HaulSocietyTask {
         write!(f, "Haul {} to {}", self.item, self.dst)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::JobPriority;
+    use crate::society::MAX_WORK_PREFERENCE;
+    use crate::IntoEnumIterator;
+
+    #[test]
+    fn task_weights_below_needs() {
+        let world = EcsWorld::new();
+        let task = SocietyTask::BreakBlock((1, 2, 3).into());
+        let weight = |reservation, multiplier| {
+            task.as_dse(&world, reservation, multiplier)
+                .expect("task should have a dse")
+                .weight()
+        };
+
+        let max = weight(
+            Reservation::ReservedBySelf,
+            JobPriority::Urgent.weight() * MAX_WORK_PREFERENCE,
+        );
+        assert!(max < DecisionWeight::BasicNeeds.multiplier());
+        assert!(max < DecisionWeight::Emergency.multiplier());
+
+        // priorities are still ordered below the cap
+        let weights = JobPriority::iter()
+            .map(|priority| weight(Reservation::Unreserved, priority.weight()))
+            .collect_vec();
+        assert!(weights.windows(2).all(|w| w[0] < w[1]));
+        assert!(weights.iter().all(|w| *w <= max));
+    }
+}
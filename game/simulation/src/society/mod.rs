@@ -1,10 +1,18 @@
 mod component;
+mod hauling;
 pub mod job;
 mod names;
 mod registry;
 mod society;
+mod stockpile;
+mod work;
 
 pub use self::registry::{PlayerSociety, Societies, SocietyHandle, SocietyVisibility};
 pub use self::society::Society;
+pub use self::stockpile::{
+    stockpile_filter_by_name, stockpile_filter_name, Stockpile, StockpileHandle, Stockpiles,
+    STOCKPILE_FILTERS,
+};
 pub use component::SocietyComponent;
 pub use names::NameGeneration;
+pub use work::{WorkPreferencesComponent, MAX_WORK_PREFERENCE};
@@ -3,6 +3,9 @@ use common::{slog_value_debug, Formatter};
 use std::fmt::Debug;
 use std::num::NonZeroU32;
 
+use serde::{Deserialize, Serialize};
+use world::DoorOwner;
+
 // TODO keep society registry sorted by handle for quick lookup
 
 /// World resource to hold society registry
@@ -24,7 +27,7 @@ pub enum SocietyVisibility {
     JustOwn,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[repr(transparent)]
 pub struct SocietyHandle(NonZeroU32);
 
@@ -152,4 +155,10 @@ impl Debug for SocietyHandle {
     }
 }
 
+impl From<SocietyHandle> for DoorOwner {
+    fn from(handle: SocietyHandle) -> Self {
+        DoorOwner(handle.0.get())
+    }
+}
+
 slog_value_debug!(SocietyHandle);
@@ -8,14 +8,18 @@ use crate::{ComponentWorld, SocietyHandle};
 
 use crate::item::ContainerComponent;
 use crate::job::SocietyJobList;
+use crate::society::hauling::ContainerHauls;
+use crate::society::Stockpiles;
 
 pub struct Society {
     name: String,
     handle: SocietyHandle,
     jobs: RefCell<SocietyJobList>,
+    stockpiles: RefCell<Stockpiles>,
 
     /// Communal containers
     containers: HashSet<Entity>,
+    container_hauls: RefCell<ContainerHauls>,
 }
 
 impl Society {
@@ -24,7 +28,9 @@ impl Society {
             name,
             handle,
             jobs: RefCell::new(SocietyJobList::new(handle)),
+            stockpiles: RefCell::new(Stockpiles::default()),
             containers: HashSet::new(),
+            container_hauls: RefCell::new(ContainerHauls::default()),
         }
     }
 
@@ -44,6 +50,14 @@ impl Society {
         self.jobs.borrow_mut()
     }
 
+    pub fn stockpiles(&self) -> Ref<Stockpiles> {
+        self.stockpiles.borrow()
+    }
+
+    pub fn stockpiles_mut(&self) -> RefMut<Stockpiles> {
+        self.stockpiles.borrow_mut()
+    }
+
     /// The given container must already be set to communal, returns true if successful
     pub fn add_communal_container(
         &mut self,
@@ -75,10 +89,24 @@ impl Society {
                 "society did not contain removed communal container {}",
                 container
             );
+
+            self.container_hauls
+                .get_mut()
+                .on_container_removed(container, self.jobs.get_mut());
             true
         }
     }
 
+    /// Submits haul jobs for loose items to stockpiles, then communal containers
+    pub(crate) fn generate_haul_jobs(&self, world: &EcsWorld, jobs: &mut SocietyJobList) {
+        let mut stockpiles = self.stockpiles.borrow_mut();
+        stockpiles.generate_jobs(world, jobs);
+
+        self.container_hauls
+            .borrow_mut()
+            .generate_jobs(&self.containers, &stockpiles, world, jobs);
+    }
+
     fn is_communal_to_this(&self, container: Entity, world: &impl ComponentWorld) -> bool {
         let communal = world
             .component::<ContainerComponent>(container)
@@ -95,6 +123,7 @@ impl Debug for Society {
             .field("name", &self.name)
             .field("handle", &self.handle)
             .field("jobs", &*self.jobs.borrow())
+            .field("stockpiles", &self.stockpiles.borrow().iter().count())
             .field("containers", &self.containers.len())
             .finish()
     }
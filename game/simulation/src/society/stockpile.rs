@@ -0,0 +1,325 @@
+use std::collections::{HashMap, HashSet};
+
+use common::*;
+use unit::world::{WorldPosition, WorldPositionRange};
+
+use crate::ecs::*;
+use crate::item::{ItemFilter, ItemFilterable};
+use crate::job::{HaulJob, SocietyJobHandle, SocietyJobList};
+use crate::simulation::Tick;
+use crate::society::hauling::{items_on_ground, items_with_haul_jobs};
+
+/// Stockpiles are checked for new haul jobs every this many ticks
+const STOCKPILE_INTERVAL: u32 = 100;
+
+/// Max number of generated haul jobs in progress per stockpile at once
+const MAX_STOCKPILE_HAULS: usize = 4;
+
+/// (name, filter) presets for stockpiles that can be chosen by the player
+pub const STOCKPILE_FILTERS: [(&str, ItemFilter); 4] = [
+    ("Everything", ItemFilter::HasComponent("haulable")),
+    ("Food", ItemFilter::HasComponent("edible")),
+    ("Drinks", ItemFilter::HasComponent("drinkable")),
+    ("Weapons", ItemFilter::HasComponent("weapon")),
+];
+
+/// Name of the preset with the given filter, for serialization
+pub fn stockpile_filter_name(filter: ItemFilter) -> Option<&'static str> {
+    STOCKPILE_FILTERS
+        .iter()
+        .find(|(_, f)| *f == filter)
+        .map(|(name, _)| *name)
+}
+
+pub fn stockpile_filter_by_name(name: &str) -> Option<ItemFilter> {
+    STOCKPILE_FILTERS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, filter)| *filter)
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct StockpileHandle(u32);
+
+/// Zone that loose items matching the filter are hauled to, one item per tile
+pub struct Stockpile {
+    handle: StockpileHandle,
+
+    /// Solid blocks that items are placed on top of
+    range: WorldPositionRange,
+    filter: ItemFilter,
+
+    /// Items with a generated haul job in progress, and the tile they're being hauled to
+    in_progress: HashMap<Entity, (SocietyJobHandle, WorldPosition)>,
+}
+
+/// All stockpiles belonging to a society
+#[derive(Default)]
+pub struct Stockpiles {
+    stockpiles: Vec<Stockpile>,
+    next_handle: u32,
+}
+
+impl Stockpile {
+    pub fn handle(&self) -> StockpileHandle {
+        self.handle
+    }
+
+    pub fn range(&self) -> &WorldPositionRange {
+        &self.range
+    }
+
+    pub fn filter(&self) -> ItemFilter {
+        self.filter
+    }
+
+    /// Drops items whose haul jobs have finished, successfully or not
+    fn prune_finished(&mut self, jobs: &SocietyJobList) {
+        self.in_progress
+            .retain(|_, (job, _)| jobs.find_job(*job).is_some());
+    }
+}
+
+impl Stockpiles {
+    pub fn add(&mut self, range: WorldPositionRange, filter: ItemFilter) -> StockpileHandle {
+        let handle = StockpileHandle(self.next_handle);
+        self.next_handle += 1;
+
+        debug!("adding stockpile"; "handle" => ?handle, "range" => %range, "filter" => %filter);
+        self.stockpiles.push(Stockpile {
+            handle,
+            range,
+            filter,
+            in_progress: HashMap::new(),
+        });
+
+        handle
+    }
+
+    /// Removes all stockpiles that overlap the given range, and cancels their haul jobs
+    pub fn remove_overlapping(&mut self, range: &WorldPositionRange, jobs: &mut SocietyJobList) {
+        self.stockpiles.retain(|stockpile| {
+            if !stockpile.range.intersects(range) {
+                return true;
+            }
+
+            debug!("removing stockpile"; "handle" => ?stockpile.handle, "range" => %stockpile.range);
+            for (job, _) in stockpile.in_progress.values() {
+                jobs.cancel(*job);
+            }
+
+            false
+        });
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Stockpile> + '_ {
+        self.stockpiles.iter()
+    }
+
+    /// True if the given solid block is part of any stockpile
+    pub fn is_stockpiled(&self, block: WorldPosition) -> bool {
+        self.stockpiles.iter().any(|s| s.range.contains(&block))
+    }
+
+    /// Periodically submits haul jobs for loose items to stockpiles with free tiles
+    pub(in crate::society) fn generate_jobs(
+        &mut self,
+        world: &EcsWorld,
+        jobs: &mut SocietyJobList,
+    ) {
+        if self.stockpiles.is_empty() || Tick::fetch().value() % STOCKPILE_INTERVAL != 0 {
+            return;
+        }
+
+        for stockpile in self.stockpiles.iter_mut() {
+            stockpile.prune_finished(jobs);
+        }
+
+        // gather loose items that are free to be moved, and the tiles they occupy
+        let mut occupied = HashSet::new();
+        let mut loose = Vec::new();
+        for (item, pos) in items_on_ground(world) {
+            let block = pos.floor().below();
+            occupied.insert(block);
+
+            if !self.is_stockpiled(block) {
+                loose.push(item);
+            }
+        }
+
+        // includes items with in progress stockpile hauls
+        let mut in_progress = items_with_haul_jobs(jobs);
+
+        let voxel_world = world.voxel_world();
+        for stockpile in self.stockpiles.iter_mut() {
+            let capacity = MAX_STOCKPILE_HAULS.saturating_sub(stockpile.in_progress.len());
+            if capacity == 0 {
+                continue;
+            }
+
+            let free_tiles = {
+                let voxel_world = voxel_world.borrow();
+                let targeted = stockpile
+                    .in_progress
+                    .values()
+                    .map(|(_, tile)| *tile)
+                    .collect::<HashSet<_>>();
+
+                stockpile
+                    .range
+                    .iter_blocks()
+                    .filter(|block| !occupied.contains(block) && !targeted.contains(block))
+                    .filter(|block| voxel_world.area(block.above()).ok().is_some())
+                    .take(capacity)
+                    .collect_vec()
+            };
+
+            for tile in free_tiles {
+                let item = match loose.iter().copied().find(|item| {
+                    !in_progress.contains(item) && (*item, Some(world)).matches(stockpile.filter)
+                }) {
+                    Some(item) => item,
+                    None => break,
+                };
+
+                // dont try this item again this time, even if the job can't be created
+                in_progress.insert(item);
+
+                let job = match HaulJob::with_target_position(item, tile.above().centred(), world) {
+                    Some(job) => job,
+                    None => continue,
+                };
+
+                let job = jobs.submit(world, job);
+                trace!("generated stockpile haul job"; "item" => item, "tile" => %tile, "job" => ?job);
+                stockpile.in_progress.insert(item, (job, tile));
+                occupied.insert(tile);
+            }
+        }
+    }
+}
+
+impl Debug for Stockpile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("Stockpile")
+            .field("handle", &self.handle)
+            .field("range", &self.range)
+            .field("filter", &self.filter)
+            .field("in_progress", &self.in_progress.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use unit::world::WorldPoint;
+    use world::block::BlockType;
+    use world::ChunkBuilder;
+
+    use super::*;
+    use crate::activity::HaulTarget;
+    use crate::item::HaulableItemComponent;
+    use crate::society::Societies;
+    use crate::{TransformComponent, WorldContext, WorldRef};
+
+    fn setup() -> EcsWorld {
+        let voxel_world = world::helpers::context_world_from_chunks_blocking::<WorldContext>(vec![
+            ChunkBuilder::new()
+                .fill_slice(0, BlockType::Stone)
+                .build((0, 0)),
+        ]);
+
+        let mut world = EcsWorld::new();
+        world.insert(voxel_world);
+        world
+    }
+
+    fn spawn_item(world: &EcsWorld, pos: (i32, i32, i32), haulable: bool) -> Entity {
+        let mut builder = world
+            .create_entity()
+            .with(TransformComponent::new(WorldPosition::from(pos).centred()));
+        if haulable {
+            builder = builder.with(HaulableItemComponent { extra_hands: 0 });
+        }
+        builder.build().into()
+    }
+
+    /// (item, haul target) of all haul jobs
+    fn hauls(jobs: &SocietyJobList) -> Vec<(Entity, WorldPoint)> {
+        jobs.iter_all()
+            .filter_map(|job| {
+                let job = job.borrow();
+                let haul = job.cast::<HaulJob>()?;
+                match haul.target() {
+                    HaulTarget::Drop(pos) => Some((haul.entity(), pos)),
+                    _ => None,
+                }
+            })
+            .sorted_by_key(|(_, pos)| *pos)
+            .collect()
+    }
+
+    #[test]
+    fn loose_items_hauled_to_free_tiles() {
+        let world = setup();
+        let mut societies = Societies::default();
+        let society = societies.new_society("test".to_owned()).unwrap();
+        let society = societies.society_by_handle(society).unwrap();
+        let mut jobs = society.jobs_mut();
+
+        let mut stockpiles = Stockpiles::default();
+        stockpiles.add(
+            WorldPositionRange::with_inclusive_range((0, 0, 0), (2, 0, 0)),
+            STOCKPILE_FILTERS[0].1,
+        );
+
+        // first tile is already taken
+        let stockpiled = spawn_item(&world, (0, 0, 1), true);
+        let loose = [
+            spawn_item(&world, (5, 5, 1), true),
+            spawn_item(&world, (6, 5, 1), true),
+            spawn_item(&world, (7, 5, 1), true),
+        ];
+        let _not_haulable = spawn_item(&world, (8, 5, 1), false);
+
+        stockpiles.generate_jobs(&world, &mut jobs);
+        let targeted = hauls(&jobs);
+        assert_eq!(
+            targeted.iter().map(|(_, pos)| *pos).collect_vec(),
+            vec![
+                WorldPosition::from((1, 0, 1)).centred(),
+                WorldPosition::from((2, 0, 1)).centred(),
+            ]
+        );
+        assert!(targeted
+            .iter()
+            .all(|(item, _)| *item != stockpiled && loose.contains(item)));
+        assert_ne!(targeted[0].0, targeted[1].0);
+
+        // all free tiles are targeted already
+        stockpiles.generate_jobs(&world, &mut jobs);
+        assert_eq!(hauls(&jobs).len(), 2);
+    }
+
+    #[test]
+    fn remove_overlapping_stockpiles() {
+        let mut societies = Societies::default();
+        let society = societies.new_society("test".to_owned()).unwrap();
+        let society = societies.society_by_handle(society).unwrap();
+        let mut stockpiles = Stockpiles::default();
+
+        let a = WorldPositionRange::with_inclusive_range((0, 0, 0), (3, 3, 0));
+        let b = WorldPositionRange::with_inclusive_range((10, 10, 0), (12, 12, 0));
+        stockpiles.add(a, STOCKPILE_FILTERS[0].1);
+        stockpiles.add(b, STOCKPILE_FILTERS[1].1);
+
+        assert!(stockpiles.is_stockpiled((2, 2, 0).into()));
+        assert!(!stockpiles.is_stockpiled((2, 2, 1).into()));
+
+        let remove = WorldPositionRange::with_single((3, 3, 0));
+        stockpiles.remove_overlapping(&remove, &mut society.jobs_mut());
+        assert!(!stockpiles.is_stockpiled((2, 2, 0).into()));
+        assert!(stockpiles.is_stockpiled((11, 11, 0).into()));
+        assert_eq!(stockpiles.iter().count(), 1);
+    }
+}
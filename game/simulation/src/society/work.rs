@@ -0,0 +1,175 @@
+use std::rc::Rc;
+use std::str::FromStr;
+
+use strum::EnumCount;
+
+use common::*;
+
+use crate::ecs::*;
+use crate::job::JobCategory;
+use crate::string::StringCache;
+use crate::IntoEnumIterator;
+
+/// Weight applied to society tasks of each [JobCategory] for this entity. A weight of 0 means the
+/// category is disabled and its tasks are not considered at all
+#[derive(Component, EcsComponent, Clone, Debug)]
+#[storage(DenseVecStorage)]
+#[name("work-preferences")]
+#[interactive]
+#[save]
+pub struct WorkPreferencesComponent {
+    weights: [f32; JobCategory::COUNT],
+}
+
+/// Max weight of a single category
+pub const MAX_WORK_PREFERENCE: f32 = 2.0;
+
+impl WorkPreferencesComponent {
+    pub fn weight(&self, category: JobCategory) -> f32 {
+        self.weights[category.index()]
+    }
+
+    pub fn is_enabled(&self, category: JobCategory) -> bool {
+        self.weight(category) > 0.0
+    }
+
+    /// Clamped to [0, [MAX_WORK_PREFERENCE]]
+    pub fn set_weight(&mut self, category: JobCategory, weight: f32) {
+        self.weights[category.index()] = weight.max(0.0).min(MAX_WORK_PREFERENCE);
+    }
+}
+
+impl Default for WorkPreferencesComponent {
+    /// Everything enabled with equal weight
+    fn default() -> Self {
+        Self {
+            weights: [1.0; JobCategory::COUNT],
+        }
+    }
+}
+
+/// "category=weight" pairs separated by commas, unspecified categories default to 1
+impl FromStr for WorkPreferencesComponent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut prefs = Self::default();
+        for entry in s.split(',').filter(|s| !s.is_empty()) {
+            let (category, weight) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("missing = in {:?}", entry))?;
+            let category: JobCategory = category
+                .trim()
+                .parse()
+                .map_err(|_| format!("unknown job category {:?}", category))?;
+            let weight: f32 = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("bad weight {:?}", weight))?;
+
+            prefs.set_weight(category, weight);
+        }
+
+        Ok(prefs)
+    }
+}
+
+impl SaveComponent for WorkPreferencesComponent {
+    type Saved = [f32; JobCategory::COUNT];
+
+    fn save(&self, _: &SaveContext) -> Option<Self::Saved> {
+        Some(self.weights)
+    }
+
+    fn load(
+        weights: Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        _: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let _ = world.add_now(entity, Self { weights });
+        Ok(())
+    }
+}
+
+impl<V: Value> ComponentTemplate<V> for WorkPreferencesComponent {
+    fn construct(
+        values: &mut Map<V>,
+        _: &StringCache,
+    ) -> Result<Rc<dyn ComponentTemplate<V>>, ComponentBuildError>
+    where
+        Self: Sized,
+    {
+        let prefs = match values.get_string("weights") {
+            Ok(weights) => weights.parse().map_err(|e| {
+                ComponentBuildError::TemplateSpecific(format!("failed to parse weights: {}", e))
+            })?,
+            Err(ComponentBuildError::KeyNotFound(_)) => Self::default(),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Rc::new(prefs))
+    }
+
+    fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
+        builder.with(self.clone())
+    }
+
+    crate::as_any!();
+}
+
+impl InteractiveComponent for WorkPreferencesComponent {
+    fn as_debug(&self) -> Option<&dyn Debug> {
+        Some(self)
+    }
+}
+
+impl Display for WorkPreferencesComponent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let mut first = true;
+        for category in JobCategory::iter() {
+            if !first {
+                write!(f, ", ")?;
+            } else {
+                first = false;
+            }
+
+            write!(f, "{}={:.2}", category, self.weight(category))?;
+        }
+
+        Ok(())
+    }
+}
+
+register_component_template!("work-preferences", WorkPreferencesComponent);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_preferences() {
+        let prefs: WorkPreferencesComponent = "haul=0,build = 1.5,farm=10".parse().unwrap();
+        assert!(!prefs.is_enabled(JobCategory::Haul));
+        assert_eq!(prefs.weight(JobCategory::Build), 1.5);
+        assert_eq!(prefs.weight(JobCategory::Farm), MAX_WORK_PREFERENCE);
+        assert_eq!(prefs.weight(JobCategory::Break), 1.0);
+
+        assert!("".parse::<WorkPreferencesComponent>().is_ok());
+        assert!("dance=1".parse::<WorkPreferencesComponent>().is_err());
+        assert!("build".parse::<WorkPreferencesComponent>().is_err());
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let mut prefs = WorkPreferencesComponent::default();
+        prefs.set_weight(JobCategory::Haul, 0.0);
+        prefs.set_weight(JobCategory::Farm, 1.5);
+
+        let displayed = prefs.to_string();
+        assert!(displayed.contains("haul=0.00"));
+
+        let parsed: WorkPreferencesComponent = displayed.parse().expect("failed to parse");
+        assert_eq!(parsed.weights, prefs.weights);
+    }
+}
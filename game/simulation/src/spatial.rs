@@ -1,5 +1,5 @@
 use std::cell::{RefCell, RefMut};
-use std::collections::HashSet;
+use std::collections::HashMap;
 
 use common::*;
 use unit::world::WorldPoint;
@@ -7,7 +7,11 @@ use unit::world::WorldPoint;
 use crate::ecs::*;
 use crate::{PhysicalComponent, Tick, TransformComponent};
 
-// TODO reimplement with octree
+/// Side length of each cubic grid cell in the index
+const CELL_SIZE: f32 = 8.0;
+
+/// Index is updated with entity movement every this many ticks
+const UPDATE_FREQUENCY: u32 = 5;
 
 /// Implements efficient spatial entity queries
 pub struct Spatial {
@@ -15,13 +19,24 @@ pub struct Spatial {
 }
 
 struct SpatialInner {
-    /// Really stupid implementation for now
-    positions: Vec<(Entity, WorldPoint)>,
-    entities: HashSet<Entity>,
+    grid: SpatialGrid,
     newly_created_entities: Vec<Entity>,
     query_cache: Vec<(Entity, WorldPoint, f32)>,
 }
 
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+struct Cell(i32, i32, i32);
+
+/// Sparse grid of cells containing entities, updated incrementally so only entities that move
+/// between cells are shuffled around
+#[derive(Default)]
+struct SpatialGrid {
+    cells: HashMap<Cell, Vec<(Entity, WorldPoint)>>,
+    /// Current cell of each entity, and the update generation it was last seen in
+    entities: HashMap<Entity, (Cell, u32)>,
+    generation: u32,
+}
+
 /// Update spatial resource
 pub struct SpatialSystem;
 
@@ -29,14 +44,14 @@ impl Default for Spatial {
     fn default() -> Self {
         Self {
             inner: RefCell::new(SpatialInner {
-                positions: Vec::with_capacity(256),
-                entities: HashSet::with_capacity(256),
+                grid: SpatialGrid::default(),
                 newly_created_entities: Vec::new(),
                 query_cache: Vec::new(),
             }),
         }
     }
 }
+
 impl Spatial {
     fn update(
         &mut self,
@@ -44,27 +59,26 @@ impl Spatial {
         transforms: ReadStorage<TransformComponent>,
         physicals: ReadStorage<PhysicalComponent>,
     ) {
-        let mut inner = self.inner.borrow_mut();
+        let inner = self.inner.get_mut();
 
-        inner.positions.clear();
-        inner.entities.clear();
-
-        for (e, transform, _) in (&entities, &transforms, &physicals).join() {
-            inner.positions.push((e.into(), transform.position));
-            inner.entities.insert(e.into());
-        }
+        inner.grid.update_all(
+            (&entities, &transforms, &physicals)
+                .join()
+                .map(|(e, transform, _)| (e.into(), transform.position)),
+        );
 
         // already included new entities
         debug_assert!(inner
             .newly_created_entities
             .iter()
-            .all(|e| inner.positions.iter().any(|(existing, _)| existing == e)));
+            .all(|e| inner.grid.entities.contains_key(e)));
         inner.newly_created_entities.clear();
 
-        if !inner.positions.is_empty() {
+        if !inner.grid.entities.is_empty() {
             trace!(
-                "updated spatial index with {count} entities",
-                count = inner.positions.len()
+                "updated spatial index with {count} entities in {cells} cells",
+                count = inner.grid.entities.len(),
+                cells = inner.grid.cells.len(),
             );
         }
     }
@@ -74,96 +88,334 @@ impl Spatial {
         self.inner.borrow_mut().newly_created_entities.push(entity);
     }
 
-    // The sort is a massive hotspot in profiling, keeping this not-inlined helps this terrible
-    // TEMPORARY method stand out
-    #[inline(never)]
+    /// Sorted by distance from centre
     pub fn query_in_radius(
         &self,
         world: &EcsWorld,
         centre: WorldPoint,
         radius: f32,
     ) -> impl Iterator<Item = (Entity, WorldPoint, f32)> + '_ {
+        self.query_sphere(world, centre, radius, None, |_| true)
+    }
+
+    /// Sorted by distance from centre, only includes entities with the given component
+    pub fn query_in_radius_with<C: Component>(
+        &self,
+        world: &EcsWorld,
+        centre: WorldPoint,
+        radius: f32,
+    ) -> impl Iterator<Item = (Entity, WorldPoint, f32)> + '_ {
+        let storage = world.read_storage::<C>();
+        self.query_sphere(world, centre, radius, None, |e| e.has(&storage))
+    }
+
+    /// Entities with a position inside the given inclusive bounds, sorted by distance from the
+    /// centre of the box
+    pub fn query_in_box(
+        &self,
+        world: &EcsWorld,
+        min: WorldPoint,
+        max: WorldPoint,
+    ) -> impl Iterator<Item = (Entity, WorldPoint, f32)> + '_ {
+        self.query_box(world, min, max, |_| true)
+    }
+
+    /// Entities with a position inside the given inclusive bounds with the given component, sorted
+    /// by distance from the centre of the box
+    pub fn query_in_box_with<C: Component>(
+        &self,
+        world: &EcsWorld,
+        min: WorldPoint,
+        max: WorldPoint,
+    ) -> impl Iterator<Item = (Entity, WorldPoint, f32)> + '_ {
+        let storage = world.read_storage::<C>();
+        self.query_box(world, min, max, |e| e.has(&storage))
+    }
+
+    /// Up to `count` closest entities within `max_radius`, sorted by distance from centre
+    pub fn query_nearest(
+        &self,
+        world: &EcsWorld,
+        centre: WorldPoint,
+        count: usize,
+        max_radius: f32,
+    ) -> impl Iterator<Item = (Entity, WorldPoint, f32)> + '_ {
+        self.query_sphere(world, centre, max_radius, Some(count), |_| true)
+    }
+
+    /// Up to `count` closest entities with the given component within `max_radius`, sorted by
+    /// distance from centre
+    pub fn query_nearest_with<C: Component>(
+        &self,
+        world: &EcsWorld,
+        centre: WorldPoint,
+        count: usize,
+        max_radius: f32,
+    ) -> impl Iterator<Item = (Entity, WorldPoint, f32)> + '_ {
+        let storage = world.read_storage::<C>();
+        self.query_sphere(world, centre, max_radius, Some(count), |e| e.has(&storage))
+    }
+
+    /// If `count` is given, the search starts small and expands up to `radius` until enough
+    /// entities are found
+    fn query_sphere(
+        &self,
+        world: &EcsWorld,
+        centre: WorldPoint,
+        radius: f32,
+        count: Option<usize>,
+        filter: impl Fn(Entity) -> bool,
+    ) -> QueryIterator<'_> {
         let mut inner = self.inner.borrow_mut();
         let transforms = world.read_storage::<TransformComponent>();
+        inner.add_new_entities(world, &transforms);
 
-        // add any new entities
-        if !inner.newly_created_entities.is_empty() {
-            let physicals = world.read_storage::<PhysicalComponent>();
-
-            let max = inner.newly_created_entities.len();
-            let mut total = 0;
-            let mut new_entities = std::mem::take(&mut inner.newly_created_entities);
-            for new_entity in new_entities.drain(..) {
-                if let Some((transform, _)) =
-                    world.components(new_entity, (&transforms, &physicals))
-                {
-                    if inner.entities.insert(new_entity) {
-                        inner.positions.push((new_entity, transform.position));
-                        total += 1;
-                    }
+        let SpatialInner {
+            grid, query_cache, ..
+        } = &mut *inner;
+
+        let mut search_radius = match count {
+            Some(_) => CELL_SIZE.min(radius),
+            None => radius,
+        };
+
+        loop {
+            query_cache.clear();
+
+            let radius2 = search_radius.powi(2);
+            let extent = (search_radius, search_radius, search_radius);
+            grid.query(centre + negate(extent), centre + extent, |e, point| {
+                let distance2 = point.distance2(centre);
+                // positions are cached so check transform is still present
+                if distance2 < radius2 && e.has(&transforms) && filter(e) {
+                    query_cache.push((e, point, distance2.sqrt()));
                 }
+            });
+
+            match count {
+                Some(count) if query_cache.len() < count && search_radius < radius => {
+                    // not enough found yet, expand search
+                    search_radius = (search_radius * 2.0).min(radius);
+                }
+                _ => break,
             }
+        }
 
-            // put vec back
-            std::mem::forget(std::mem::replace(
-                &mut inner.newly_created_entities,
-                new_entities,
-            ));
+        sort_results(query_cache);
+        if let Some(count) = count {
+            query_cache.truncate(count);
+        }
 
-            trace!(
-                "updated spatial with {}/{} of newly created entities",
-                total,
-                max
-            );
+        QueryIterator {
+            result: RefMut::map(inner, |inner| &mut inner.query_cache),
+            idx: 0,
         }
+    }
+
+    fn query_box(
+        &self,
+        world: &EcsWorld,
+        min: WorldPoint,
+        max: WorldPoint,
+        filter: impl Fn(Entity) -> bool,
+    ) -> QueryIterator<'_> {
+        let mut inner = self.inner.borrow_mut();
+        let transforms = world.read_storage::<TransformComponent>();
+        inner.add_new_entities(world, &transforms);
 
-        let radius2 = radius.powi(2);
+        let SpatialInner {
+            grid, query_cache, ..
+        } = &mut *inner;
 
-        {
-            // safety: query cache is not referenced anywhere else
-            let query_cache = unsafe { &mut *(&mut inner.query_cache as *mut Vec<_>) };
-            query_cache.clear();
+        let centre = {
+            let (ax, ay, az) = min.xyz();
+            let (bx, by, bz) = max.xyz();
+            WorldPoint::new_unchecked((ax + bx) / 2.0, (ay + by) / 2.0, (az + bz) / 2.0)
+        };
 
-            query_cache.extend(
-                inner
-                    .positions
-                    .iter()
-                    .map(|(e, point)| {
-                        let distance2 = point.distance2(centre);
-                        (e, point, distance2)
-                    })
-                    // positions are cached so check transform is still present after filtering by distance
-                    .filter(|(e, _, dist2)| *dist2 < radius2 && e.has(&transforms))
-                    .map(|(e, point, dist2)| (*e, *point, dist2.sqrt())),
-            );
+        query_cache.clear();
+        grid.query(min, max, |e, point| {
+            let (x, y, z) = point.xyz();
+            let inside = (min.x()..=max.x()).contains(&x)
+                && (min.y()..=max.y()).contains(&y)
+                && (min.z()..=max.z()).contains(&z);
+
+            // positions are cached so check transform is still present
+            if inside && e.has(&transforms) && filter(e) {
+                query_cache.push((e, point, point.distance2(centre).sqrt()));
+            }
+        });
+
+        sort_results(query_cache);
+
+        QueryIterator {
+            result: RefMut::map(inner, |inner| &mut inner.query_cache),
+            idx: 0,
+        }
+    }
+}
+
+impl SpatialInner {
+    fn add_new_entities(&mut self, world: &EcsWorld, transforms: &ReadStorage<TransformComponent>) {
+        if self.newly_created_entities.is_empty() {
+            return;
         }
 
-        inner
-            .query_cache
-            .sort_unstable_by_key(|(_, _, dist)| OrderedFloat(*dist));
+        let physicals = world.read_storage::<PhysicalComponent>();
 
-        struct QueryIterator<'a> {
-            result: RefMut<'a, Vec<(Entity, WorldPoint, f32)>>,
-            idx: usize,
+        let max = self.newly_created_entities.len();
+        let mut total = 0;
+        for new_entity in self.newly_created_entities.drain(..) {
+            if let Some((transform, _)) = world.components(new_entity, (transforms, &physicals)) {
+                if !self.grid.entities.contains_key(&new_entity) {
+                    self.grid.update(new_entity, transform.position);
+                    total += 1;
+                }
+            }
         }
 
-        impl<'a> Iterator for QueryIterator<'a> {
-            type Item = (Entity, WorldPoint, f32);
+        trace!(
+            "updated spatial with {}/{} of newly created entities",
+            total,
+            max
+        );
+    }
+}
 
-            fn next(&mut self) -> Option<Self::Item> {
-                let res = self.result.get(self.idx);
-                self.idx += 1;
-                res.copied()
+impl Cell {
+    fn containing(point: WorldPoint) -> Self {
+        let (x, y, z) = point.xyz();
+        let cell = |f: f32| (f / CELL_SIZE).floor() as i32;
+        Self(cell(x), cell(y), cell(z))
+    }
+}
+
+impl SpatialGrid {
+    /// Inserts or moves the entity
+    fn update(&mut self, entity: Entity, pos: WorldPoint) {
+        let cell = Cell::containing(pos);
+        match self.entities.get_mut(&entity) {
+            Some((current, seen)) => {
+                *seen = self.generation;
+
+                if *current == cell {
+                    // same cell, just update position
+                    if let Some(entry) = self
+                        .cells
+                        .get_mut(&cell)
+                        .and_then(|entities| entities.iter_mut().find(|(e, _)| *e == entity))
+                    {
+                        entry.1 = pos;
+                    }
+                } else {
+                    // moved into a new cell
+                    Self::remove_from_cell(&mut self.cells, *current, entity);
+                    self.cells.entry(cell).or_default().push((entity, pos));
+                    *current = cell;
+                }
+            }
+            None => {
+                self.entities.insert(entity, (cell, self.generation));
+                self.cells.entry(cell).or_default().push((entity, pos));
             }
         }
+    }
 
-        QueryIterator {
-            result: RefMut::map(inner, |inner| &mut inner.query_cache),
-            idx: 0,
+    /// Updates all entities, removing any that are no longer present
+    fn update_all(&mut self, entities: impl Iterator<Item = (Entity, WorldPoint)>) {
+        self.generation = self.generation.wrapping_add(1);
+
+        for (entity, pos) in entities {
+            self.update(entity, pos);
+        }
+
+        let generation = self.generation;
+        let cells = &mut self.cells;
+        self.entities.retain(|entity, (cell, seen)| {
+            let keep = *seen == generation;
+            if !keep {
+                Self::remove_from_cell(cells, *cell, *entity);
+            }
+            keep
+        });
+    }
+
+    fn remove_from_cell(
+        cells: &mut HashMap<Cell, Vec<(Entity, WorldPoint)>>,
+        cell: Cell,
+        entity: Entity,
+    ) {
+        if let Some(entities) = cells.get_mut(&cell) {
+            if let Some(idx) = entities.iter().position(|(e, _)| *e == entity) {
+                entities.swap_remove(idx);
+            }
+
+            if entities.is_empty() {
+                cells.remove(&cell);
+            }
+        }
+    }
+
+    /// Calls `found` for every entity in the cells overlapping the given bounds, which may
+    /// include some outside of the bounds
+    fn query(&self, min: WorldPoint, max: WorldPoint, mut found: impl FnMut(Entity, WorldPoint)) {
+        let Cell(x0, y0, z0) = Cell::containing(min);
+        let Cell(x1, y1, z1) = Cell::containing(max);
+
+        let span = |a: i32, b: i32| (b as i64 - a as i64 + 1) as usize;
+        let cell_count = span(x0, x1)
+            .saturating_mul(span(y0, y1))
+            .saturating_mul(span(z0, z1));
+        if cell_count > self.cells.len() {
+            // cheaper to check every occupied cell than every cell in range
+            let in_range = |Cell(x, y, z): &Cell| {
+                (x0..=x1).contains(x) && (y0..=y1).contains(y) && (z0..=z1).contains(z)
+            };
+
+            self.cells
+                .iter()
+                .filter(|(cell, _)| in_range(cell))
+                .flat_map(|(_, entities)| entities.iter())
+                .for_each(|(e, pos)| found(*e, *pos));
+        } else {
+            for x in x0..=x1 {
+                for y in y0..=y1 {
+                    for z in z0..=z1 {
+                        if let Some(entities) = self.cells.get(&Cell(x, y, z)) {
+                            entities.iter().for_each(|(e, pos)| found(*e, *pos));
+                        }
+                    }
+                }
+            }
         }
     }
 }
 
+/// Sorted by distance, then by entity so results are the same regardless of the iteration order
+/// of cells
+fn sort_results(results: &mut [(Entity, WorldPoint, f32)]) {
+    results.sort_unstable_by_key(|(e, _, dist)| (OrderedFloat(*dist), *e));
+}
+
+fn negate((x, y, z): (f32, f32, f32)) -> (f32, f32, f32) {
+    (-x, -y, -z)
+}
+
+struct QueryIterator<'a> {
+    result: RefMut<'a, Vec<(Entity, WorldPoint, f32)>>,
+    idx: usize,
+}
+
+impl<'a> Iterator for QueryIterator<'a> {
+    type Item = (Entity, WorldPoint, f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = self.result.get(self.idx);
+        self.idx += 1;
+        res.copied()
+    }
+}
+
 impl<'a> System<'a> for SpatialSystem {
     type SystemData = (
         Read<'a, EntitiesRes>,
@@ -175,8 +427,207 @@ impl<'a> System<'a> for SpatialSystem {
     fn run(&mut self, (entities, transforms, physicals, mut spatial): Self::SystemData) {
         // only update occasionally
         let tick = Tick::fetch();
-        if tick.value() % 5 == 0 {
+        if tick.value() % UPDATE_FREQUENCY == 0 {
             spatial.update(entities, transforms, physicals);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use unit::space::length::Length3;
+    use unit::space::volume::Volume;
+
+    use super::*;
+    use crate::item::ThrowableItemComponent;
+
+    fn point(x: f32, y: f32, z: f32) -> WorldPoint {
+        WorldPoint::new(x, y, z).unwrap()
+    }
+
+    fn query(grid: &SpatialGrid, min: WorldPoint, max: WorldPoint) -> Vec<Entity> {
+        let mut found = vec![];
+        grid.query(min, max, |e, _| found.push(e));
+        found.sort_unstable();
+        found
+    }
+
+    fn spawn(world: &EcsWorld, spatial: &Spatial, pos: WorldPoint, throwable: bool) -> Entity {
+        let mut builder = world
+            .create_entity()
+            .with(TransformComponent::new(pos))
+            .with(PhysicalComponent::new(
+                Volume::new(1),
+                Length3::new(1, 1, 1),
+            ));
+        if throwable {
+            builder = builder.with(ThrowableItemComponent);
+        }
+
+        let entity = Entity::from(builder.build());
+        spatial.register_new_entity(entity);
+        entity
+    }
+
+    fn entities(results: impl Iterator<Item = (Entity, WorldPoint, f32)>) -> Vec<Entity> {
+        results.map(|(e, _, _)| e).collect()
+    }
+
+    #[test]
+    fn nearest_expands_search() {
+        let world = EcsWorld::new();
+        let spatial = Spatial::default();
+        let centre = point(100.0, 100.0, 10.0);
+
+        // only the first is within the initial search radius
+        let near = spawn(&world, &spatial, point(101.0, 100.0, 10.0), false);
+        let mid = spawn(&world, &spatial, point(100.0, 120.0, 10.0), false);
+        let far = spawn(&world, &spatial, point(140.0, 100.0, 10.0), false);
+
+        assert_eq!(
+            entities(spatial.query_nearest(&world, centre, 2, 100.0)),
+            vec![near, mid]
+        );
+        assert_eq!(
+            entities(spatial.query_nearest(&world, centre, 5, 100.0)),
+            vec![near, mid, far]
+        );
+
+        // expansion stops at the max radius
+        assert_eq!(
+            entities(spatial.query_nearest(&world, centre, 5, 30.0)),
+            vec![near, mid]
+        );
+        assert_eq!(
+            entities(spatial.query_nearest(&world, centre, 1, 100.0)),
+            vec![near]
+        );
+    }
+
+    #[test]
+    fn equal_distances_sorted_by_entity() {
+        let world = EcsWorld::new();
+        let spatial = Spatial::default();
+        let centre = point(100.0, 100.0, 10.0);
+
+        // same distance from the centre, spread across different cells
+        let mut expected = [
+            point(110.0, 100.0, 10.0),
+            point(90.0, 100.0, 10.0),
+            point(100.0, 110.0, 10.0),
+            point(100.0, 90.0, 10.0),
+            point(100.0, 100.0, 20.0),
+            point(100.0, 100.0, 0.0),
+        ]
+        .iter()
+        .map(|pos| spawn(&world, &spatial, *pos, false))
+        .collect_vec();
+        expected.sort_unstable();
+
+        assert_eq!(
+            entities(spatial.query_in_radius(&world, centre, 20.0)),
+            expected
+        );
+        assert_eq!(
+            entities(spatial.query_nearest(&world, centre, 3, 20.0)),
+            &expected[..3]
+        );
+    }
+
+    #[test]
+    fn box_is_inclusive_and_sorted() {
+        let world = EcsWorld::new();
+        let spatial = Spatial::default();
+
+        let centre = spawn(&world, &spatial, point(10.0, 10.0, 5.0), false);
+        let edge = spawn(&world, &spatial, point(20.0, 20.0, 5.0), false);
+        let corner = spawn(&world, &spatial, point(0.0, 0.0, 0.0), false);
+        let _outside = spawn(&world, &spatial, point(20.5, 10.0, 5.0), false);
+        let _above = spawn(&world, &spatial, point(10.0, 10.0, 11.0), false);
+
+        let found = spatial
+            .query_in_box(&world, point(0.0, 0.0, 0.0), point(20.0, 20.0, 10.0))
+            .collect_vec();
+        assert_eq!(
+            found.iter().map(|(e, _, _)| *e).collect_vec(),
+            vec![centre, edge, corner]
+        );
+
+        // distance is from the centre of the box
+        assert!(found[0].2 < 0.0001);
+    }
+
+    #[test]
+    fn filtered_queries() {
+        let world = EcsWorld::new();
+        let spatial = Spatial::default();
+        let centre = point(50.0, 50.0, 5.0);
+
+        let _plain_near = spawn(&world, &spatial, point(51.0, 50.0, 5.0), false);
+        let throwable_near = spawn(&world, &spatial, point(53.0, 50.0, 5.0), true);
+        let _plain_far = spawn(&world, &spatial, point(70.0, 50.0, 5.0), false);
+        let throwable_far = spawn(&world, &spatial, point(80.0, 50.0, 5.0), true);
+
+        assert_eq!(
+            entities(spatial.query_in_radius_with::<ThrowableItemComponent>(&world, centre, 50.0)),
+            vec![throwable_near, throwable_far]
+        );
+
+        // filtered entities don't count towards the requested count
+        assert_eq!(
+            entities(spatial.query_nearest_with::<ThrowableItemComponent>(&world, centre, 2, 50.0)),
+            vec![throwable_near, throwable_far]
+        );
+
+        assert_eq!(
+            entities(spatial.query_in_box_with::<ThrowableItemComponent>(
+                &world,
+                point(60.0, 40.0, 0.0),
+                point(90.0, 60.0, 10.0)
+            )),
+            vec![throwable_far]
+        );
+    }
+
+    #[test]
+    fn grid_incremental_update() {
+        let mut world = specs::World::new();
+        let a = Entity::from(world.create_entity().build());
+        let b = Entity::from(world.create_entity().build());
+
+        let mut grid = SpatialGrid::default();
+        grid.update_all(vec![(a, point(1.0, 1.0, 1.0)), (b, point(50.0, 1.0, 1.0))].into_iter());
+
+        assert_eq!(grid.cells.len(), 2);
+        assert_eq!(
+            query(&grid, point(0.0, 0.0, 0.0), point(4.0, 4.0, 4.0)),
+            vec![a]
+        );
+
+        // move into the same cell as a
+        grid.update_all(vec![(a, point(1.0, 1.0, 1.0)), (b, point(2.0, 1.0, 1.0))].into_iter());
+        assert_eq!(grid.cells.len(), 1);
+        assert_eq!(
+            query(&grid, point(0.0, 0.0, 0.0), point(4.0, 4.0, 4.0)),
+            vec![a, b]
+        );
+
+        // a is removed
+        grid.update_all(vec![(b, point(2.0, 1.0, 1.0))].into_iter());
+        assert_eq!(
+            query(&grid, point(0.0, 0.0, 0.0), point(4.0, 4.0, 4.0)),
+            vec![b]
+        );
+        assert!(!grid.entities.contains_key(&a));
+
+        // huge query range falls back to scanning occupied cells
+        assert_eq!(
+            query(
+                &grid,
+                point(-1000.0, -1000.0, -1000.0),
+                point(1000.0, 1000.0, 1000.0)
+            ),
+            vec![b]
+        );
+    }
+}
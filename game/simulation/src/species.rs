@@ -15,6 +15,12 @@ pub struct Species(CachedStr);
 #[name("species")]
 pub struct SpeciesComponent(Species);
 
+impl Species {
+    pub fn new(name: &str, string_cache: &StringCache) -> Self {
+        Self(string_cache.get(&name.to_lowercase())) // normalise case
+    }
+}
+
 impl SpeciesComponent {
     pub fn species(&self) -> Species {
         self.0
@@ -53,8 +59,8 @@ impl<V: Value> ComponentTemplate<V> for SpeciesComponent {
     where
         Self: Sized,
     {
-        let name = values.get_string("name")?.to_lowercase(); // normalise case
-        Ok(Rc::new(Self(Species(string_cache.get(&name)))))
+        let name = values.get_string("name")?;
+        Ok(Rc::new(Self(Species::new(&name, string_cache))))
     }
 
     fn instantiate<'b>(&self, builder: EntityBuilder<'b>) -> EntityBuilder<'b> {
@@ -8,6 +8,7 @@ use crate::TransformComponent;
 pub enum SteeringBehaviour {
     Stop(Stop),
     Seek(Seek),
+    Climb(Climb),
 }
 
 /// Arrest current movement
@@ -21,6 +22,16 @@ pub struct Seek {
     speed: NormalizedFloat,
 }
 
+/// Climb vertically to the target, e.g. along a ladder, while staying centred over it
+#[derive(Debug)]
+pub struct Climb {
+    target: WorldPoint,
+    speed: NormalizedFloat,
+}
+
+/// Vertical distance from the target that counts as arrived when climbing
+const CLIMB_ARRIVAL: f32 = 0.1;
+
 /// When steering is complete
 #[derive(Debug)]
 pub enum SteeringResult {
@@ -42,6 +53,10 @@ impl SteeringBehaviour {
         SteeringBehaviour::Seek(Seek::with_target(target.into(), speed))
     }
 
+    pub fn climb<P: Into<WorldPoint>>(target: P, speed: NormalizedFloat) -> Self {
+        SteeringBehaviour::Climb(Climb::with_target(target.into(), speed))
+    }
+
     pub fn tick(
         &mut self,
         transform: &TransformComponent,
@@ -55,6 +70,17 @@ impl SteeringBehaviour {
             SteeringBehaviour::Seek(behaviour) => {
                 behaviour.tick(transform, bounding_radius, interests)
             }
+            SteeringBehaviour::Climb(behaviour) => {
+                behaviour.tick(transform, bounding_radius, interests)
+            }
+        }
+    }
+
+    /// Desired vertical movement in the range [-1, 1], only present while climbing
+    pub fn climb_direction(&self, transform: &TransformComponent) -> Option<f32> {
+        match self {
+            SteeringBehaviour::Climb(behaviour) => Some(behaviour.direction(transform)),
+            _ => None,
         }
     }
 
@@ -145,6 +171,47 @@ impl DoASteer for Seek {
     }
 }
 
+impl Climb {
+    pub fn with_target(target: WorldPoint, speed: NormalizedFloat) -> Self {
+        Self { target, speed }
+    }
+
+    fn direction(&self, transform: &TransformComponent) -> f32 {
+        let dz = self.target.z() - transform.position.z();
+        if dz.abs() < CLIMB_ARRIVAL {
+            0.0
+        } else {
+            dz.signum() * self.speed.value()
+        }
+    }
+}
+
+impl DoASteer for Climb {
+    fn tick(
+        &mut self,
+        transform: &TransformComponent,
+        bounding_radius: f32,
+        interests: &mut InterestsContextMap,
+    ) -> SteeringResult {
+        let tgt = Vector2::from(self.target);
+        let pos = Vector2::from(transform.position);
+        let distance = pos.distance(tgt);
+
+        let dz = self.target.z() - transform.position.z();
+        if dz.abs() < CLIMB_ARRIVAL && distance < bounding_radius {
+            // we've arrived
+            return SteeringResult::Finished;
+        }
+
+        // stay centred on the climbable block, the vertical movement is applied separately
+        if distance > CLIMB_ARRIVAL {
+            self.register_interest(tgt - pos, self.speed, interests);
+        }
+
+        SteeringResult::Ongoing
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -196,6 +263,35 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn climb_until_level() {
+        let mut climb = Climb::with_target(
+            WorldPoint::new_unchecked(2.5, 2.5, 6.0),
+            NormalizedFloat::one(),
+        );
+
+        // directly below the target
+        let mut transform = TransformComponent::new(WorldPoint::new_unchecked(2.5, 2.5, 3.0));
+        let mut output = InterestsContextMap::default();
+        assert!(matches!(
+            climb.tick(&transform, 0.5, &mut output),
+            SteeringResult::Ongoing
+        ));
+        assert!(climb.direction(&transform) > 0.0);
+
+        // overshot
+        transform.position.modify_z(|_| 6.5);
+        assert!(climb.direction(&transform) < 0.0);
+
+        // level
+        transform.position.modify_z(|_| 6.02);
+        assert_eq!(climb.direction(&transform), 0.0);
+        assert!(matches!(
+            climb.tick(&transform, 0.5, &mut output),
+            SteeringResult::Finished
+        ));
+    }
+
     #[test]
     fn seek_exact_pos() {
         // we are not exactly lined up with the target, and a tiny radius
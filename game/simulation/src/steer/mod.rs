@@ -1,4 +1,4 @@
-pub use behaviour::{Seek, SteeringBehaviour};
+pub use behaviour::{Climb, Seek, SteeringBehaviour};
 pub use debug::SteeringDebugRenderer;
 pub use system::{SteeringComponent, SteeringSystem};
 
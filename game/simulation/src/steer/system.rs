@@ -49,6 +49,8 @@ impl<'a> System<'a> for SteeringSystem {
                     .behaviour
                     .tick(transform, bounding_radius, context_map.interests_mut());
 
+            let climb = steer.behaviour.climb_direction(transform);
+
             if let SteeringResult::Finished = result {
                 trace!(
                     "finished steering, reverting to default behaviour";
@@ -70,7 +72,8 @@ impl<'a> System<'a> for SteeringSystem {
                 }
             }
 
-            movement.0 = context_map;
+            movement.steering = context_map;
+            movement.climb = climb;
         }
     }
 }
@@ -16,6 +16,7 @@ use crate::string::StringCache;
 #[derive(Debug, Clone, Component, EcsComponent)]
 #[storage(VecStorage)]
 #[name("transform")]
+#[save]
 pub struct TransformComponent {
     /// Position in world, center of entity in x/y and bottom of entity in z
     pub position: WorldPoint,
@@ -147,6 +148,35 @@ impl PhysicalComponent {
     }
 }
 
+/// (x, y, z, rotation in radians)
+type SavedTransform = (f32, f32, f32, f32);
+
+impl SaveComponent for TransformComponent {
+    type Saved = SavedTransform;
+
+    fn save(&self, _: &SaveContext) -> Option<Self::Saved> {
+        let (x, y, z) = self.position.xyz();
+        let rotation = AXIS_FWD_2.angle(self.forwards());
+        Some((x, y, z, rotation.0))
+    }
+
+    fn load(
+        (x, y, z, rotation): Self::Saved,
+        world: &EcsWorld,
+        entity: Entity,
+        _: &LoadContext,
+    ) -> Result<(), ComponentSaveError> {
+        let pos = WorldPoint::new(x, y, z).ok_or_else(|| {
+            ComponentSaveError::Specific(format!("invalid position ({}, {}, {})", x, y, z))
+        })?;
+
+        let mut transform = TransformComponent::new(pos);
+        transform.rotate_to(rad(rotation));
+        let _ = world.add_now(entity, transform);
+        Ok(())
+    }
+}
+
 #[derive(Deserialize)]
 pub(crate) struct Size {
     pub x: u16,
@@ -2,6 +2,7 @@ use common::Proportion;
 use unit::world::GlobalSliceIndex;
 pub use world_types::{BlockDurability, BlockOpacity, BlockType};
 
+use crate::liquid::LiquidLevel;
 use crate::navigation::{ChunkArea, SlabAreaIndex};
 use crate::occlusion::BlockOcclusion;
 
@@ -18,6 +19,8 @@ pub struct Block {
     area: SlabAreaIndex,
     /// Lighting
     occlusion: BlockOcclusion,
+    /// Flowing liquid in this block, only in non-solid blocks
+    liquid: LiquidLevel,
 }
 
 impl Block {
@@ -27,6 +30,7 @@ impl Block {
             durability: block_type.durability(),
             area: SlabAreaIndex::UNINITIALIZED,
             occlusion: BlockOcclusion::default(),
+            liquid: LiquidLevel::EMPTY,
         }
     }
 
@@ -36,6 +40,7 @@ impl Block {
             durability: Proportion::default_empty(),
             area: SlabAreaIndex::UNINITIALIZED,
             occlusion: BlockOcclusion::default_const(),
+            liquid: LiquidLevel::EMPTY,
         }
     }
 
@@ -88,6 +93,19 @@ impl Block {
         &self.occlusion
     }
 
+    pub fn liquid(&self) -> LiquidLevel {
+        self.liquid
+    }
+
+    pub(crate) fn liquid_mut(&mut self) -> &mut LiquidLevel {
+        &mut self.liquid
+    }
+
+    /// Solid water or any flowing liquid
+    pub fn is_water(&self) -> bool {
+        self.block_type == BlockType::SolidWater || !self.liquid.is_empty()
+    }
+
     pub(crate) fn durability_mut(&mut self) -> &mut Proportion<BlockDurability> {
         &mut self.durability
     }
@@ -13,6 +13,7 @@ use crate::world::LoadNotifier;
 use crate::{SliceRange, World, WorldContext};
 use parking_lot::RwLock;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 pub type ChunkId = u64;
 
@@ -25,8 +26,8 @@ pub struct Chunk<C: WorldContext> {
     /// Sparse associated data with each block
     block_data: HashMap<BlockPosition, C::AssociatedBlockData>,
 
-    /// Navigation lookup
-    areas: HashMap<ChunkArea, BlockGraph>,
+    /// Navigation lookup, shared with the world's navigation graphs once finalized
+    areas: HashMap<ChunkArea, Arc<BlockGraph>>,
 
     slab_progress: RwLock<HashMap<SlabIndex, SlabLoadingStatus>>,
     slab_notify: LoadNotifier,
@@ -122,7 +123,11 @@ impl<C: WorldContext> Chunk<C> {
     }
 
     pub(crate) fn block_graph_for_area(&self, area: WorldArea) -> Option<&BlockGraph> {
-        self.areas.get(&area.into())
+        self.areas.get(&area.into()).map(|graph| &**graph)
+    }
+
+    pub(crate) fn block_graphs(&self) -> impl Iterator<Item = (ChunkArea, &Arc<BlockGraph>)> {
+        self.areas.iter().map(|(area, graph)| (*area, graph))
     }
 
     pub(crate) fn update_block_graphs(
@@ -131,7 +136,7 @@ impl<C: WorldContext> Chunk<C> {
     ) {
         for (area, graph) in slab_nav {
             let (new_edges, new_nodes) = graph.len();
-            self.areas.insert(area, graph);
+            self.areas.insert(area, Arc::new(graph));
             debug!("added {edges} edges and {nodes} nodes", edges = new_edges, nodes = new_nodes; "area" => ?area)
         }
     }
@@ -286,7 +286,7 @@ impl Slab {
                 WorldRange::Single(pos) => {
                     let prev_block = self.slice_mut(pos.z()).set_block(pos, block_type);
                     let world_pos = pos.to_world_position(this_slab);
-                    let event = WorldChangeEvent::new(world_pos, prev_block, block_type);
+                    let event = WorldChangeEvent::replacing(world_pos, prev_block, block_type);
                     changes_out.push(event);
                 }
                 range @ WorldRange::Range(_, _) => {
@@ -301,7 +301,7 @@ impl Slab {
                                 let world_pos = SlabPosition::new_unchecked(x, y, z)
                                     .to_world_position(this_slab);
                                 let event =
-                                    WorldChangeEvent::new(world_pos, prev_block, block_type);
+                                    WorldChangeEvent::replacing(world_pos, prev_block, block_type);
                                 changes_out.push(event);
                             }
                         }
@@ -346,8 +346,12 @@ impl SliceSource<'_> {
 
 #[cfg(test)]
 mod tests {
+    use unit::world::{LocalSliceIndex, SlabLocation, SlabPosition, WorldRange};
+
+    use crate::block::BlockType;
     use crate::chunk::slab::Slab;
-    use crate::DeepClone;
+    use crate::loader::GenericTerrainUpdate;
+    use crate::{DeepClone, LiquidLevel};
 
     #[test]
     fn deep_clone() {
@@ -358,4 +362,30 @@ mod tests {
         assert!(std::ptr::eq(a.raw(), b.raw()));
         assert!(!std::ptr::eq(a.raw(), c.raw()));
     }
+
+    #[test]
+    fn replacing_block_clears_liquid() {
+        let mut slab = Slab::empty();
+        let pos = SlabPosition::new_unchecked(2, 2, LocalSliceIndex::new_unchecked(3));
+        let dry = SlabPosition::new_unchecked(4, 2, LocalSliceIndex::new_unchecked(3));
+        *slab.slice_mut(pos.z())[pos].liquid_mut() = LiquidLevel::new(5);
+
+        let mut changes = Vec::new();
+        let updates = vec![
+            GenericTerrainUpdate(WorldRange::Single(pos), BlockType::Stone),
+            GenericTerrainUpdate(WorldRange::Single(dry), BlockType::Stone),
+        ];
+        slab.apply_terrain_updates(
+            SlabLocation::new(0, (0, 0)),
+            updates.into_iter(),
+            &mut changes,
+        );
+
+        assert!(slab.slice(pos.z())[pos].liquid().is_empty());
+        assert_eq!(
+            changes[0].liquid,
+            Some((LiquidLevel::new(5), LiquidLevel::EMPTY))
+        );
+        assert_eq!(changes[1].liquid, None);
+    }
 }
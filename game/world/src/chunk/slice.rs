@@ -140,17 +140,14 @@ impl<'a> SliceMut<'a> {
         Self::new(slice)
     }
 
-    pub(crate) fn set_block<P, B>(&mut self, pos: P, block: B) -> BlockType
+    /// Returns the replaced block
+    pub(crate) fn set_block<P, B>(&mut self, pos: P, block: B) -> Block
     where
         P: Into<SliceBlock>,
         B: Into<Block>,
     {
         let index = flatten_coords(pos.into());
-        let b = &mut self.slice[index];
-
-        let prev = b.block_type();
-        *b = block.into();
-        prev
+        std::mem::replace(&mut self.slice[index], block.into())
     }
 
     pub fn fill<B>(&mut self, block: B)
@@ -17,7 +17,7 @@ use crate::chunk::slab::DeepClone;
 use crate::chunk::slab::Slab;
 use crate::chunk::slice::{Slice, SliceMut};
 
-use crate::navigation::ChunkArea;
+use crate::navigation::{ChunkArea, PortDoors};
 use crate::neighbour::NeighbourOffset;
 use crate::occlusion::NeighbourOpacity;
 use crate::{EdgeCost, SliceRange};
@@ -499,7 +499,7 @@ impl RawChunkTerrain {
     }
 
     pub(crate) fn cross_chunk_pairs_nav_foreach<
-        F: FnMut(ChunkArea, ChunkArea, EdgeCost, BlockCoord, GlobalSliceIndex),
+        F: FnMut(ChunkArea, ChunkArea, EdgeCost, BlockCoord, GlobalSliceIndex, PortDoors),
     >(
         &'_ self,
         other: &'_ Self,
@@ -563,8 +563,13 @@ impl RawChunkTerrain {
                                 area: other_area,
                             };
 
+                            let doors = PortDoors {
+                                exit: slice[(wx, wy)].block_type().is_door(),
+                                entry: ur_slice[ur_sliceblock].block_type().is_door(),
+                            };
+
                             let coord = if x_coord_changes { wx } else { wy };
-                            f(src, dst, cost, coord, slice_idx.to_global(slab_idx));
+                            f(src, dst, cost, coord, slice_idx.to_global(slab_idx), doors);
 
                             // done with this slice
                             // TODO could skip next slice because it cant be walkable if this one was?
@@ -7,8 +7,12 @@ pub use self::chunk::{
     BaseTerrain, BlockDamageResult, Chunk, ChunkBuilder, ChunkDescriptor, DeepClone,
     OcclusionChunkUpdate,
 };
+pub use self::liquid::{LiquidLevel, LiquidSimulation};
 pub use self::mesh::BaseVertex;
-pub use self::navigation::{EdgeCost, NavigationError, SearchGoal, WorldArea, WorldPath};
+pub use self::navigation::{
+    AsyncPathfinder, DoorOwner, EdgeCost, NavigationError, NavigationProfile, PathSearchContext,
+    SearchGoal, WorldArea, WorldPath,
+};
 pub use self::viewer::{SliceRange, WorldViewer};
 pub use self::world::{
     helpers, ExplorationFilter, ExplorationResult, World, WorldChangeEvent, WorldContext,
@@ -20,6 +24,7 @@ pub use procgen::{BiomeType, RegionLocation};
 
 pub mod block;
 mod chunk;
+mod liquid;
 pub mod loader;
 mod mesh;
 mod navigation;
@@ -0,0 +1,256 @@
+//! Cellular liquid that flows downhill and spreads out, stored as a fill level in each block
+
+use std::collections::{HashMap, HashSet};
+
+use common::*;
+use unit::world::WorldPosition;
+use world_types::BlockType;
+
+use crate::neighbour::WorldNeighbours;
+use crate::{World, WorldChangeEvent, WorldContext};
+
+/// Max number of active cells flowed in a single step, the rest are left for the next one
+const MAX_CELLS_PER_STEP: usize = 4096;
+
+/// How full of liquid a block is, from empty to [LiquidLevel::FULL]
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LiquidLevel(u8);
+
+impl LiquidLevel {
+    pub const EMPTY: Self = Self(0);
+    pub const FULL: Self = Self(8);
+
+    /// Liquid at least this deep can't be walked through
+    const DEEP: u8 = 5;
+
+    /// Clamped to [LiquidLevel::FULL]
+    pub fn new(level: u8) -> Self {
+        Self(level.min(Self::FULL.0))
+    }
+
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn is_full(self) -> bool {
+        self.0 >= Self::FULL.0
+    }
+
+    pub const fn is_deep(self) -> bool {
+        self.0 >= Self::DEEP
+    }
+}
+
+/// Flows liquid between blocks that have changed recently. Solid water blocks are infinite
+/// sources that flow into open space next to them, e.g. after digging into an aquifer
+#[derive(Default)]
+pub struct LiquidSimulation {
+    /// Cells that might flow in the next step
+    active: HashSet<WorldPosition>,
+
+    /// Current level of cells changed in this step, reused between steps
+    levels: HashMap<WorldPosition, LiquidLevel>,
+}
+
+#[derive(Copy, Clone)]
+enum LiquidCell {
+    /// Can't hold liquid, or is not loaded
+    Blocked,
+    /// Infinite liquid
+    Source,
+    Open(LiquidLevel),
+}
+
+impl LiquidSimulation {
+    /// Wakes up liquid around blocks that have been placed, removed or had their liquid changed
+    pub fn on_world_changes(&mut self, events: &[WorldChangeEvent]) {
+        for event in events
+            .iter()
+            .filter(|event| event.prev != event.new || event.liquid.is_some())
+        {
+            self.activate_around(event.pos);
+        }
+    }
+
+    pub fn activate_around(&mut self, pos: WorldPosition) {
+        self.active.extend(
+            WorldNeighbours::new(pos)
+                .chain(once(pos))
+                .chain(once(pos.above()))
+                .chain(once(pos.below())),
+        );
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Flows liquid in active cells once, lowest first so falling liquid lands before it spreads.
+    /// The new level of every changed block is pushed into `changes_out`, to be applied to the
+    /// world through the loader
+    pub fn step<C: WorldContext>(
+        &mut self,
+        world: &World<C>,
+        changes_out: &mut Vec<(WorldPosition, LiquidLevel)>,
+    ) {
+        if self.active.is_empty() {
+            return;
+        }
+
+        let mut cells = self.active.drain().collect_vec();
+        cells.sort_unstable_by_key(|pos| (pos.slice().slice(), pos.0, pos.1));
+        if cells.len() > MAX_CELLS_PER_STEP {
+            self.active.extend(cells.drain(MAX_CELLS_PER_STEP..));
+        }
+
+        let mut levels = std::mem::take(&mut self.levels);
+        levels.clear();
+
+        let cell = |levels: &HashMap<WorldPosition, LiquidLevel>, pos: WorldPosition| match world
+            .block(pos)
+        {
+            None => LiquidCell::Blocked,
+            Some(b) if b.block_type() == BlockType::SolidWater => LiquidCell::Source,
+            Some(b) if b.opacity().solid() => LiquidCell::Blocked,
+            Some(b) => LiquidCell::Open(levels.get(&pos).copied().unwrap_or_else(|| b.liquid())),
+        };
+
+        for pos in cells {
+            let (level, infinite) = match cell(&levels, pos) {
+                LiquidCell::Source => (LiquidLevel::FULL.value(), true),
+                LiquidCell::Open(level) if !level.is_empty() => (level.value(), false),
+                _ => continue,
+            };
+
+            let mut remaining = level;
+
+            // fall into the block below
+            if let LiquidCell::Open(below) = cell(&levels, pos.below()) {
+                let fall = remaining.min(LiquidLevel::FULL.value() - below.value());
+                if fall > 0 {
+                    levels.insert(pos.below(), LiquidLevel::new(below.value() + fall));
+                    if !infinite {
+                        remaining -= fall;
+                    }
+                }
+            }
+
+            // spread out a unit at a time into lower neighbours
+            for n in WorldNeighbours::new(pos) {
+                if remaining <= 1 {
+                    break;
+                }
+
+                if let LiquidCell::Open(n_level) = cell(&levels, n) {
+                    if n_level.value() + 1 < remaining {
+                        levels.insert(n, LiquidLevel::new(n_level.value() + 1));
+                        if !infinite {
+                            remaining -= 1;
+                        }
+                    }
+                }
+            }
+
+            if remaining != level {
+                levels.insert(pos, LiquidLevel::new(remaining));
+            }
+        }
+
+        for (&pos, &level) in levels.iter() {
+            let changed = world.block(pos).map_or(false, |b| b.liquid() != level);
+            if changed {
+                changes_out.push((pos, level));
+                self.activate_around(pos);
+            }
+        }
+
+        self.levels = levels;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Itertools;
+    use unit::world::{WorldPosition, CHUNK_SIZE};
+
+    use crate::block::BlockType;
+    use crate::chunk::ChunkBuilder;
+    use crate::liquid::{LiquidLevel, LiquidSimulation};
+    use crate::world::helpers::world_from_chunks_blocking;
+    use crate::{World, WorldContext};
+
+    fn run_until_settled<C: WorldContext>(world: &mut World<C>, liquids: &mut LiquidSimulation) {
+        let mut changes = Vec::new();
+        for _ in 0..200 {
+            if liquids.is_settled() {
+                return;
+            }
+
+            changes.clear();
+            liquids.step(world, &mut changes);
+            for &(pos, level) in changes.iter() {
+                world.set_liquid_level(pos, level);
+            }
+        }
+
+        panic!("liquid did not settle");
+    }
+
+    #[test]
+    fn flow_from_source() {
+        // solid water with a trench dug out next to it
+        let mut world = world_from_chunks_blocking(vec![ChunkBuilder::new()
+            .fill_slice(1, BlockType::Stone)
+            .fill_slice(2, BlockType::Stone)
+            .set_block((5, 5, 2), BlockType::SolidWater)
+            .fill_range((6, 5, 2), (14, 5, 2), |_| BlockType::Air)
+            .build((0, 0))])
+        .into_inner();
+
+        let mut liquids = LiquidSimulation::default();
+        liquids.activate_around(WorldPosition::from((6, 5, 2)));
+        run_until_settled(&mut world, &mut liquids);
+
+        let level = |x| world.block((x, 5, 2)).unwrap().liquid();
+
+        // gets shallower away from the source, but doesn't spread forever
+        assert_eq!(level(6).value(), LiquidLevel::FULL.value() - 1);
+        assert!(level(6) > level(9));
+        assert!(!level(12).is_empty());
+        assert!(level(13).is_empty());
+        assert!(level(14).is_empty());
+
+        // never inside solid blocks
+        assert!(world.block((6, 6, 2)).unwrap().liquid().is_empty());
+    }
+
+    #[test]
+    fn finite_liquid_is_conserved() {
+        let mut world = world_from_chunks_blocking(vec![ChunkBuilder::new()
+            .fill_slice(1, BlockType::Stone)
+            .build((0, 0))])
+        .into_inner();
+
+        let start = WorldPosition::from((8, 8, 4));
+        world.set_liquid_level(start, LiquidLevel::FULL);
+
+        let mut liquids = LiquidSimulation::default();
+        liquids.activate_around(start);
+        run_until_settled(&mut world, &mut liquids);
+
+        // fell to the ground and spread out
+        assert!(world.block(start).unwrap().liquid().is_empty());
+        assert!(!world.block((8, 8, 2)).unwrap().liquid().is_deep());
+
+        let size = CHUNK_SIZE.as_i32();
+        let total: u32 = (0..size)
+            .cartesian_product(0..size)
+            .map(|(x, y)| world.block((x, y, 2)).unwrap().liquid().value() as u32)
+            .sum();
+        assert_eq!(total, LiquidLevel::FULL.value() as u32);
+    }
+}
@@ -172,7 +172,7 @@ impl<C: WorldContext> SlabFinalizer<C> {
                 neighbour_terrain,
                 direction,
                 slab_range,
-                |src_area, dst_area, edge_cost, i, z| {
+                |src_area, dst_area, edge_cost, i, z, doors| {
                     trace!("adding cross-chunk link to neighbour {neighbour:?}",
                         neighbour = neighbour; "to_area" => ?dst_area,
                         "from_area" => ?src_area, "direction" => ?direction, "xy" => i, "z" => ?z
@@ -181,22 +181,22 @@ impl<C: WorldContext> SlabFinalizer<C> {
                     let src_area = src_area.into_world_area(chunk);
                     let dst_area = dst_area.into_world_area(neighbour);
 
-                    links.push((src_area, dst_area, edge_cost, i, z));
+                    links.push((src_area, dst_area, edge_cost, i, z, doors));
                 },
             );
 
-            links.sort_unstable_by_key(|(_, _, _, i, _)| *i);
+            links.sort_unstable_by_key(|(_, _, _, i, _, _)| *i);
 
             for ((src_area, dst_area), group) in links
                 .iter()
-                .group_by(|(src, dst, _, _, _)| (src, dst))
+                .group_by(|(src, dst, _, _, _, _)| (src, dst))
                 .into_iter()
             {
                 let direction = NeighbourOffset::between_aligned(src_area.chunk, dst_area.chunk);
 
                 AreaNavEdge::discover_ports_between(
                     direction,
-                    group.map(|(_, _, cost, idx, z)| (*cost, *idx, *z)),
+                    group.map(|(_, _, cost, idx, z, doors)| (*cost, *idx, *z, *doors)),
                     &mut ports,
                 );
                 for edge in ports.drain(..) {
@@ -205,6 +205,41 @@ impl<C: WorldContext> SlabFinalizer<C> {
             }
         }
 
+        // link climbable columns across slab boundaries within this chunk, including the
+        // boundaries with the unchanged slabs directly above and below the range
+        {
+            let world = self.world.borrow();
+            let this_terrain = world.find_chunk_with_pos(chunk).unwrap(); // should be present
+
+            for (lower_slice_idx, lower, upper) in this_terrain.raw_terrain().slab_boundary_slices()
+            {
+                let lower_slab = lower_slice_idx.slab_index();
+                if lower_slab < slab_range.0 - 1 || lower_slab > slab_range.1 {
+                    continue;
+                }
+
+                let upper_slice_idx = lower_slice_idx + 1;
+                for (i, block) in lower.into_iter().enumerate() {
+                    if !block.block_type().is_climbable() {
+                        continue;
+                    }
+
+                    let lower_area = block.chunk_area(lower_slice_idx);
+                    let upper_area = (*upper)[i].chunk_area(upper_slice_idx);
+                    if let Some((src, dst)) = lower_area.zip(upper_area) {
+                        let exit = unflatten_index(i).to_block_position(lower_slice_idx);
+                        trace!("adding climb link between slabs"; "from_area" => ?src, "to_area" => ?dst, "exit" => ?exit);
+
+                        area_edges.push((
+                            src.into_world_area(chunk),
+                            dst.into_world_area(chunk),
+                            AreaNavEdge::climb(exit),
+                        ));
+                    }
+                }
+            }
+        }
+
         area_edges
     }
     fn finalize_occlusion(&mut self, chunk: ChunkLocation, slab_range: (SlabIndex, SlabIndex)) {
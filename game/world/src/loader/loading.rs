@@ -9,7 +9,7 @@ use crate::chunk::slab::{Slab, SlabInternalNavigability, SlabType};
 use crate::loader::batch::UpdateBatchUniqueId;
 use crate::loader::worker_pool::LoadTerrainResult;
 use crate::world::{ContiguousChunkIterator, WorldChangeEvent};
-use crate::{OcclusionChunkUpdate, WorldContext, WorldRef};
+use crate::{AsyncPathfinder, LiquidLevel, OcclusionChunkUpdate, WorldContext, WorldRef};
 
 use crate::loader::{
     terrain_source, AsyncWorkerPool, TerrainSource, TerrainSourceError, UpdateBatch,
@@ -75,6 +75,15 @@ impl<C: WorldContext> WorldLoader<C> {
         self.world.clone()
     }
 
+    /// Finds paths in this world on the loader's worker pool
+    pub fn pathfinder<R, T>(&self) -> AsyncPathfinder<C, R, T>
+    where
+        R: Copy + Eq + Hash + Send + 'static,
+        T: Copy + Eq + Send + 'static,
+    {
+        AsyncPathfinder::new(self.world(), &self.pool)
+    }
+
     /// Requests slabs as a single batch. Must be sorted as per [self.request_slabs_with_count]
     pub fn request_slabs(&mut self, slabs: impl ExactSizeIterator<Item = SlabLocation> + Clone) {
         let count = slabs.len();
@@ -370,7 +379,80 @@ impl<C: WorldContext> WorldLoader<C> {
         );
         debug_assert_eq!(upper_slab_limit, slab_locs.capacity());
 
-        // submit slabs for finalization
+        self.submit_slabs_for_finalization(&world_ref, slab_locs);
+    }
+
+    /// Applies new liquid levels calculated by [LiquidSimulation](crate::LiquidSimulation).
+    /// Updates for slabs that are currently loading are left in `liquid_updates` to be retried
+    /// later, and those for unloaded chunks are dropped. Slabs are only refinalized when liquid
+    /// appears, disappears or becomes too deep to walk through, to update navigation and meshes
+    pub fn apply_liquid_updates(
+        &mut self,
+        liquid_updates: &mut Vec<(WorldPosition, LiquidLevel)>,
+        changes_out: &mut Vec<WorldChangeEvent>,
+    ) {
+        if liquid_updates.is_empty() {
+            return;
+        }
+
+        let world_ref = self.world.clone();
+        let mut slab_locs = Vec::new();
+        {
+            let mut world = world_ref.borrow_mut();
+            let mut deferred = 0;
+            liquid_updates.retain(|&(pos, level)| {
+                let slab = SlabLocation::new(pos.slice().slab_index(), ChunkLocation::from(pos));
+                match world.find_chunk_with_pos(slab.chunk) {
+                    None => return false,
+                    Some(chunk) if !chunk.is_slab_loaded(slab.slab) => {
+                        deferred += 1;
+                        return true;
+                    }
+                    Some(_) => {}
+                }
+
+                if let Some((block_type, prev)) = world.set_liquid_level(pos, level) {
+                    changes_out.push(WorldChangeEvent::with_liquid(pos, block_type, prev, level));
+
+                    if prev.is_empty() != level.is_empty() || prev.is_deep() != level.is_deep() {
+                        slab_locs.push(slab);
+                    }
+                }
+
+                false
+            });
+
+            if deferred > 0 {
+                trace!(
+                    "deferring {count} liquid updates because their slabs are currently loading",
+                    count = deferred
+                );
+            }
+        }
+
+        if slab_locs.is_empty() {
+            return;
+        }
+
+        slab_locs.sort_unstable();
+        slab_locs.dedup();
+        debug!(
+            "applied liquid updates, refinalizing {count} slabs",
+            count = slab_locs.len()
+        );
+        self.submit_slabs_for_finalization(&world_ref, slab_locs);
+    }
+
+    fn submit_slabs_for_finalization(
+        &mut self,
+        world_ref: &WorldRef<C>,
+        slab_locs: Vec<SlabLocation>,
+    ) {
+        if slab_locs.is_empty() {
+            return;
+        }
+
+        let real_slab_count = slab_locs.len();
         let mut batches = UpdateBatch::builder(&mut self.batch_ids, real_slab_count);
 
         for slab_loc in slab_locs.into_iter() {
@@ -532,7 +614,7 @@ mod tests {
     use crate::loader::terrain_source::MemoryTerrainSource;
     use crate::loader::{AsyncWorkerPool, WorldTerrainUpdate};
     use crate::world::helpers::DummyWorldContext;
-    use crate::BaseTerrain;
+    use crate::{BaseTerrain, LiquidLevel};
     use common::{Itertools, Rng, SeedableRng, SliceRandom, SmallRng};
     use std::collections::{HashMap, HashSet};
     use unit::world::SlabLocation;
@@ -560,6 +642,40 @@ mod tests {
         assert_eq!(loader.world.borrow().all_chunks().count(), 1);
     }
 
+    #[test]
+    fn liquid_updates_wait_for_loading_slabs() {
+        let chunk = ChunkBuilder::new()
+            .set_block((2, 2, 1), BlockType::Stone)
+            .set_block((2, 2, 39), BlockType::Stone)
+            .into_inner();
+        let source = MemoryTerrainSource::from_chunks(vec![((0, 0), chunk)].into_iter()).unwrap();
+
+        let mut loader =
+            WorldLoader::<DummyWorldContext>::new(source, AsyncWorkerPool::new_blocking().unwrap());
+        loader.request_slabs(vec![SlabLocation::new(0, (0, 0))].into_iter());
+        loader.block_for_last_batch(test_world_timeout()).unwrap();
+
+        // slab 1 isn't loaded yet, and the second chunk doesn't exist at all
+        let pos = WorldPosition::from((2, 2, 40));
+        let level = LiquidLevel::new(4);
+        let mut updates = vec![
+            (pos, level),
+            (WorldPosition::from((CHUNK_SIZE.as_i32() * 5, 2, 2)), level),
+        ];
+        let mut changes = Vec::new();
+        loader.apply_liquid_updates(&mut updates, &mut changes);
+        assert_eq!(updates, vec![(pos, level)]);
+        assert!(changes.is_empty());
+
+        loader.request_slabs(vec![SlabLocation::new(1, (0, 0))].into_iter());
+        loader.block_for_last_batch(test_world_timeout()).unwrap();
+
+        loader.apply_liquid_updates(&mut updates, &mut changes);
+        assert!(updates.is_empty());
+        assert_eq!(changes.len(), 1);
+        assert_eq!(loader.world.borrow().block(pos).unwrap().liquid(), level);
+    }
+
     #[test]
     #[ignore]
     /// Ensure block updates are applied as expected when stressed. Came out of debugging a race
@@ -1,9 +1,11 @@
 use color::Color;
 use common::*;
 
+use crate::block::{Block, BlockType};
 use crate::chunk::slab::Slab;
 use crate::chunk::slice::unflatten_index;
 use crate::chunk::Chunk;
+use crate::liquid::LiquidLevel;
 use crate::occlusion::{BlockOcclusion, OcclusionFlip};
 use crate::viewer::SliceRange;
 use crate::{BaseTerrain, WorldContext};
@@ -11,7 +13,6 @@ use grid::GridImpl;
 use std::mem::MaybeUninit;
 use unit::world::CHUNK_SIZE;
 use unit::world::{GlobalSliceIndex, SliceBlock, SLAB_SIZE};
-use world_types::BlockType;
 
 // for ease of declaration. /2 for radius as this is based around the center of the block
 const X: f32 = unit::world::BLOCKS_SCALE / 2.0;
@@ -40,7 +41,9 @@ pub fn make_simple_render_mesh<V: BaseVertex, C: WorldContext>(
         let slice_above = chunk.slice_or_dummy(slice_index + 1);
         let slice_index = shifted_slice_index(slice_index);
 
-        for (i, block_pos, block) in slice.non_air_blocks() {
+        for (i, block_pos, block) in
+            slice.filter_blocks(|b| b.block_type() != BlockType::Air || !b.liquid().is_empty())
+        {
             // if above is solid, render a "blocked" colour
             let tile = if slice_above
                 .index_unchecked(i)
@@ -54,7 +57,7 @@ pub fn make_simple_render_mesh<V: BaseVertex, C: WorldContext>(
                 // render as normal
                 make_corners_with_ao(
                     block_pos,
-                    block_color(block.block_type()),
+                    block_color(block),
                     block.occlusion(),
                     slice_index,
                 )
@@ -91,20 +94,16 @@ pub fn make_simple_render_mesh<V: BaseVertex, C: WorldContext>(
     vertices
 }
 
-fn block_color(ty: BlockType) -> Color {
-    match ty {
-        BlockType::Air => Color::rgb(0, 0, 0),
-        BlockType::Dirt => Color::rgb(86, 38, 23),
-        BlockType::Grass => Color::rgb(49, 152, 56),
-        BlockType::LightGrass => Color::rgb(91, 152, 51),
-        BlockType::Leaves => Color::rgb(49, 132, 2),
-        BlockType::TreeTrunk => Color::rgb(79, 52, 16),
-        BlockType::Stone => Color::rgb(106, 106, 117),
-        BlockType::Sand => 0xBCA748FF.into(),
-        BlockType::SolidWater => 0x3374BCFF.into(),
-        BlockType::StoneBrickWall => 0x4A4A4AFF.into(),
-        BlockType::Chest => Color::rgb(184, 125, 31),
+/// Flowing liquid is rendered as water, lighter when shallow
+fn block_color(block: &Block) -> Color {
+    let block_type = block.block_type();
+    let liquid = block.liquid();
+    if block_type.opacity().solid() || liquid.is_empty() {
+        return block_type.color();
     }
+
+    let depth = liquid.value() as f32 / LiquidLevel::FULL.value() as f32;
+    BlockType::SolidWater.color() * (1.5 - depth * 0.5)
 }
 
 fn block_centre(block: SliceBlock) -> (f32, f32) {
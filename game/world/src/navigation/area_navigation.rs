@@ -3,16 +3,18 @@ use std::iter::once;
 
 use petgraph::graph::EdgeIndex;
 use petgraph::stable_graph::StableGraph;
-use petgraph::visit::Visitable;
+use petgraph::visit::{EdgeRef, Visitable};
 use petgraph::Directed;
 
 use common::*;
 use unit::world::CHUNK_SIZE;
-use unit::world::{BlockCoord, BlockPosition, ChunkLocation, GlobalSliceIndex, SliceBlock};
+use unit::world::{
+    BlockCoord, BlockPosition, ChunkLocation, GlobalSliceIndex, SliceBlock, WorldPosition,
+};
 
 use crate::navigation::path::AreaPathNode;
 use crate::navigation::search::{astar, SearchContext};
-use crate::navigation::{AreaPath, WorldArea};
+use crate::navigation::{AreaPath, DoorOwner, NavigationProfile, WorldArea};
 use crate::neighbour::NeighbourOffset;
 use crate::EdgeCost;
 
@@ -28,19 +30,40 @@ pub struct AreaNavNode(pub WorldArea);
 #[derive(Copy, Clone)]
 #[cfg_attr(test, derive(Eq, PartialEq))]
 pub struct AreaNavEdge {
-    pub direction: NeighbourOffset,
+    /// None for a vertical climb between slabs in the same chunk
+    pub direction: Option<NeighbourOffset>,
     pub cost: EdgeCost,
 
     /// Block in the exiting chunk
     pub exit: BlockPosition,
     pub width: BlockCoord,
+
+    /// Door blocks either side of the port, only passable by agents that can open them
+    pub doors: PortDoors,
+}
+
+/// Which sides of a port are door blocks. Ports with any doors are always 1 block wide, as
+/// each door can have its own owner
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq)]
+pub struct PortDoors {
+    /// The exit block in the exiting chunk
+    pub exit: bool,
+    /// The block entered in the next chunk
+    pub entry: bool,
 }
 
-#[cfg_attr(test, derive(Clone))]
+/// Cloned when modified while shared with path finding snapshots
+#[derive(Clone)]
 pub struct AreaGraph {
     graph: AreaNavGraph,
     // TODO use graphmap to just use areas as nodes? but we need parallel edges
     node_lookup: HashMap<WorldArea, NodeIndex>,
+
+    /// Mean movement cost of the ground in each area, missing means 1
+    movement_costs: HashMap<WorldArea, f32>,
+
+    /// Lowest movement cost of any area so far, to keep the search heuristic from overestimating
+    min_movement_cost: f32,
 }
 
 impl Default for AreaGraph {
@@ -48,6 +71,8 @@ impl Default for AreaGraph {
         Self {
             graph: AreaNavGraph::with_capacity(256, 256),
             node_lookup: HashMap::with_capacity(256),
+            movement_costs: HashMap::new(),
+            min_movement_cost: 1.0,
         }
     }
 }
@@ -65,60 +90,81 @@ impl AreaNavEdge {
     /// Should be sorted so BlockCoords are ascending
     pub fn discover_ports_between(
         direction: NeighbourOffset,
-        connecting_blocks: impl Iterator<Item = (EdgeCost, BlockCoord, GlobalSliceIndex)>,
+        connecting_blocks: impl Iterator<Item = (EdgeCost, BlockCoord, GlobalSliceIndex, PortDoors)>,
         out: &mut Vec<Self>,
     ) {
         let mut group_id = 0;
         connecting_blocks
-            .map(|(edge, coord, slice)| (edge, coord, Some(slice)))
-            .chain(once((EdgeCost::Walk, 255, None))) // dummy last
+            .map(|(edge, coord, slice, doors)| (edge, coord, Some(slice), doors))
+            .chain(once((EdgeCost::Walk, 255, None, PortDoors::default()))) // dummy last
             .tuple_windows()
-            .map(|((a_cost, a_coord, a_z), (b_cost, b_coord, b_z))| {
-                let a_z = a_z.unwrap(); // always Some
-
-                let diff = b_coord - a_coord;
-                let this_group_id = if diff == 1 && a_cost == b_cost && Some(a_z) == b_z {
-                    // group
-                    group_id
-                } else {
-                    // next doesn't belong in this group
-                    group_id += 1;
-                    group_id - 1
-                };
-
-                (a_cost, a_coord, a_z, this_group_id)
-            })
-            .group_by(|(_, _, _, group)| *group)
+            .map(
+                |((a_cost, a_coord, a_z, a_doors), (b_cost, b_coord, b_z, b_doors))| {
+                    let a_z = a_z.unwrap(); // always Some
+
+                    let diff = b_coord - a_coord;
+                    let this_group_id = if diff == 1
+                        && a_cost == b_cost
+                        && Some(a_z) == b_z
+                        && !a_doors.any()
+                        && !b_doors.any()
+                    {
+                        // group
+                        group_id
+                    } else {
+                        // next doesn't belong in this group
+                        group_id += 1;
+                        group_id - 1
+                    };
+
+                    (a_cost, a_coord, a_z, a_doors, this_group_id)
+                },
+            )
+            .group_by(|(_, _, _, _, group)| *group)
             .into_iter()
             .for_each(|(_, mut ports)| {
-                let (cost, start, z, _) = ports.next().unwrap(); // definitely 1
-                let end = ports.last().map(|(_, end, _, _)| end).unwrap_or(start);
+                let (cost, start, z, doors, _) = ports.next().unwrap(); // definitely 1
+                let end = ports.last().map(|(_, end, _, _, _)| end).unwrap_or(start);
                 let width = (end - start) + 1;
 
                 let (x, y) = direction.position_on_boundary(start);
 
                 out.push(Self {
-                    direction,
+                    direction: Some(direction),
                     cost,
                     exit: (x, y, z).into(),
                     width,
+                    doors,
                 });
             });
     }
 
+    /// Climb up from the top of a climbable column in one slab into the slab above it
+    pub fn climb(exit: BlockPosition) -> Self {
+        Self {
+            direction: None,
+            cost: EdgeCost::ClimbUp,
+            exit,
+            width: 1,
+            doors: PortDoors::default(),
+        }
+    }
+
     pub fn reversed(self) -> Self {
         let cost = self.cost.opposite();
-        let direction = self.direction.opposite();
+        let direction = self.direction.map(NeighbourOffset::opposite);
 
         let exit = {
             let (mut x, mut y, mut z) = self.exit.xyz();
 
             // move to other side of the chunk
             match direction {
-                NeighbourOffset::North | NeighbourOffset::South => {
+                Some(NeighbourOffset::North) | Some(NeighbourOffset::South) => {
                     y = CHUNK_SIZE.as_block_coord() - 1 - y
                 }
-                _ => x = CHUNK_SIZE.as_block_coord() - 1 - x,
+                Some(_) => x = CHUNK_SIZE.as_block_coord() - 1 - x,
+                // climbs stay in the same column
+                None => {}
             };
 
             // a reversed jump up/down requires the exit point moving down or up
@@ -131,14 +177,46 @@ impl AreaNavEdge {
             direction,
             cost,
             exit,
+            doors: self.doors.reversed(),
             ..self
         }
     }
 
+    /// The block entered in the next area when leaving through the given exit block
+    pub fn entry(&self, exit: BlockPosition) -> BlockPosition {
+        let extended = match self.direction {
+            Some(direction) => direction.extend_across_boundary_aligned(exit),
+            // climbing into the slab above or below in the same column
+            None => exit,
+        };
+        extended.above_by(self.cost.z_offset())
+    }
+
+    /// Can be passed through by the given agent, considering its size and any doors along it
+    fn is_passable(
+        &self,
+        from: WorldArea,
+        to: WorldArea,
+        profile: &NavigationProfile,
+        door_owner: impl Fn(WorldPosition) -> Option<DoorOwner>,
+    ) -> bool {
+        // TODO could prefer wider ports
+        if self.width < profile.size {
+            return false;
+        }
+
+        // doors are always in 1 wide ports
+        let can_open = |door: BlockPosition, chunk: ChunkLocation| {
+            profile.can_open_door(door_owner(door.to_world_position(chunk)))
+        };
+        (!self.doors.exit || can_open(self.exit, from.chunk))
+            && (!self.doors.entry || can_open(self.entry(self.exit), to.chunk))
+    }
+
     fn iter_exit_blocks(&self) -> impl Iterator<Item = SliceBlock> + '_ {
         let start = SliceBlock::from(self.exit);
         (0..self.width as i16).map(move |i| {
-            let offset = if self.direction.is_vertical() {
+            let offset = if self.direction.map_or(false, NeighbourOffset::is_vertical) {
                 (i, 0)
             } else {
                 (0, i)
@@ -175,15 +253,31 @@ impl AreaNavEdge {
     }
 }
 
+impl PortDoors {
+    pub fn any(self) -> bool {
+        self.exit || self.entry
+    }
+
+    fn reversed(self) -> Self {
+        Self {
+            exit: self.entry,
+            entry: self.exit,
+        }
+    }
+}
+
 impl AreaGraph {
     pub fn search_context() -> AreaGraphSearchContext {
         AreaGraphSearchContext::new::<AreaNavGraph>()
     }
 
+    /// Ports narrower than the agent or through doors it can't open are not considered
     pub(crate) fn find_area_path(
         &self,
         start: WorldArea,
         goal: WorldArea,
+        profile: &NavigationProfile,
+        door_owner: impl Fn(WorldPosition) -> Option<DoorOwner>,
         context: &AreaGraphSearchContext,
     ) -> Result<AreaPath, AreaPathError> {
         let src_node = self.get_node(start)?;
@@ -197,15 +291,26 @@ impl AreaGraph {
             &self.graph,
             src_node,
             |n| n == dst_node,
-            |edge| edge.weight().cost.weight(), // TODO could prefer wider ports
+            |edge| {
+                let from = self.graph[edge.source()].0;
+                let to = self.graph[edge.target()].0;
+                let edge = edge.weight();
+                if edge.is_passable(from, to, profile, &door_owner) {
+                    // assume about a chunk's width of the next area is crossed
+                    let crossing = CHUNK_SIZE.as_i32() as f32 * self.movement_cost(to);
+                    Some(edge.cost.weight() * crossing)
+                } else {
+                    None
+                }
+            },
             |n| {
-                // manhattan distance * chunk size, underestimates
+                // manhattan distance * chunk size * cheapest movement cost, underestimates
                 let ChunkLocation(nx, ny) = &self.graph[n].0.chunk;
                 let ChunkLocation(gx, gy) = goal.chunk;
 
                 let dx = (nx - gx).abs() * CHUNK_SIZE.as_i32();
                 let dy = (ny - gy).abs() * CHUNK_SIZE.as_i32();
-                (dx + dy) as f32
+                (dx + dy) as f32 * self.min_movement_cost
             },
             context,
         );
@@ -251,10 +356,13 @@ impl AreaGraph {
         &self,
         start: WorldArea,
         goal: WorldArea,
+        profile: &NavigationProfile,
+        door_owner: impl Fn(WorldPosition) -> Option<DoorOwner>,
         context: &AreaGraphSearchContext,
     ) -> bool {
         // TODO avoid calculating path just to throw it away
-        self.find_area_path(start, goal, context).is_ok()
+        self.find_area_path(start, goal, profile, door_owner, context)
+            .is_ok()
     }
 
     pub(crate) fn add_edge(&mut self, from: WorldArea, to: WorldArea, edge: AreaNavEdge) {
@@ -265,6 +373,19 @@ impl AreaGraph {
         self.graph.add_edge(b, a, edge.reversed());
     }
 
+    pub(crate) fn set_movement_cost(&mut self, area: WorldArea, cost: f32) {
+        if (cost - 1.0).abs() > f32::EPSILON {
+            self.movement_costs.insert(area, cost);
+            self.min_movement_cost = self.min_movement_cost.min(cost);
+        } else {
+            self.movement_costs.remove(&area);
+        }
+    }
+
+    fn movement_cost(&self, area: WorldArea) -> f32 {
+        self.movement_costs.get(&area).copied().unwrap_or(1.0)
+    }
+
     pub(crate) fn add_node(&mut self, area: WorldArea) -> NodeIndex {
         match self.node_lookup.get(&area) {
             Some(n) => *n,
@@ -298,6 +419,7 @@ impl AreaGraph {
         debug_assert_eq!(prev_n.0, prev_n.1);
 
         self.node_lookup.retain(|n, _| f(n));
+        self.movement_costs.retain(|n, _| f(n));
         self.graph.retain_nodes(|graph, idx| {
             let node = graph.node_weight(idx).unwrap();
             f(&node.0)
@@ -345,8 +467,8 @@ impl Debug for AreaNavEdge {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         write!(
             f,
-            "AreaNavEdge(direction={:?}, {:?}, exit={}, width={})",
-            self.direction, self.cost, self.exit, self.width
+            "AreaNavEdge(direction={:?}, {:?}, exit={}, width={}, doors={:?})",
+            self.direction, self.cost, self.exit, self.width, self.doors
         )
     }
 }
@@ -361,7 +483,10 @@ mod tests {
     use crate::block::BlockType;
     use crate::chunk::ChunkBuilder;
     use crate::navigation::path::AreaPathNode;
-    use crate::navigation::{AreaGraph, AreaNavEdge, AreaPathError, SlabAreaIndex, WorldArea};
+    use crate::navigation::{
+        AreaGraph, AreaNavEdge, AreaPathError, NavigationProfile, PathSearchContext, PortDoors,
+        SlabAreaIndex, WorldArea,
+    };
     use crate::neighbour::NeighbourOffset;
     use crate::world::helpers::world_from_chunks_blocking;
     use crate::{ChunkDescriptor, EdgeCost};
@@ -537,23 +662,35 @@ mod tests {
 
     #[test]
     fn pure_port_discovery() {
+        const NO_DOORS: PortDoors = PortDoors {
+            exit: false,
+            entry: false,
+        };
+        const EXIT_DOOR: PortDoors = PortDoors {
+            exit: true,
+            entry: false,
+        };
+
         // pure = isolated test
         let link_blocks = vec![
             // one group
-            (EdgeCost::Walk, 0, GlobalSliceIndex::new(0)),
-            (EdgeCost::Walk, 1, GlobalSliceIndex::new(0)),
-            (EdgeCost::Walk, 2, GlobalSliceIndex::new(0)),
+            (EdgeCost::Walk, 0, GlobalSliceIndex::new(0), NO_DOORS),
+            (EdgeCost::Walk, 1, GlobalSliceIndex::new(0), NO_DOORS),
+            (EdgeCost::Walk, 2, GlobalSliceIndex::new(0), NO_DOORS),
             // another group
-            (EdgeCost::Walk, 4, GlobalSliceIndex::new(0)),
-            (EdgeCost::Walk, 5, GlobalSliceIndex::new(0)),
-            (EdgeCost::Walk, 6, GlobalSliceIndex::new(0)),
+            (EdgeCost::Walk, 4, GlobalSliceIndex::new(0), NO_DOORS),
+            (EdgeCost::Walk, 5, GlobalSliceIndex::new(0), NO_DOORS),
+            (EdgeCost::Walk, 6, GlobalSliceIndex::new(0), NO_DOORS),
             // another group
-            (EdgeCost::JumpUp, 7, GlobalSliceIndex::new(0)),
-            (EdgeCost::JumpUp, 8, GlobalSliceIndex::new(0)),
+            (EdgeCost::JumpUp, 7, GlobalSliceIndex::new(0), NO_DOORS),
+            (EdgeCost::JumpUp, 8, GlobalSliceIndex::new(0), NO_DOORS),
             // all alone groups
-            (EdgeCost::JumpUp, 10, GlobalSliceIndex::new(0)),
-            (EdgeCost::JumpUp, 11, GlobalSliceIndex::new(5)), // different z
-            (EdgeCost::JumpDown, 12, GlobalSliceIndex::new(5)), // different cost
+            (EdgeCost::JumpUp, 10, GlobalSliceIndex::new(0), NO_DOORS),
+            (EdgeCost::JumpUp, 11, GlobalSliceIndex::new(5), NO_DOORS), // different z
+            (EdgeCost::JumpDown, 12, GlobalSliceIndex::new(5), NO_DOORS), // different cost
+            // doors are always alone
+            (EdgeCost::Walk, 14, GlobalSliceIndex::new(0), NO_DOORS),
+            (EdgeCost::Walk, 15, GlobalSliceIndex::new(0), EXIT_DOOR),
         ];
 
         let direction = NeighbourOffset::West;
@@ -564,38 +701,58 @@ mod tests {
             AreaNavEdge {
                 cost: EdgeCost::Walk,
                 width: 3,
+                doors: NO_DOORS,
                 exit: BlockPosition::new_unchecked(0, 0, GlobalSliceIndex::new(0)),
-                direction,
+                direction: Some(direction),
             },
             AreaNavEdge {
                 cost: EdgeCost::Walk,
                 width: 3,
+                doors: NO_DOORS,
                 exit: BlockPosition::new_unchecked(0, 4, GlobalSliceIndex::new(0)),
-                direction,
+                direction: Some(direction),
             },
             AreaNavEdge {
                 cost: EdgeCost::JumpUp,
                 width: 2,
+                doors: NO_DOORS,
                 exit: BlockPosition::new_unchecked(0, 7, GlobalSliceIndex::new(0)),
-                direction,
+                direction: Some(direction),
             },
             AreaNavEdge {
                 cost: EdgeCost::JumpUp,
                 width: 1,
+                doors: NO_DOORS,
                 exit: BlockPosition::new_unchecked(0, 10, GlobalSliceIndex::new(0)),
-                direction,
+                direction: Some(direction),
             },
             AreaNavEdge {
                 cost: EdgeCost::JumpUp,
                 width: 1,
+                doors: NO_DOORS,
                 exit: BlockPosition::new_unchecked(0, 11, GlobalSliceIndex::new(5)),
-                direction,
+                direction: Some(direction),
             },
             AreaNavEdge {
                 cost: EdgeCost::JumpDown,
                 width: 1,
+                doors: NO_DOORS,
                 exit: BlockPosition::new_unchecked(0, 12, GlobalSliceIndex::new(5)),
-                direction,
+                direction: Some(direction),
+            },
+            AreaNavEdge {
+                cost: EdgeCost::Walk,
+                width: 1,
+                doors: NO_DOORS,
+                exit: BlockPosition::new_unchecked(0, 14, GlobalSliceIndex::new(0)),
+                direction: Some(direction),
+            },
+            AreaNavEdge {
+                cost: EdgeCost::Walk,
+                width: 1,
+                doors: EXIT_DOOR,
+                exit: BlockPosition::new_unchecked(0, 15, GlobalSliceIndex::new(0)),
+                direction: Some(direction),
             },
         ];
 
@@ -628,16 +785,18 @@ mod tests {
 
         let mut expected = vec![
             AreaNavEdge {
-                direction: NeighbourOffset::East,
+                direction: Some(NeighbourOffset::East),
                 cost: EdgeCost::Walk,
                 exit: (15, 5, 4).try_into().unwrap(),
                 width: 3,
+                doors: PortDoors::default(),
             },
             AreaNavEdge {
-                direction: NeighbourOffset::East,
+                direction: Some(NeighbourOffset::East),
                 cost: EdgeCost::JumpUp,
                 exit: (15, 10, 4).try_into().unwrap(),
                 width: 1,
+                doors: PortDoors::default(),
             },
         ];
 
@@ -663,6 +822,8 @@ mod tests {
                 .find_area_path(
                     WorldArea::new_with_slab((-1, 1), SLAB),
                     WorldArea::new_with_slab((0, 1), SLAB),
+                    &NavigationProfile::default(),
+                    |_| None,
                     &AreaGraph::search_context(),
                 )
                 .expect("path should succeed");
@@ -673,30 +834,33 @@ mod tests {
                 AreaPathNode::new(
                     WorldArea::new_with_slab((-1, 0), SLAB),
                     AreaNavEdge {
-                        direction: NeighbourOffset::South,
+                        direction: Some(NeighbourOffset::South),
                         cost: EdgeCost::JumpUp,
                         exit: (3, 0, 301).try_into().unwrap(),
                         width: 1,
+                        doors: PortDoors::default(),
                     },
                 ),
                 // east
                 AreaPathNode::new(
                     WorldArea::new_with_slab((0, 0), SLAB),
                     AreaNavEdge {
-                        direction: NeighbourOffset::East,
+                        direction: Some(NeighbourOffset::East),
                         cost: EdgeCost::JumpDown,
                         exit: (CHUNK_SIZE.as_i32() - 1, 3, 302).try_into().unwrap(),
                         width: 1,
+                        doors: PortDoors::default(),
                     },
                 ),
                 // north
                 AreaPathNode::new(
                     WorldArea::new_with_slab((0, 1), SLAB),
                     AreaNavEdge {
-                        direction: NeighbourOffset::North,
+                        direction: Some(NeighbourOffset::North),
                         cost: EdgeCost::JumpUp,
                         exit: (3, CHUNK_SIZE.as_i32() - 1, 301).try_into().unwrap(),
                         width: 1,
+                        doors: PortDoors::default(),
                     },
                 ),
             ];
@@ -711,6 +875,8 @@ mod tests {
                 .find_area_path(
                     WorldArea::new_with_slab((0, 1), SLAB),
                     WorldArea::new_with_slab((-1, 1), SLAB),
+                    &NavigationProfile::default(),
+                    |_| None,
                     &AreaGraph::search_context(),
                 )
                 .expect("path should succeed");
@@ -721,30 +887,33 @@ mod tests {
                 AreaPathNode::new(
                     WorldArea::new_with_slab((0, 0), SLAB),
                     AreaNavEdge {
-                        direction: NeighbourOffset::South,
+                        direction: Some(NeighbourOffset::South),
                         cost: EdgeCost::JumpDown,
                         exit: (3, 0, 302).try_into().unwrap(),
                         width: 1,
+                        doors: PortDoors::default(),
                     },
                 ),
                 // west
                 AreaPathNode::new(
                     WorldArea::new_with_slab((-1, 0), SLAB),
                     AreaNavEdge {
-                        direction: NeighbourOffset::West,
+                        direction: Some(NeighbourOffset::West),
                         cost: EdgeCost::JumpUp,
                         exit: (0, 3, 301).try_into().unwrap(),
                         width: 1,
+                        doors: PortDoors::default(),
                     },
                 ),
                 // north
                 AreaPathNode::new(
                     WorldArea::new_with_slab((-1, 1), SLAB),
                     AreaNavEdge {
-                        direction: NeighbourOffset::North,
+                        direction: Some(NeighbourOffset::North),
                         cost: EdgeCost::JumpDown,
                         exit: (3, CHUNK_SIZE.as_i32() - 1, 302).try_into().unwrap(),
                         width: 1,
+                        doors: PortDoors::default(),
                     },
                 ),
             ];
@@ -776,6 +945,8 @@ mod tests {
             .find_area_path(
                 WorldArea::new((-1, 0)),
                 WorldArea::new((1, 0)),
+                &NavigationProfile::default(),
+                |_| None,
                 &AreaGraph::search_context(),
             )
             .expect("path should succeed");
@@ -785,19 +956,21 @@ mod tests {
             AreaPathNode::new(
                 WorldArea::new((0, 0)),
                 AreaNavEdge {
-                    direction: NeighbourOffset::East,
+                    direction: Some(NeighbourOffset::East),
                     cost: EdgeCost::JumpUp,
                     exit: (15, 2, 3).try_into().unwrap(),
                     width: 2,
+                    doors: PortDoors::default(),
                 },
             ),
             AreaPathNode::new(
                 WorldArea::new((1, 0)),
                 AreaNavEdge {
-                    direction: NeighbourOffset::East,
+                    direction: Some(NeighbourOffset::East),
                     cost: EdgeCost::JumpDown,
                     exit: (15, 5, 4).try_into().unwrap(),
                     width: 1,
+                    doors: PortDoors::default(),
                 },
             ),
         ];
@@ -805,6 +978,49 @@ mod tests {
         assert_eq!(path.0, expected);
     }
 
+    #[test]
+    fn area_path_prefers_cheap_ground() {
+        // 2 equally long routes around a square of chunks, one across slow sand
+        let graph = make_graph(vec![
+            ChunkBuilder::new()
+                .fill_slice(1, BlockType::Stone)
+                .build((0, 0)),
+            ChunkBuilder::new()
+                .fill_slice(1, BlockType::Sand)
+                .build((1, 0)),
+            ChunkBuilder::new()
+                .fill_slice(1, BlockType::Stone)
+                .build((0, 1)),
+            ChunkBuilder::new()
+                .fill_slice(1, BlockType::Stone)
+                .build((1, 1)),
+        ]);
+
+        let path = graph
+            .find_area_path(
+                WorldArea::new((0, 0)),
+                WorldArea::new((1, 1)),
+                &NavigationProfile::default(),
+                |_| None,
+                &AreaGraph::search_context(),
+            )
+            .expect("path should succeed");
+
+        let chunks = path
+            .0
+            .iter()
+            .map(|node| node.area.chunk)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            chunks,
+            vec![
+                ChunkLocation(0, 0),
+                ChunkLocation(0, 1),
+                ChunkLocation(1, 1)
+            ]
+        );
+    }
+
     #[test]
     fn area_path_across_two_chunks() {
         // also the blocks are ridiculously high and not in slab 0
@@ -825,6 +1041,8 @@ mod tests {
             .find_area_path(
                 (-2, 2, 202),  // chunk -1, 0
                 (10, 10, 202), // chunk 0, 0
+                &NavigationProfile::default(),
+                &PathSearchContext::default(),
             )
             .expect("path should succeed");
 
@@ -833,10 +1051,11 @@ mod tests {
             AreaPathNode::new(
                 WorldArea::new_with_slab((0, 0), SLAB),
                 AreaNavEdge {
-                    direction: NeighbourOffset::East,
+                    direction: Some(NeighbourOffset::East),
                     cost: EdgeCost::Walk,
                     exit: (15, 2, 202).try_into().unwrap(),
                     width: 1,
+                    doors: PortDoors::default(),
                 },
             ),
         ];
@@ -858,6 +1077,8 @@ mod tests {
             .find_area_path(
                 (2, 2, 202), // chunk 0, 0
                 (8, 3, 202), // also chunk 0, 0
+                &NavigationProfile::default(),
+                &PathSearchContext::default(),
             )
             .expect("path should succeed");
 
@@ -879,6 +1100,8 @@ mod tests {
         let err = graph.find_area_path(
             WorldArea::new((0, 0)),
             WorldArea::new((100, 20)),
+            &NavigationProfile::default(),
+            |_| None,
             &AreaGraph::search_context(),
         );
 
@@ -888,14 +1111,15 @@ mod tests {
     #[test]
     fn area_edge_reverse() {
         let edge = AreaNavEdge {
-            direction: NeighbourOffset::South,
+            direction: Some(NeighbourOffset::South),
             cost: EdgeCost::JumpUp,
             exit: (5, 0, 5).try_into().unwrap(),
             width: 2,
+            doors: PortDoors::default(),
         };
 
         let reversed = AreaNavEdge {
-            direction: NeighbourOffset::North,
+            direction: Some(NeighbourOffset::North),
             cost: EdgeCost::JumpDown,
             exit: BlockPosition::new_unchecked(
                 5,
@@ -903,30 +1127,46 @@ mod tests {
                 GlobalSliceIndex::new(6),
             ),
             width: 2,
+            doors: PortDoors::default(),
         };
 
         assert_eq!(edge.reversed(), reversed);
         assert_eq!(reversed.reversed(), edge);
+
+        // climbs stay in the same column
+        let climb = AreaNavEdge::climb((5, 6, 31).try_into().unwrap());
+        let reversed = AreaNavEdge {
+            direction: None,
+            cost: EdgeCost::ClimbDown,
+            exit: (5, 6, 32).try_into().unwrap(),
+            width: 1,
+            doors: PortDoors::default(),
+        };
+
+        assert_eq!(climb.reversed(), reversed);
+        assert_eq!(reversed.reversed(), climb);
     }
 
     #[test]
     fn port_exit_closest() {
         assert_eq!(
             AreaNavEdge {
-                direction: NeighbourOffset::South,
+                direction: Some(NeighbourOffset::South),
                 cost: EdgeCost::Walk,
                 exit: (4, 4, 4).try_into().unwrap(),
-                width: 1
+                width: 1,
+                doors: PortDoors::default(),
             }
             .exit_closest((10, 10, 4).try_into().unwrap()), // doesn't matter, there is only 1 candidate
             (4, 4, 4).try_into().unwrap()
         );
 
         let edge = AreaNavEdge {
-            direction: NeighbourOffset::South,
+            direction: Some(NeighbourOffset::South),
             cost: EdgeCost::Walk,
             exit: (4, 4, 4).try_into().unwrap(), // [4, 8] in x axis
             width: 5,
+            doors: PortDoors::default(),
         };
 
         assert_eq!(
@@ -0,0 +1,402 @@
+//! Path finding on the world loader's worker pool, so many requests don't stall the caller
+
+use std::collections::{HashMap, VecDeque};
+
+use futures::channel::mpsc as async_channel;
+use tokio::runtime::Handle;
+
+use common::*;
+use unit::world::WorldPosition;
+
+use crate::loader::AsyncWorkerPool;
+use crate::navigation::{
+    NavigationError, NavigationProfile, NavigationSnapshot, PathEndpoints, PathSearchContext,
+    SearchCancellation, SearchGoal,
+};
+use crate::{WorldContext, WorldPath, WorldRef};
+
+/// Max number of searches running on the pool at once, the rest wait in a queue
+const MAX_RUNNING_SEARCHES: usize = 8;
+
+/// Results are delivered no sooner than this many polls after their request, so the delivery tick
+/// only varies when the workers fall behind
+const DELIVERY_DELAY: u64 = 2;
+
+/// Queue of path requests that are searched for on worker threads, in a snapshot of the world's
+/// navigation graphs taken at request time. Each requester (e.g. an entity) has at most one
+/// request at a time, identified by a token. Making a new request for the same requester cancels
+/// the previous one, and results are only delivered for the latest token.
+///
+/// Results are delivered a fixed number of polls after the request if the search has finished by
+/// then, otherwise on the first poll after it does. The caller is never blocked on a search.
+pub struct AsyncPathfinder<C: WorldContext, R, T> {
+    world: WorldRef<C>,
+    runtime: Handle,
+    requests: HashMap<R, PathRequest<T>>,
+    /// Requesters waiting for a free slot, may contain requesters that have since been cancelled
+    queue: VecDeque<R>,
+    /// Searches on the pool, including cancelled ones that haven't stopped yet
+    running: usize,
+    results_tx: async_channel::UnboundedSender<CompletedSearch<R>>,
+    results_rx: async_channel::UnboundedReceiver<CompletedSearch<R>>,
+    /// Contexts returned from finished searches to be reused
+    contexts: Vec<PathSearchContext>,
+    /// Number of polls so far
+    tick: u64,
+    /// Increases with each request
+    next_id: u64,
+}
+
+// safety: the search contexts are the only part that isn't Sync, and they are only accessed
+// through &mut self
+unsafe impl<C, R, T> Sync for AsyncPathfinder<C, R, T>
+where
+    C: WorldContext,
+    R: Send + Sync,
+    T: Send + Sync,
+{
+}
+
+struct PathRequest<T> {
+    token: T,
+    /// Distinguishes a result from one for an earlier cancelled request with the same requester
+    id: u64,
+    /// Tick to be delivered in
+    due: u64,
+    state: SearchState,
+}
+
+enum SearchState {
+    Queued(PathQuery),
+    Running(SearchCancellation),
+    Done(Result<WorldPath, NavigationError>),
+}
+
+struct PathQuery {
+    snapshot: NavigationSnapshot,
+    endpoints: PathEndpoints,
+    profile: NavigationProfile,
+}
+
+struct CompletedSearch<R> {
+    requester: R,
+    id: u64,
+    result: Result<WorldPath, NavigationError>,
+    context: PathSearchContext,
+}
+
+impl<C, R, T> AsyncPathfinder<C, R, T>
+where
+    C: WorldContext,
+    R: Copy + Eq + Hash + Send + 'static,
+    T: Copy + Eq + Send + 'static,
+{
+    pub fn new(world: WorldRef<C>, pool: &AsyncWorkerPool) -> Self {
+        let (results_tx, results_rx) = async_channel::unbounded();
+        Self {
+            world,
+            runtime: pool.runtime().handle().clone(),
+            requests: HashMap::new(),
+            queue: VecDeque::new(),
+            running: 0,
+            results_tx,
+            results_rx,
+            contexts: Vec::new(),
+            tick: 0,
+            next_id: 0,
+        }
+    }
+
+    /// Replaces and cancels any other request for this requester. The result is delivered through
+    /// [Self::poll_completed] once due
+    pub fn request(
+        &mut self,
+        requester: R,
+        token: T,
+        from: WorldPosition,
+        to: WorldPosition,
+        goal: SearchGoal,
+        profile: NavigationProfile,
+    ) {
+        self.cancel(requester);
+
+        // the terrain is only needed to resolve the endpoints, the search itself only needs the
+        // navigation graphs so doesn't hold onto the world
+        let state = {
+            let world = self.world.borrow();
+            match world.resolve_path_endpoints(from, to, goal) {
+                Ok(endpoints) => SearchState::Queued(PathQuery {
+                    snapshot: world.navigation_snapshot(),
+                    endpoints,
+                    profile,
+                }),
+                Err(err) => SearchState::Done(Err(err)),
+            }
+        };
+
+        let queued = matches!(state, SearchState::Queued(_));
+        let request = PathRequest {
+            token,
+            id: self.next_id,
+            due: self.tick + DELIVERY_DELAY,
+            state,
+        };
+        self.next_id += 1;
+        self.requests.insert(requester, request);
+
+        if queued {
+            self.queue.push_back(requester);
+            self.start_queued();
+        }
+    }
+
+    /// Cancels the current request for this requester, if any. Its result will never be delivered
+    pub fn cancel(&mut self, requester: R) {
+        if let Some(PathRequest {
+            state: SearchState::Running(cancellation),
+            ..
+        }) = self.requests.remove(&requester)
+        {
+            // its slot is freed once the search notices and stops
+            cancellation.cancel();
+        }
+    }
+
+    /// Whether the requester has a request that has not yet been delivered
+    pub fn is_pending(&self, requester: R) -> bool {
+        self.requests.contains_key(&requester)
+    }
+
+    /// Counts as a tick. Calls `f` in request order with the result of every current request that
+    /// is due and has finished, then starts more queued searches. Unfinished requests stay pending
+    /// until a later poll
+    pub fn poll_completed(&mut self, mut f: impl FnMut(R, T, Result<WorldPath, NavigationError>)) {
+        self.tick += 1;
+
+        while let Ok(Some(done)) = self.results_rx.try_next() {
+            self.on_completed(done);
+        }
+
+        let tick = self.tick;
+        let mut ready = self
+            .requests
+            .iter()
+            .filter(|(_, request)| {
+                request.due <= tick && matches!(request.state, SearchState::Done(_))
+            })
+            .map(|(requester, request)| (request.id, *requester))
+            .collect_vec();
+        ready.sort_unstable_by_key(|(id, _)| *id);
+
+        for (_, requester) in ready {
+            if let Some(PathRequest {
+                token,
+                state: SearchState::Done(result),
+                ..
+            }) = self.requests.remove(&requester)
+            {
+                f(requester, token, result);
+            }
+        }
+
+        self.start_queued();
+    }
+
+    fn on_completed(&mut self, done: CompletedSearch<R>) {
+        // cancelled searches held onto their slot until now too
+        self.running -= 1;
+        self.contexts.push(done.context);
+
+        if let Some(request) = self.requests.get_mut(&done.requester) {
+            if request.id == done.id {
+                request.state = SearchState::Done(done.result);
+            }
+        }
+    }
+
+    fn start_queued(&mut self) {
+        while self.running < MAX_RUNNING_SEARCHES {
+            let requester = match self.queue.pop_front() {
+                Some(r) => r,
+                None => break,
+            };
+
+            let request = match self.requests.get_mut(&requester) {
+                Some(request) => request,
+                None => continue, // cancelled
+            };
+
+            let cancellation = SearchCancellation::default();
+            let running = SearchState::Running(cancellation.clone());
+            let query = match std::mem::replace(&mut request.state, running) {
+                SearchState::Queued(query) => query,
+                other => {
+                    // already started or found from a duplicate queue entry
+                    request.state = other;
+                    continue;
+                }
+            };
+
+            let id = request.id;
+            let results_tx = self.results_tx.clone();
+            let context = self.contexts.pop().unwrap_or_default();
+            context.set_cancellation(cancellation);
+
+            // the search never yields, so keep it off the async workers
+            self.runtime.spawn_blocking(move || {
+                let result = query.find_path(&context);
+
+                // receiver is only dropped with the pathfinder, in which case no one cares
+                let _ = results_tx.unbounded_send(CompletedSearch {
+                    requester,
+                    id,
+                    result,
+                    context,
+                });
+            });
+
+            self.running += 1;
+        }
+    }
+}
+
+impl PathQuery {
+    fn find_path(&self, context: &PathSearchContext) -> Result<WorldPath, NavigationError> {
+        self.snapshot
+            .find_path(&self.endpoints, &self.profile, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use common::*;
+    use unit::world::WorldPosition;
+
+    use crate::block::BlockType;
+    use crate::chunk::ChunkBuilder;
+    use crate::loader::AsyncWorkerPool;
+    use crate::navigation::async_path::{DELIVERY_DELAY, MAX_RUNNING_SEARCHES};
+    use crate::navigation::{AsyncPathfinder, NavigationError, NavigationProfile, SearchGoal};
+    use crate::world::helpers::{world_from_chunks_blocking, DummyWorldContext};
+    use crate::{WorldPath, WorldRef};
+
+    fn world() -> WorldRef<DummyWorldContext> {
+        world_from_chunks_blocking(vec![ChunkBuilder::new()
+            .fill_slice(1, BlockType::Stone)
+            .build((0, 0))])
+    }
+
+    fn request(
+        pathfinder: &mut AsyncPathfinder<DummyWorldContext, u32, u32>,
+        requester: u32,
+        token: u32,
+        to: (i32, i32, i32),
+    ) {
+        pathfinder.request(
+            requester,
+            token,
+            WorldPosition::from((2, 2, 2)),
+            WorldPosition::from(to),
+            SearchGoal::Arrive,
+            NavigationProfile::default(),
+        )
+    }
+
+    /// Polls until nothing is pending, collecting results along with the tick they arrived in
+    fn poll_all(
+        pathfinder: &mut AsyncPathfinder<DummyWorldContext, u32, u32>,
+    ) -> Vec<(u64, u32, u32, Result<WorldPath, NavigationError>)> {
+        let mut results = Vec::new();
+        let start = Instant::now();
+        while !pathfinder.requests.is_empty() {
+            assert!(start.elapsed() < Duration::from_secs(5), "path timed out");
+            let tick = pathfinder.tick + 1;
+            pathfinder.poll_completed(|requester, token, result| {
+                results.push((tick, requester, token, result))
+            });
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        results
+    }
+
+    #[test]
+    fn latest_request_only() {
+        let pool = AsyncWorkerPool::new(2).unwrap();
+        let mut pathfinder = AsyncPathfinder::new(world(), &pool);
+
+        // superseded by the second
+        request(&mut pathfinder, 1, 1, (10, 10, 2));
+        request(&mut pathfinder, 1, 2, (12, 4, 2));
+
+        let mut results = poll_all(&mut pathfinder);
+        assert_eq!(results.len(), 1);
+        let (tick, requester, token, result) = results.pop().unwrap();
+        assert!(tick >= DELIVERY_DELAY, "delivered too early");
+        assert_eq!((requester, token), (1, 2));
+        assert_eq!(
+            result.expect("path should succeed").target(),
+            WorldPosition::from((12, 4, 2))
+        );
+    }
+
+    #[test]
+    fn burst_is_delivered_in_request_order() {
+        let pool = AsyncWorkerPool::new(2).unwrap();
+        let mut pathfinder = AsyncPathfinder::new(world(), &pool);
+
+        // more than can run at once, the rest wait for a slot rather than being searched inline
+        let count = MAX_RUNNING_SEARCHES as u32 * 3;
+        for requester in 0..count {
+            request(
+                &mut pathfinder,
+                requester,
+                requester,
+                (14, 2 + (requester % 12) as i32, 2),
+            );
+            assert!(pathfinder.running <= MAX_RUNNING_SEARCHES);
+        }
+
+        let results = poll_all(&mut pathfinder);
+        assert_eq!(results.len(), count as usize);
+        assert!(results
+            .iter()
+            .all(|(_, requester, token, result)| { requester == token && result.is_ok() }));
+
+        // requests delivered in the same poll are in request order
+        for (_, group) in &results.iter().group_by(|(tick, _, _, _)| *tick) {
+            let requesters = group.map(|(_, requester, _, _)| *requester).collect_vec();
+            assert!(
+                requesters.windows(2).all(|w| w[0] < w[1]),
+                "{:?}",
+                requesters
+            );
+        }
+    }
+
+    #[test]
+    fn cancelled_searches_free_slots() {
+        let pool = AsyncWorkerPool::new(2).unwrap();
+        let mut pathfinder = AsyncPathfinder::new(world(), &pool);
+
+        for requester in 0..20 {
+            request(&mut pathfinder, requester, 1, (14, 14, 2));
+            pathfinder.cancel(requester);
+        }
+
+        // nothing is delivered for cancelled requests, but their slots are freed once they stop
+        let start = Instant::now();
+        while pathfinder.running > 0 {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "searches never stopped"
+            );
+            std::thread::sleep(Duration::from_millis(5));
+            pathfinder.poll_completed(|_, _, _| panic!("cancelled request was delivered"));
+        }
+
+        assert!(pathfinder.requests.is_empty());
+        assert!(!pathfinder.contexts.is_empty());
+    }
+}
@@ -1,5 +1,7 @@
 //! Navigation inside an area
 
+use std::collections::{HashMap, HashSet};
+
 use petgraph::graphmap::DiGraphMap;
 use petgraph::prelude::EdgeRef;
 use petgraph::visit::Visitable;
@@ -7,9 +9,10 @@ use petgraph::visit::Visitable;
 use common::*;
 use unit::world::{BlockPosition, ChunkLocation, SlabIndex, SlabPosition};
 
+use crate::navigation::cost::congestion_penalty;
 use crate::navigation::path::{BlockPath, BlockPathNode};
 use crate::navigation::search::{self, ExploreResult, SearchContext};
-use crate::navigation::{EdgeCost, SearchGoal};
+use crate::navigation::{DoorOwner, EdgeCost, NavigationProfile, SearchGoal};
 use crate::{ExplorationFilter, ExplorationResult};
 
 type BlockNavGraph = DiGraphMap<BlockNavNode, BlockNavEdge>;
@@ -29,6 +32,15 @@ pub struct BlockNavEdge(pub EdgeCost);
 #[cfg_attr(test, derive(Clone))]
 pub struct BlockGraph {
     graph: BlockNavGraph,
+
+    /// Walkable door blocks, only passable by agents that can open them
+    doors: HashSet<BlockPosition>,
+
+    /// Multiplier for the cost of moving into a block from the ground below it, missing means 1
+    movement_costs: HashMap<BlockPosition, f32>,
+
+    /// Lowest movement cost in this graph, to keep the search heuristic from overestimating
+    min_movement_cost: f32,
 }
 
 #[derive(Debug, Clone, Error)]
@@ -41,6 +53,9 @@ impl BlockGraph {
     pub fn new() -> Self {
         Self {
             graph: BlockNavGraph::new(),
+            doors: HashSet::new(),
+            movement_costs: HashMap::new(),
+            min_movement_cost: 1.0,
         }
     }
 
@@ -60,6 +75,35 @@ impl BlockGraph {
         self.graph.add_edge(to, from, BlockNavEdge(cost.opposite()));
     }
 
+    pub fn add_door<P: Into<SlabPosition>>(&mut self, door: P, slab: SlabIndex) {
+        self.doors.insert(door.into().to_block_position(slab));
+    }
+
+    pub fn set_movement_cost<P: Into<SlabPosition>>(&mut self, pos: P, cost: f32, slab: SlabIndex) {
+        let pos = pos.into().to_block_position(slab);
+        if (cost - 1.0).abs() > f32::EPSILON {
+            self.movement_costs.insert(pos, cost);
+            self.min_movement_cost = self.min_movement_cost.min(cost);
+        } else {
+            self.movement_costs.remove(&pos);
+        }
+    }
+
+    fn movement_cost(&self, block: BlockPosition) -> f32 {
+        self.movement_costs.get(&block).copied().unwrap_or(1.0)
+    }
+
+    /// Average movement cost across the whole area, to weigh it against others in area paths
+    pub fn mean_movement_cost(&self) -> f32 {
+        let blocks = self.graph.node_count().max(self.movement_costs.len());
+        if blocks == 0 {
+            return 1.0;
+        }
+
+        let others = (blocks - self.movement_costs.len()) as f32;
+        (self.movement_costs.values().sum::<f32>() + others) / blocks as f32
+    }
+
     #[cfg(test)]
     pub fn edges(&self, block: BlockPosition) -> Vec<(BlockPosition, EdgeCost)> {
         let node = BlockNavNode(block);
@@ -78,6 +122,9 @@ impl BlockGraph {
         from: BlockPosition,
         to: BlockPosition,
         goal: SearchGoal,
+        profile: &NavigationProfile,
+        door_owner: impl Fn(BlockPosition) -> Option<DoorOwner>,
+        congestion: impl Fn(BlockPosition) -> u16,
         context: &BlockGraphSearchContext,
     ) -> Result<BlockPath, BlockPathError> {
         // same source and dest is a success, if not a pointless one
@@ -104,12 +151,13 @@ impl BlockGraph {
         let src = BlockNavNode(from);
         let dst = BlockNavNode(to);
 
+        let min_cost = self.min_movement_cost;
         let heuristic: Box<dyn FnMut(BlockNavNode) -> f32> = match goal {
             SearchGoal::Nearby(range) => {
                 let range = range as f32;
-                Box::new(move |n| (manhattan(&n.0, &dst.0) as f32 - range).max(0.0))
+                Box::new(move |n| (manhattan(&n.0, &dst.0) as f32 - range).max(0.0) * min_cost)
             }
-            _ => Box::new(|n| manhattan(&n.0, &dst.0) as f32),
+            _ => Box::new(move |n| manhattan(&n.0, &dst.0) as f32 * min_cost),
         };
 
         let is_goal: Box<dyn FnMut(BlockNavNode) -> bool> = match goal {
@@ -126,7 +174,19 @@ impl BlockGraph {
             &self.graph,
             src,
             is_goal,
-            |(_, _, e)| e.0.weight(),
+            |(_, to, e)| {
+                let passable = self.fits(to.0, profile.size)
+                    && (!self.doors.contains(&to.0) || profile.can_open_door(door_owner(to.0)));
+                if passable {
+                    let mut cost = e.0.weight() * self.movement_cost(to.0);
+                    if profile.avoid_congestion {
+                        cost += congestion_penalty(congestion(to.0));
+                    }
+                    Some(cost)
+                } else {
+                    None
+                }
+            },
             heuristic,
             context,
         );
@@ -135,6 +195,30 @@ impl BlockGraph {
             .ok_or(BlockPathError::NoPath(to, from))
     }
 
+    /// Whether an agent `size` blocks wide can stand on this block, i.e. it is part of a square
+    /// of that size made only of walkable blocks. Blocks outside this chunk are assumed to be
+    /// clear as only this area's graph is available
+    // TODO check clearance against neighbouring chunks too
+    fn fits(&self, block: BlockPosition, size: u8) -> bool {
+        if size <= 1 {
+            return true;
+        }
+
+        let size = size as i16;
+        let is_clear = |dx: i16, dy: i16| {
+            block
+                .try_add_xy((dx, dy))
+                .map_or(true, |pos| self.graph.contains_node(BlockNavNode(pos)))
+        };
+
+        // try every square containing this block
+        (0..size).cartesian_product(0..size).any(|(ax, ay)| {
+            (0..size)
+                .cartesian_product(0..size)
+                .all(|(x, y)| is_clear(x - ax, y - ay))
+        })
+    }
+
     /// Uses as much fuel as possible to find a reachable block
     pub(crate) fn explore(
         &self,
@@ -214,7 +298,7 @@ mod tests {
     use unit::world::ChunkLocation;
 
     use crate::block::BlockType;
-    use crate::navigation::{BlockGraph, BlockPathNode, SearchGoal, WorldArea};
+    use crate::navigation::{BlockGraph, BlockPathNode, NavigationProfile, SearchGoal, WorldArea};
     use crate::world::helpers::world_from_chunks_blocking;
     use crate::{ChunkBuilder, EdgeCost};
 
@@ -234,6 +318,9 @@ mod tests {
                 (3, 5, 2).try_into().unwrap(),
                 (6, 5, 4).try_into().unwrap(),
                 SearchGoal::Arrive,
+                &NavigationProfile::default(),
+                |_| None,
+                |_| 0,
                 &BlockGraph::search_context(),
             )
             .expect("path should succeed");
@@ -261,6 +348,9 @@ mod tests {
                 (6, 5, 4).try_into().unwrap(),
                 (3, 5, 2).try_into().unwrap(),
                 SearchGoal::Arrive,
+                &NavigationProfile::default(),
+                |_| None,
+                |_| 0,
                 &BlockGraph::search_context(),
             )
             .expect("reverse path should succeed");
@@ -307,6 +397,9 @@ mod tests {
                     start.try_into().unwrap(),
                     end.try_into().unwrap(),
                     SearchGoal::Arrive,
+                    &NavigationProfile::default(),
+                    |_| None,
+                    |_| 0,
                     &BlockGraph::search_context(),
                 )
                 .expect("path should succeed")
@@ -8,15 +8,32 @@ pub enum EdgeCost {
 
     /// Flat walk
     Walk,
+
+    /// Climb 1 block up a climbable block, e.g. a ladder
+    ClimbUp,
+
+    /// Climb 1 block down a climbable block
+    ClimbDown,
+}
+
+/// Extra cost of moving into a block per agent already in it
+const CONGESTION_PENALTY: f32 = 1.5;
+
+/// Extra cost of moving into a block occupied by this many agents, to spread traffic out
+pub fn congestion_penalty(agents: u16) -> f32 {
+    agents as f32 * CONGESTION_PENALTY
 }
 
 impl EdgeCost {
+    /// Base cost, scaled by the movement cost of the ground being moved onto
     pub fn weight(self) -> f32 {
         // TODO currently arbitrary, should depend on physical attributes
         match self {
             EdgeCost::JumpUp => 1.2,
             EdgeCost::JumpDown => 1.1,
             EdgeCost::Walk => 1.0,
+            EdgeCost::ClimbUp => 2.0,
+            EdgeCost::ClimbDown => 1.6,
         }
     }
 
@@ -35,14 +52,21 @@ impl EdgeCost {
             EdgeCost::JumpUp => EdgeCost::JumpDown,
             EdgeCost::JumpDown => EdgeCost::JumpUp,
             EdgeCost::Walk => EdgeCost::Walk,
+            EdgeCost::ClimbUp => EdgeCost::ClimbDown,
+            EdgeCost::ClimbDown => EdgeCost::ClimbUp,
         }
     }
 
     pub fn z_offset(self) -> i32 {
         match self {
-            EdgeCost::JumpUp => 1,
-            EdgeCost::JumpDown => -1,
+            EdgeCost::JumpUp | EdgeCost::ClimbUp => 1,
+            EdgeCost::JumpDown | EdgeCost::ClimbDown => -1,
             EdgeCost::Walk => 0,
         }
     }
+
+    /// Vertical movement along a climbable column rather than across the ground
+    pub fn is_climb(self) -> bool {
+        matches!(self, EdgeCost::ClimbUp | EdgeCost::ClimbDown)
+    }
 }
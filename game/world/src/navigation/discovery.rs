@@ -22,6 +22,10 @@ grid_declare!(struct AreaDiscoveryGrid<AreaDiscoveryGridImpl, AreaDiscoveryGridB
 #[derive(Default, Copy, Clone)]
 struct AreaDiscoveryGridBlock {
     opacity: OcclusionOpacity,
+    climbable: bool,
+    door: bool,
+    deep_liquid: bool,
+    movement_cost: f32,
 
     area: SlabAreaIndex,
 }
@@ -51,6 +55,10 @@ impl From<&Block> for AreaDiscoveryGridBlock {
     fn from(block: &Block) -> Self {
         AreaDiscoveryGridBlock {
             opacity: block.opacity().into(),
+            climbable: block.block_type().is_climbable(),
+            door: block.block_type().is_door(),
+            deep_liquid: block.liquid().is_deep(),
+            movement_cost: block.block_type().movement_cost(),
             area: Default::default(),
         }
     }
@@ -157,6 +165,14 @@ impl<'a> AreaDiscovery<'a> {
                 .area = self.current;
             count += 1;
 
+            if self.grid.get_unchecked(SlabPositionAsCoord(current)).door {
+                graph.add_door(current, self.slab_index);
+            }
+
+            // walking is slower or faster depending on the ground
+            let ground = self.get_vertical_offset(current, VerticalOffset::Below);
+            graph.set_movement_cost(current, ground.movement_cost, self.slab_index);
+
             // add horizontal neighbours
             for n in SlabNeighbours::new(current) {
                 let cost = EdgeCost::Walk;
@@ -205,6 +221,30 @@ impl<'a> AreaDiscovery<'a> {
                     }
                 }
             }
+
+            // climb up and down climbable columns, linking to the slab above and below is done
+            // separately between areas
+            let current_block = self.grid.get_unchecked(SlabPositionAsCoord(current));
+            if current_block.climbable && current.z().slice() < SLAB_SIZE.as_i32() - 1 {
+                let (x, y, z) = current.xyz();
+                // z is not at the top
+                let above =
+                    SlabPosition::new_unchecked(x, y, LocalSliceIndex::new_unchecked(z + 1));
+                self.queue.push((above, Some((current, EdgeCost::ClimbUp))));
+            }
+
+            if current.z().slice() > 0
+                && self
+                    .get_vertical_offset(current, VerticalOffset::Below)
+                    .climbable
+            {
+                let (x, y, z) = current.xyz();
+                // z is not at the bottom
+                let below =
+                    SlabPosition::new_unchecked(x, y, LocalSliceIndex::new_unchecked(z - 1));
+                self.queue
+                    .push((below, Some((current, EdgeCost::ClimbDown))));
+            }
         }
 
         // increment area
@@ -226,12 +266,33 @@ impl<'a> AreaDiscovery<'a> {
     fn is_walkable(&self, pos: SlabPosition) -> bool {
         let marker = self.grid.get_unchecked(SlabPositionAsCoord(pos));
 
-        if marker.opacity.solid() {
+        // doors are solid but can be walked through, depending on who is asking
+        if marker.opacity.solid() && !marker.door {
+            return false;
+        }
+
+        // can't wade through deep liquid
+        if marker.deep_liquid {
             return false;
         }
 
+        // can hold onto a climbable block anywhere along it
+        if marker.climbable {
+            return true;
+        }
+
         let below = self.get_vertical_offset(pos, VerticalOffset::Below);
 
+        // standing on top of a climbable block
+        if below.climbable {
+            return true;
+        }
+
+        // can't stand on top of a door
+        if below.door {
+            return false;
+        }
+
         // below not solid either: nope
         if below.opacity.transparent() {
             return false;
@@ -1,4 +1,7 @@
-pub use area_navigation::{AreaGraph, AreaGraphSearchContext, AreaNavEdge, AreaPathError};
+pub use area_navigation::{
+    AreaGraph, AreaGraphSearchContext, AreaNavEdge, AreaPathError, PortDoors,
+};
+pub use async_path::AsyncPathfinder;
 pub use block_navigation::{BlockGraph, BlockGraphSearchContext, BlockPathError};
 use common::*;
 pub use cost::EdgeCost;
@@ -6,15 +9,27 @@ pub use cost::EdgeCost;
 pub use path::{
     AreaPath, BlockPath, BlockPathNode, NavigationError, SearchGoal, WorldPath, WorldPathNode,
 };
-pub use search::ExploreResult;
+pub use profile::{DoorOwner, NavigationProfile};
+pub use search::{ExploreResult, SearchCancellation};
+pub(crate) use snapshot::{BlockGraphs, NavigationGraphs};
+pub use snapshot::{NavigationSnapshot, PathEndpoints};
 use unit::world::{ChunkLocation, SlabIndex};
 
 mod area_navigation;
+mod async_path;
 mod block_navigation;
 mod cost;
 pub(crate) mod discovery;
 mod path;
+mod profile;
 mod search;
+mod snapshot;
+
+/// Allocations reused between path searches. Each concurrent search needs its own
+pub struct PathSearchContext {
+    pub(crate) area: AreaGraphSearchContext,
+    pub(crate) block: BlockGraphSearchContext,
+}
 
 /// Area index in a slab. 0 is uninitialized, starts at 1
 #[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -84,6 +99,27 @@ impl ChunkArea {
     }
 }
 
+impl Default for PathSearchContext {
+    fn default() -> Self {
+        Self {
+            area: AreaGraph::search_context(),
+            block: BlockGraph::search_context(),
+        }
+    }
+}
+
+impl PathSearchContext {
+    /// Searches in this context stop early once the given flag is set
+    pub(crate) fn set_cancellation(&self, cancellation: SearchCancellation) {
+        self.area.set_cancellation(cancellation.clone());
+        self.block.set_cancellation(cancellation);
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.area.is_cancelled()
+    }
+}
+
 impl From<WorldArea> for ChunkArea {
     fn from(area: WorldArea) -> Self {
         ChunkArea {
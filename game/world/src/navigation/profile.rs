@@ -0,0 +1,42 @@
+/// Opaque owner of a door, e.g. a society. Owned doors can only be opened by agents with the
+/// same owner
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct DoorOwner(pub u32);
+
+/// Physical and social attributes of the agent a path is being found for
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct NavigationProfile {
+    /// Width in blocks, agents wider than 1 block can't fit through 1 block wide gaps
+    pub size: u8,
+
+    /// Able to open doors at all
+    pub can_open_doors: bool,
+
+    /// Doors owned by this can be opened, as well as unowned doors
+    pub owner: Option<DoorOwner>,
+
+    /// Prefer routes around blocks crowded with other agents
+    pub avoid_congestion: bool,
+}
+
+impl NavigationProfile {
+    pub fn can_open_door(&self, door: Option<DoorOwner>) -> bool {
+        self.can_open_doors
+            && match door {
+                None => true,
+                Some(owner) => self.owner == Some(owner),
+            }
+    }
+}
+
+impl Default for NavigationProfile {
+    /// Single block wide, can open unowned doors only, ignores congestion
+    fn default() -> Self {
+        Self {
+            size: 1,
+            can_open_doors: true,
+            owner: None,
+            avoid_congestion: false,
+        }
+    }
+}
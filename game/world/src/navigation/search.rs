@@ -7,6 +7,8 @@ use std::collections::{BinaryHeap, HashMap};
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::Arc;
 
 use petgraph::algo::Measure;
 use petgraph::visit::{EdgeRef, IntoEdges, VisitMap, Visitable};
@@ -34,9 +36,15 @@ where
     scores: HashMap<N, K>,
     path_tracker: PathTracker<N, E>,
     result: Vec<(N, E)>,
+    cancellation: SearchCancellation,
 }
 
-/// Path is populated in context, left empty if search failed. On success, doesn't include goal node
+/// Flag to stop a search early from another thread, checked between node visits
+#[derive(Clone, Default)]
+pub struct SearchCancellation(Arc<AtomicBool>);
+
+/// Path is populated in context, left empty if search failed or was cancelled. On success, doesn't
+/// include goal node. Edges with a cost of None are not traversable
 pub fn astar<G, F, H, K, IsGoal>(
     graph: G,
     start: G::NodeId,
@@ -48,7 +56,7 @@ pub fn astar<G, F, H, K, IsGoal>(
     G: IntoEdges + Visitable,
     IsGoal: FnMut(G::NodeId) -> bool,
     G::NodeId: Eq + Hash + Copy,
-    F: FnMut(G::EdgeRef) -> K,
+    F: FnMut(G::EdgeRef) -> Option<K>,
     H: FnMut(G::NodeId) -> K,
     K: Measure + Copy,
 {
@@ -60,6 +68,10 @@ pub fn astar<G, F, H, K, IsGoal>(
     ctx.visit_next.push(MinScored(estimate_cost(start), start));
 
     while let Some(MinScored(_, node)) = ctx.visit_next.pop() {
+        if ctx.cancellation.is_cancelled() {
+            break;
+        }
+
         if is_goal(node) {
             {
                 // safety: not referenced anywhere else
@@ -85,7 +97,10 @@ pub fn astar<G, F, H, K, IsGoal>(
                 continue;
             }
 
-            let mut next_score = node_score + edge_cost(edge);
+            let mut next_score = match edge_cost(edge) {
+                Some(cost) => node_score + cost,
+                None => continue,
+            };
 
             match ctx.scores.entry(next) {
                 Occupied(ent) => {
@@ -300,12 +315,32 @@ where
             scores: HashMap::new(),
             path_tracker: PathTracker::new(),
             result: Vec::new(),
+            cancellation: SearchCancellation::default(),
         }))
     }
 
     pub fn result(&self) -> impl Deref<Target = [(N, E)]> + '_ {
         Ref::map(self.0.borrow(), |inner| &inner.result[..])
     }
+
+    /// Replaces the flag checked by all future searches in this context
+    pub fn set_cancellation(&self, cancellation: SearchCancellation) {
+        self.0.borrow_mut().cancellation = cancellation;
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.borrow().cancellation.is_cancelled()
+    }
+}
+
+impl SearchCancellation {
+    pub fn cancel(&self) {
+        self.0.store(true, AtomicOrdering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(AtomicOrdering::Relaxed)
+    }
 }
 
 impl<N, E, K, V> SearchContextInner<N, E, K, V>
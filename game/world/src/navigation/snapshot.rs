@@ -0,0 +1,206 @@
+//! Navigation graphs separated from the rest of the world, so paths can be found without holding
+//! onto the world
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common::*;
+use unit::world::{BlockPosition, WorldPosition, CHUNK_SIZE};
+
+use crate::navigation::{
+    AreaGraph, AreaNavEdge, AreaPath, BlockGraph, BlockPath, DoorOwner, NavigationError,
+    NavigationProfile, PathSearchContext, SearchGoal, WorldArea, WorldPath, WorldPathNode,
+};
+
+/// Block graph of every area in the world
+pub(crate) type BlockGraphs = HashMap<WorldArea, Arc<BlockGraph>>;
+
+/// Everything path finding needs from the world, borrowed from either the world itself or a
+/// [NavigationSnapshot]
+#[derive(Copy, Clone)]
+pub(crate) struct NavigationGraphs<'a> {
+    pub area_graph: &'a AreaGraph,
+    pub block_graphs: &'a BlockGraphs,
+    pub door_owners: &'a HashMap<WorldPosition, DoorOwner>,
+    pub congestion: &'a HashMap<WorldPosition, u16>,
+}
+
+/// The world's navigation graphs, door owners and congestion at the time it was taken. Shares
+/// its data with the world until the world next changes it, so is cheap to take
+#[derive(Clone)]
+pub struct NavigationSnapshot {
+    pub(crate) area_graph: Arc<AreaGraph>,
+    pub(crate) block_graphs: Arc<BlockGraphs>,
+    pub(crate) door_owners: Arc<HashMap<WorldPosition, DoorOwner>>,
+    pub(crate) congestion: Arc<HashMap<WorldPosition, u16>>,
+}
+
+/// Start and end of a path resolved against the world's terrain, which isn't available to a
+/// [NavigationSnapshot]
+#[derive(Copy, Clone, Debug)]
+pub struct PathEndpoints {
+    pub(crate) from: WorldPosition,
+    pub(crate) from_area: WorldArea,
+    pub(crate) to: WorldPosition,
+    pub(crate) to_area: WorldArea,
+    /// Adjusted from the requested goal, e.g. Adjacent resolves to arriving at a neighbour
+    pub(crate) goal: SearchGoal,
+}
+
+impl NavigationSnapshot {
+    /// Stops early with [NavigationError::Aborted] if the context's search is cancelled
+    pub fn find_path(
+        &self,
+        endpoints: &PathEndpoints,
+        profile: &NavigationProfile,
+        context: &PathSearchContext,
+    ) -> Result<WorldPath, NavigationError> {
+        self.graphs().find_path(endpoints, profile, context)
+    }
+
+    fn graphs(&self) -> NavigationGraphs<'_> {
+        NavigationGraphs {
+            area_graph: &self.area_graph,
+            block_graphs: &self.block_graphs,
+            door_owners: &self.door_owners,
+            congestion: &self.congestion,
+        }
+    }
+}
+
+impl<'a> NavigationGraphs<'a> {
+    pub fn door_owner(&self, pos: WorldPosition) -> Option<DoorOwner> {
+        self.door_owners.get(&pos).copied()
+    }
+
+    pub fn congestion(&self, pos: WorldPosition) -> u16 {
+        self.congestion.get(&pos).copied().unwrap_or(0)
+    }
+
+    pub fn find_area_path(
+        &self,
+        from: WorldArea,
+        to: WorldArea,
+        profile: &NavigationProfile,
+        context: &PathSearchContext,
+    ) -> Result<AreaPath, NavigationError> {
+        Ok(self.area_graph.find_area_path(
+            from,
+            to,
+            profile,
+            |door| self.door_owner(door),
+            &context.area,
+        )?)
+    }
+
+    fn find_block_path(
+        &self,
+        area: WorldArea,
+        from: BlockPosition,
+        to: BlockPosition,
+        target: SearchGoal,
+        profile: &NavigationProfile,
+        context: &PathSearchContext,
+    ) -> Result<BlockPath, NavigationError> {
+        let block_graph = self
+            .block_graphs
+            .get(&area)
+            .ok_or(NavigationError::NoSuchArea(area))?;
+
+        block_graph
+            .find_block_path(
+                from,
+                to,
+                target,
+                profile,
+                |door| self.door_owner(door.to_world_position(area.chunk)),
+                |block| self.congestion(block.to_world_position(area.chunk)),
+                &context.block,
+            )
+            .map_err(|e| NavigationError::BlockError(area, e))
+    }
+
+    /// Finds the area path then expands it to blocks. Failure is reported as
+    /// [NavigationError::Aborted] if the context's search was cancelled
+    pub fn find_path(
+        &self,
+        endpoints: &PathEndpoints,
+        profile: &NavigationProfile,
+        context: &PathSearchContext,
+    ) -> Result<WorldPath, NavigationError> {
+        self.find_path_uncancelled(endpoints, profile, context)
+            .map_err(|err| {
+                if context.is_cancelled() {
+                    NavigationError::Aborted
+                } else {
+                    err
+                }
+            })
+    }
+
+    fn find_path_uncancelled(
+        &self,
+        endpoints: &PathEndpoints,
+        profile: &NavigationProfile,
+        context: &PathSearchContext,
+    ) -> Result<WorldPath, NavigationError> {
+        let PathEndpoints {
+            from,
+            from_area,
+            to,
+            to_area,
+            goal,
+        } = *endpoints;
+
+        // same blocks
+        if from == to {
+            return Ok(WorldPath::new(Vec::new(), to));
+        }
+
+        // find area path
+        let area_path = self.find_area_path(from_area, to_area, profile, context)?;
+
+        // TODO optimize path with raytracing (#50)
+        // TODO only calculate path for each area as needed (#51)
+
+        // stupidly expand to block level path right now
+        let mut full_path = Vec::with_capacity(CHUNK_SIZE.as_usize() / 2 * area_path.0.len()); // random estimate
+        let mut start = BlockPosition::from(from);
+
+        for (a, b) in area_path.0.iter().tuple_windows() {
+            // unwrap ok because all except the first are Some
+            let b_entry: AreaNavEdge = b.entry.unwrap();
+            let exit = b_entry.exit_closest(start);
+
+            // block path from last point to exiting this area
+            let block_path =
+                self.find_block_path(a.area, start, exit, SearchGoal::Arrive, profile, context)?;
+            full_path.extend(convert_block_path(a.area, block_path));
+
+            // add transition edge from exit of this area to entering the next
+            full_path.push(WorldPathNode {
+                block: exit.to_world_position(a.area.chunk),
+                exit_cost: b_entry.cost,
+            });
+
+            // continue from the entry point in the next chunk
+            start = b_entry.entry(exit);
+        }
+
+        // final block path from entry of final area to goal
+        let final_area = area_path.0.last().unwrap();
+        let block_path =
+            self.find_block_path(final_area.area, start, to.into(), goal, profile, context)?;
+        let real_target = block_path.target.to_world_position(final_area.area.chunk);
+        full_path.extend(convert_block_path(final_area.area, block_path));
+
+        Ok(WorldPath::new(full_path, real_target))
+    }
+}
+
+fn convert_block_path(area: WorldArea, path: BlockPath) -> impl Iterator<Item = WorldPathNode> {
+    path.path.into_iter().map(move |n| WorldPathNode {
+        block: n.block.to_world_position(area.chunk),
+        exit_cost: n.exit_cost,
+    })
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter::once;
 
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -6,7 +6,6 @@ use std::sync::Arc;
 
 use tokio::sync::broadcast;
 
-use common::derive_more::Constructor;
 use common::*;
 use unit::world::CHUNK_SIZE;
 use unit::world::{
@@ -17,10 +16,12 @@ use world_types::{BlockDurability, BlockType, EntityDescription};
 
 use crate::block::Block;
 use crate::chunk::{BaseTerrain, BlockDamageResult, Chunk};
+use crate::liquid::LiquidLevel;
 use crate::loader::{LoadedSlab, SlabTerrainUpdate};
 use crate::navigation::{
-    AreaGraph, AreaGraphSearchContext, AreaNavEdge, AreaPath, BlockGraph, BlockGraphSearchContext,
-    BlockPath, ExploreResult, NavigationError, SearchGoal, WorldArea, WorldPath, WorldPathNode,
+    AreaGraph, AreaNavEdge, AreaPath, BlockGraphs, DoorOwner, ExploreResult, NavigationError,
+    NavigationGraphs, NavigationProfile, NavigationSnapshot, PathEndpoints, PathSearchContext,
+    SearchGoal, WorldArea, WorldPath,
 };
 use crate::neighbour::{NeighbourOffset, WorldNeighbours};
 use crate::{OcclusionChunkUpdate, SliceRange};
@@ -32,12 +33,23 @@ pub trait WorldContext: 'static + Send + Sync {
 /// All mutable world changes must go through `loader.apply_terrain_updates`
 pub struct World<C: WorldContext> {
     chunks: Vec<Chunk<C>>,
-    area_graph: AreaGraph,
+
+    /// Navigation graphs are shared with snapshots taken for path finding, and cloned on write
+    /// while any are still alive
+    area_graph: Arc<AreaGraph>,
+    /// Block graph of each area, as of when its chunk was last finalized
+    block_graphs: Arc<BlockGraphs>,
+
     dirty_slabs: HashSet<SlabLocation>,
     entities_to_spawn: Vec<EntityDescription>,
     load_notifier: LoadNotifier,
-    block_search_context: BlockGraphSearchContext,
-    area_search_context: AreaGraphSearchContext,
+    search_context: PathSearchContext,
+
+    /// Doors not in here are unowned and can be opened by anyone able to open doors
+    door_owners: Arc<HashMap<WorldPosition, DoorOwner>>,
+
+    /// Number of agents in each occupied block, refreshed periodically for congestion avoidance
+    congestion: Arc<HashMap<WorldPosition, u16>>,
 }
 
 pub struct LoadNotifier {
@@ -55,11 +67,53 @@ pub enum WaitResult {
     Retry,
 }
 
-#[derive(Constructor)]
 pub struct WorldChangeEvent {
     pub pos: WorldPosition,
     pub prev: BlockType,
     pub new: BlockType,
+
+    /// (prev, new) liquid levels if the liquid in this block changed
+    pub liquid: Option<(LiquidLevel, LiquidLevel)>,
+}
+
+impl WorldChangeEvent {
+    pub fn new(pos: WorldPosition, prev: BlockType, new: BlockType) -> Self {
+        Self {
+            pos,
+            prev,
+            new,
+            liquid: None,
+        }
+    }
+
+    /// Includes the liquid in the replaced block, which is cleared by the new one
+    pub(crate) fn replacing(pos: WorldPosition, prev: Block, new: BlockType) -> Self {
+        let liquid = prev.liquid();
+        Self {
+            pos,
+            prev: prev.block_type(),
+            new,
+            liquid: if liquid.is_empty() {
+                None
+            } else {
+                Some((liquid, LiquidLevel::EMPTY))
+            },
+        }
+    }
+
+    pub fn with_liquid(
+        pos: WorldPosition,
+        block_type: BlockType,
+        prev: LiquidLevel,
+        new: LiquidLevel,
+    ) -> Self {
+        Self {
+            pos,
+            prev: block_type,
+            new: block_type,
+            liquid: Some((prev, new)),
+        }
+    }
 }
 
 impl<C: WorldContext> Default for World<C> {
@@ -112,12 +166,14 @@ impl<C: WorldContext> World<C> {
     pub fn empty() -> Self {
         Self {
             chunks: Vec::new(),
-            area_graph: AreaGraph::default(),
+            area_graph: Arc::new(AreaGraph::default()),
+            block_graphs: Arc::new(BlockGraphs::new()),
             dirty_slabs: HashSet::with_capacity(32),
             entities_to_spawn: Vec::default(),
             load_notifier: LoadNotifier::default(),
-            block_search_context: BlockGraph::search_context(),
-            area_search_context: AreaGraph::search_context(),
+            search_context: PathSearchContext::default(),
+            door_owners: Arc::new(HashMap::new()),
+            congestion: Arc::new(HashMap::new()),
         }
     }
 
@@ -164,58 +220,91 @@ impl<C: WorldContext> World<C> {
         &self,
         from: F,
         to: T,
+        profile: &NavigationProfile,
+        context: &PathSearchContext,
     ) -> Result<AreaPath, NavigationError> {
-        // resolve areas
-        let resolve_area = |pos: WorldPosition| {
-            let chunk_pos: ChunkLocation = pos.into();
-            self.find_chunk_with_pos(chunk_pos)
-                .and_then(|c| c.area_for_block(pos.into()))
-        };
-
         let from = from.into();
         let to = to.into();
 
-        let from_area = resolve_area(from).ok_or(NavigationError::SourceNotWalkable(from))?;
+        let from_area = self
+            .resolve_area(from)
+            .ok_or(NavigationError::SourceNotWalkable(from))?;
+        let to_area = self
+            .resolve_area(to)
+            .ok_or(NavigationError::TargetNotWalkable(to))?;
 
-        let to_area = resolve_area(to).ok_or(NavigationError::TargetNotWalkable(to))?;
+        self.navigation_graphs()
+            .find_area_path(from_area, to_area, profile, context)
+    }
 
-        Ok(self
-            .area_graph
-            .find_area_path(from_area, to_area, &self.area_search_context)?)
+    fn resolve_area(&self, pos: WorldPosition) -> Option<WorldArea> {
+        self.find_chunk_with_pos(ChunkLocation::from(pos))
+            .and_then(|c| c.area_for_block(pos.into()))
     }
 
-    fn find_block_path(
-        &self,
-        area: WorldArea,
-        from: BlockPosition,
-        to: BlockPosition,
-        target: SearchGoal,
-    ) -> Result<BlockPath, NavigationError> {
-        let block_graph = self
-            .find_chunk_with_pos(area.chunk)
-            .and_then(|c| c.block_graph_for_area(area))
-            .ok_or(NavigationError::NoSuchArea(area))?;
-
-        block_graph
-            .find_block_path(from, to, target, &self.block_search_context)
-            .map_err(|e| NavigationError::BlockError(area, e))
-    }
-
-    /// Finds a path between 2 arbitrary positions in the world
+    fn navigation_graphs(&self) -> NavigationGraphs<'_> {
+        NavigationGraphs {
+            area_graph: &self.area_graph,
+            block_graphs: &self.block_graphs,
+            door_owners: &self.door_owners,
+            congestion: &self.congestion,
+        }
+    }
+
+    /// Shares the current navigation graphs to search for paths in without holding onto the world
+    pub fn navigation_snapshot(&self) -> NavigationSnapshot {
+        NavigationSnapshot {
+            area_graph: self.area_graph.clone(),
+            block_graphs: self.block_graphs.clone(),
+            door_owners: self.door_owners.clone(),
+            congestion: self.congestion.clone(),
+        }
+    }
+
+    /// Finds a path between 2 arbitrary positions in the world for the given agent
     pub fn find_path<F: Into<WorldPosition>, T: Into<WorldPosition>>(
         &self,
         from: F,
         to: T,
+        profile: &NavigationProfile,
     ) -> Result<WorldPath, NavigationError> {
-        self.find_path_with_goal(from.into(), to.into(), SearchGoal::Arrive)
+        self.find_path_with_goal(from.into(), to.into(), SearchGoal::Arrive, profile)
     }
 
+    /// Only traverses blocks passable by the given agent, i.e. gaps wide enough and doors it can
+    /// open. Note congestion is only considered within areas, not when crossing area boundaries
     pub fn find_path_with_goal(
         &self,
         from: WorldPosition,
         to: WorldPosition,
         goal: SearchGoal,
+        profile: &NavigationProfile,
     ) -> Result<WorldPath, NavigationError> {
+        self.find_path_in_context(from, to, goal, profile, &self.search_context)
+    }
+
+    /// Uses the given search context instead of the world's own
+    pub fn find_path_in_context(
+        &self,
+        from: WorldPosition,
+        to: WorldPosition,
+        goal: SearchGoal,
+        profile: &NavigationProfile,
+        context: &PathSearchContext,
+    ) -> Result<WorldPath, NavigationError> {
+        let endpoints = self.resolve_path_endpoints(from, to, goal)?;
+        self.navigation_graphs()
+            .find_path(&endpoints, profile, context)
+    }
+
+    /// Finds the accessible blocks and areas to path between, which needs the terrain. The path
+    /// itself only needs the navigation graphs, so can be found later in a [NavigationSnapshot]
+    pub fn resolve_path_endpoints(
+        &self,
+        from: WorldPosition,
+        to: WorldPosition,
+        goal: SearchGoal,
+    ) -> Result<PathEndpoints, NavigationError> {
         let from = self
             .find_accessible_block_in_column_with_range(from, None)
             .ok_or(NavigationError::SourceNotWalkable(from))?;
@@ -249,56 +338,19 @@ impl<C: WorldContext> World<C> {
         }
         .ok_or(NavigationError::TargetNotWalkable(to))?;
 
-        // same blocks
-        if from == to {
-            return Ok(WorldPath::new(Vec::new(), to));
-        }
-
-        // find area path
-        let area_path = self.find_area_path(from, to)?;
-
-        // TODO optimize path with raytracing (#50)
-        // TODO only calculate path for each area as needed (#51)
-
-        // stupidly expand to block level path right now
-        let mut full_path = Vec::with_capacity(CHUNK_SIZE.as_usize() / 2 * area_path.0.len()); // random estimate
-        let mut start = BlockPosition::from(from);
-
-        for (a, b) in area_path.0.iter().tuple_windows() {
-            // unwrap ok because all except the first are Some
-            let b_entry: AreaNavEdge = b.entry.unwrap();
-            let exit = b_entry.exit_closest(start);
-
-            // block path from last point to exiting this area
-            let block_path = self.find_block_path(a.area, start, exit, SearchGoal::Arrive)?;
-            full_path.extend(Self::convert_block_path(a.area, block_path));
-
-            // add transition edge from exit of this area to entering the next
-            full_path.push(WorldPathNode {
-                block: exit.to_world_position(a.area.chunk),
-                exit_cost: b_entry.cost,
-            });
-
-            // continue from the entry point in the next chunk
-            start = {
-                let extended = b_entry.direction.extend_across_boundary_aligned(exit);
-                extended.above_by(b_entry.cost.z_offset())
-            };
-        }
-
-        // final block path from entry of final area to goal
-        let final_area = area_path.0.last().unwrap();
-        let block_path = self.find_block_path(final_area.area, start, to.into(), goal)?;
-        let real_target = block_path.target.to_world_position(final_area.area.chunk);
-        full_path.extend(Self::convert_block_path(final_area.area, block_path));
-
-        Ok(WorldPath::new(full_path, real_target))
-    }
-
-    fn convert_block_path(area: WorldArea, path: BlockPath) -> impl Iterator<Item = WorldPathNode> {
-        path.path.into_iter().map(move |n| WorldPathNode {
-            block: n.block.to_world_position(area.chunk),
-            exit_cost: n.exit_cost,
+        let from_area = self
+            .resolve_area(from)
+            .ok_or(NavigationError::SourceNotWalkable(from))?;
+        let to_area = self
+            .resolve_area(to)
+            .ok_or(NavigationError::TargetNotWalkable(to))?;
+
+        Ok(PathEndpoints {
+            from,
+            from_area,
+            to,
+            to_area,
+            goal,
         })
     }
 
@@ -314,7 +366,7 @@ impl<C: WorldContext> World<C> {
             .and_then(|pos| self.area(from).ok().map(|area| (pos, area)))
             .ok_or(NavigationError::SourceNotWalkable(from))?;
 
-        let mut random = thread_rng();
+        let mut rng = random::get();
 
         // loop vars
         let mut current_pos = from;
@@ -331,8 +383,8 @@ impl<C: WorldContext> World<C> {
             let (explore_result, target_block) = block_graph.explore(
                 current_pos.into(),
                 &mut fuel,
-                &self.block_search_context,
-                &mut random,
+                &self.search_context.block,
+                &mut *rng,
                 filter.as_ref().map(|func| (func, current_chunk)),
             );
 
@@ -350,7 +402,7 @@ impl<C: WorldContext> World<C> {
             let (next_block, next_area) = match self
                 .find_candidate_chunks_to_explore(current_final_target)
                 .collect::<ArrayVec<_, 3>>()
-                .choose(&mut random)
+                .choose(&mut *rng)
             {
                 Some(next) => *next,
                 _ => break,
@@ -387,19 +439,35 @@ impl<C: WorldContext> World<C> {
         })
     }
 
-    /// Cheap check if an area path exists between the areas of the 2 blocks
-    pub fn path_exists(&self, from: WorldPosition, to: WorldPosition) -> bool {
+    /// Cheap check if an area path exists between the areas of the 2 blocks for the given agent
+    pub fn path_exists(
+        &self,
+        from: WorldPosition,
+        to: WorldPosition,
+        profile: &NavigationProfile,
+    ) -> bool {
         self.area(from)
             .ok()
             .and_then(|from| self.area(to).ok().map(|to| (from, to)))
-            .map(|(from, to)| self.area_path_exists(from, to))
+            .map(|(from, to)| self.area_path_exists(from, to, profile))
             .unwrap_or(false)
     }
 
-    /// Cheap check if an path exists between the 2 areas
-    pub fn area_path_exists(&self, from: WorldArea, to: WorldArea) -> bool {
-        self.area_graph
-            .path_exists(from, to, &self.area_search_context)
+    /// Cheap check if an path exists between the 2 areas for the given agent. Doors within the
+    /// areas themselves are not considered
+    pub fn area_path_exists(
+        &self,
+        from: WorldArea,
+        to: WorldArea,
+        profile: &NavigationProfile,
+    ) -> bool {
+        self.area_graph.path_exists(
+            from,
+            to,
+            profile,
+            |door| self.door_owner(door),
+            &self.search_context.area,
+        )
     }
 
     pub fn find_accessible_block_in_column(&self, x: i32, y: i32) -> Option<WorldPosition> {
@@ -454,7 +522,7 @@ impl<C: WorldContext> World<C> {
         None
     }
 
-    pub(in crate) fn ensure_chunk(&mut self, chunk: ChunkLocation) -> &mut Chunk<C> {
+    pub(crate) fn ensure_chunk(&mut self, chunk: ChunkLocation) -> &mut Chunk<C> {
         let idx = match self.find_chunk_index(chunk) {
             Ok(idx) => idx,
             Err(idx) => {
@@ -485,35 +553,40 @@ impl<C: WorldContext> World<C> {
         area_nav: &[(WorldArea, WorldArea, AreaNavEdge)],
         slab_range: (SlabIndex, SlabIndex),
     ) {
-        // add all areas even if they currently have no edges
-        {
-            // safety: area graph is totally separate from chunk lookup
-            let naughty_area_graph = unsafe { &mut *(&mut self.area_graph as *mut AreaGraph) };
-
-            let chunk = self.find_chunk_with_pos(chunk_loc).expect("no such chunk");
+        let chunk = self.find_chunk_with_pos(chunk_loc).expect("no such chunk");
+        let new_graphs = chunk
+            .block_graphs()
+            .map(|(area, graph)| (area.into_world_area(chunk_loc), graph.clone()))
+            .collect_vec();
 
-            // remove all previous areas and edges for the slab range in this chunk
-            let removed = naughty_area_graph.retain(|area| {
-                !(area.chunk == chunk_loc
-                    && (area.slab >= slab_range.0 && area.slab <= slab_range.1))
-            });
+        let area_graph = Arc::make_mut(&mut self.area_graph);
+        let block_graphs = Arc::make_mut(&mut self.block_graphs);
 
-            for area in chunk.areas() {
-                trace!("has area {:?}", area);
-                naughty_area_graph.add_node(area.into_world_area(chunk_loc));
-            }
+        // remove all previous areas and edges for the slab range in this chunk
+        let is_removed = |area: &WorldArea| {
+            area.chunk == chunk_loc && (area.slab >= slab_range.0 && area.slab <= slab_range.1)
+        };
+        let removed = area_graph.retain(|area| !is_removed(area));
+        block_graphs.retain(|area, _| !is_removed(area));
 
-            let added = chunk.area_count();
-            debug!(
-                "removed {removed} areas and added {added}",
-                removed = removed,
-                added = added
-            );
+        // add all areas even if they currently have no edges
+        let added = new_graphs.len();
+        for (area, graph) in new_graphs {
+            trace!("has area {:?}", area);
+            area_graph.add_node(area);
+            area_graph.set_movement_cost(area, graph.mean_movement_cost());
+            block_graphs.insert(area, graph);
         }
 
+        debug!(
+            "removed {removed} areas and added {added}",
+            removed = removed,
+            added = added
+        );
+
         // update area nodes and edges
         for &(src, dst, edge) in area_nav {
-            self.area_graph.add_edge(src, dst, edge);
+            area_graph.add_edge(src, dst, edge);
         }
 
         // mark slabs dirty
@@ -643,6 +716,65 @@ impl<C: WorldContext> World<C> {
             .and_then(|chunk| chunk.get_block(pos.into()))
     }
 
+    /// Only sets liquid in loaded slabs, returning the block type and previous level. Mutates
+    /// terrain silently to the loader, use `loader.apply_liquid_updates` instead
+    pub(crate) fn set_liquid_level(
+        &mut self,
+        pos: WorldPosition,
+        level: LiquidLevel,
+    ) -> Option<(BlockType, LiquidLevel)> {
+        let chunk = self.find_chunk_with_pos_mut(ChunkLocation::from(pos))?;
+        if !chunk.is_slab_loaded(pos.slice().slab_index()) {
+            return None;
+        }
+
+        let mut slice = chunk.slice_mut_with_cow(pos.slice())?;
+        let block = &mut slice[BlockPosition::from(pos)];
+        let prev = std::mem::replace(block.liquid_mut(), level);
+        Some((block.block_type(), prev))
+    }
+
+    /// Owner of the door at the given position, if any. Doesn't check there is still a door there
+    pub fn door_owner(&self, pos: WorldPosition) -> Option<DoorOwner> {
+        self.door_owners.get(&pos).copied()
+    }
+
+    /// None to clear ownership so it can be opened by anyone
+    pub fn set_door_owner(&mut self, pos: WorldPosition, owner: Option<DoorOwner>) {
+        let door_owners = Arc::make_mut(&mut self.door_owners);
+        match owner {
+            Some(owner) => {
+                door_owners.insert(pos, owner);
+            }
+            None => {
+                door_owners.remove(&pos);
+            }
+        }
+    }
+
+    /// Owned doors that still exist
+    pub fn owned_doors(&self) -> impl Iterator<Item = (WorldPosition, DoorOwner)> + '_ {
+        self.door_owners.iter().filter_map(move |(&pos, &owner)| {
+            self.block(pos)
+                .filter(|b| b.block_type().is_door())
+                .map(|_| (pos, owner))
+        })
+    }
+
+    /// Number of agents in the given block as of the last [World::set_congestion]
+    pub fn congestion(&self, pos: WorldPosition) -> u16 {
+        self.congestion.get(&pos).copied().unwrap_or(0)
+    }
+
+    /// Replaces all congestion with the given agent positions, duplicates are counted
+    pub fn set_congestion(&mut self, agents: impl Iterator<Item = WorldPosition>) {
+        let mut congestion = HashMap::new();
+        for pos in agents {
+            *congestion.entry(pos).or_default() += 1;
+        }
+        self.congestion = Arc::new(congestion);
+    }
+
     /// Mutates terrain silently to the loader, ensure the loader knows about this
     pub fn damage_block(
         &mut self,
@@ -717,6 +849,7 @@ impl<C: WorldContext> World<C> {
         &self,
         accessible_from: WorldPosition,
         radius: u16,
+        profile: &NavigationProfile,
         max_attempts: usize,
     ) -> Option<WorldPosition> {
         let src_area = self.area(accessible_from).ok()?;
@@ -729,7 +862,7 @@ impl<C: WorldContext> World<C> {
             |pos| {
                 self.area(pos)
                     .ok()
-                    .map(|area| self.area_path_exists(src_area, area))
+                    .map(|area| self.area_path_exists(src_area, area, profile))
                     .unwrap_or(false)
             },
             max_attempts,
@@ -780,11 +913,11 @@ impl<C: WorldContext> World<C> {
     pub fn filter_reachable_blocks_in_range<'a>(
         &'a self,
         range: &WorldPositionRange,
-        mut f: impl FnMut(BlockType) -> bool + 'a,
+        mut f: impl FnMut(Block) -> bool + 'a,
     ) -> impl Iterator<Item = WorldPosition> + 'a {
         self.filter_blocks_in_range(range, move |b, pos| {
-            // check block type
-            if !f(b.block_type()) {
+            // check block
+            if !f(b) {
                 return false;
             }
 
@@ -1241,6 +1374,19 @@ pub mod helpers {
     pub fn loader_from_chunks_blocking(
         chunks: Vec<ChunkDescriptor>,
     ) -> WorldLoader<DummyWorldContext> {
+        context_loader_from_chunks_blocking(chunks)
+    }
+
+    /// For tests in crates with their own world context
+    pub fn context_world_from_chunks_blocking<C: WorldContext>(
+        chunks: Vec<ChunkDescriptor>,
+    ) -> WorldRef<C> {
+        context_loader_from_chunks_blocking(chunks).world()
+    }
+
+    fn context_loader_from_chunks_blocking<C: WorldContext>(
+        chunks: Vec<ChunkDescriptor>,
+    ) -> WorldLoader<C> {
         let source = MemoryTerrainSource::from_chunks(chunks.into_iter()).expect("bad chunks");
         load_world(source, AsyncWorkerPool::new_blocking().unwrap())
     }
@@ -1320,14 +1466,14 @@ mod tests {
     use crate::chunk::ChunkBuilder;
     use crate::helpers::load_world;
     use crate::loader::{AsyncWorkerPool, MemoryTerrainSource, WorldLoader, WorldTerrainUpdate};
-    use crate::navigation::EdgeCost;
+    use crate::navigation::{DoorOwner, EdgeCost, NavigationProfile, WorldPath};
     use crate::occlusion::{NeighbourOpacity, VertexOcclusion};
     use crate::presets::from_preset;
     use crate::world::helpers::{
         apply_updates, loader_from_chunks_blocking, world_from_chunks_blocking,
     };
     use crate::world::ContiguousChunkIterator;
-    use crate::{presets, BaseTerrain, OcclusionChunkUpdate, SearchGoal, WorldContext};
+    use crate::{presets, BaseTerrain, OcclusionChunkUpdate, SearchGoal, World, WorldContext};
 
     #[test]
     fn world_path_single_block_in_y_direction() {
@@ -1337,7 +1483,7 @@ mod tests {
         .into_inner();
 
         let path = w
-            .find_path((2, 2, 2), (2, 3, 2))
+            .find_path((2, 2, 2), (2, 3, 2), &NavigationProfile::default())
             .expect("path should succeed");
 
         assert_eq!(path.path().len(), 1);
@@ -1350,8 +1496,14 @@ mod tests {
             .build((0, 0))])
         .into_inner();
 
+        // immediately satisfied
         let path = w
-            .find_path_with_goal((2, 2, 2).into(), (4, 3, 2).into(), SearchGoal::Nearby(5)) // immediately satisfied
+            .find_path_with_goal(
+                (2, 2, 2).into(),
+                (4, 3, 2).into(),
+                SearchGoal::Nearby(5),
+                &NavigationProfile::default(),
+            )
             .expect("path should succeed");
 
         assert_eq!(path.path().len(), 1);
@@ -1442,13 +1594,203 @@ mod tests {
         .into_inner();
 
         let path = world
-            .find_path((0, 0, 4), (8, 8, 4))
+            .find_path((0, 0, 4), (8, 8, 4), &NavigationProfile::default())
             .expect("path should succeed");
 
         assert_eq!(path.path().first().unwrap().exit_cost, EdgeCost::JumpDown);
         assert_eq!(path.path().last().unwrap().exit_cost, EdgeCost::JumpUp);
     }
 
+    #[test]
+    fn world_path_climb_ladder_across_slabs() {
+        // cliff crossing the slab boundary with a ladder up the side of it
+        let top = SLAB_SIZE.as_i32() + 3;
+        let world = world_from_chunks_blocking(vec![ChunkBuilder::new()
+            .fill_slice(1, BlockType::Stone)
+            .fill_range((6, 0, 2), (10, 10, top), |_| BlockType::Stone)
+            .fill_range((5, 4, 2), (5, 4, top), |_| BlockType::Ladder)
+            .build((0, 0))])
+        .into_inner();
+
+        let path = world
+            .find_path((2, 4, 2), (8, 4, top + 1), &NavigationProfile::default())
+            .expect("path should succeed");
+
+        let climbs = path
+            .path()
+            .iter()
+            .filter(|node| node.exit_cost == EdgeCost::ClimbUp)
+            .count();
+        assert!(climbs >= (top - 2) as usize, "only climbed {}", climbs);
+
+        // and back down again
+        let path = world
+            .find_path((8, 4, top + 1), (2, 4, 2), &NavigationProfile::default())
+            .expect("path should succeed");
+
+        assert!(path
+            .path()
+            .iter()
+            .any(|node| node.exit_cost == EdgeCost::ClimbDown));
+    }
+
+    #[test]
+    fn world_path_through_door() {
+        // wall across the whole chunk with a single door in it
+        let mut world = world_from_chunks_blocking(vec![ChunkBuilder::new()
+            .fill_slice(1, BlockType::Stone)
+            .fill_range((6, 0, 2), (6, 15, 4), |_| BlockType::Stone)
+            .set_block((6, 4, 2), BlockType::Door)
+            .build((0, 0))])
+        .into_inner();
+
+        let find_path = |world: &World<_>, profile: NavigationProfile| {
+            world.find_path_with_goal(
+                WorldPosition::from((2, 4, 2)),
+                WorldPosition::from((10, 4, 2)),
+                SearchGoal::Arrive,
+                &profile,
+            )
+        };
+
+        let path = find_path(&world, NavigationProfile::default()).expect("path should succeed");
+        assert!(path
+            .path()
+            .iter()
+            .any(|node| node.block == WorldPosition::from((6, 4, 2))));
+
+        let cant_open = NavigationProfile {
+            can_open_doors: false,
+            ..NavigationProfile::default()
+        };
+        assert!(find_path(&world, cant_open).is_err());
+
+        // owned by someone else
+        world.set_door_owner((6, 4, 2).into(), Some(DoorOwner(1)));
+        let stranger = NavigationProfile {
+            owner: Some(DoorOwner(2)),
+            ..NavigationProfile::default()
+        };
+        let owner = NavigationProfile {
+            owner: Some(DoorOwner(1)),
+            ..NavigationProfile::default()
+        };
+        assert!(find_path(&world, NavigationProfile::default()).is_err());
+        assert!(find_path(&world, stranger).is_err());
+        assert!(find_path(&world, owner).is_ok());
+        assert_eq!(world.owned_doors().count(), 1);
+    }
+
+    #[test]
+    fn world_path_through_door_between_chunks() {
+        // wall along the chunk boundary with a single door in it, on the entering side
+        let mut world = world_from_chunks_blocking(vec![
+            ChunkBuilder::new()
+                .fill_slice(1, BlockType::Stone)
+                .build((0, 0)),
+            ChunkBuilder::new()
+                .fill_slice(1, BlockType::Stone)
+                .fill_range((0, 0, 2), (0, 15, 4), |_| BlockType::Stone)
+                .set_block((0, 4, 2), BlockType::Door)
+                .build((1, 0)),
+        ])
+        .into_inner();
+
+        let from = WorldPosition::from((2, 4, 2));
+        let to = WorldPosition::from((20, 4, 2));
+        let cant_open = NavigationProfile {
+            can_open_doors: false,
+            ..NavigationProfile::default()
+        };
+        assert!(world.path_exists(from, to, &NavigationProfile::default()));
+        assert!(!world.path_exists(from, to, &cant_open));
+        assert!(world.find_path(from, to, &cant_open).is_err());
+
+        // owned by someone else
+        world.set_door_owner((16, 4, 2).into(), Some(DoorOwner(1)));
+        let owner = NavigationProfile {
+            owner: Some(DoorOwner(1)),
+            ..NavigationProfile::default()
+        };
+        assert!(!world.path_exists(from, to, &NavigationProfile::default()));
+        assert!(world.path_exists(from, to, &owner));
+        assert!(world.find_path(from, to, &owner).is_ok());
+    }
+
+    #[test]
+    fn world_path_too_wide_for_gap() {
+        // wall across the whole chunk with a 1 block gap in it
+        let world = world_from_chunks_blocking(vec![ChunkBuilder::new()
+            .fill_slice(1, BlockType::Stone)
+            .fill_range((6, 0, 2), (6, 15, 4), |_| BlockType::Stone)
+            .fill_range((6, 4, 2), (6, 4, 4), |_| BlockType::Air)
+            .build((0, 0))])
+        .into_inner();
+
+        let find_path = |size| {
+            world.find_path_with_goal(
+                WorldPosition::from((2, 4, 2)),
+                WorldPosition::from((10, 4, 2)),
+                SearchGoal::Arrive,
+                &NavigationProfile {
+                    size,
+                    ..NavigationProfile::default()
+                },
+            )
+        };
+
+        assert!(find_path(1).is_ok());
+        assert!(find_path(2).is_err());
+    }
+
+    #[test]
+    fn world_path_avoids_slow_ground() {
+        // strip of sand along the direct route
+        let world = world_from_chunks_blocking(vec![ChunkBuilder::new()
+            .fill_slice(1, BlockType::Stone)
+            .fill_range((3, 4, 1), (9, 4, 1), |_| BlockType::Sand)
+            .build((0, 0))])
+        .into_inner();
+
+        let path = world
+            .find_path((2, 4, 2), (10, 4, 2), &NavigationProfile::default())
+            .expect("path should succeed");
+
+        assert!(path.path().iter().all(|node| {
+            let ground = world.block(node.block.below()).unwrap().block_type();
+            ground != BlockType::Sand
+        }));
+    }
+
+    #[test]
+    fn world_path_avoids_congestion() {
+        let mut world = world_from_chunks_blocking(vec![ChunkBuilder::new()
+            .fill_slice(1, BlockType::Stone)
+            .build((0, 0))])
+        .into_inner();
+
+        let crowded = WorldPosition::from((6, 4, 2));
+        world.set_congestion(std::iter::repeat(crowded).take(3));
+
+        let find_path = |avoid_congestion| {
+            world
+                .find_path_with_goal(
+                    WorldPosition::from((2, 4, 2)),
+                    WorldPosition::from((10, 4, 2)),
+                    SearchGoal::Arrive,
+                    &NavigationProfile {
+                        avoid_congestion,
+                        ..NavigationProfile::default()
+                    },
+                )
+                .expect("path should succeed")
+        };
+
+        let through_crowd = |path: WorldPath| path.path().iter().any(|node| node.block == crowded);
+        assert!(through_crowd(find_path(false)));
+        assert!(!through_crowd(find_path(true)));
+    }
+
     #[test]
     fn world_path_cross_areas() {
         // cross chunks
@@ -1478,7 +1820,9 @@ mod tests {
         let from = BlockPosition::new_unchecked(0, 2, 5.into()).to_world_position((3, 5));
         let to = BlockPosition::new_unchecked(5, 8, 7.into()).to_world_position((6, 3));
 
-        let path = world.find_path(from, to).expect("path should succeed");
+        let path = world
+            .find_path(from, to, &NavigationProfile::default())
+            .expect("path should succeed");
         assert_eq!(path.target(), to);
 
         // all should be adjacent
@@ -1510,7 +1854,9 @@ mod tests {
         let dst =
             BlockPosition::new_unchecked(5, 5, GlobalSliceIndex::top()).to_world_position((-1, 1));
 
-        let _ = world.find_path(src, dst).expect("path should succeed");
+        let _ = world
+            .find_path(src, dst, &NavigationProfile::default())
+            .expect("path should succeed");
     }
 
     #[test]
@@ -1521,7 +1867,12 @@ mod tests {
         .into_inner();
 
         let path = world
-            .find_path_with_goal((2, 2, 3).into(), (6, 2, 3).into(), SearchGoal::Adjacent)
+            .find_path_with_goal(
+                (2, 2, 3).into(),
+                (6, 2, 3).into(),
+                SearchGoal::Adjacent,
+                &NavigationProfile::default(),
+            )
             .expect("path should succeed");
 
         // target should be the adjacent block, not the given target
@@ -1809,7 +2160,7 @@ mod tests {
 
         let is_reachable = |xyz: (i32, i32, i32)| {
             let range = WorldPositionRange::Single(xyz.into());
-            w.filter_reachable_blocks_in_range(&range, |bt| bt != BlockType::Air)
+            w.filter_reachable_blocks_in_range(&range, |b| b.block_type() != BlockType::Air)
                 .count()
                 == 1
         };
@@ -1,33 +1,21 @@
-//! Block type definitions, shared between procgen, the voxel world and simulation. Will eventually
-//! be defined in data instead
-// TODO define block types in data
-
-use common::{derive_more::Display, Proportion};
-use strum::{EnumIter, EnumString};
-
-#[derive(
-    Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd, EnumIter, EnumString, Display,
-)]
-pub enum BlockType {
-    Air,
-    Dirt,
-    Grass,
-    #[display(fmt = "Light grass")]
-    LightGrass,
-    Leaves,
-    #[display(fmt = "Tree trunk")]
-    TreeTrunk,
-    Stone,
-    Sand,
-    #[display(fmt = "Solid water")]
-    SolidWater,
-
-    /// Temporary substitute for something to build
-    #[display(fmt = "Stone wall")]
-    StoneBrickWall,
-
-    Chest,
-}
+//! Block type definitions, shared between procgen, the voxel world and simulation. Block types
+//! and their properties are defined in data and registered once at startup with
+//! [BlockTypes::init]. Builtin block types that the engine refers to directly have fixed ids, but
+//! must still be defined in data.
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use color::Color;
+use common::*;
+use once_cell::sync::OnceCell;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+
+/// Compact id of a block type, stored in every block
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[repr(transparent)]
+pub struct BlockType(u16);
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum BlockOpacity {
@@ -37,6 +25,101 @@ pub enum BlockOpacity {
 
 pub type BlockDurability = u8;
 
+/// Properties of a block type as declared in data
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockTypeDefinition {
+    /// Unique identifier, must match the builtin name to define a builtin block type
+    pub name: String,
+    /// Defaults to `name`
+    #[serde(default, rename = "display")]
+    pub display_name: Option<String>,
+    #[serde(deserialize_with = "deserialize_hex_color")]
+    pub color: Color,
+    pub durability: BlockDurability,
+    #[serde(default, deserialize_with = "deserialize_opacity")]
+    pub opacity: BlockOpacity,
+    pub walkable: bool,
+    /// Can be climbed vertically like a ladder, regardless of what's below it
+    #[serde(default)]
+    pub climbable: bool,
+    /// Solid but can be passed through by agents that are able to open it
+    #[serde(default)]
+    pub door: bool,
+    /// Multiplier for the cost of walking on top of or climbing this block, lower is faster.
+    /// Must be positive
+    #[serde(
+        default = "default_movement_cost",
+        deserialize_with = "deserialize_movement_cost"
+    )]
+    pub movement_cost: f32,
+    /// (definition uid, count) of entities dropped when the block is broken
+    #[serde(default)]
+    pub drops: Vec<(String, u16)>,
+}
+
+/// Registry of all block types, indexed by [BlockType]
+pub struct BlockTypes {
+    types: Vec<BlockTypeDefinition>,
+    by_name: HashMap<String, BlockType>,
+}
+
+#[derive(Debug, Error)]
+pub enum BlockTypeError {
+    #[error("Block types have already been initialized")]
+    AlreadyInitialized,
+
+    #[error("Duplicate block type {0:?}")]
+    Duplicate(String),
+
+    #[error("Too many block types, max is {}", u16::MAX)]
+    TooMany,
+
+    #[error("Builtin block type {0:?} is not defined")]
+    MissingBuiltin(&'static str),
+}
+
+#[derive(Debug, Error)]
+#[error("Unknown block type {0:?}")]
+pub struct UnknownBlockType(pub String);
+
+static BLOCK_TYPES: OnceCell<BlockTypes> = OnceCell::new();
+
+/// Block types that the engine refers to directly, with fixed ids. Their properties are declared in
+/// data like any other block type
+macro_rules! builtin_block_types {
+    ($($id:literal $name:ident;)*) => {
+        #[allow(non_upper_case_globals)]
+        impl BlockType {
+            $(pub const $name: Self = Self($id);)*
+        }
+
+        /// Indexed by id
+        const BUILTIN_NAMES: &[&str] = &[$(stringify!($name)),*];
+    };
+}
+
+builtin_block_types! {
+    0 Air;
+    1 Dirt;
+    2 Grass;
+    3 LightGrass;
+    4 Leaves;
+    5 TreeTrunk;
+    6 Stone;
+    7 Sand;
+    8 SolidWater;
+    9 StoneBrickWall;
+    10 Chest;
+    11 Ladder;
+    12 Door;
+}
+
+impl Default for BlockOpacity {
+    fn default() -> Self {
+        Self::Solid
+    }
+}
+
 impl BlockOpacity {
     pub fn solid(self) -> bool {
         matches!(self, Self::Solid)
@@ -49,36 +132,347 @@ impl BlockOpacity {
 
 impl BlockType {
     pub fn opacity(self) -> BlockOpacity {
-        if let BlockType::Air = self {
-            BlockOpacity::Transparent
-        } else {
-            BlockOpacity::Solid
-        }
+        self.definition().opacity
     }
 
     pub fn durability(self) -> Proportion<BlockDurability> {
-        use BlockType::*;
-        let max = match self {
-            Air => 0,
-            Leaves => 10,
-            Sand => 30,
-            Dirt | Grass | LightGrass => 40,
-            TreeTrunk => 70,
-            Stone => 90,
-            Chest | StoneBrickWall => 60,
-            SolidWater => u8::MAX,
-        };
-
+        let max = self.definition().durability;
         Proportion::with_value(max, max)
     }
 
-    /// TODO very temporary "walkability" for block types
     pub fn can_be_walked_on(self) -> bool {
-        use BlockType::*;
-        !matches!(self, Air | Leaves | SolidWater)
+        self.definition().walkable
+    }
+
+    pub fn is_climbable(self) -> bool {
+        self.definition().climbable
+    }
+
+    pub fn is_door(self) -> bool {
+        self.definition().door
+    }
+
+    /// Multiplier for the cost of walking on top of this block
+    pub fn movement_cost(self) -> f32 {
+        self.definition().movement_cost
+    }
+
+    pub fn color(self) -> Color {
+        self.definition().color
+    }
+
+    /// (definition uid, count)
+    pub fn drops(self) -> &'static [(String, u16)] {
+        &self.definition().drops
+    }
+
+    pub fn name(self) -> &'static str {
+        &self.definition().name
     }
 
     pub fn is_air(self) -> bool {
-        matches!(self, BlockType::Air)
+        self == Self::Air
+    }
+
+    pub const fn id(self) -> u16 {
+        self.0
+    }
+
+    /// All registered block types in id order
+    pub fn iter() -> impl Iterator<Item = BlockType> {
+        (0..BlockTypes::get().types.len() as u16).map(BlockType)
+    }
+
+    fn definition(self) -> &'static BlockTypeDefinition {
+        BlockTypes::get().definition(self)
+    }
+}
+
+impl BlockTypes {
+    /// Registers all block types, which must include every builtin. Must be called before any
+    /// block type is used
+    pub fn init(
+        definitions: impl IntoIterator<Item = BlockTypeDefinition>,
+    ) -> Result<(), BlockTypeError> {
+        let registry = Self::with_definitions(definitions)?;
+        let count = registry.types.len();
+        BLOCK_TYPES
+            .set(registry)
+            .map_err(|_| BlockTypeError::AlreadyInitialized)?;
+
+        info!("registered {count} block types", count = count);
+        Ok(())
+    }
+
+    pub fn is_initialized() -> bool {
+        BLOCK_TYPES.get().is_some()
+    }
+
+    /// Panics if not yet initialized, unless the `testing` feature is enabled in which case the
+    /// core block types are loaded from the game's definitions
+    pub fn get() -> &'static Self {
+        BLOCK_TYPES.get().unwrap_or_else(uninitialized)
+    }
+
+    fn with_definitions(
+        definitions: impl IntoIterator<Item = BlockTypeDefinition>,
+    ) -> Result<Self, BlockTypeError> {
+        let mut builtins = vec![None; BUILTIN_NAMES.len()];
+        let mut others = Vec::new();
+
+        let mut seen = HashSet::new();
+        for def in definitions {
+            if !seen.insert(def.name.clone()) {
+                return Err(BlockTypeError::Duplicate(def.name));
+            }
+
+            match BUILTIN_NAMES.iter().position(|name| *name == def.name) {
+                Some(id) => builtins[id] = Some(def),
+                None => others.push(def),
+            }
+        }
+
+        let mut types = builtins
+            .into_iter()
+            .zip(BUILTIN_NAMES)
+            .map(|(def, name)| def.ok_or(BlockTypeError::MissingBuiltin(name)))
+            .collect::<Result<Vec<_>, _>>()?;
+        types.extend(others);
+
+        if types.len() > usize::from(u16::MAX) {
+            return Err(BlockTypeError::TooMany);
+        }
+
+        let by_name = types
+            .iter()
+            .enumerate()
+            .map(|(i, def)| (def.name.clone(), BlockType(i as u16)))
+            .collect();
+
+        Ok(Self { types, by_name })
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<BlockType> {
+        self.by_name.get(name).copied()
+    }
+
+    fn definition(&self, ty: BlockType) -> &BlockTypeDefinition {
+        // ids are only created by this registry
+        &self.types[ty.0 as usize]
+    }
+}
+
+impl BlockTypeDefinition {
+    /// Parses the fields of a block definition, e.g. from the "block" component of an entity
+    /// definition
+    pub fn from_fields(
+        fields: impl IntoIterator<Item = (String, ron::Value)>,
+    ) -> Result<Self, ron::Error> {
+        let map = fields
+            .into_iter()
+            .map(|(key, val)| (ron::Value::String(key), val))
+            .collect();
+        ron::Value::Map(map).into_rust()
+    }
+}
+
+fn default_movement_cost() -> f32 {
+    1.0
+}
+
+fn deserialize_movement_cost<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    let cost = f32::deserialize(deserializer)?;
+    if cost > 0.0 && cost.is_finite() {
+        Ok(cost)
+    } else {
+        Err(D::Error::custom(format!(
+            "movement cost must be positive, not {}",
+            cost
+        )))
+    }
+}
+
+/// RGB hex string, e.g. "BCA748"
+fn deserialize_hex_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    match u32::from_str_radix(&hex, 16) {
+        Ok(rgb) if hex.len() == 6 => Ok(Color::from((rgb << 8) | 0xFF)),
+        _ => Err(D::Error::custom(format!("invalid hex color {:?}", hex))),
+    }
+}
+
+fn deserialize_opacity<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BlockOpacity, D::Error> {
+    match String::deserialize(deserializer)?.as_str() {
+        "Solid" => Ok(BlockOpacity::Solid),
+        "Transparent" => Ok(BlockOpacity::Transparent),
+        other => Err(D::Error::unknown_variant(other, &["Solid", "Transparent"])),
+    }
+}
+
+#[cfg(not(feature = "testing"))]
+fn uninitialized() -> &'static BlockTypes {
+    panic!("block types have not been initialized")
+}
+
+#[cfg(feature = "testing")]
+fn uninitialized() -> &'static BlockTypes {
+    BLOCK_TYPES.get_or_init(testing::load_core_block_types)
+}
+
+#[cfg(feature = "testing")]
+mod testing {
+    use std::path::Path;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct RawDefinition {
+        #[serde(default)]
+        components: Vec<HashMap<String, ron::Value>>,
+    }
+
+    /// Loads block types from the game's definitions, for tests that don't go through the full
+    /// definition loader
+    pub fn load_core_block_types() -> BlockTypes {
+        let dir =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../resources/definitions/blocks");
+        let files = std::fs::read_dir(&dir)
+            .unwrap_or_else(|err| panic!("failed to read {}: {}", dir.display(), err));
+
+        let mut definitions = Vec::new();
+        for file in files {
+            let path = file.expect("failed to read block definitions").path();
+            let contents =
+                std::fs::read_to_string(&path).expect("failed to read block definitions");
+            let raw: Vec<RawDefinition> = ron::from_str(&contents)
+                .unwrap_or_else(|err| panic!("invalid definitions in {}: {}", path.display(), err));
+
+            for mut component in raw.into_iter().flat_map(|def| def.components) {
+                if let Some(block) = component.remove("block") {
+                    let def = block.into_rust().unwrap_or_else(|err| {
+                        panic!("invalid block in {}: {}", path.display(), err)
+                    });
+                    definitions.push(def);
+                }
+            }
+        }
+
+        BlockTypes::with_definitions(definitions).expect("invalid core block types")
+    }
+}
+
+impl FromStr for BlockType {
+    type Err = UnknownBlockType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BlockTypes::get()
+            .by_name(s)
+            .ok_or_else(|| UnknownBlockType(s.to_owned()))
+    }
+}
+
+impl Debug for BlockType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Display for BlockType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let def = self.definition();
+        write!(f, "{}", def.display_name.as_ref().unwrap_or(&def.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn def(name: &str, durability: BlockDurability) -> BlockTypeDefinition {
+        BlockTypeDefinition {
+            name: name.to_owned(),
+            display_name: None,
+            color: Color::rgb(1, 2, 3),
+            durability,
+            opacity: BlockOpacity::Solid,
+            walkable: true,
+            climbable: false,
+            door: false,
+            movement_cost: 1.0,
+            drops: vec![],
+        }
+    }
+
+    fn with_builtins(defs: Vec<BlockTypeDefinition>) -> Vec<BlockTypeDefinition> {
+        BUILTIN_NAMES
+            .iter()
+            .map(|name| def(name, 10))
+            .filter(|builtin| defs.iter().all(|def| def.name != builtin.name))
+            .chain(defs)
+            .collect()
+    }
+
+    #[test]
+    fn builtins_and_extend() {
+        let registry = BlockTypes::with_definitions(with_builtins(vec![
+            def("Granite", 100),
+            def("Stone", 20),
+        ]))
+        .unwrap();
+
+        let stone = registry.definition(BlockType::Stone);
+        assert_eq!(stone.name, "Stone");
+        assert_eq!(stone.durability, 20);
+
+        let granite = registry.by_name("Granite").expect("not registered");
+        assert_eq!(granite.0 as usize, BUILTIN_NAMES.len());
+        assert_eq!(registry.definition(granite).durability, 100);
+    }
+
+    #[test]
+    fn missing_builtin() {
+        let mut defs = with_builtins(vec![]);
+        defs.retain(|def| def.name != "Sand");
+
+        assert!(matches!(
+            BlockTypes::with_definitions(defs),
+            Err(BlockTypeError::MissingBuiltin("Sand"))
+        ));
+    }
+
+    #[test]
+    fn duplicates() {
+        assert!(matches!(
+            BlockTypes::with_definitions(with_builtins(vec![def("Granite", 1), def("Granite", 2)])),
+            Err(BlockTypeError::Duplicate(_))
+        ));
+    }
+
+    #[test]
+    fn parse_fields() {
+        use ron::Value::*;
+        let fields = vec![
+            ("name".to_owned(), String("Mud".to_owned())),
+            ("color".to_owned(), String("562617".to_owned())),
+            ("durability".to_owned(), Number(ron::Number::Integer(20))),
+            ("walkable".to_owned(), Bool(true)),
+        ];
+
+        let def = BlockTypeDefinition::from_fields(fields.clone()).expect("should parse");
+        assert_eq!(def.color, Color::rgb(0x56, 0x26, 0x17));
+        assert_eq!(def.opacity, BlockOpacity::Solid);
+        assert!(!def.climbable);
+        assert!((def.movement_cost - 1.0).abs() < f32::EPSILON);
+        assert!(def.drops.is_empty());
+
+        let mut bad_cost = fields.clone();
+        bad_cost.push(("movement_cost".to_owned(), Number(ron::Number::new(0.0))));
+        assert!(BlockTypeDefinition::from_fields(bad_cost).is_err());
+
+        let mut bad_color = fields;
+        bad_color[1].1 = String("nope".to_owned());
+        assert!(BlockTypeDefinition::from_fields(bad_color).is_err());
     }
 }
@@ -1,4 +1,7 @@
-pub use block::{BlockDurability, BlockOpacity, BlockType};
+pub use block::{
+    BlockDurability, BlockOpacity, BlockType, BlockTypeDefinition, BlockTypeError, BlockTypes,
+    UnknownBlockType,
+};
 pub use entity::{EntityDescription, PlantDescription};
 
 mod block;
@@ -0,0 +1,52 @@
+use common::*;
+use simulation::{
+    PhysicalComponent, RenderComponent, Renderer, TransformRenderDescription, UiElementComponent,
+};
+use unit::world::WorldPoint;
+
+/// Renders nothing, for backends without a window
+pub struct DummyRenderer;
+
+#[derive(Debug, Error)]
+#[error("Big dummy")]
+pub struct DummyError;
+
+impl Renderer for DummyRenderer {
+    type FrameContext = ();
+    type Error = DummyError;
+
+    fn init(&mut self, _target: Self::FrameContext) {}
+
+    fn sim_start(&mut self) {}
+
+    fn sim_entity(
+        &mut self,
+        _transform: &TransformRenderDescription,
+        _render: &RenderComponent,
+        _physical: &PhysicalComponent,
+    ) {
+    }
+
+    fn sim_selected(
+        &mut self,
+        _transform: &TransformRenderDescription,
+        _physical: &PhysicalComponent,
+    ) {
+    }
+
+    fn sim_ui_element(
+        &mut self,
+        _transform: &TransformRenderDescription,
+        _ui: &UiElementComponent,
+        _selected: bool,
+    ) {
+    }
+
+    fn sim_finish(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn debug_text(&mut self, _centre: WorldPoint, _text: &str) {}
+
+    fn deinit(&mut self) -> Self::FrameContext {}
+}
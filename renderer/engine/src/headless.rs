@@ -0,0 +1,848 @@
+//! Backend without a window that is driven by tooling over a local TCP socket. Clients send
+//! newline-delimited JSON [HeadlessRequest]s, and receive newline-delimited JSON
+//! [HeadlessMessage]s with a summary of every tick and its entity events.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::sync::mpsc::{
+    channel, sync_channel, Receiver, Sender, SyncSender, TryRecvError, TrySendError,
+};
+use std::sync::Arc;
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use common::*;
+use resources::Resources;
+use simulation::input::{
+    BlockPlacement, SelectedEntities, SelectedTiles, UiCommand, UiCommands, UiRequest, UiResponse,
+    UiResponsePayload,
+};
+use simulation::job::SocietyCommand;
+use simulation::{
+    parse_entity_id, BackendData, BlockType, ComponentWorld, EcsWorld, Entity, EntityEvent,
+    EntityEventLog, EntityEventPayload, Exit, GameSpeedChange, InitializedSimulationBackend,
+    PerfAvg, PersistentSimulationBackend, PlayerSociety, Simulation, SocietyHandle, WorldViewer,
+};
+use unit::world::{WorldPoint, WorldPosition, WorldPositionRange};
+
+use crate::dummy::DummyRenderer;
+
+type ClientId = u32;
+type Position = (i32, i32, i32);
+type Point = (f32, f32, f32);
+
+/// Clients with more than this many messages waiting to be written are too far behind, and are
+/// disconnected rather than stalling the game
+const CLIENT_QUEUE_SIZE: usize = 256;
+
+pub struct HeadlessBackendPersistent {
+    client_events: Receiver<ClientEvent>,
+    clients: Vec<Client>,
+}
+
+/// Connected client, messages are written to it on its own thread
+struct Client {
+    id: ClientId,
+    queue: SyncSender<Arc<str>>,
+
+    /// Only used to close the connection
+    stream: TcpStream,
+}
+
+pub struct HeadlessBackendInit {
+    persistent: HeadlessBackendPersistent,
+    world_viewer: WorldViewer,
+
+    /// Last tick a summary was sent for
+    last_tick: u32,
+
+    /// Scripts waiting to be executed, to send the output back to the client that asked
+    pending_scripts: Vec<(ClientId, UiResponse)>,
+}
+
+#[derive(Debug, Error)]
+pub enum HeadlessError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid entity {0:?}")]
+    InvalidEntity(String),
+
+    #[error("Invalid point {0:?}")]
+    InvalidPoint(Point),
+
+    #[error("Unknown block type {0:?}")]
+    UnknownBlockType(String),
+
+    #[error("Unknown build template {0:?}")]
+    UnknownBuildTemplate(String),
+
+    #[error("Unknown recipe {0:?}")]
+    UnknownRecipe(String),
+
+    #[error("No society specified and the player has no society")]
+    NoPlayerSociety,
+
+    #[error(
+        "Refusing to listen on non-loopback address {0:?}, set headless.allow_remote to allow"
+    )]
+    NonLoopbackAddress(String),
+}
+
+/// Request from a client, entities are referenced in the same format they are displayed in
+/// e.g. "E1:23"
+#[derive(Deserialize)]
+pub enum HeadlessRequest {
+    /// Replaces the current entity selection
+    SelectEntities(Vec<String>),
+
+    /// Selects the exact inclusive range of blocks
+    SelectTiles {
+        from: Position,
+        to: Position,
+    },
+
+    /// Clears entity and tile selections
+    ClearSelection,
+
+    /// Fills the tile selection with the given block type, or the blocks above it
+    FillTiles {
+        block: String,
+        #[serde(default)]
+        above: bool,
+    },
+
+    /// Submits a command to the given society, defaulting to the player's society
+    SocietyCommand {
+        society: Option<SocietyHandle>,
+        command: HeadlessSocietyCommand,
+    },
+
+    /// Eval the script at the given path, responding with [HeadlessMessage::ScriptOutput]
+    ExecuteScript(PathBuf),
+
+    TogglePaused,
+
+    ChangeSpeed(HeadlessSpeedChange),
+
+    Exit,
+}
+
+#[derive(Deserialize)]
+pub enum HeadlessSocietyCommand {
+    BreakBlocks {
+        from: Position,
+        to: Position,
+    },
+    Build {
+        from: Position,
+        to: Position,
+        template: String,
+    },
+    HaulToPosition {
+        thing: String,
+        target: Point,
+    },
+    HaulIntoContainer {
+        thing: String,
+        container: String,
+    },
+    Craft {
+        workshop: String,
+        recipe: String,
+    },
+}
+
+#[derive(Deserialize)]
+pub enum HeadlessSpeedChange {
+    Faster,
+    Slower,
+}
+
+/// Message sent to clients
+#[derive(Serialize)]
+pub enum HeadlessMessage {
+    /// Sent to all clients when the game has ticked, with all entity events since the last
+    /// summary
+    Tick {
+        tick: u32,
+        paused: bool,
+        events: Vec<HeadlessEvent>,
+    },
+
+    /// Output of a script executed by this client
+    ScriptOutput(Result<String, String>),
+
+    /// A request from this client was invalid
+    Error(String),
+}
+
+#[derive(Serialize)]
+pub struct HeadlessEvent {
+    subject: String,
+
+    #[serde(flatten)]
+    payload: HeadlessEventPayload,
+}
+
+/// Serializable form of [EntityEventPayload], with entities in their display format and errors
+/// as their messages
+#[derive(Serialize)]
+#[serde(tag = "event")]
+pub enum HeadlessEventPayload {
+    Arrived {
+        result: Result<Point, String>,
+    },
+    BeenPickedUp {
+        holder: String,
+        result: Result<(), String>,
+    },
+    HasPickedUp {
+        item: String,
+    },
+    BeenEaten {
+        eater: Result<String, String>,
+    },
+    HasEaten {
+        food: String,
+    },
+    BeenEquipped {
+        equipper: Result<String, String>,
+    },
+    HasEquipped {
+        item: String,
+    },
+    Hauled {
+        hauler: String,
+        result: Result<(), String>,
+    },
+    HauledButSplit {
+        hauler: String,
+        split_stack: Result<String, String>,
+    },
+    ExitedContainer {
+        container: Result<String, String>,
+    },
+    EnteredContainer {
+        container: Result<String, String>,
+    },
+    JoinedStack {
+        stack: String,
+    },
+    Died {
+        reason: String,
+    },
+
+    /// Event that isn't exposed to clients, e.g. test events
+    Other,
+}
+
+enum ClientEvent {
+    Connected(Client),
+    Request(ClientId, HeadlessRequest),
+    Invalid(ClientId, String),
+    Disconnected(ClientId),
+}
+
+impl InitializedSimulationBackend for HeadlessBackendInit {
+    type Renderer = DummyRenderer;
+    type Persistent = HeadlessBackendPersistent;
+
+    fn start(&mut self, _commands_out: &mut UiCommands) {}
+
+    fn consume_events(&mut self, _commands: &mut UiCommands) -> BackendData {
+        BackendData::default()
+    }
+
+    fn tick(&mut self) {}
+
+    /// Requests need the simulation to be resolved, so are handled here instead of when
+    /// consuming events
+    fn render(
+        &mut self,
+        simulation: &mut Simulation<Self::Renderer>,
+        _: f64,
+        _: PerfAvg,
+        commands: &mut UiCommands,
+    ) {
+        self.handle_client_events(simulation, commands);
+        self.send_script_outputs();
+        self.send_tick_summary(simulation);
+    }
+
+    fn world_viewer(&mut self) -> &mut WorldViewer {
+        &mut self.world_viewer
+    }
+
+    fn end(self) -> Self::Persistent {
+        self.persistent
+    }
+}
+
+impl PersistentSimulationBackend for HeadlessBackendPersistent {
+    type Error = HeadlessError;
+    type Initialized = HeadlessBackendInit;
+
+    fn new(_: &Resources) -> Result<Self, Self::Error> {
+        let addresses = {
+            let config = &config::get().headless;
+            resolve_address(&config.address, config.allow_remote)?
+        };
+        let listener = TcpListener::bind(&*addresses)?;
+        info!("listening for headless clients"; "address" => ?listener.local_addr().ok());
+
+        let (tx, rx) = channel();
+        thread::Builder::new()
+            .name("headless".to_owned())
+            .spawn(move || accept_clients(listener, tx))?;
+
+        Ok(Self {
+            client_events: rx,
+            clients: Vec::new(),
+        })
+    }
+
+    fn start(self, world_viewer: WorldViewer, _: WorldPosition) -> Self::Initialized {
+        HeadlessBackendInit {
+            persistent: self,
+            world_viewer,
+            last_tick: simulation::current_tick(),
+            pending_scripts: Vec::new(),
+        }
+    }
+
+    fn name() -> &'static str {
+        "Headless"
+    }
+}
+
+impl HeadlessBackendInit {
+    fn handle_client_events(
+        &mut self,
+        simulation: &mut Simulation<DummyRenderer>,
+        commands: &mut UiCommands,
+    ) {
+        loop {
+            let event = match self.persistent.client_events.try_recv() {
+                Ok(event) => event,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    warn!("headless listener has stopped");
+                    break;
+                }
+            };
+
+            match event {
+                ClientEvent::Connected(client) => {
+                    self.persistent.clients.push(client);
+                }
+                ClientEvent::Disconnected(client) => {
+                    info!("headless client disconnected"; "client" => client);
+                    self.persistent.clients.retain(|c| c.id != client);
+                }
+                ClientEvent::Invalid(client, err) => {
+                    debug!("invalid request from headless client"; "client" => client, "error" => %err);
+                    send(
+                        &mut self.persistent.clients,
+                        Some(client),
+                        &HeadlessMessage::Error(err),
+                    );
+                }
+                ClientEvent::Request(client, req) => {
+                    match apply_request(req, simulation, commands) {
+                        Ok(Some(response)) => self.pending_scripts.push((client, response)),
+                        Ok(None) => {}
+                        Err(err) => {
+                            debug!("failed to apply headless request"; "client" => client, "error" => %err);
+                            send(
+                                &mut self.persistent.clients,
+                                Some(client),
+                                &HeadlessMessage::Error(err.to_string()),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // only bother logging events when someone is listening
+        simulation
+            .world()
+            .resource_mut::<EntityEventLog>()
+            .set_enabled(!self.persistent.clients.is_empty());
+    }
+
+    fn send_script_outputs(&mut self) {
+        let clients = &mut self.persistent.clients;
+        self.pending_scripts
+            .retain(|(client, response)| match response.take_response() {
+                Some(UiResponsePayload::ScriptOutput(result)) => {
+                    let result = result.map_err(|err| err.to_string());
+                    send(
+                        clients,
+                        Some(*client),
+                        &HeadlessMessage::ScriptOutput(result),
+                    );
+                    false
+                }
+                Some(_) => false,
+                None => true,
+            });
+    }
+
+    fn send_tick_summary(&mut self, simulation: &Simulation<DummyRenderer>) {
+        let tick = simulation::current_tick();
+        if tick == self.last_tick {
+            return;
+        }
+
+        self.last_tick = tick;
+        let events = simulation
+            .world()
+            .resource_mut::<EntityEventLog>()
+            .drain()
+            .map(HeadlessEvent::from)
+            .collect();
+
+        send(
+            &mut self.persistent.clients,
+            None,
+            &HeadlessMessage::Tick {
+                tick,
+                paused: simulation.is_paused(),
+                events,
+            },
+        );
+    }
+}
+
+/// Returns the response to wait on for requests that produce output
+fn apply_request(
+    req: HeadlessRequest,
+    simulation: &Simulation<DummyRenderer>,
+    commands: &mut UiCommands,
+) -> Result<Option<UiResponse>, HeadlessError> {
+    let world = simulation.world();
+    let req = match req {
+        HeadlessRequest::SelectEntities(entities) => {
+            let entities = entities
+                .iter()
+                .map(|e| parse_entity(e))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let selection = world.resource_mut::<SelectedEntities>();
+            selection.unselect_all(world);
+            for e in entities {
+                selection.select(world, e);
+            }
+
+            return Ok(None);
+        }
+        HeadlessRequest::SelectTiles { from, to } => {
+            world
+                .resource_mut::<SelectedTiles>()
+                .select_range(to_range(from, to), &simulation.voxel_world());
+            return Ok(None);
+        }
+        HeadlessRequest::ClearSelection => UiRequest::CancelSelection,
+        HeadlessRequest::FillTiles { block, above } => {
+            let placement = if above {
+                BlockPlacement::PlaceAbove
+            } else {
+                BlockPlacement::Set
+            };
+            let block_type = block
+                .parse::<BlockType>()
+                .map_err(|_| HeadlessError::UnknownBlockType(block))?;
+            UiRequest::FillSelectedTiles(placement, block_type)
+        }
+        HeadlessRequest::SocietyCommand { society, command } => {
+            let society = match society {
+                Some(society) => society,
+                None => world
+                    .resource::<PlayerSociety>()
+                    .get()
+                    .ok_or(HeadlessError::NoPlayerSociety)?,
+            };
+
+            UiRequest::IssueSocietyCommand(society, command.resolve(world)?)
+        }
+        HeadlessRequest::ExecuteScript(path) => UiRequest::ExecuteScript(path),
+        HeadlessRequest::TogglePaused => UiRequest::TogglePaused,
+        HeadlessRequest::ChangeSpeed(change) => UiRequest::ChangeGameSpeed(match change {
+            HeadlessSpeedChange::Faster => GameSpeedChange::Faster,
+            HeadlessSpeedChange::Slower => GameSpeedChange::Slower,
+        }),
+        HeadlessRequest::Exit => UiRequest::ExitGame(Exit::Stop),
+    };
+
+    let is_script = matches!(req, UiRequest::ExecuteScript(_));
+    let command = UiCommand::new(req);
+    let response = command.response();
+    commands.push(command);
+
+    Ok(Some(response).filter(|_| is_script))
+}
+
+impl HeadlessSocietyCommand {
+    fn resolve(self, world: &EcsWorld) -> Result<SocietyCommand, HeadlessError> {
+        Ok(match self {
+            Self::BreakBlocks { from, to } => SocietyCommand::BreakBlocks(to_range(from, to)),
+            Self::Build { from, to, template } => {
+                let template = world
+                    .find_build_template(&template)
+                    .ok_or(HeadlessError::UnknownBuildTemplate(template))?;
+                SocietyCommand::Build(to_range(from, to), template)
+            }
+            Self::HaulToPosition { thing, target } => {
+                SocietyCommand::HaulToPosition(parse_entity(&thing)?, to_point(target)?)
+            }
+            Self::HaulIntoContainer { thing, container } => {
+                SocietyCommand::HaulIntoContainer(parse_entity(&thing)?, parse_entity(&container)?)
+            }
+            Self::Craft { workshop, recipe } => {
+                let recipe = world
+                    .find_recipe(&recipe)
+                    .ok_or(HeadlessError::UnknownRecipe(recipe))?;
+                SocietyCommand::Craft(parse_entity(&workshop)?, recipe)
+            }
+        })
+    }
+}
+
+impl From<EntityEvent> for HeadlessEvent {
+    fn from(event: EntityEvent) -> Self {
+        use EntityEventPayload as E;
+        use HeadlessEventPayload as H;
+
+        fn entity(e: Entity) -> String {
+            e.to_string()
+        }
+
+        fn entity_result(result: Result<Entity, impl Display>) -> Result<String, String> {
+            result.map(entity).map_err(|err| err.to_string())
+        }
+
+        let payload = match event.payload {
+            E::Arrived(_, result) => H::Arrived {
+                result: result.map(|pos| pos.xyz()).map_err(|err| err.to_string()),
+            },
+            E::BeenPickedUp(holder, result) => H::BeenPickedUp {
+                holder: entity(holder),
+                result: result.map_err(|err| err.to_string()),
+            },
+            E::HasPickedUp(item) => H::HasPickedUp { item: entity(item) },
+            E::BeenEaten(eater) => H::BeenEaten {
+                eater: entity_result(eater),
+            },
+            E::HasEaten(food) => H::HasEaten { food: entity(food) },
+            E::BeenEquipped(equipper) => H::BeenEquipped {
+                equipper: entity_result(equipper),
+            },
+            E::HasEquipped(item) => H::HasEquipped { item: entity(item) },
+            E::Hauled(hauler, result) => H::Hauled {
+                hauler: entity(hauler),
+                result: result.map_err(|err| err.to_string()),
+            },
+            E::HauledButSplit(hauler, split_stack) => H::HauledButSplit {
+                hauler: entity(hauler),
+                split_stack: entity_result(split_stack),
+            },
+            E::ExitedContainer(container) => H::ExitedContainer {
+                container: entity_result(container),
+            },
+            E::EnteredContainer(container) => H::EnteredContainer {
+                container: entity_result(container),
+            },
+            E::JoinedStack(stack) => H::JoinedStack {
+                stack: entity(stack),
+            },
+            E::Died(reason) => H::Died {
+                reason: reason.to_string(),
+            },
+            _ => H::Other,
+        };
+
+        HeadlessEvent {
+            subject: entity(event.subject),
+            payload,
+        }
+    }
+}
+
+/// Tooling has full control of the game, so only loopback addresses are allowed unless remote
+/// connections are explicitly allowed in the config
+fn resolve_address(address: &str, allow_remote: bool) -> Result<Vec<SocketAddr>, HeadlessError> {
+    let addresses = address.to_socket_addrs()?.collect_vec();
+    if !allow_remote && addresses.iter().any(|addr| !addr.ip().is_loopback()) {
+        return Err(HeadlessError::NonLoopbackAddress(address.to_owned()));
+    }
+
+    Ok(addresses)
+}
+
+fn parse_entity(e: &str) -> Result<Entity, HeadlessError> {
+    parse_entity_id(e)
+        .map(Entity::from)
+        .ok_or_else(|| HeadlessError::InvalidEntity(e.to_owned()))
+}
+
+fn to_range(from: Position, to: Position) -> WorldPositionRange {
+    WorldPositionRange::with_inclusive_range(WorldPosition::from(from), WorldPosition::from(to))
+}
+
+fn to_point((x, y, z): Point) -> Result<WorldPoint, HeadlessError> {
+    WorldPoint::new(x, y, z).ok_or(HeadlessError::InvalidPoint((x, y, z)))
+}
+
+/// Queues for the given client only, or all clients if None. Clients that have disconnected or
+/// fallen too far behind are dropped
+fn send(clients: &mut Vec<Client>, to: Option<ClientId>, msg: &HeadlessMessage) {
+    let line: Arc<str> = match serde_json::to_string(msg) {
+        Ok(mut line) => {
+            line.push('\n');
+            line.into()
+        }
+        Err(err) => {
+            error!("failed to serialize headless message"; "error" => %err);
+            return;
+        }
+    };
+
+    clients.retain(|client| {
+        if to.map(|to| to != client.id).unwrap_or(false) {
+            return true;
+        }
+
+        match client.queue.try_send(line.clone()) {
+            Ok(_) => true,
+            Err(TrySendError::Full(_)) => {
+                warn!("headless client has fallen too far behind, disconnecting"; "client" => client.id);
+                let _ = client.stream.shutdown(Shutdown::Both);
+                false
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                debug!("headless client writer has stopped, disconnecting"; "client" => client.id);
+                false
+            }
+        }
+    });
+}
+
+/// Runs on its own thread forever
+fn accept_clients(listener: TcpListener, tx: Sender<ClientEvent>) {
+    let mut next_client = 0;
+    for stream in listener.incoming() {
+        let client = next_client;
+        let connected = stream.and_then(|stream| {
+            info!("headless client connected"; "client" => client, "address" => ?stream.peer_addr().ok());
+            connect_client(client, stream, &tx)
+        });
+
+        match connected {
+            Ok(true) => next_client += 1,
+            Ok(false) => break, // game has ended
+            Err(err) => warn!("failed to accept headless client"; "error" => %err),
+        }
+    }
+}
+
+/// Spawns the reader and writer threads for a new client. Returns false if the game has ended
+fn connect_client(
+    client: ClientId,
+    stream: TcpStream,
+    tx: &Sender<ClientEvent>,
+) -> std::io::Result<bool> {
+    let reader = stream.try_clone()?;
+    let writer = stream.try_clone()?;
+    let (queue_tx, queue_rx) = sync_channel(CLIENT_QUEUE_SIZE);
+
+    thread::Builder::new()
+        .name(format!("headless-write-{}", client))
+        .spawn(move || write_messages(client, writer, queue_rx))?;
+
+    // register before reading any requests so responses can be sent
+    let registered = tx.send(ClientEvent::Connected(Client {
+        id: client,
+        queue: queue_tx,
+        stream,
+    }));
+
+    if registered.is_err() {
+        return Ok(false);
+    }
+
+    let tx = tx.clone();
+    thread::Builder::new()
+        .name(format!("headless-read-{}", client))
+        .spawn(move || read_requests(client, reader, tx))?;
+
+    Ok(true)
+}
+
+/// Runs on its own thread until the client disconnects or is dropped
+fn write_messages(client: ClientId, mut stream: TcpStream, queue: Receiver<Arc<str>>) {
+    for line in queue {
+        if let Err(err) = stream.write_all(line.as_bytes()) {
+            debug!("failed to write to headless client"; "client" => client, "error" => %err);
+            break;
+        }
+    }
+
+    // also stops the reader thread
+    let _ = stream.shutdown(Shutdown::Both);
+}
+
+/// Runs on its own thread until the client disconnects
+fn read_requests(client: ClientId, stream: TcpStream, tx: Sender<ClientEvent>) {
+    for line in BufReader::new(stream).lines() {
+        let event = match line {
+            Ok(line) if line.trim().is_empty() => continue,
+            Ok(line) => match serde_json::from_str(&line) {
+                Ok(req) => ClientEvent::Request(client, req),
+                Err(err) => ClientEvent::Invalid(client, err.to_string()),
+            },
+            Err(err) => {
+                debug!("failed to read from headless client"; "client" => client, "error" => %err);
+                break;
+            }
+        };
+
+        if tx.send(event).is_err() {
+            return;
+        }
+    }
+
+    let _ = tx.send(ClientEvent::Disconnected(client));
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use simulation::DeathReason;
+
+    use super::*;
+
+    fn test_client(id: ClientId) -> (Client, Receiver<Arc<str>>, TcpListener) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (queue, rx) = sync_channel(CLIENT_QUEUE_SIZE);
+        (Client { id, queue, stream }, rx, listener)
+    }
+
+    #[test]
+    fn only_loopback_by_default() {
+        assert!(resolve_address("127.0.0.1:0", false).is_ok());
+        assert!(resolve_address("[::1]:0", false).is_ok());
+
+        assert!(matches!(
+            resolve_address("0.0.0.0:0", false),
+            Err(HeadlessError::NonLoopbackAddress(_))
+        ));
+        assert!(resolve_address("0.0.0.0:0", true).is_ok());
+    }
+
+    #[test]
+    fn parse_requests() {
+        let parse = |json: &str| serde_json::from_str::<HeadlessRequest>(json).unwrap();
+
+        assert!(matches!(
+            parse(r#""TogglePaused""#),
+            HeadlessRequest::TogglePaused
+        ));
+        assert!(matches!(
+            parse(r#"{"SelectTiles": {"from": [0, 1, 2], "to": [3, 4, 5]}}"#),
+            HeadlessRequest::SelectTiles {
+                from: (0, 1, 2),
+                to: (3, 4, 5)
+            }
+        ));
+        assert!(matches!(
+            parse(r#"{"FillTiles": {"block": "Stone"}}"#),
+            HeadlessRequest::FillTiles { above: false, .. }
+        ));
+        assert!(matches!(
+            parse(
+                r#"{"SocietyCommand": {"society": null, "command": {"BreakBlocks": {"from": [0, 0, 0], "to": [1, 1, 1]}}}}"#
+            ),
+            HeadlessRequest::SocietyCommand {
+                society: None,
+                command: HeadlessSocietyCommand::BreakBlocks { .. }
+            }
+        ));
+
+        assert!(serde_json::from_str::<HeadlessRequest>(r#""Explode""#).is_err());
+    }
+
+    #[test]
+    fn events_are_structured() {
+        let subject = parse_entity("E1:2").unwrap();
+        let killer = parse_entity("E3:4").unwrap();
+
+        let event = HeadlessEvent::from(EntityEvent {
+            subject,
+            payload: EntityEventPayload::Died(DeathReason::Killed(killer)),
+        });
+
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({"subject": "E1:2", "event": "Died", "reason": "Killed by E3:4"})
+        );
+
+        let event = HeadlessEvent::from(EntityEvent {
+            subject,
+            payload: EntityEventPayload::JoinedStack(killer),
+        });
+
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({"subject": "E1:2", "event": "JoinedStack", "stack": "E3:4"})
+        );
+    }
+
+    #[test]
+    fn lagging_clients_are_dropped() {
+        let (slow, _slow_rx, _a) = test_client(0);
+        let (fast, fast_rx, _b) = test_client(1);
+        let mut clients = vec![slow, fast];
+
+        let msg = HeadlessMessage::Error("hello".to_owned());
+        for _ in 0..CLIENT_QUEUE_SIZE {
+            send(&mut clients, None, &msg);
+            assert_eq!(fast_rx.recv().unwrap().as_ref(), "{\"Error\":\"hello\"}\n");
+        }
+        assert_eq!(clients.len(), 2);
+
+        // only the slow client's queue is full
+        send(&mut clients, None, &msg);
+        assert_eq!(clients.iter().map(|c| c.id).collect_vec(), vec![1]);
+    }
+
+    #[test]
+    fn disconnected_clients_are_dropped() {
+        let (a, a_rx, _a) = test_client(0);
+        let (b, _b_rx, _b) = test_client(1);
+        let mut clients = vec![a, b];
+
+        // writer thread has stopped
+        drop(a_rx);
+
+        // not sent to a so it's not noticed yet
+        send(
+            &mut clients,
+            Some(1),
+            &HeadlessMessage::Error("hello".to_owned()),
+        );
+        assert_eq!(clients.len(), 2);
+
+        send(
+            &mut clients,
+            None,
+            &HeadlessMessage::Error("hello".to_owned()),
+        );
+        assert_eq!(clients.iter().map(|c| c.id).collect_vec(), vec![1]);
+    }
+}
@@ -4,11 +4,19 @@ mod render;
 #[cfg(feature = "use-sdl")]
 pub use render::sdl::{SdlBackendInit, SdlBackendPersistent};
 
+#[cfg(any(feature = "lite", feature = "headless"))]
+mod dummy;
+
 #[cfg(feature = "lite")]
 mod lite;
 #[cfg(feature = "lite")]
 pub use lite::{DummyBackendInit, DummyBackendPersistent};
 
+#[cfg(feature = "headless")]
+mod headless;
+#[cfg(feature = "headless")]
+pub use headless::{HeadlessBackendInit, HeadlessBackendPersistent};
+
 mod engine;
 pub use crate::engine::Engine;
 
@@ -1,20 +1,14 @@
 use std::time::{Duration, Instant};
 
-use common::*;
 use resources::Resources;
 use simulation::input::{UiCommand, UiCommands, UiRequest};
 use simulation::{
     BackendData, Exit, InitializedSimulationBackend, PerfAvg, PersistentSimulationBackend,
-    PhysicalComponent, RenderComponent, Renderer, Simulation, TransformRenderDescription,
-    UiElementComponent, WorldViewer,
+    Simulation, WorldViewer,
 };
-use unit::world::{WorldPoint, WorldPosition};
+use unit::world::WorldPosition;
 
-pub struct DummyRenderer;
-
-#[derive(Debug, Error)]
-#[error("Big dummy")]
-pub struct DummyError;
+use crate::dummy::{DummyError, DummyRenderer};
 
 pub struct DummyBackendPersistent;
 pub struct DummyBackendInit {
@@ -22,46 +16,6 @@ pub struct DummyBackendInit {
     world_viewer: WorldViewer,
 }
 
-impl Renderer for DummyRenderer {
-    type FrameContext = ();
-    type Error = DummyError;
-
-    fn init(&mut self, _target: Self::FrameContext) {}
-
-    fn sim_start(&mut self) {}
-
-    fn sim_entity(
-        &mut self,
-        _transform: &TransformRenderDescription,
-        _render: &RenderComponent,
-        _physical: &PhysicalComponent,
-    ) {
-    }
-
-    fn sim_selected(
-        &mut self,
-        _transform: &TransformRenderDescription,
-        _physical: &PhysicalComponent,
-    ) {
-    }
-
-    fn sim_ui_element(
-        &mut self,
-        _transform: &TransformRenderDescription,
-        _ui: &UiElementComponent,
-        _selected: bool,
-    ) {
-    }
-
-    fn sim_finish(&mut self) -> Result<(), Self::Error> {
-        Ok(())
-    }
-
-    fn debug_text(&mut self, _centre: WorldPoint, _text: &str) {}
-
-    fn deinit(&mut self) -> Self::FrameContext {}
-}
-
 impl InitializedSimulationBackend for DummyBackendInit {
     type Renderer = DummyRenderer;
     type Persistent = DummyBackendPersistent;
@@ -16,8 +16,8 @@ use simulation::input::{
     InputEvent, SelectType, UiCommand, UiCommands, UiPopup, UiRequest, WorldColumn,
 };
 use simulation::{
-    BackendData, ComponentWorld, Exit, GameSpeedChange, InitializedSimulationBackend, PerfAvg,
-    PersistentSimulationBackend, Simulation, WorldViewer,
+    BackendData, Calendar, ComponentWorld, Exit, GameSpeedChange, InitializedSimulationBackend,
+    PerfAvg, PersistentSimulationBackend, Simulation, WorldViewer,
 };
 use unit::world::{WorldPoint, WorldPoint2d, WorldPosition};
 
@@ -321,10 +321,16 @@ impl InitializedSimulationBackend for SdlBackendInit {
         };
         let view = self.camera.view_matrix(camera_z);
 
+        let ambient_light = simulation
+            .world()
+            .resource::<Calendar>()
+            .light_level()
+            .value();
+
         // render world
         self.renderer
             .terrain()
-            .render(&projection, &view, &self.world_viewer);
+            .render(&projection, &view, &self.world_viewer, ambient_light);
 
         // render simulation
         let lower_limit = terrain_range.bottom().slice() as f32;
@@ -334,6 +340,7 @@ impl InitializedSimulationBackend for SdlBackendInit {
             zoom: self.camera.zoom(),
             view,
             z_offset: lower_limit,
+            ambient_light,
         };
         let _ = simulation.render(
             &self.world_viewer,
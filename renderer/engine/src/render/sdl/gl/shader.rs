@@ -194,6 +194,18 @@ impl ScopedBind<'_, Program> {
         }
     }
 
+    /// Name must be null terminated
+    pub fn set_uniform_float(&self, name: &'static str, value: F) {
+        let mut cache = self.1.borrow_mut();
+        let result = cache
+            .resolve(self.0, name)
+            .and_then(|location| unsafe { errchk!(gl::Uniform1f(location, value)) });
+
+        if let Err(e) = result {
+            warn!("failed to set uniform"; "uniform" => name, "error" => %e);
+        }
+    }
+
     /// Name must be null terminated
     pub fn bind_frag_data_location(&self, color: u32, name: &str) -> GlResult<()> {
         ensure_null_terminated(name);
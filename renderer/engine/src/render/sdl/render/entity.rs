@@ -163,6 +163,7 @@ impl EntityRenderer {
         // these are the same for all entities, so multiply them once on the cpu
         let proj_view = frame_ctx.projection * frame_ctx.view;
         p.set_uniform_matrix("proj_view\0", proj_view.as_ptr());
+        p.set_uniform_float("ambient\0", frame_ctx.ambient_light);
 
         let render_data = [
             (Primitive::TriangleStrip, 0, CIRCLE_VERTEX_COUNT),
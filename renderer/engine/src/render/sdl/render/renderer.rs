@@ -38,6 +38,9 @@ pub struct GlFrameContext {
     /// Amount to subtract from every entity's z pos, to normalize z around 0
     pub z_offset: f32,
     pub zoom: f32,
+
+    /// Multiplier for all world colours, from the time of day
+    pub ambient_light: f32,
 }
 
 #[repr(C)]
@@ -84,10 +84,17 @@ impl TerrainRenderer {
         Ok(())
     }
 
-    pub fn render(&self, proj: &Matrix4, view: &Matrix4, world_viewer: &WorldViewer) {
+    pub fn render(
+        &self,
+        proj: &Matrix4,
+        view: &Matrix4,
+        world_viewer: &WorldViewer,
+        ambient_light: f32,
+    ) {
         // use program and setup common uniforms
         let prog = self.program.scoped_bind();
         prog.set_uniform_matrix("proj\0", proj.as_ptr());
+        prog.set_uniform_float("ambient\0", ambient_light);
 
         // enable face culling
         let _cull = Capability::CullFace.scoped_enable();
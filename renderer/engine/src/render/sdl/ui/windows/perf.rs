@@ -2,7 +2,7 @@ use crate::render::sdl::ui::context::{DefaultOpen, UiContext};
 use crate::{open_or_ret, ui_str};
 
 use serde::{Deserialize, Serialize};
-use simulation::TICKS_PER_SECOND;
+use simulation::{Calendar, ComponentWorld, TICKS_PER_SECOND};
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct PerformanceWindow;
@@ -24,5 +24,8 @@ impl PerformanceWindow {
 
         mk_stat(context, "Tick:  ", perf.tick, 1.0 / TICKS_PER_SECOND as f64);
         mk_stat(context, "Render:", perf.render, 1.0 / 60.0);
+
+        let calendar = context.simulation().ecs.resource::<Calendar>();
+        context.text(ui_str!(in context, "Time:    {}", calendar));
     }
 }
@@ -12,9 +12,9 @@ use simulation::input::{
 use simulation::job::BuildThingJob;
 use simulation::{
     ActivityComponent, AssociatedBlockData, AssociatedBlockDataType, BlockType, ComponentRef,
-    ComponentWorld, ConditionComponent, Container, ContainerComponent, EdibleItemComponent, Entity,
-    EntityLoggingComponent, FollowPathComponent, HerdedComponent, HungerComponent,
-    IntoEnumIterator, InventoryComponent, ItemStackComponent, NameComponent, PhysicalComponent,
+    ComponentWorld, ConditionComponent, Container, ContainerComponent, EdibleItemComponent,
+    EnergyComponent, Entity, EntityLoggingComponent, FollowPathComponent, HerdedComponent,
+    HungerComponent, InventoryComponent, ItemStackComponent, NameComponent, PhysicalComponent,
     Societies, SocietyComponent, SpeciesComponent, TransformComponent, UiElementComponent,
 };
 
@@ -24,7 +24,7 @@ use crate::{open_or_ret, ui_str};
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct SelectionWindow {
-    /// Index into [BlockType::into_enum_iter]
+    /// Index into [BlockType::iter]
     edit_selection: usize,
 }
 
@@ -303,6 +303,17 @@ impl SelectionWindow {
                     COLOR_ORANGE,
                 );
 
+                context.key_value(
+                    "Energy:",
+                    || {
+                        details
+                            .component::<EnergyComponent>(context)
+                            .map(|e| ui_str!(in context, "{:.0}%", e.energy().value() * 100.0))
+                    },
+                    None,
+                    COLOR_ORANGE,
+                );
+
                 context.key_value(
                     "Navigating to:",
                     || {
@@ -10,7 +10,9 @@ use engine::Engine;
 use resources::ResourceContainer;
 use resources::Resources;
 use simulation::state::BackendState;
-use simulation::{Exit, InitializedSimulationBackend, PersistentSimulationBackend, WorldViewer};
+use simulation::{
+    Exit, InitializedSimulationBackend, PersistentSimulationBackend, ReplayPlayer, WorldViewer,
+};
 
 use crate::scenarios::Scenario;
 
@@ -52,6 +54,9 @@ type Backend = engine::SdlBackendPersistent;
 #[cfg(feature = "lite")]
 type Backend = engine::DummyBackendPersistent;
 
+#[cfg(feature = "headless")]
+type Backend = engine::HeadlessBackendPersistent;
+
 type BackendInit = <Backend as PersistentSimulationBackend>::Initialized;
 type Renderer = <BackendInit as InitializedSimulationBackend>::Renderer;
 
@@ -71,23 +76,43 @@ struct Args {
     #[argh(option)]
     scenario: Option<String>,
     // TODO specify e2e test by name (feature = "tests")
+    /// record player input to the given replay file
+    #[argh(option)]
+    record: Option<PathBuf>,
+
+    /// play back player input from the given replay file, using its recorded seed, config and
+    /// scenario
+    #[argh(option)]
+    replay: Option<PathBuf>,
+}
+
+/// Recording or playback of player input
+enum ReplayMode {
+    None,
+    Record { path: PathBuf, config: String },
+    Play(ReplayPlayer),
 }
 
 #[derive(Debug, Error)]
 enum StartError {
     #[error("No such scenario '{0}'")]
     NoSuchScenario(String),
+
+    #[error("Can't record and play a replay at the same time")]
+    RecordAndReplay,
 }
 
-fn resolve_scenario(args: &Args) -> BoxedResult<Scenario> {
+/// Scenario name in the replay takes precedence over the one in args
+fn resolve_scenario(args: &Args, replay: Option<&str>) -> BoxedResult<(&'static str, Scenario)> {
     let name;
     #[cfg(feature = "tests")]
     {
+        let _ = replay;
         name = Some("nop");
     }
     #[cfg(not(feature = "tests"))]
     {
-        name = args.scenario.as_deref();
+        name = replay.or(args.scenario.as_deref());
     }
 
     let resolved = scenarios::resolve(name);
@@ -98,7 +123,7 @@ fn resolve_scenario(args: &Args) -> BoxedResult<Scenario> {
                 s as usize,
                 scenario = name,
             );
-            Ok(s)
+            Ok((name, s))
         }
         None => {
             let name = name.unwrap(); // would have panicked already if bad default
@@ -115,7 +140,18 @@ fn resolve_scenario(args: &Args) -> BoxedResult<Scenario> {
 #[allow(unused_mut)]
 fn do_main() -> BoxedResult<()> {
     let args = argh::from_env::<Args>();
-    let scenario = resolve_scenario(&args)?;
+    if args.record.is_some() && args.replay.is_some() {
+        return Err(StartError::RecordAndReplay.into());
+    }
+
+    // read replay header first, as it determines the config and scenario
+    let mut replay_player = args.replay.as_deref().map(ReplayPlayer::open).transpose()?;
+    let scenario = resolve_scenario(
+        &args,
+        replay_player
+            .as_ref()
+            .and_then(|player| player.header().scenario.as_deref()),
+    )?;
 
     // start metrics server
     #[cfg(feature = "metrics")]
@@ -141,7 +177,14 @@ fn do_main() -> BoxedResult<()> {
         .file_path()
         .expect("non file config not yet supported"); // TODO
 
-    config::init(ConfigType::WatchedFile(file_path))?;
+    // replays use their recorded config, without watching for changes to stay deterministic
+    match replay_player.as_ref() {
+        Some(player) => {
+            info!("using config from replay");
+            config::init(ConfigType::String(&player.header().config))?;
+        }
+        None => config::init(ConfigType::WatchedFile(file_path))?,
+    }
 
     // initialize persistent backend
     let mut backend_state = {
@@ -150,7 +193,24 @@ fn do_main() -> BoxedResult<()> {
     };
 
     let ret = loop {
-        let (simulation, initial_block) = start::create_simulation(resources.clone(), scenario)?;
+        let replay = if let Some(path) = args.record.as_ref() {
+            ReplayMode::Record {
+                path: path.clone(),
+                config: std::fs::read_to_string(file_path)?,
+            }
+        } else if let Some(path) = args.replay.as_deref() {
+            // reopen on restart
+            let player = match replay_player.take() {
+                Some(player) => player,
+                None => ReplayPlayer::open(path)?,
+            };
+            ReplayMode::Play(player)
+        } else {
+            ReplayMode::None
+        };
+
+        let (simulation, initial_block) =
+            start::create_simulation(resources.clone(), scenario, replay)?;
 
         // initialize backend with simulation world
         let world_viewer = WorldViewer::with_world(simulation.voxel_world(), initial_block)?;
@@ -277,20 +337,24 @@ mod start {
     use common::*;
     use config::WorldSource;
     use engine::simulation::{
-        self, all_slabs_in_range, presets, AsyncWorkerPool, ChunkLocation, Simulation,
-        SlabLocation, TerrainSourceError, WorldLoader, WorldPosition,
+        self, all_slabs_in_range, presets, AsyncWorkerPool, ChunkLocation, ReplayHeader,
+        ReplayRecorder, Simulation, SlabLocation, TerrainSourceError, WorldLoader, WorldPosition,
     };
     use resources::Resources;
 
     use crate::scenarios::Scenario;
 
-    use super::Renderer;
+    use super::{ReplayMode, Renderer};
 
     /// (new empty simulation, initial block to centre camera on)
     pub fn create_simulation(
         resources: Resources,
-        scenario: Scenario,
+        scenario: (&'static str, Scenario),
+        replay: ReplayMode,
     ) -> BoxedResult<(Simulation<Renderer>, WorldPosition)> {
+        // block types are needed by the world, so register them before anything else
+        simulation::load_block_types(resources.definitions()?)?;
+
         // create world loader
         let mut world_loader = {
             let thread_count = config::get()
@@ -311,7 +375,7 @@ mod start {
         info!("centring camera on block"; "block" => %initial_block);
 
         let mut sim = Simulation::new(world_loader, resources)?;
-        init_simulation(&mut sim, scenario)?;
+        init_simulation(&mut sim, scenario, replay)?;
         Ok((sim, initial_block))
     }
 
@@ -418,8 +482,14 @@ mod start {
         Ok(initial_block)
     }
 
-    fn init_simulation(sim: &mut Simulation<Renderer>, scenario: Scenario) -> BoxedResult<()> {
-        let (seed, source) = if let Some(seed) = config::get().simulation.random_seed {
+    fn init_simulation(
+        sim: &mut Simulation<Renderer>,
+        (scenario_name, scenario): (&'static str, Scenario),
+        replay: ReplayMode,
+    ) -> BoxedResult<()> {
+        let (seed, source) = if let ReplayMode::Play(player) = &replay {
+            (player.header().seed, "replay")
+        } else if let Some(seed) = config::get().simulation.random_seed {
             (seed, "config")
         } else {
             (thread_rng().next_u64(), "randomly generated")
@@ -431,6 +501,15 @@ mod start {
             seed = seed; "source" => source
         );
 
+        match replay {
+            ReplayMode::None => {}
+            ReplayMode::Record { path, config } => {
+                let header = ReplayHeader::new(seed, config, Some(scenario_name.to_owned()));
+                sim.start_recording(ReplayRecorder::create(&path, &header)?);
+            }
+            ReplayMode::Play(player) => sim.start_replay(player),
+        }
+
         // create society for player to control
         let player_society = sim
             .societies_mut()
@@ -2,7 +2,7 @@
 
 use common::*;
 use engine::simulation;
-use simulation::job::BuildThingJob;
+use simulation::job::{BuildThingJob, CraftJob};
 use simulation::{ComponentWorld, EcsWorld, PlayerSociety, Societies};
 
 use crate::scenarios::helpers::{spawn_entities_randomly, Placement};
@@ -46,6 +46,7 @@ scenario!(nop);
 scenario!(wander_and_eat);
 scenario!(haul_to_container);
 scenario!(building);
+scenario!(crafting);
 scenario!(herding);
 
 fn following_dogs(ecs: &EcsWorld) {
@@ -181,6 +182,58 @@ fn building(ecs: &EcsWorld) {
     }
 }
 
+fn crafting(ecs: &EcsWorld) {
+    let world = ecs.voxel_world();
+    let world = world.borrow();
+
+    let mut colors = helpers::entity_colours();
+    let humans = helpers::get_config_count("humans");
+
+    let society = ecs
+        .resource_mut::<PlayerSociety>()
+        .get()
+        .expect("no player society");
+
+    let _humans = spawn_entities_randomly(&world, humans, Placement::RandomPos, |pos| {
+        helpers::new_entity("core_living_human", ecs, pos)
+            .with_color(colors.next().unwrap())
+            .with_player_society()
+            .with_name()
+            .thanks()
+    });
+
+    let _logs = spawn_entities_randomly(&world, 4, Placement::RandomPos, |pos| {
+        helpers::new_entity("core_item_log", ecs, pos).thanks()
+    });
+
+    let _meat = spawn_entities_randomly(&world, 4, Placement::RandomPos, |pos| {
+        helpers::new_entity("core_food_meat_raw", ecs, pos).thanks()
+    });
+
+    let society = ecs
+        .resource_mut::<Societies>()
+        .society_by_handle(society)
+        .expect("bad society");
+
+    for (workshop, recipe) in [
+        ("core_workshop_carpentry", "core_recipe_planks"),
+        ("core_workshop_kitchen", "core_recipe_cooked_meat"),
+    ] {
+        let pos = helpers::random_walkable_pos(&world);
+        let workshop = helpers::new_entity(workshop, ecs, pos).thanks();
+
+        let job = ecs
+            .find_recipe(recipe)
+            .and_then(|recipe| CraftJob::new(workshop, recipe, ecs));
+        match job {
+            Some(job) => {
+                society.jobs_mut().submit(ecs, job);
+            }
+            None => warn!("failed to create craft job for {}", recipe),
+        }
+    }
+}
+
 fn herding(ecs: &EcsWorld) {
     let world = ecs.voxel_world();
     let world = world.borrow();
@@ -7,3 +7,31 @@ print("selected an entity for you!")
 
 local society = GetPlayerSociety()
 print(string.format("player society = %s", society))
+
+-- spawn a dog and give it an apple to carry around
+local x, y, z = GetPosition(e)
+local dog = SpawnEntity("core_living_dog", math.floor(x) + 2, math.floor(y))
+local apple = SpawnEntity("core_food_apple", math.floor(x) + 2, math.floor(y))
+GiveItem(dog, apple)
+print(string.format("spawned %s holding %d item(s)", dog, #GetInventory(dog)))
+
+-- make everything hungry nearby
+for _, hungry in ipairs(FindEntitiesNear({x, y, z}, 10, "hunger")) do
+    SetHunger(hungry, GetHunger(hungry) * 0.5)
+end
+
+-- build a little wall, and then dig a hole next to it
+local base = {math.floor(x) - 3, math.floor(y) - 3, math.floor(z)}
+Build(society, "core_build_wall", base, {base[1] + 2, base[2], base[3]})
+BreakBlocks(society, {base[1], base[2] - 2, base[3] - 1}, {base[1] + 1, base[2] - 1, base[3] - 1})
+
+-- place some stone for free
+SetBlocks("Stone", {base[1] - 2, base[2], base[3]}, {base[1] - 2, base[2] + 2, base[3]})
+
+-- report on the dog every now and then
+ClearTickCallbacks()
+OnTick(function(tick)
+    if tick % 100 == 0 then
+        print(string.format("tick %d: dog hunger is %.2f", tick, GetHunger(dog)))
+    end
+end)
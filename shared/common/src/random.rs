@@ -1,22 +1,39 @@
-//! Deterministic random generator seeded from config and shared between all threads, only use for
-//! things that really need to be deterministic
+//! Deterministic random generator seeded from config, only use for things that really need to be
+//! deterministic. Once seeded it belongs to the main thread, as the order of use from any other
+//! thread is not deterministic and would break replays
 //!
 use crate::*;
 use parking_lot::{Mutex, MutexGuard};
 use std::ops::DerefMut;
+use std::thread::ThreadId;
 
 lazy_static! {
     static ref RANDY: Mutex<SmallRng> = Mutex::new(SmallRng::from_entropy());
+
+    /// The thread that seeded the generator, the only one allowed to use it
+    static ref OWNER: Mutex<Option<ThreadId>> = Mutex::new(None);
 }
 
+/// Should be called from the main thread, which then becomes the only thread allowed to [get]
 pub fn reseed(seed: u64) {
+    *OWNER.lock() = Some(std::thread::current().id());
+
     let mut randy = RANDY.lock();
     *randy.deref_mut() = SmallRng::seed_from_u64(seed);
 }
 
-/// May block!! In debug builds panics on deadlock
+/// Must only be called from the thread that called [reseed]. May block!! In debug builds panics
+/// on deadlock or use from another thread
 pub fn get<'a>() -> MutexGuard<'a, SmallRng> {
     if cfg!(debug_assertions) {
+        if let Some(owner) = *OWNER.lock() {
+            assert_eq!(
+                owner,
+                std::thread::current().id(),
+                "deterministic random generator used off the main thread"
+            );
+        }
+
         RANDY
             .try_lock()
             .unwrap_or_else(|| panic!("can't take the random mutex"))
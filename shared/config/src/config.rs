@@ -7,6 +7,7 @@ pub struct Config {
     pub display: Display,
     pub world: World,
     pub simulation: Simulation,
+    pub headless: Headless,
 }
 
 #[derive(Deserialize)]
@@ -20,6 +21,14 @@ pub struct Display {
     pub persist_ui: bool,
 }
 
+#[derive(Deserialize)]
+pub struct Headless {
+    /// Local address to listen on for the headless backend
+    pub address: String,
+    /// Allow listening on non-loopback addresses, exposing full control of the game to the network
+    pub allow_remote: bool,
+}
+
 #[derive(Deserialize)]
 pub struct World {
     pub source: WorldSource,
@@ -59,6 +68,11 @@ pub struct Simulation {
     pub entity_logging_capacity: usize,
     pub herd_radius: f32,
     pub herd_expiry_ticks: u32,
+    /// Ticks in a full day and night
+    pub day_length_ticks: u32,
+    pub days_per_season: u32,
+    /// 0=midnight, 0.5=noon
+    pub start_time_of_day: f32,
 }
 
 impl WorldSource {
@@ -0,0 +1,4 @@
+need_quantity! {
+    /// Base unit of hydration for thirst and drinking
+    Hydration
+}
@@ -1,6 +1,10 @@
 #![allow(clippy::many_single_char_names)]
 
+#[macro_use]
+mod need;
+
 pub mod dim;
+pub mod drink;
 pub mod food;
 pub mod space;
 pub mod world;
@@ -0,0 +1,58 @@
+/// Declares a quantity that is consumed to satisfy a need, e.g. [Nutrition](crate::food::Nutrition)
+/// for hunger. Each need gets its own type so they can't be mixed up, but they behave identically
+macro_rules! need_quantity {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Copy,
+            Clone,
+            Debug,
+            common::derive_more::Add,
+            common::derive_more::Display,
+            Ord,
+            PartialOrd,
+            PartialEq,
+            Eq,
+        )]
+        pub struct $name(u16);
+
+        impl common::newtype::Accumulative for $name {
+            fn from_f32(val: f32) -> Self {
+                Self(val as u16)
+            }
+
+            fn saturating_sub(self, x: Self) -> Self {
+                Self(self.0.saturating_sub(x.0))
+            }
+        }
+
+        impl std::ops::Mul<common::NormalizedFloat> for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: common::NormalizedFloat) -> Self::Output {
+                Self((self.0 as f32 * rhs.value()) as u16)
+            }
+        }
+
+        impl $name {
+            pub const fn new(val: u16) -> Self {
+                Self(val)
+            }
+
+            pub fn proportion_of(self, max: Self) -> common::NormalizedFloat {
+                // TODO casting to floats leads to loss of precision when large
+                let div = self.0 as f64 / max.0 as f64;
+                common::NormalizedFloat::clamped(div as f32)
+            }
+
+            pub fn remaining(self, max: Self) -> Self {
+                debug_assert!(max.0 >= self.0, "max should be bigger than value");
+                Self(max.0.saturating_sub(self.0))
+            }
+
+            pub fn is_zero(self) -> bool {
+                self.0 == 0
+            }
+        }
+    };
+}
@@ -157,6 +157,14 @@ impl<P: RangePosition> WorldRange<P> {
 
         ax <= x && x <= bx && ay <= y && y <= by && az <= z && z <= bz
     }
+
+    /// True if the ranges share at least 1 point
+    pub fn intersects(&self, other: &Self) -> bool {
+        let ((ax, bx), (ay, by), (az, bz)) = self.ranges();
+        let ((cx, dx), (cy, dy), (cz, dz)) = other.ranges();
+
+        ax <= dx && cx <= bx && ay <= dy && cy <= by && az <= dz && cz <= bz
+    }
 }
 
 impl<XY: Copy, Z: Copy, P: RangePosition + Add<(XY, XY, Z), Output = P>> Add<(XY, XY, Z)>
@@ -378,6 +386,24 @@ mod tests {
         assert_eq!(range.count(), 2 * 2 * 3);
     }
 
+    #[test]
+    fn intersects() {
+        let range = WorldPositionRange::with_inclusive_range((0, 0, 0), (3, 3, 0));
+
+        let overlapping = WorldPositionRange::with_inclusive_range((3, 3, 0), (5, 5, 2));
+        assert!(range.intersects(&overlapping));
+        assert!(overlapping.intersects(&range));
+
+        let inside = WorldPositionRange::with_single((1, 2, 0));
+        assert!(range.intersects(&inside));
+
+        let next_to = WorldPositionRange::with_inclusive_range((4, 0, 0), (5, 3, 0));
+        assert!(!range.intersects(&next_to));
+
+        let above = WorldPositionRange::with_inclusive_range((0, 0, 1), (3, 3, 1));
+        assert!(!range.intersects(&above));
+    }
+
     #[test]
     fn outline_no_overlap() {
         let range = WorldPositionRange::with_inclusive_range((0, 0, 0), (3, 3, 3));